@@ -1,10 +1,11 @@
 //! Tauri commands for controlling background tasks
 
+use serde::Deserialize;
 use tauri::State;
 
 use super::{
-    BackgroundTaskManager, MAX_POLL_INTERVAL, MAX_REMOTE_POLL_INTERVAL, MIN_POLL_INTERVAL,
-    MIN_REMOTE_POLL_INTERVAL,
+    BackgroundTaskManager, PollPriority, PolledWorktree, MAX_POLL_INTERVAL,
+    MAX_REMOTE_POLL_INTERVAL, MIN_POLL_INTERVAL, MIN_REMOTE_POLL_INTERVAL,
 };
 use crate::projects::git_status::ActiveWorktreeInfo;
 
@@ -48,6 +49,49 @@ pub fn set_active_worktree_for_polling(
     Ok(())
 }
 
+/// A worktree the frontend wants polled in the background
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolledWorktreeInput {
+    pub worktree_id: String,
+    pub worktree_path: String,
+    pub base_branch: String,
+    #[serde(default)]
+    pub pr_number: Option<u32>,
+    #[serde(default)]
+    pub pr_url: Option<String>,
+    /// `visible` for worktrees shown in the sidebar, `idle` otherwise
+    #[serde(default)]
+    pub priority: PollPriority,
+}
+
+/// Replace the set of worktrees polled for git status in the background
+///
+/// Every listed worktree emits `git:status-update` events just like the
+/// active worktree, at an interval derived from its priority.
+#[tauri::command]
+pub fn set_polled_worktrees(
+    state: State<'_, BackgroundTaskManager>,
+    worktrees: Vec<PolledWorktreeInput>,
+) -> Result<(), String> {
+    let worktrees = worktrees
+        .into_iter()
+        .map(|w| PolledWorktree {
+            info: ActiveWorktreeInfo {
+                worktree_id: w.worktree_id,
+                worktree_path: w.worktree_path,
+                base_branch: w.base_branch,
+                pr_number: w.pr_number,
+                pr_url: w.pr_url,
+            },
+            priority: w.priority,
+        })
+        .collect();
+
+    state.set_tracked_worktrees(worktrees);
+    Ok(())
+}
+
 /// Set the git polling interval in seconds
 ///
 /// The interval must be between 10 and 600 seconds (10 seconds to 10 minutes).
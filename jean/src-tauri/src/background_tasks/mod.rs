@@ -1,26 +1,40 @@
 //! Background task management for periodic operations
 //!
 //! This module provides a task manager that runs periodic background tasks,
-//! such as checking git status for the active worktree.
+//! such as checking git status for every worktree shown in the sidebar.
 //!
 //! Polling is split into two categories:
 //! - **Local**: Git commands that run locally (fast, can run frequently)
 //! - **Remote**: API calls like PR status via `gh` (slower, rate-limited)
+//!
+//! Local polling is prioritized per worktree (see [`PollPriority`]): the active
+//! worktree polls at the configured interval, visible worktrees less often and
+//! idle worktrees least often.
+//!
+//! Between polls, tracked worktrees are watched on disk (see [`watcher`]) and
+//! refreshed shortly after a file, index or ref change. The interval acts as a
+//! fallback and is what picks up new commits on origin.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
 use std::time::Duration;
 
+use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 
 use crate::gh_cli::config::resolve_gh_binary;
 use crate::http_server::EmitExt;
-use crate::projects::git_status::{get_branch_status, ActiveWorktreeInfo, GitBranchStatus};
+use crate::projects::git_status::{
+    get_branch_status, get_local_branch_status, ActiveWorktreeInfo, GitBranchStatus,
+};
 use crate::projects::pr_status::{get_pr_status, PrStatus};
 
 pub mod commands;
+pub mod watcher;
+
+use watcher::GitStatusWatcher;
 
 // ============================================================================
 // Local polling constants (git commands that run locally)
@@ -38,6 +52,21 @@ pub const DEFAULT_POLL_INTERVAL: u64 = 60;
 /// Minimum seconds between local polls (debounce for focus changes)
 const MIN_LOCAL_POLL_DEBOUNCE: u64 = 10;
 
+/// Multiplier applied to the poll interval for visible (non-active) worktrees
+const VISIBLE_POLL_MULTIPLIER: u64 = 3;
+
+/// Multiplier applied to the poll interval for idle worktrees
+const IDLE_POLL_MULTIPLIER: u64 = 10;
+
+/// Upper bound for the idle worktree polling interval in seconds (30 minutes)
+const MAX_IDLE_POLL_INTERVAL: u64 = 1800;
+
+/// Maximum number of `get_branch_status` calls running at the same time
+///
+/// Each call spawns several git subprocesses (including `git fetch`), so
+/// polling 30 worktrees at once would saturate the machine.
+pub const MAX_CONCURRENT_STATUS_POLLS: usize = 4;
+
 // ============================================================================
 // Remote polling constants (API calls like PR status)
 // ============================================================================
@@ -51,10 +80,86 @@ pub const MAX_REMOTE_POLL_INTERVAL: u64 = 600;
 /// Default remote polling interval in seconds (1 minute)
 pub const DEFAULT_REMOTE_POLL_INTERVAL: u64 = 60;
 
+/// How often a tracked worktree should be polled for local git status
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum PollPriority {
+    /// The worktree currently open in the UI (polls at the configured interval)
+    Active,
+    /// Worktrees visible in the sidebar (polls at 3x the configured interval)
+    Visible,
+    /// Everything else (polls at 10x the configured interval, max 30 minutes)
+    #[default]
+    Idle,
+}
+
+impl PollPriority {
+    /// Local polling interval in seconds for this priority, given the base interval
+    pub fn interval_secs(self, base_interval: u64) -> u64 {
+        match self {
+            PollPriority::Active => base_interval,
+            PollPriority::Visible => base_interval
+                .saturating_mul(VISIBLE_POLL_MULTIPLIER)
+                .min(MAX_POLL_INTERVAL),
+            PollPriority::Idle => base_interval
+                .saturating_mul(IDLE_POLL_MULTIPLIER)
+                .min(MAX_IDLE_POLL_INTERVAL),
+        }
+    }
+}
+
+/// A worktree registered for background git status polling
+#[derive(Debug, Clone)]
+pub struct PolledWorktree {
+    pub info: ActiveWorktreeInfo,
+    pub priority: PollPriority,
+}
+
+/// Wakes the polling loop from its sleep
+#[derive(Default)]
+struct Wakeup {
+    pending: Mutex<bool>,
+    condvar: Condvar,
+}
+
+impl Wakeup {
+    fn notify(&self) {
+        *self.pending.lock().unwrap() = true;
+        self.condvar.notify_one();
+    }
+
+    /// Sleep until notified or `timeout` elapses (`None` waits for a notify)
+    fn wait(&self, timeout: Option<Duration>) {
+        let pending = self.pending.lock().unwrap();
+        let mut pending = match timeout {
+            Some(timeout) => {
+                self.condvar
+                    .wait_timeout_while(pending, timeout, |pending| !*pending)
+                    .unwrap()
+                    .0
+            }
+            None => self
+                .condvar
+                .wait_while(pending, |pending| !*pending)
+                .unwrap(),
+        };
+        *pending = false;
+    }
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
 /// Manages background tasks for the application
 ///
 /// The task manager runs a polling loop that periodically checks git status
-/// for the active worktree when the application is focused.
+/// for all tracked worktrees when the application is focused.
 ///
 /// Polling is split into local (git commands) and remote (API calls) categories:
 /// - Local polls run on focus changes with a short debounce (10s)
@@ -63,6 +168,8 @@ pub struct BackgroundTaskManager {
     app: AppHandle,
     is_focused: Arc<AtomicBool>,
     active_worktree: Arc<Mutex<Option<ActiveWorktreeInfo>>>,
+    /// Additional worktrees polled in the background, keyed by worktree ID
+    tracked_worktrees: Arc<Mutex<HashMap<String, PolledWorktree>>>,
     /// Interval for local git status polling (background timer)
     poll_interval_secs: Arc<AtomicU64>,
     /// Interval for remote API calls (PR status, etc.)
@@ -76,15 +183,37 @@ pub struct BackgroundTaskManager {
     last_local_poll_times: Arc<Mutex<HashMap<String, u64>>>,
     /// Per-worktree timestamps of last remote poll
     last_remote_poll_times: Arc<Mutex<HashMap<String, u64>>>,
+    /// Worktrees with filesystem changes waiting for a local status refresh
+    changed_worktrees: Arc<Mutex<HashSet<String>>>,
+    /// Filesystem watcher feeding `changed_worktrees`
+    watcher: GitStatusWatcher,
+    /// Worktrees with a local status poll running
+    in_flight: Arc<Mutex<HashSet<String>>>,
+    /// Whether a PR status poll is running
+    remote_in_flight: Arc<AtomicBool>,
+    /// Wakes the polling loop before its next poll is due
+    wakeup: Arc<Wakeup>,
 }
 
 impl BackgroundTaskManager {
     /// Create a new background task manager
     pub fn new(app: AppHandle) -> Self {
+        let changed_worktrees = Arc::new(Mutex::new(HashSet::new()));
+        let wakeup = Arc::new(Wakeup::default());
+        let watcher = {
+            let changed_worktrees = Arc::clone(&changed_worktrees);
+            let wakeup = Arc::clone(&wakeup);
+            GitStatusWatcher::new(Box::new(move |ids| {
+                changed_worktrees.lock().unwrap().extend(ids);
+                wakeup.notify();
+            }))
+        };
+
         Self {
             app,
             is_focused: Arc::new(AtomicBool::new(true)), // Assume focused on startup
             active_worktree: Arc::new(Mutex::new(None)),
+            tracked_worktrees: Arc::new(Mutex::new(HashMap::new())),
             poll_interval_secs: Arc::new(AtomicU64::new(DEFAULT_POLL_INTERVAL)),
             remote_poll_interval_secs: Arc::new(AtomicU64::new(DEFAULT_REMOTE_POLL_INTERVAL)),
             shutdown: Arc::new(AtomicBool::new(false)),
@@ -92,23 +221,34 @@ impl BackgroundTaskManager {
             immediate_remote_poll: Arc::new(AtomicBool::new(false)),
             last_local_poll_times: Arc::new(Mutex::new(HashMap::new())),
             last_remote_poll_times: Arc::new(Mutex::new(HashMap::new())),
+            changed_worktrees,
+            watcher,
+            in_flight: Arc::new(Mutex::new(HashSet::new())),
+            remote_in_flight: Arc::new(AtomicBool::new(false)),
+            wakeup,
         }
     }
 
     /// Start the background polling loop
     ///
     /// This spawns a new thread that will periodically check git status
-    /// for the active worktree when the application is focused.
+    /// for all tracked worktrees when the application is focused.
     ///
     /// The polling loop handles two types of checks:
-    /// - **Local**: Git commands (fast, 10s debounce on focus events)
-    /// - **Remote**: PR status via `gh` (separate interval, default 60s)
+    /// - **Local**: Git commands, scheduled per worktree by [`PollPriority`]
+    ///   and run at most [`MAX_CONCURRENT_STATUS_POLLS`] at a time
+    /// - **Remote**: PR status via `gh` for the active worktree (separate interval, default 60s)
+    ///
+    /// Polls run on their own threads, so a slow `git fetch` or `gh` call never
+    /// holds up the loop. Between rounds the loop sleeps until the next poll is
+    /// due or it is woken by a change (focus, tracked worktrees, a finished poll).
     pub fn start(&self) {
         log::trace!("Starting background task manager");
 
         let app = self.app.clone();
         let is_focused = Arc::clone(&self.is_focused);
         let active_worktree = Arc::clone(&self.active_worktree);
+        let tracked_worktrees = Arc::clone(&self.tracked_worktrees);
         let poll_interval_secs = Arc::clone(&self.poll_interval_secs);
         let remote_poll_interval_secs = Arc::clone(&self.remote_poll_interval_secs);
         let shutdown = Arc::clone(&self.shutdown);
@@ -116,6 +256,10 @@ impl BackgroundTaskManager {
         let immediate_remote_poll = Arc::clone(&self.immediate_remote_poll);
         let last_local_poll_times = Arc::clone(&self.last_local_poll_times);
         let last_remote_poll_times = Arc::clone(&self.last_remote_poll_times);
+        let changed_worktrees = Arc::clone(&self.changed_worktrees);
+        let in_flight = Arc::clone(&self.in_flight);
+        let remote_in_flight = Arc::clone(&self.remote_in_flight);
+        let wakeup = Arc::clone(&self.wakeup);
 
         thread::spawn(move || {
             log::trace!("Background task polling loop started");
@@ -129,87 +273,105 @@ impl BackgroundTaskManager {
 
                 // Only poll when app is focused
                 if !is_focused.load(Ordering::Relaxed) {
-                    thread::sleep(Duration::from_secs(1));
+                    wakeup.wait(None);
                     continue;
                 }
 
-                // Check if we have an active worktree to poll
-                let worktree_info = {
+                let active_info = {
                     let guard = active_worktree.lock().unwrap();
                     guard.clone()
                 };
 
-                if worktree_info.is_none() {
-                    log::trace!("No active worktree for polling");
-                    thread::sleep(Duration::from_secs(1));
-                    continue;
+                // Active worktree first, then every other tracked worktree
+                let mut candidates: Vec<PolledWorktree> = Vec::new();
+                if let Some(info) = &active_info {
+                    candidates.push(PolledWorktree {
+                        info: info.clone(),
+                        priority: PollPriority::Active,
+                    });
                 }
-
-                if let Some(info) = worktree_info {
-                    log::trace!(
-                        "Polling loop: worktree={}, pr_number={:?}, pr_url={:?}",
-                        info.worktree_id,
-                        info.pr_number,
-                        info.pr_url
+                {
+                    let tracked = tracked_worktrees.lock().unwrap();
+                    let active_id = active_info.as_ref().map(|i| i.worktree_id.as_str());
+                    candidates.extend(
+                        tracked
+                            .values()
+                            .filter(|w| Some(w.info.worktree_id.as_str()) != active_id)
+                            .cloned(),
                     );
+                }
 
-                    let now = std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .map(|d| d.as_secs())
-                        .unwrap_or(0);
-
-                    // ================================================================
-                    // Local polling (git commands - fast, short debounce)
-                    // ================================================================
-                    let last_local = {
-                        let times = last_local_poll_times.lock().unwrap();
-                        times.get(&info.worktree_id).copied().unwrap_or(0)
-                    };
-                    let time_since_local = now.saturating_sub(last_local);
-                    let is_immediate_local = immediate_poll.swap(false, Ordering::Relaxed);
-
-                    let should_poll_local =
-                        is_immediate_local || time_since_local >= MIN_LOCAL_POLL_DEBOUNCE;
-
-                    if should_poll_local {
-                        {
-                            let mut times = last_local_poll_times.lock().unwrap();
-                            times.insert(info.worktree_id.clone(), now);
-                        }
+                let now = unix_now();
+
+                // ================================================================
+                // Local polling (git commands - prioritized, bounded concurrency)
+                // ================================================================
+                let base_interval = poll_interval_secs.load(Ordering::Relaxed);
+                let busy = in_flight.lock().unwrap().clone();
+                let free_slots = MAX_CONCURRENT_STATUS_POLLS.saturating_sub(busy.len());
+                let idle: Vec<PolledWorktree> = candidates
+                    .iter()
+                    .filter(|w| !busy.contains(&w.info.worktree_id))
+                    .cloned()
+                    .collect();
+                // Keep an immediate request for the active worktree until it is free
+                let active_busy = active_info
+                    .as_ref()
+                    .is_some_and(|info| busy.contains(&info.worktree_id));
+                let is_immediate_local =
+                    !active_busy && immediate_poll.swap(false, Ordering::Relaxed);
+
+                // Worktrees changed on disk are refreshed first, without fetching
+                let changed = {
+                    let mut pending = changed_worktrees.lock().unwrap();
+                    take_changed_worktrees(&candidates, &busy, &mut pending, free_slots)
+                };
 
-                        match get_branch_status(&info) {
-                            Ok(status) => {
-                                log::trace!(
-                                    "Git status for {}: behind={}, ahead={}, has_updates={}",
-                                    info.worktree_id,
-                                    status.behind_count,
-                                    status.ahead_count,
-                                    status.has_updates
-                                );
-
-                                if let Err(e) = emit_git_status(&app, status) {
-                                    log::error!("Failed to emit git status event: {e}");
-                                }
-                            }
-                            Err(e) => {
-                                log::warn!(
-                                    "Failed to get git status for {}: {e}",
-                                    info.worktree_id
-                                );
-                            }
-                        }
+                let due = {
+                    let mut times = last_local_poll_times.lock().unwrap();
+                    let changed_ids: HashSet<&str> =
+                        changed.iter().map(|i| i.worktree_id.as_str()).collect();
+                    let due = select_due_worktrees(
+                        idle.iter()
+                            .filter(|w| !changed_ids.contains(w.info.worktree_id.as_str()))
+                            .cloned()
+                            .collect(),
+                        &times,
+                        now,
+                        base_interval,
+                        is_immediate_local,
+                        free_slots - changed.len(),
+                    );
+                    for info in &due {
+                        times.insert(info.worktree_id.clone(), now);
                     }
+                    due
+                };
 
-                    // ================================================================
-                    // Remote polling (PR status - separate, longer interval)
-                    // ================================================================
-                    if let (Some(pr_number), Some(pr_url)) = (&info.pr_number, &info.pr_url) {
+                if !changed.is_empty() || !due.is_empty() {
+                    log::trace!(
+                        "Polling git status: changed={:?}, due={:?}",
+                        changed.iter().map(|i| &i.worktree_id).collect::<Vec<_>>(),
+                        due.iter().map(|i| &i.worktree_id).collect::<Vec<_>>()
+                    );
+                    spawn_status_polls(&app, changed, due, &in_flight, &wakeup);
+                }
+
+                // ================================================================
+                // Remote polling (PR status - active worktree only, longer interval)
+                // ================================================================
+                let remote_interval = remote_poll_interval_secs.load(Ordering::Relaxed);
+                let mut next_remote_in = None;
+                let pr = active_info
+                    .as_ref()
+                    .and_then(|info| Some((info, info.pr_number?, info.pr_url.clone()?)));
+                if let Some((info, pr_number, pr_url)) = pr {
+                    if !remote_in_flight.load(Ordering::Relaxed) {
                         let last_remote = {
                             let times = last_remote_poll_times.lock().unwrap();
                             times.get(&info.worktree_id).copied().unwrap_or(0)
                         };
                         let time_since_remote = now.saturating_sub(last_remote);
-                        let remote_interval = remote_poll_interval_secs.load(Ordering::Relaxed);
                         let is_immediate_remote =
                             immediate_remote_poll.swap(false, Ordering::Relaxed);
 
@@ -230,49 +392,46 @@ impl BackgroundTaskManager {
                                 let mut times = last_remote_poll_times.lock().unwrap();
                                 times.insert(info.worktree_id.clone(), now);
                             }
-
-                            let gh = resolve_gh_binary(&app);
-                            match get_pr_status(
-                                &info.worktree_path,
-                                *pr_number,
+                            spawn_pr_poll(
+                                &app,
+                                info.clone(),
+                                pr_number,
                                 pr_url,
-                                &info.worktree_id,
-                                &gh,
-                            ) {
-                                Ok(status) => {
-                                    log::trace!(
-                                        "PR status for #{}: display_status={:?}, check_status={:?}",
-                                        pr_number,
-                                        status.display_status,
-                                        status.check_status
-                                    );
-
-                                    if let Err(e) = emit_pr_status(&app, status) {
-                                        log::error!("Failed to emit PR status event: {e}");
-                                    }
-                                }
-                                Err(e) => {
-                                    log::warn!("Failed to get PR status for #{}: {e}", pr_number);
-                                }
-                            }
+                                &remote_in_flight,
+                                &wakeup,
+                            );
+                        } else {
+                            next_remote_in = Some(remote_interval - time_since_remote);
                         }
                     }
                 }
 
-                // Wait for a short interval before next check
-                // Use 1-second sleep intervals to respond to shutdown/focus/immediate changes quickly
-                let interval = poll_interval_secs.load(Ordering::Relaxed);
-                for _ in 0..interval {
-                    // Break early if shutdown, unfocused, or immediate poll requested
-                    if shutdown.load(Ordering::Relaxed)
-                        || !is_focused.load(Ordering::Relaxed)
-                        || immediate_poll.load(Ordering::Relaxed)
-                        || immediate_remote_poll.load(Ordering::Relaxed)
-                    {
-                        break;
-                    }
-                    thread::sleep(Duration::from_secs(1));
+                // Sleep until the next poll is due. With every slot busy, a
+                // finished poll wakes the loop instead. Filesystem changes and
+                // immediate poll requests wake it early too.
+                let next_local_in = if in_flight.lock().unwrap().len() < MAX_CONCURRENT_STATUS_POLLS
+                {
+                    let busy = in_flight.lock().unwrap().clone();
+                    let times = last_local_poll_times.lock().unwrap();
+                    next_due_in(
+                        candidates
+                            .iter()
+                            .filter(|w| !busy.contains(&w.info.worktree_id)),
+                        &times,
+                        now,
+                        base_interval,
+                    )
+                } else {
+                    None
+                };
+                let timeout = match (next_local_in, next_remote_in) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+                if timeout.is_none() && candidates.is_empty() {
+                    log::trace!("No worktrees registered for polling");
                 }
+                wakeup.wait(timeout.map(|secs| Duration::from_secs(secs.max(1))));
             }
         });
     }
@@ -282,6 +441,7 @@ impl BackgroundTaskManager {
     pub fn stop(&self) {
         log::trace!("Signaling background task manager to stop");
         self.shutdown.store(true, Ordering::Relaxed);
+        self.wakeup.notify();
     }
 
     /// Set whether the application is focused
@@ -291,16 +451,16 @@ impl BackgroundTaskManager {
     /// Remote polling continues on its own interval.
     pub fn set_focused(&self, focused: bool) {
         let was_focused = self.is_focused.swap(focused, Ordering::Relaxed);
+        if focused != was_focused {
+            self.wakeup.notify();
+        }
 
         if focused && !was_focused {
             // App gained focus - check if we should poll immediately
             let worktree_info = self.active_worktree.lock().ok().and_then(|g| g.clone());
 
             if let Some(info) = worktree_info {
-                let now = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .map(|d| d.as_secs())
-                    .unwrap_or(0);
+                let now = unix_now();
 
                 let last_poll = {
                     let times = self.last_local_poll_times.lock().unwrap();
@@ -346,6 +506,63 @@ impl BackgroundTaskManager {
         if should_poll_immediately {
             self.immediate_poll.store(true, Ordering::Relaxed);
         }
+
+        self.sync_watched_worktrees();
+        self.wakeup.notify();
+    }
+
+    /// Replace the set of worktrees polled in the background
+    ///
+    /// The active worktree (see [`Self::set_active_worktree`]) is always polled
+    /// at [`PollPriority::Active`] regardless of its entry here. Poll timestamps
+    /// of worktrees that are no longer tracked are dropped. Newly added
+    /// worktrees are due immediately on the next tick.
+    pub fn set_tracked_worktrees(&self, worktrees: Vec<PolledWorktree>) {
+        log::trace!(
+            "Tracking {} worktree(s) for background polling",
+            worktrees.len()
+        );
+
+        let tracked: HashMap<String, PolledWorktree> = worktrees
+            .into_iter()
+            .map(|w| (w.info.worktree_id.clone(), w))
+            .collect();
+
+        let active_id = self
+            .active_worktree
+            .lock()
+            .ok()
+            .and_then(|g| g.as_ref().map(|i| i.worktree_id.clone()));
+
+        {
+            let mut times = self.last_local_poll_times.lock().unwrap();
+            times.retain(|id, _| tracked.contains_key(id) || Some(id) == active_id.as_ref());
+        }
+
+        *self.tracked_worktrees.lock().unwrap() = tracked;
+
+        self.sync_watched_worktrees();
+        self.wakeup.notify();
+    }
+
+    /// Point the filesystem watcher at the active and tracked worktrees
+    fn sync_watched_worktrees(&self) {
+        let mut paths: HashMap<String, String> = self
+            .tracked_worktrees
+            .lock()
+            .unwrap()
+            .values()
+            .map(|w| (w.info.worktree_id.clone(), w.info.worktree_path.clone()))
+            .collect();
+        if let Some(info) = self.active_worktree.lock().unwrap().as_ref() {
+            paths.insert(info.worktree_id.clone(), info.worktree_path.clone());
+        }
+
+        self.watcher.sync(&paths);
+        self.changed_worktrees
+            .lock()
+            .unwrap()
+            .retain(|id| paths.contains_key(id));
     }
 
     /// Set the local polling interval in seconds
@@ -355,6 +572,7 @@ impl BackgroundTaskManager {
         let clamped = seconds.clamp(MIN_POLL_INTERVAL, MAX_POLL_INTERVAL);
         log::trace!("Setting local git poll interval to {clamped} seconds");
         self.poll_interval_secs.store(clamped, Ordering::Relaxed);
+        self.wakeup.notify();
     }
 
     /// Get the current local polling interval in seconds
@@ -371,6 +589,7 @@ impl BackgroundTaskManager {
         log::trace!("Setting remote poll interval to {clamped} seconds");
         self.remote_poll_interval_secs
             .store(clamped, Ordering::Relaxed);
+        self.wakeup.notify();
     }
 
     /// Get the current remote polling interval in seconds
@@ -385,6 +604,7 @@ impl BackgroundTaskManager {
     pub fn trigger_immediate_poll(&self) {
         log::trace!("Triggering immediate local git poll");
         self.immediate_poll.store(true, Ordering::Relaxed);
+        self.wakeup.notify();
     }
 
     /// Trigger an immediate remote poll
@@ -394,9 +614,188 @@ impl BackgroundTaskManager {
     pub fn trigger_immediate_remote_poll(&self) {
         log::trace!("Triggering immediate remote poll");
         self.immediate_remote_poll.store(true, Ordering::Relaxed);
+        self.wakeup.notify();
+    }
+}
+
+/// Pick the worktrees whose local poll is due, most urgent first
+///
+/// A worktree is due once its priority interval has elapsed since its last
+/// poll. When `immediate` is set, active worktrees are due regardless of their
+/// last poll time. At most `limit` worktrees are returned, ordered by priority
+/// and then by how long ago they were last polled.
+fn select_due_worktrees(
+    candidates: Vec<PolledWorktree>,
+    last_poll_times: &HashMap<String, u64>,
+    now: u64,
+    base_interval: u64,
+    immediate: bool,
+    limit: usize,
+) -> Vec<ActiveWorktreeInfo> {
+    let mut due: Vec<(PollPriority, u64, ActiveWorktreeInfo)> = candidates
+        .into_iter()
+        .filter_map(|w| {
+            let last_poll = last_poll_times
+                .get(&w.info.worktree_id)
+                .copied()
+                .unwrap_or(0);
+            let elapsed = now.saturating_sub(last_poll);
+            let is_due = (immediate && w.priority == PollPriority::Active)
+                || elapsed >= w.priority.interval_secs(base_interval);
+            is_due.then_some((w.priority, last_poll, w.info))
+        })
+        .collect();
+
+    due.sort_by_key(|(priority, last_poll, _)| (*priority, *last_poll));
+    due.into_iter()
+        .take(limit)
+        .map(|(_, _, info)| info)
+        .collect()
+}
+
+/// Remove up to `limit` changed worktrees from `pending`, in candidate order
+///
+/// Worktrees with a poll running (`busy`) stay pending for a later round. IDs
+/// that are no longer polling candidates are discarded.
+fn take_changed_worktrees(
+    candidates: &[PolledWorktree],
+    busy: &HashSet<String>,
+    pending: &mut HashSet<String>,
+    limit: usize,
+) -> Vec<ActiveWorktreeInfo> {
+    if pending.is_empty() {
+        return Vec::new();
+    }
+
+    let changed: Vec<ActiveWorktreeInfo> = candidates
+        .iter()
+        .filter(|w| pending.contains(&w.info.worktree_id) && !busy.contains(&w.info.worktree_id))
+        .take(limit)
+        .map(|w| w.info.clone())
+        .collect();
+
+    for info in &changed {
+        pending.remove(&info.worktree_id);
+    }
+    pending.retain(|id| candidates.iter().any(|w| &w.info.worktree_id == id));
+    changed
+}
+
+/// Seconds until the next of `candidates` is due for a local poll
+///
+/// `None` when there are no candidates.
+fn next_due_in<'a>(
+    candidates: impl Iterator<Item = &'a PolledWorktree>,
+    last_poll_times: &HashMap<String, u64>,
+    now: u64,
+    base_interval: u64,
+) -> Option<u64> {
+    candidates
+        .map(|w| {
+            let last_poll = last_poll_times
+                .get(&w.info.worktree_id)
+                .copied()
+                .unwrap_or(0);
+            (last_poll + w.priority.interval_secs(base_interval)).saturating_sub(now)
+        })
+        .min()
+}
+
+/// Refresh git status for each worktree on its own thread and emit the results
+///
+/// `changed` worktrees get a local-only refresh (no `git fetch`), `due`
+/// worktrees a full one. Each worktree is in `in_flight` until its poll ends,
+/// which bounds concurrency at [`MAX_CONCURRENT_STATUS_POLLS`]; a hung remote
+/// only holds its own slot until the fetch times out.
+fn spawn_status_polls(
+    app: &AppHandle,
+    changed: Vec<ActiveWorktreeInfo>,
+    due: Vec<ActiveWorktreeInfo>,
+    in_flight: &Arc<Mutex<HashSet<String>>>,
+    wakeup: &Arc<Wakeup>,
+) {
+    let batch = changed
+        .into_iter()
+        .map(|info| (info, false))
+        .chain(due.into_iter().map(|info| (info, true)));
+
+    for (info, fetch) in batch {
+        in_flight.lock().unwrap().insert(info.worktree_id.clone());
+        let app = app.clone();
+        let in_flight = Arc::clone(in_flight);
+        let wakeup = Arc::clone(wakeup);
+        thread::spawn(move || {
+            let status = if fetch {
+                get_branch_status(&info)
+            } else {
+                get_local_branch_status(&info)
+            };
+            match status {
+                Ok(status) => {
+                    log::trace!(
+                        "Git status for {}: behind={}, ahead={}",
+                        info.worktree_id,
+                        status.behind_count,
+                        status.ahead_count
+                    );
+
+                    if let Err(e) = emit_git_status(&app, status) {
+                        log::error!("Failed to emit git status event: {e}");
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Failed to get git status for {}: {e}", info.worktree_id);
+                }
+            }
+            in_flight.lock().unwrap().remove(&info.worktree_id);
+            wakeup.notify();
+        });
     }
 }
 
+/// Fetch PR status for the active worktree on its own thread and emit it
+fn spawn_pr_poll(
+    app: &AppHandle,
+    info: ActiveWorktreeInfo,
+    pr_number: u32,
+    pr_url: String,
+    in_flight: &Arc<AtomicBool>,
+    wakeup: &Arc<Wakeup>,
+) {
+    in_flight.store(true, Ordering::Relaxed);
+    let app = app.clone();
+    let in_flight = Arc::clone(in_flight);
+    let wakeup = Arc::clone(wakeup);
+    thread::spawn(move || {
+        let gh = resolve_gh_binary(&app);
+        match get_pr_status(
+            &info.worktree_path,
+            pr_number,
+            &pr_url,
+            &info.worktree_id,
+            &gh,
+        ) {
+            Ok(status) => {
+                log::trace!(
+                    "PR status for #{}: display_status={:?}, check_status={:?}",
+                    pr_number,
+                    status.display_status,
+                    status.check_status
+                );
+
+                if let Err(e) = emit_pr_status(&app, status) {
+                    log::error!("Failed to emit PR status event: {e}");
+                }
+            }
+            Err(e) => {
+                log::warn!("Failed to get PR status for #{}: {e}", pr_number);
+            }
+        }
+        in_flight.store(false, Ordering::Relaxed);
+        wakeup.notify();
+    });
+}
+
 /// Emit a git status event to the frontend
 fn emit_git_status(app: &AppHandle, status: GitBranchStatus) -> Result<(), String> {
     app.emit_all("git:status-update", &status)
@@ -408,3 +807,152 @@ fn emit_pr_status(app: &AppHandle, status: PrStatus) -> Result<(), String> {
     app.emit_all("pr:status-update", &status)
         .map_err(|e| format!("Failed to emit pr:status-update event: {e}"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worktree(id: &str, priority: PollPriority) -> PolledWorktree {
+        PolledWorktree {
+            info: ActiveWorktreeInfo {
+                worktree_id: id.to_string(),
+                worktree_path: format!("/tmp/{id}"),
+                base_branch: "main".to_string(),
+                pr_number: None,
+                pr_url: None,
+            },
+            priority,
+        }
+    }
+
+    fn ids(infos: &[ActiveWorktreeInfo]) -> Vec<&str> {
+        infos.iter().map(|i| i.worktree_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_interval_secs_by_priority() {
+        assert_eq!(PollPriority::Active.interval_secs(60), 60);
+        assert_eq!(PollPriority::Visible.interval_secs(60), 180);
+        assert_eq!(PollPriority::Idle.interval_secs(60), 600);
+        assert_eq!(PollPriority::Visible.interval_secs(600), MAX_POLL_INTERVAL);
+        assert_eq!(
+            PollPriority::Idle.interval_secs(600),
+            MAX_IDLE_POLL_INTERVAL
+        );
+    }
+
+    #[test]
+    fn test_select_due_respects_priority_intervals() {
+        let now = 10_000;
+        let mut times = HashMap::new();
+        times.insert("active".to_string(), now - 60);
+        times.insert("visible".to_string(), now - 60);
+        times.insert("idle".to_string(), now - 60);
+
+        let due = select_due_worktrees(
+            vec![
+                worktree("idle", PollPriority::Idle),
+                worktree("visible", PollPriority::Visible),
+                worktree("active", PollPriority::Active),
+            ],
+            &times,
+            now,
+            60,
+            false,
+            10,
+        );
+        assert_eq!(ids(&due), vec!["active"]);
+    }
+
+    #[test]
+    fn test_select_due_orders_and_limits() {
+        let due = select_due_worktrees(
+            vec![
+                worktree("idle", PollPriority::Idle),
+                worktree("visible", PollPriority::Visible),
+                worktree("active", PollPriority::Active),
+            ],
+            &HashMap::new(),
+            10_000,
+            60,
+            false,
+            2,
+        );
+        assert_eq!(ids(&due), vec!["active", "visible"]);
+    }
+
+    #[test]
+    fn test_take_changed_worktrees() {
+        let candidates = vec![
+            worktree("active", PollPriority::Active),
+            worktree("visible", PollPriority::Visible),
+            worktree("idle", PollPriority::Idle),
+        ];
+        let mut pending: HashSet<String> = ["idle", "active", "removed"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let none = HashSet::new();
+        let changed = take_changed_worktrees(&candidates, &none, &mut pending, 1);
+        assert_eq!(ids(&changed), vec!["active"]);
+        assert_eq!(pending, HashSet::from(["idle".to_string()]));
+
+        let busy = HashSet::from(["idle".to_string()]);
+        let changed = take_changed_worktrees(&candidates, &busy, &mut pending, 4);
+        assert!(changed.is_empty());
+        assert_eq!(pending, HashSet::from(["idle".to_string()]));
+
+        let changed = take_changed_worktrees(&candidates, &none, &mut pending, 4);
+        assert_eq!(ids(&changed), vec!["idle"]);
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_next_due_in() {
+        let now = 10_000;
+        let mut times = HashMap::new();
+        times.insert("active".to_string(), now - 50);
+        times.insert("idle".to_string(), now - 50);
+        let candidates = [
+            worktree("active", PollPriority::Active),
+            worktree("idle", PollPriority::Idle),
+        ];
+        assert_eq!(next_due_in(candidates.iter(), &times, now, 60), Some(10));
+        assert_eq!(
+            next_due_in(candidates[1..].iter(), &times, now, 60),
+            Some(550)
+        );
+        assert_eq!(
+            next_due_in(
+                [worktree("new", PollPriority::Idle)].iter(),
+                &times,
+                now,
+                60
+            ),
+            Some(0)
+        );
+        assert_eq!(next_due_in([].iter(), &times, now, 60), None);
+    }
+
+    #[test]
+    fn test_select_due_immediate_only_forces_active() {
+        let now = 10_000;
+        let mut times = HashMap::new();
+        times.insert("active".to_string(), now);
+        times.insert("visible".to_string(), now);
+
+        let due = select_due_worktrees(
+            vec![
+                worktree("visible", PollPriority::Visible),
+                worktree("active", PollPriority::Active),
+            ],
+            &times,
+            now,
+            60,
+            true,
+            10,
+        );
+        assert_eq!(ids(&due), vec!["active"]);
+    }
+}
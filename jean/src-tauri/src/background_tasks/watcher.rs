@@ -0,0 +1,640 @@
+//! Filesystem watching for git status refreshes
+//!
+//! Instead of waiting for the next poll, each tracked worktree is watched for
+//! changes to its git metadata (`HEAD`, `index`, local branch refs) and to
+//! files in the working tree that are not ignored by `.gitignore`. Bursts of
+//! events are debounced and reported as a set of changed worktree IDs, which
+//! the [`super::BackgroundTaskManager`] turns into local (no-fetch) status
+//! polls. Remote-tracking refs are left out: they are mostly written by the
+//! remote poll's own `git fetch`, which refreshes the status anyway.
+//!
+//! All worktrees share one OS watcher (a single inotify instance on Linux).
+//! Directories are watched individually (non-recursively) after walking the
+//! worktree with the `ignore` crate, so ignored trees such as `node_modules`
+//! or `target` never consume watch handles, and the total number of watches is
+//! capped. Walks run on their own threads, so neither switching worktrees nor
+//! events for other worktrees wait for them.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{Match, WalkBuilder};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Quiet period after the last event before a change is reported
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Upper bound on how long a continuous stream of events can delay a report
+const MAX_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Maximum number of directories watched per worktree
+///
+/// Very large worktrees fall back to interval polling for files beyond this
+/// limit; git metadata is always watched.
+const MAX_WATCHED_DIRS: usize = 2000;
+
+/// Maximum number of watches across all worktrees, well below the usual
+/// `fs.inotify.max_user_watches` so other programs keep theirs
+const MAX_TOTAL_WATCHES: usize = 6000;
+
+/// Callback invoked with the IDs of worktrees that changed
+pub type ChangeHandler = Box<dyn Fn(Vec<String>) + Send + 'static>;
+
+/// Work for the watcher thread
+enum WatcherMessage {
+    /// Watch exactly these worktrees (ID → path)
+    Sync(HashMap<String, String>),
+    /// A worktree has been walked and can be watched (`None` if it couldn't be)
+    Scanned(String, Option<Box<WorktreeWatch>>),
+    /// Filesystem event in any watched worktree
+    Event(Event),
+    Shutdown,
+}
+
+/// Paths of a worktree's git metadata
+///
+/// For linked worktrees (`git worktree add`), `HEAD` and `index` live in a
+/// per-worktree git dir while refs live in the shared common dir.
+#[derive(Debug, Clone)]
+struct GitDirs {
+    git_dir: PathBuf,
+    common_dir: PathBuf,
+}
+
+impl GitDirs {
+    /// Resolve the git dirs for a worktree by reading `.git` directly
+    fn resolve(worktree_path: &Path) -> Option<Self> {
+        let dot_git = worktree_path.join(".git");
+        if dot_git.is_dir() {
+            let git_dir = fs::canonicalize(&dot_git).ok()?;
+            return Some(Self {
+                common_dir: git_dir.clone(),
+                git_dir,
+            });
+        }
+
+        // Linked worktree: `.git` is a file containing "gitdir: <path>"
+        let contents = fs::read_to_string(&dot_git).ok()?;
+        let git_dir = contents.trim().strip_prefix("gitdir:")?.trim();
+        let git_dir = fs::canonicalize(worktree_path.join(git_dir)).ok()?;
+        let common_dir = fs::read_to_string(git_dir.join("commondir"))
+            .ok()
+            .and_then(|c| fs::canonicalize(git_dir.join(c.trim())).ok())
+            .unwrap_or_else(|| git_dir.clone());
+
+        Some(Self {
+            git_dir,
+            common_dir,
+        })
+    }
+}
+
+/// The OS watcher shared by all worktrees
+///
+/// Worktrees of one repository share its common git dir, so watches are
+/// reference counted.
+struct SharedWatcher {
+    watcher: RecommendedWatcher,
+    refs: HashMap<PathBuf, usize>,
+}
+
+impl SharedWatcher {
+    fn watch(&mut self, path: &Path, mode: RecursiveMode) -> bool {
+        if let Some(count) = self.refs.get_mut(path) {
+            *count += 1;
+            return true;
+        }
+        match self.watcher.watch(path, mode) {
+            Ok(()) => {
+                self.refs.insert(path.to_path_buf(), 1);
+                true
+            }
+            Err(e) => {
+                log::trace!("Failed to watch {}: {e}", path.display());
+                false
+            }
+        }
+    }
+
+    fn unwatch(&mut self, path: &Path) {
+        let Some(count) = self.refs.get_mut(path) else {
+            return;
+        };
+        *count -= 1;
+        if *count == 0 {
+            self.refs.remove(path);
+            // Fails harmlessly if the directory is already gone
+            let _ = self.watcher.unwatch(path);
+        }
+    }
+}
+
+/// Watch state for a single worktree
+struct WorktreeWatch {
+    /// Worktree path as registered by the caller
+    path: PathBuf,
+    /// Canonicalized worktree path (event paths are reported canonicalized)
+    root: PathBuf,
+    git_dirs: Option<GitDirs>,
+    /// `.gitignore` of each directory that has one
+    gitignores: HashMap<PathBuf, Gitignore>,
+    /// `info/exclude`, which applies below every `.gitignore`
+    exclude: Gitignore,
+    /// Directories found by the walk, not watched yet
+    scanned_dirs: Vec<PathBuf>,
+    /// Git metadata paths watched for this worktree
+    metadata_paths: Vec<PathBuf>,
+    watched_dirs: HashSet<PathBuf>,
+}
+
+impl WorktreeWatch {
+    /// Walk a worktree and load its ignore rules, without watching anything
+    ///
+    /// This does all the disk IO and runs off the watcher thread.
+    fn scan(path: PathBuf) -> Option<Self> {
+        let root = fs::canonicalize(&path).ok()?;
+        let git_dirs = GitDirs::resolve(&root);
+        let exclude = build_exclude(&root, git_dirs.as_ref());
+
+        let mut watch = Self {
+            path,
+            root,
+            git_dirs,
+            gitignores: HashMap::new(),
+            exclude,
+            scanned_dirs: Vec::new(),
+            metadata_paths: Vec::new(),
+            watched_dirs: HashSet::new(),
+        };
+
+        let walker = WalkBuilder::new(&watch.root)
+            .hidden(false)
+            .filter_entry(|entry| entry.file_name() != ".git")
+            .build();
+        for entry in walker.flatten() {
+            if !entry.file_type().is_some_and(|t| t.is_dir()) {
+                continue;
+            }
+            if watch.scanned_dirs.len() >= MAX_WATCHED_DIRS {
+                log::debug!(
+                    "Watch limit of {MAX_WATCHED_DIRS} directories reached for {}",
+                    watch.root.display()
+                );
+                break;
+            }
+            let dir = entry.into_path();
+            watch.load_gitignore(&dir);
+            watch.scanned_dirs.push(dir);
+        }
+        Some(watch)
+    }
+
+    /// Watch the git metadata and the scanned directories
+    fn register(&mut self, shared: &mut SharedWatcher) {
+        self.watch_git_metadata(shared);
+        for dir in std::mem::take(&mut self.scanned_dirs) {
+            if !self.watch_dir(shared, dir) {
+                break;
+            }
+        }
+    }
+
+    /// Drop every watch held for this worktree
+    fn unregister(&mut self, shared: &mut SharedWatcher) {
+        for path in self
+            .metadata_paths
+            .drain(..)
+            .chain(self.watched_dirs.drain())
+        {
+            shared.unwatch(&path);
+        }
+    }
+
+    /// Watch `HEAD`/`index` and the local branch refs
+    fn watch_git_metadata(&mut self, shared: &mut SharedWatcher) {
+        let Some(dirs) = self.git_dirs.clone() else {
+            return;
+        };
+
+        let mut paths = vec![(dirs.git_dir.clone(), RecursiveMode::NonRecursive)];
+        if dirs.common_dir != dirs.git_dir {
+            paths.push((dirs.common_dir.clone(), RecursiveMode::NonRecursive));
+        }
+        let heads = dirs.common_dir.join("refs/heads");
+        if heads.is_dir() {
+            paths.push((heads, RecursiveMode::Recursive));
+        }
+        for (path, mode) in paths {
+            if shared.watch(&path, mode) {
+                self.metadata_paths.push(path);
+            }
+        }
+    }
+
+    /// Add a non-recursive watch for a directory. Returns false once a limit is hit.
+    fn watch_dir(&mut self, shared: &mut SharedWatcher, dir: PathBuf) -> bool {
+        if self.watched_dirs.len() >= MAX_WATCHED_DIRS || shared.refs.len() >= MAX_TOTAL_WATCHES {
+            log::debug!("Watch limit reached for {}", self.root.display());
+            return false;
+        }
+        if self.watched_dirs.contains(&dir) {
+            return true;
+        }
+        if shared.watch(&dir, RecursiveMode::NonRecursive) {
+            self.watched_dirs.insert(dir);
+        }
+        true
+    }
+
+    /// (Re)load the `.gitignore` of a directory
+    fn load_gitignore(&mut self, dir: &Path) {
+        let file = dir.join(".gitignore");
+        if !file.is_file() {
+            self.gitignores.remove(dir);
+            return;
+        }
+        let mut builder = GitignoreBuilder::new(dir);
+        builder.add(&file);
+        match builder.build() {
+            Ok(gitignore) => {
+                self.gitignores.insert(dir.to_path_buf(), gitignore);
+            }
+            Err(e) => log::warn!("Failed to parse {}: {e}", file.display()),
+        }
+    }
+
+    /// Whether a path in the working tree is ignored
+    ///
+    /// Follows git's precedence: the `.gitignore` closest to the path decides,
+    /// and `info/exclude` only applies when no `.gitignore` matches. Parent
+    /// directories aren't checked, since ignored directories are never watched.
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        for dir in path.ancestors().skip(1) {
+            if let Some(gitignore) = self.gitignores.get(dir) {
+                match gitignore.matched(path, is_dir) {
+                    Match::Ignore(_) => return true,
+                    Match::Whitelist(_) => return false,
+                    Match::None => {}
+                }
+            }
+            if dir == self.root {
+                break;
+            }
+        }
+        self.exclude.matched(path, is_dir).is_ignore()
+    }
+
+    /// Whether an event should trigger a status refresh for this worktree
+    ///
+    /// Newly created working tree directories are added to the watch set.
+    fn handle_event(&mut self, shared: &mut SharedWatcher, event: &Event) -> bool {
+        if matches!(event.kind, EventKind::Access(_)) {
+            return false;
+        }
+
+        let mut relevant = false;
+        for path in &event.paths {
+            if self.is_git_metadata_change(path) {
+                relevant = true;
+                continue;
+            }
+            if !path.starts_with(&self.root) || is_inside_dot_git(&self.root, path) {
+                continue;
+            }
+
+            if path.file_name().is_some_and(|name| name == ".gitignore") {
+                if let Some(dir) = path.parent().filter(|d| self.watched_dirs.contains(*d)) {
+                    self.load_gitignore(dir);
+                }
+            }
+
+            let is_dir = path.is_dir();
+            if self.is_ignored(path, is_dir) {
+                continue;
+            }
+            if is_dir && matches!(event.kind, EventKind::Create(_)) {
+                self.load_gitignore(path);
+                self.watch_dir(shared, path.clone());
+            }
+            relevant = true;
+        }
+        relevant
+    }
+
+    /// `HEAD`, `index`, `packed-refs` and local branch refs count; lock files,
+    /// fetch bookkeeping (`FETCH_HEAD`) and remote-tracking refs, all written
+    /// by our own remote polls, do not.
+    fn is_git_metadata_change(&self, path: &Path) -> bool {
+        let Some(dirs) = &self.git_dirs else {
+            return false;
+        };
+        if path.extension().is_some_and(|ext| ext == "lock") {
+            return false;
+        }
+        if path == dirs.common_dir.join("packed-refs") {
+            return true;
+        }
+        if path.parent() == Some(dirs.git_dir.as_path()) {
+            let name = path.file_name().and_then(|n| n.to_str());
+            return matches!(name, Some("HEAD" | "index"));
+        }
+        path.starts_with(dirs.common_dir.join("refs/heads"))
+    }
+}
+
+/// Whether `path` is inside the worktree's own `.git` directory
+fn is_inside_dot_git(root: &Path, path: &Path) -> bool {
+    path.strip_prefix(root)
+        .ok()
+        .and_then(|rel| rel.components().next())
+        .is_some_and(|first| first.as_os_str() == ".git")
+}
+
+/// Build a matcher from the repository's `info/exclude`
+fn build_exclude(root: &Path, git_dirs: Option<&GitDirs>) -> Gitignore {
+    let Some(dirs) = git_dirs else {
+        return Gitignore::empty();
+    };
+    let mut builder = GitignoreBuilder::new(root);
+    builder.add(dirs.common_dir.join("info/exclude"));
+    builder.build().unwrap_or_else(|e| {
+        log::warn!("Failed to parse info/exclude in {}: {e}", root.display());
+        Gitignore::empty()
+    })
+}
+
+/// Watches worktrees and reports debounced changes
+///
+/// Dropping it stops the watcher thread.
+pub struct GitStatusWatcher {
+    messages: Sender<WatcherMessage>,
+}
+
+impl GitStatusWatcher {
+    /// Create a watcher and start its thread
+    ///
+    /// The thread owns the OS watcher and processes events. `on_change` is
+    /// called from it with the IDs of worktrees that changed since the last
+    /// report.
+    pub fn new(on_change: ChangeHandler) -> Self {
+        let (tx, rx) = mpsc::channel::<WatcherMessage>();
+        let thread_tx = tx.clone();
+
+        thread::spawn(move || {
+            let events_tx = thread_tx.clone();
+            let watcher =
+                notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+                    Ok(event) => {
+                        let _ = events_tx.send(WatcherMessage::Event(event));
+                    }
+                    Err(e) => log::warn!("Filesystem watch error: {e}"),
+                });
+            let mut shared = match watcher {
+                Ok(watcher) => SharedWatcher {
+                    watcher,
+                    refs: HashMap::new(),
+                },
+                Err(e) => {
+                    log::warn!("Failed to create filesystem watcher: {e}");
+                    return;
+                }
+            };
+
+            let mut wanted: HashMap<String, String> = HashMap::new();
+            let mut scanning: HashSet<String> = HashSet::new();
+            let mut watches: HashMap<String, WorktreeWatch> = HashMap::new();
+            let mut pending: HashSet<String> = HashSet::new();
+            let mut first_event: Option<Instant> = None;
+            let mut last_event = Instant::now();
+
+            loop {
+                match rx.recv_timeout(DEBOUNCE) {
+                    Ok(WatcherMessage::Event(event)) => {
+                        for (worktree_id, watch) in watches.iter_mut() {
+                            if watch.handle_event(&mut shared, &event) {
+                                log::trace!(
+                                    "Filesystem change in {worktree_id}: {:?}",
+                                    event.paths
+                                );
+                                pending.insert(worktree_id.clone());
+                                last_event = Instant::now();
+                                first_event.get_or_insert(last_event);
+                            }
+                        }
+                    }
+                    Ok(WatcherMessage::Sync(worktrees)) => {
+                        wanted = worktrees;
+                        sync_watches(
+                            &mut shared,
+                            &mut watches,
+                            &mut scanning,
+                            &wanted,
+                            &thread_tx,
+                        );
+                        pending.retain(|id| watches.contains_key(id));
+                    }
+                    Ok(WatcherMessage::Scanned(worktree_id, None)) => {
+                        // Retried on the next sync
+                        scanning.remove(&worktree_id);
+                    }
+                    Ok(WatcherMessage::Scanned(worktree_id, Some(mut watch))) => {
+                        scanning.remove(&worktree_id);
+                        let still_wanted = wanted
+                            .get(&worktree_id)
+                            .is_some_and(|path| Path::new(path) == watch.path);
+                        if still_wanted && !watches.contains_key(&worktree_id) {
+                            watch.register(&mut shared);
+                            log::trace!(
+                                "Watching worktree {worktree_id} ({} directories)",
+                                watch.watched_dirs.len()
+                            );
+                            watches.insert(worktree_id, *watch);
+                        } else if wanted.contains_key(&worktree_id) {
+                            // The path changed while walking; walk the new one
+                            sync_watches(
+                                &mut shared,
+                                &mut watches,
+                                &mut scanning,
+                                &wanted,
+                                &thread_tx,
+                            );
+                        }
+                    }
+                    Ok(WatcherMessage::Shutdown) | Err(RecvTimeoutError::Disconnected) => break,
+                    Err(RecvTimeoutError::Timeout) => {}
+                }
+
+                let Some(first) = first_event else {
+                    continue;
+                };
+                if last_event.elapsed() >= DEBOUNCE || first.elapsed() >= MAX_DEBOUNCE {
+                    if !pending.is_empty() {
+                        on_change(pending.drain().collect());
+                    }
+                    first_event = None;
+                }
+            }
+
+            log::trace!("Git status watcher thread stopped");
+        });
+
+        Self { messages: tx }
+    }
+
+    /// Watch exactly the given worktrees (ID → path), dropping all others
+    ///
+    /// Returns immediately; new worktrees are walked in the background.
+    /// Existing watches are kept when the path is unchanged, so calling this
+    /// on every sidebar update is cheap.
+    pub fn sync(&self, worktrees: &HashMap<String, String>) {
+        let _ = self.messages.send(WatcherMessage::Sync(worktrees.clone()));
+    }
+}
+
+impl Drop for GitStatusWatcher {
+    fn drop(&mut self) {
+        let _ = self.messages.send(WatcherMessage::Shutdown);
+    }
+}
+
+/// Bring the watch set in line with `worktrees`
+///
+/// Removed worktrees are unwatched right away; new ones are walked on a
+/// separate thread and come back as [`WatcherMessage::Scanned`]. Worktrees
+/// that can't be walked are retried on the next sync.
+fn sync_watches(
+    shared: &mut SharedWatcher,
+    watches: &mut HashMap<String, WorktreeWatch>,
+    scanning: &mut HashSet<String>,
+    worktrees: &HashMap<String, String>,
+    messages: &Sender<WatcherMessage>,
+) {
+    watches.retain(|id, watch| {
+        let keep = worktrees
+            .get(id)
+            .is_some_and(|path| Path::new(path) == watch.path);
+        if !keep {
+            watch.unregister(shared);
+        }
+        keep
+    });
+
+    for (id, path) in worktrees {
+        if watches.contains_key(id) || scanning.contains(id) {
+            continue;
+        }
+        scanning.insert(id.clone());
+        let id = id.clone();
+        let path = PathBuf::from(path);
+        let messages = messages.clone();
+        thread::spawn(move || {
+            let watch = path
+                .is_dir()
+                .then(|| WorktreeWatch::scan(path))
+                .flatten()
+                .map(Box::new);
+            let _ = messages.send(WatcherMessage::Scanned(id, watch));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_git_dirs_for_linked_worktree() {
+        let temp = tempfile::tempdir().unwrap();
+        let main_git = temp.path().join("main/.git");
+        let linked_git_dir = main_git.join("worktrees/feature");
+        fs::create_dir_all(&linked_git_dir).unwrap();
+        fs::write(linked_git_dir.join("commondir"), "../..\n").unwrap();
+
+        let worktree = temp.path().join("feature");
+        fs::create_dir_all(&worktree).unwrap();
+        fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", linked_git_dir.display()),
+        )
+        .unwrap();
+
+        let dirs = GitDirs::resolve(&worktree).unwrap();
+        assert_eq!(dirs.git_dir, fs::canonicalize(&linked_git_dir).unwrap());
+        assert_eq!(dirs.common_dir, fs::canonicalize(&main_git).unwrap());
+    }
+
+    #[test]
+    fn test_resolve_git_dirs_for_main_worktree() {
+        let temp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(temp.path().join(".git")).unwrap();
+
+        let dirs = GitDirs::resolve(temp.path()).unwrap();
+        let expected = fs::canonicalize(temp.path().join(".git")).unwrap();
+        assert_eq!(dirs.git_dir, expected);
+        assert_eq!(dirs.common_dir, expected);
+    }
+
+    #[test]
+    fn test_nested_gitignore_takes_precedence() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        fs::write(root.join("sub/.gitignore"), "generated.rs\n!keep.log\n").unwrap();
+
+        let watch = WorktreeWatch::scan(root.to_path_buf()).unwrap();
+        let path = |rel: &str| watch.root.join(rel);
+
+        assert!(watch.is_ignored(&path("debug.log"), false));
+        assert!(watch.is_ignored(&path("sub/debug.log"), false));
+        assert!(!watch.is_ignored(&path("sub/keep.log"), false));
+        assert!(watch.is_ignored(&path("sub/generated.rs"), false));
+        assert!(!watch.is_ignored(&path("generated.rs"), false));
+    }
+
+    #[test]
+    fn test_remote_tracking_refs_are_not_metadata_changes() {
+        let temp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(temp.path().join(".git/refs/heads")).unwrap();
+
+        let watch = WorktreeWatch::scan(temp.path().to_path_buf()).unwrap();
+        let common_dir = &watch.git_dirs.as_ref().unwrap().common_dir;
+
+        assert!(watch.is_git_metadata_change(&common_dir.join("refs/heads/main")));
+        assert!(watch.is_git_metadata_change(&common_dir.join("index")));
+        assert!(!watch.is_git_metadata_change(&common_dir.join("refs/remotes/origin/main")));
+        assert!(!watch.is_git_metadata_change(&common_dir.join("FETCH_HEAD")));
+    }
+
+    #[test]
+    fn test_shared_watches_are_reference_counted() {
+        let temp = tempfile::tempdir().unwrap();
+        let watcher = notify::recommended_watcher(|_: notify::Result<Event>| {}).unwrap();
+        let mut shared = SharedWatcher {
+            watcher,
+            refs: HashMap::new(),
+        };
+
+        assert!(shared.watch(temp.path(), RecursiveMode::NonRecursive));
+        assert!(shared.watch(temp.path(), RecursiveMode::NonRecursive));
+        assert_eq!(shared.refs.get(temp.path()), Some(&2));
+        shared.unwatch(temp.path());
+        assert_eq!(shared.refs.get(temp.path()), Some(&1));
+        shared.unwatch(temp.path());
+        assert!(shared.refs.is_empty());
+    }
+
+    #[test]
+    fn test_is_inside_dot_git() {
+        let root = Path::new("/repo");
+        assert!(is_inside_dot_git(root, Path::new("/repo/.git/index")));
+        assert!(!is_inside_dot_git(root, Path::new("/repo/src/.git")));
+        assert!(!is_inside_dot_git(root, Path::new("/repo/src/main.rs")));
+    }
+}
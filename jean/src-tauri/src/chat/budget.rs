@@ -0,0 +1,578 @@
+//! Spending and token budgets
+//!
+//! Budgets are configured in preferences (`usage_budgets`) and evaluated
+//! against the usage roll-up from [`super::usage`] before every
+//! `send_chat_message`. Crossing a soft limit emits `budget:warning`; crossing
+//! a hard limit refuses the run (and emits `budget:exceeded`) unless the
+//! caller passes an explicit override.
+//!
+//! Spend is read from a running [`UsageLedger`] rather than from disk: it is
+//! built from every session's metadata once, then kept current by
+//! [`record_session_usage`] / [`forget_session_usage`], which `storage` calls
+//! whenever session metadata is saved or deleted.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::types::{SessionMetadata, UsageData};
+use super::usage::{find_pricing, load_all_session_metadata, ModelPricing};
+use crate::http_server::EmitExt;
+use crate::projects::storage::load_projects_data;
+use crate::projects::types::ProjectsData;
+
+/// What a budget applies to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BudgetScope {
+    /// All sessions in all projects
+    Global,
+    /// All worktrees of a project
+    Project { project_id: String },
+    /// A single worktree
+    Worktree { worktree_id: String },
+}
+
+/// Time window a budget is measured over
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    /// The current UTC day
+    Daily,
+    /// All recorded usage
+    Total,
+}
+
+/// A spending/token budget (stored in preferences)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageBudget {
+    pub id: String,
+    /// Optional display name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub scope: BudgetScope,
+    pub period: BudgetPeriod,
+    /// Warn once estimated cost reaches this many USD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub soft_limit_usd: Option<f64>,
+    /// Refuse runs once estimated cost reaches this many USD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hard_limit_usd: Option<f64>,
+    /// Warn once total tokens reach this count
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub soft_limit_tokens: Option<u64>,
+    /// Refuse runs once total tokens reach this count
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hard_limit_tokens: Option<u64>,
+    #[serde(default = "default_budget_enabled")]
+    pub enabled: bool,
+}
+
+fn default_budget_enabled() -> bool {
+    true
+}
+
+/// How close a budget is to its limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetLevel {
+    Ok,
+    /// Soft limit reached
+    Warning,
+    /// Hard limit reached
+    Exceeded,
+}
+
+/// Current spend against a budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetStatus {
+    pub budget: UsageBudget,
+    pub spent_usd: f64,
+    /// Input + output + cache read + cache creation tokens
+    pub spent_tokens: u64,
+    pub level: BudgetLevel,
+}
+
+impl BudgetStatus {
+    /// Human-readable description of the limit that was hit
+    pub fn describe(&self) -> String {
+        let label = self.budget.name.clone().unwrap_or_else(|| {
+            let scope = match &self.budget.scope {
+                BudgetScope::Global => "global".to_string(),
+                BudgetScope::Project { project_id } => format!("project {project_id}"),
+                BudgetScope::Worktree { worktree_id } => format!("worktree {worktree_id}"),
+            };
+            let period = match self.budget.period {
+                BudgetPeriod::Daily => "daily",
+                BudgetPeriod::Total => "total",
+            };
+            format!("{period} {scope} budget")
+        });
+
+        let (usd_limit, token_limit) = match self.level {
+            BudgetLevel::Exceeded => (self.budget.hard_limit_usd, self.budget.hard_limit_tokens),
+            _ => (self.budget.soft_limit_usd, self.budget.soft_limit_tokens),
+        };
+        let mut parts = Vec::new();
+        if let Some(limit) = usd_limit {
+            parts.push(format!("${:.2} of ${limit:.2}", self.spent_usd));
+        }
+        if let Some(limit) = token_limit {
+            parts.push(format!("{} of {limit} tokens", self.spent_tokens));
+        }
+        format!("{label}: {}", parts.join(", "))
+    }
+}
+
+/// Payload for `budget:warning` and `budget:exceeded` events
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetEvent {
+    pub session_id: String,
+    pub worktree_id: String,
+    pub statuses: Vec<BudgetStatus>,
+    /// Whether the run went ahead anyway (hard limit overridden)
+    pub overridden: bool,
+}
+
+fn total_tokens(usage: &UsageData) -> u64 {
+    usage.input_tokens
+        + usage.output_tokens
+        + usage.cache_read_input_tokens
+        + usage.cache_creation_input_tokens
+}
+
+fn add_usage(sum: &mut UsageData, usage: &UsageData) {
+    sum.input_tokens += usage.input_tokens;
+    sum.output_tokens += usage.output_tokens;
+    sum.cache_read_input_tokens += usage.cache_read_input_tokens;
+    sum.cache_creation_input_tokens += usage.cache_creation_input_tokens;
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// Running totals
+// ============================================================================
+
+/// Usage of one session, summed per model for each budget period
+#[derive(Debug, Clone, Default)]
+struct SessionUsage {
+    worktree_id: String,
+    total: HashMap<String, UsageData>,
+    /// Runs started since the ledger's `day_start`
+    today: HashMap<String, UsageData>,
+}
+
+impl SessionUsage {
+    fn from_metadata(metadata: &SessionMetadata, day_start: u64) -> Self {
+        let mut usage = SessionUsage {
+            worktree_id: metadata.worktree_id.clone(),
+            ..Default::default()
+        };
+        for run in &metadata.runs {
+            let Some(run_usage) = &run.usage else {
+                continue;
+            };
+            let model = run.model.as_deref().unwrap_or("unknown");
+            add_usage(usage.total.entry(model.to_string()).or_default(), run_usage);
+            if run.started_at >= day_start {
+                add_usage(usage.today.entry(model.to_string()).or_default(), run_usage);
+            }
+        }
+        usage
+    }
+}
+
+/// Per-session usage totals for the current UTC day and for all time
+#[derive(Debug, Default)]
+pub struct UsageLedger {
+    /// Whether every session on disk has been counted
+    loaded: bool,
+    /// Start of the UTC day that `SessionUsage::today` covers
+    day_start: u64,
+    sessions: HashMap<String, SessionUsage>,
+}
+
+impl UsageLedger {
+    /// Start a new day once midnight (UTC) has passed
+    ///
+    /// Sessions with runs started since midnight have been saved since
+    /// midnight, so clearing every `today` and recounting on save is exact.
+    fn roll_day(&mut self, now: u64) {
+        let day_start = now - now % 86_400;
+        if day_start != self.day_start {
+            for session in self.sessions.values_mut() {
+                session.today.clear();
+            }
+            self.day_start = day_start;
+        }
+    }
+
+    fn record(&mut self, metadata: &SessionMetadata, now: u64) {
+        self.roll_day(now);
+        let usage = SessionUsage::from_metadata(metadata, self.day_start);
+        self.sessions.insert(metadata.id.clone(), usage);
+    }
+
+    /// Estimated cost and total tokens of the sessions a budget covers
+    fn spend(
+        &self,
+        budget: &UsageBudget,
+        projects: &ProjectsData,
+        pricing: &HashMap<String, ModelPricing>,
+    ) -> (f64, u64) {
+        let mut spent_usd = 0.0;
+        let mut spent_tokens = 0;
+
+        for session in self.sessions.values() {
+            let covered = match &budget.scope {
+                BudgetScope::Global => true,
+                BudgetScope::Project { project_id } => projects
+                    .find_worktree(&session.worktree_id)
+                    .is_some_and(|w| w.project_id == *project_id),
+                BudgetScope::Worktree { worktree_id } => session.worktree_id == *worktree_id,
+            };
+            if !covered {
+                continue;
+            }
+
+            let by_model = match budget.period {
+                BudgetPeriod::Daily => &session.today,
+                BudgetPeriod::Total => &session.total,
+            };
+            for (model, usage) in by_model {
+                spent_tokens += total_tokens(usage);
+                if let Some(price) = find_pricing(pricing, model) {
+                    spent_usd += price.cost(usage);
+                }
+            }
+        }
+
+        (spent_usd, spent_tokens)
+    }
+}
+
+static LEDGER: Lazy<Mutex<UsageLedger>> = Lazy::new(|| Mutex::new(UsageLedger::default()));
+
+/// Update the running totals after a session's metadata was saved
+pub fn record_session_usage(metadata: &SessionMetadata) {
+    LEDGER.lock().unwrap().record(metadata, unix_now());
+}
+
+/// Drop a session from the running totals after its data was deleted
+pub fn forget_session_usage(session_id: &str) {
+    LEDGER.lock().unwrap().sessions.remove(session_id);
+}
+
+/// Count every session on disk the first time budgets are checked
+///
+/// Metadata is read without holding the ledger lock (saving metadata takes
+/// the ledger lock while holding the session lock). Sessions saved while the
+/// scan runs were already recorded, so they keep the newer entry.
+fn ensure_ledger_loaded(app: &AppHandle) -> Result<(), String> {
+    if LEDGER.lock().unwrap().loaded {
+        return Ok(());
+    }
+
+    let sessions = load_all_session_metadata(app)?;
+    let mut ledger = LEDGER.lock().unwrap();
+    if ledger.loaded {
+        return Ok(());
+    }
+    ledger.roll_day(unix_now());
+    let day_start = ledger.day_start;
+    for metadata in &sessions {
+        ledger
+            .sessions
+            .entry(metadata.id.clone())
+            .or_insert_with(|| SessionUsage::from_metadata(metadata, day_start));
+    }
+    ledger.loaded = true;
+    log::trace!("Usage ledger loaded ({} sessions)", sessions.len());
+    Ok(())
+}
+
+fn level_for(budget: &UsageBudget, spent_usd: f64, spent_tokens: u64) -> BudgetLevel {
+    let reached_usd = |limit: Option<f64>| limit.is_some_and(|l| spent_usd >= l);
+    let reached_tokens = |limit: Option<u64>| limit.is_some_and(|l| spent_tokens >= l);
+
+    if reached_usd(budget.hard_limit_usd) || reached_tokens(budget.hard_limit_tokens) {
+        BudgetLevel::Exceeded
+    } else if reached_usd(budget.soft_limit_usd) || reached_tokens(budget.soft_limit_tokens) {
+        BudgetLevel::Warning
+    } else {
+        BudgetLevel::Ok
+    }
+}
+
+/// Evaluate every enabled budget that applies to a worktree
+pub fn evaluate_budgets(
+    budgets: &[UsageBudget],
+    ledger: &UsageLedger,
+    projects: &ProjectsData,
+    pricing: &HashMap<String, ModelPricing>,
+    worktree_id: &str,
+) -> Vec<BudgetStatus> {
+    let project_id = projects
+        .find_worktree(worktree_id)
+        .map(|w| w.project_id.clone());
+
+    budgets
+        .iter()
+        .filter(|b| b.enabled)
+        .filter(|b| match &b.scope {
+            BudgetScope::Global => true,
+            BudgetScope::Project { project_id: id } => Some(id) == project_id.as_ref(),
+            BudgetScope::Worktree { worktree_id: id } => id == worktree_id,
+        })
+        .map(|budget| {
+            let (spent_usd, spent_tokens) = ledger.spend(budget, projects, pricing);
+            BudgetStatus {
+                budget: budget.clone(),
+                spent_usd,
+                spent_tokens,
+                level: level_for(budget, spent_usd, spent_tokens),
+            }
+        })
+        .collect()
+}
+
+/// Evaluate the budgets for a worktree against the running totals
+async fn load_budget_statuses(
+    app: &AppHandle,
+    worktree_id: &str,
+) -> Result<Vec<BudgetStatus>, String> {
+    let prefs = crate::load_preferences(app.clone()).await?;
+    if !prefs.usage_budgets.iter().any(|b| b.enabled) {
+        return Ok(Vec::new());
+    }
+
+    let projects = load_projects_data(app)?;
+    let app_clone = app.clone();
+    tauri::async_runtime::spawn_blocking(move || ensure_ledger_loaded(&app_clone))
+        .await
+        .map_err(|e| format!("Failed to load usage ledger: {e}"))??;
+
+    let mut ledger = LEDGER.lock().unwrap();
+    ledger.roll_day(unix_now());
+    Ok(evaluate_budgets(
+        &prefs.usage_budgets,
+        &ledger,
+        &projects,
+        &prefs.model_pricing,
+        worktree_id,
+    ))
+}
+
+/// Check budgets before starting a run
+///
+/// Returns an error if a hard limit is reached and `override_budget` is not
+/// set. Soft limits (and overridden hard limits) only emit events.
+pub async fn enforce_budgets(
+    app: &AppHandle,
+    session_id: &str,
+    worktree_id: &str,
+    override_budget: bool,
+) -> Result<(), String> {
+    let statuses = load_budget_statuses(app, worktree_id).await?;
+
+    let exceeded: Vec<BudgetStatus> = statuses
+        .iter()
+        .filter(|s| s.level == BudgetLevel::Exceeded)
+        .cloned()
+        .collect();
+    let warnings: Vec<BudgetStatus> = statuses
+        .into_iter()
+        .filter(|s| s.level == BudgetLevel::Warning)
+        .collect();
+
+    if !exceeded.is_empty() {
+        let event = BudgetEvent {
+            session_id: session_id.to_string(),
+            worktree_id: worktree_id.to_string(),
+            statuses: exceeded.clone(),
+            overridden: override_budget,
+        };
+        if let Err(e) = app.emit_all("budget:exceeded", &event) {
+            log::error!("Failed to emit budget:exceeded event: {e}");
+        }
+
+        if !override_budget {
+            let reasons: Vec<String> = exceeded.iter().map(BudgetStatus::describe).collect();
+            log::warn!(
+                "Refusing run for session {session_id}: {}",
+                reasons.join("; ")
+            );
+            return Err(format!(
+                "Usage budget exceeded ({}). Send again with the budget override to run anyway.",
+                reasons.join("; ")
+            ));
+        }
+        log::warn!("Hard budget limit overridden for session {session_id}");
+    }
+
+    if !warnings.is_empty() {
+        let event = BudgetEvent {
+            session_id: session_id.to_string(),
+            worktree_id: worktree_id.to_string(),
+            statuses: warnings,
+            overridden: false,
+        };
+        if let Err(e) = app.emit_all("budget:warning", &event) {
+            log::error!("Failed to emit budget:warning event: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Get the current spend against every budget that applies to a worktree
+#[tauri::command]
+pub async fn get_budget_status(
+    app: AppHandle,
+    worktree_id: String,
+) -> Result<Vec<BudgetStatus>, String> {
+    log::trace!("Getting budget status for worktree: {worktree_id}");
+    load_budget_statuses(&app, &worktree_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn budget(scope: BudgetScope, period: BudgetPeriod) -> UsageBudget {
+        UsageBudget {
+            id: "b1".to_string(),
+            name: None,
+            scope,
+            period,
+            soft_limit_usd: Some(1.0),
+            hard_limit_usd: Some(2.0),
+            soft_limit_tokens: None,
+            hard_limit_tokens: None,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_level_for() {
+        let b = budget(BudgetScope::Global, BudgetPeriod::Total);
+        assert_eq!(level_for(&b, 0.5, 0), BudgetLevel::Ok);
+        assert_eq!(level_for(&b, 1.0, 0), BudgetLevel::Warning);
+        assert_eq!(level_for(&b, 2.5, 0), BudgetLevel::Exceeded);
+
+        let tokens_only = UsageBudget {
+            soft_limit_usd: None,
+            hard_limit_usd: None,
+            hard_limit_tokens: Some(100),
+            ..b
+        };
+        assert_eq!(level_for(&tokens_only, 1000.0, 99), BudgetLevel::Ok);
+        assert_eq!(level_for(&tokens_only, 0.0, 100), BudgetLevel::Exceeded);
+    }
+
+    #[test]
+    fn test_evaluate_budgets_filters_scope() {
+        let projects = ProjectsData::default();
+        let budgets = vec![
+            budget(BudgetScope::Global, BudgetPeriod::Daily),
+            budget(
+                BudgetScope::Worktree {
+                    worktree_id: "other".to_string(),
+                },
+                BudgetPeriod::Total,
+            ),
+            UsageBudget {
+                enabled: false,
+                ..budget(BudgetScope::Global, BudgetPeriod::Total)
+            },
+        ];
+
+        let ledger = UsageLedger::default();
+        let statuses = evaluate_budgets(&budgets, &ledger, &projects, &HashMap::new(), "w1");
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].budget.scope, BudgetScope::Global);
+        assert_eq!(statuses[0].level, BudgetLevel::Ok);
+    }
+
+    #[test]
+    fn test_ledger_keeps_running_totals() {
+        use crate::chat::types::{RunEntry, RunStatus};
+
+        let run = |run_id: &str, started_at: u64, output_tokens: u64| RunEntry {
+            run_id: run_id.to_string(),
+            user_message_id: "msg".to_string(),
+            user_message: "hello".to_string(),
+            model: Some("sonnet".to_string()),
+            execution_mode: None,
+            thinking_level: None,
+            started_at,
+            ended_at: Some(started_at + 5),
+            status: RunStatus::Completed,
+            assistant_message_id: None,
+            cancelled: false,
+            recovered: false,
+            claude_session_id: None,
+            pid: None,
+            usage: Some(UsageData {
+                output_tokens,
+                ..Default::default()
+            }),
+        };
+        let day = 20_000 * 86_400;
+        let mut metadata =
+            SessionMetadata::new("s1".to_string(), "w1".to_string(), "S".to_string(), 0);
+        metadata.runs = vec![run("r1", day - 10, 1_000_000), run("r2", day + 10, 100)];
+
+        let pricing = super::super::usage::default_model_pricing();
+        let projects = ProjectsData::default();
+        let total = budget(BudgetScope::Global, BudgetPeriod::Total);
+        let daily = budget(BudgetScope::Global, BudgetPeriod::Daily);
+
+        let mut ledger = UsageLedger::default();
+        ledger.record(&metadata, day + 20);
+        assert_eq!(ledger.spend(&total, &projects, &pricing).1, 1_000_100);
+        assert_eq!(ledger.spend(&daily, &projects, &pricing).1, 100);
+        assert!((ledger.spend(&total, &projects, &pricing).0 - 15.0015).abs() < 1e-9);
+
+        // Saving again replaces the session's entry instead of adding to it
+        metadata.runs.push(run("r3", day + 30, 50));
+        ledger.record(&metadata, day + 40);
+        assert_eq!(ledger.spend(&daily, &projects, &pricing).1, 150);
+
+        // A new day starts from zero
+        ledger.roll_day(day + 86_400);
+        assert_eq!(ledger.spend(&daily, &projects, &pricing).1, 0);
+        assert_eq!(ledger.spend(&total, &projects, &pricing).1, 1_000_150);
+
+        let other = budget(
+            BudgetScope::Worktree {
+                worktree_id: "w2".to_string(),
+            },
+            BudgetPeriod::Total,
+        );
+        assert_eq!(ledger.spend(&other, &projects, &pricing), (0.0, 0));
+    }
+
+    #[test]
+    fn test_budget_scope_serialization() {
+        let scope = BudgetScope::Project {
+            project_id: "p1".to_string(),
+        };
+        let json = serde_json::to_value(&scope).unwrap();
+        assert_eq!(json["type"], "project");
+        assert_eq!(json["project_id"], "p1");
+    }
+}
@@ -1,6 +1,6 @@
 use tauri::Manager;
 
-use super::types::{CompactMetadata, ContentBlock, ThinkingLevel, ToolCall, UsageData};
+use super::types::{CompactMetadata, ContentBlock, ForkStart, ThinkingLevel, ToolCall, UsageData};
 use crate::http_server::EmitExt;
 use crate::projects::github_issues::{
     get_github_contexts_dir, get_worktree_issue_refs, get_worktree_pr_refs,
@@ -151,6 +151,7 @@ fn build_claude_args(
     disable_thinking_in_non_plan_modes: bool,
     parallel_execution_prompt_enabled: bool,
     ai_language: Option<&str>,
+    fork_start: Option<&ForkStart>,
 ) -> (Vec<String>, Vec<(String, String)>) {
     let mut args = Vec::new();
     let mut env_vars = Vec::new();
@@ -374,6 +375,15 @@ fn build_claude_args(
         }
     }
 
+    // Forked session replaying copied history (no Claude session to resume)
+    if let Some(ForkStart::Replay { context_file }) = fork_start {
+        let path = std::path::PathBuf::from(context_file);
+        if path.exists() {
+            log::trace!("Adding fork context file: {:?}", path);
+            all_context_paths.push(path);
+        }
+    }
+
     // If we have context files OR system prompt parts, create a combined context file
     let has_system_prompts = !system_prompt_parts.is_empty();
     if !all_context_paths.is_empty() || has_system_prompts {
@@ -470,6 +480,11 @@ fn build_claude_args(
     if let Some(claude_sid) = existing_claude_session_id {
         args.push("--resume".to_string());
         args.push(claude_sid.to_string());
+
+        // Forked session: branch off the source's Claude session instead of continuing it
+        if let Some(ForkStart::Resume { .. }) = fork_start {
+            args.push("--fork-session".to_string());
+        }
     }
 
     // Debug env vars
@@ -511,6 +526,7 @@ pub fn execute_claude_detached(
     disable_thinking_in_non_plan_modes: bool,
     parallel_execution_prompt_enabled: bool,
     ai_language: Option<&str>,
+    fork_start: Option<&ForkStart>,
 ) -> Result<(u32, ClaudeResponse), String> {
     use super::detached::spawn_detached_claude;
     use crate::claude_cli::get_cli_binary_path;
@@ -560,6 +576,7 @@ pub fn execute_claude_detached(
         disable_thinking_in_non_plan_modes,
         parallel_execution_prompt_enabled,
         ai_language,
+        fork_start,
     );
 
     // Log the full Claude CLI command for debugging
@@ -348,6 +348,7 @@ pub async fn close_session(
     if let Err(e) = delete_session_data(&app, &session_id) {
         log::warn!("Failed to delete session data: {e}");
     }
+    super::search::remove_session_from_index(&app, &session_id);
 
     // Now atomically modify the sessions file
     with_sessions_mut(&app, &worktree_path, &worktree_id, |sessions| {
@@ -692,7 +693,10 @@ pub async fn delete_archived_session(
         sessions.sessions.remove(session_idx);
         log::trace!("Archived session permanently deleted: {session_id}");
         Ok(())
-    })
+    })?;
+
+    super::search::delete_sessions_from_index(&app, vec![session_id]);
+    Ok(())
 }
 
 /// List archived sessions for a worktree
@@ -843,6 +847,7 @@ pub async fn send_chat_message(
     parallel_execution_prompt_enabled: Option<bool>,
     ai_language: Option<String>,
     allowed_tools: Option<Vec<String>>,
+    override_budget: Option<bool>,
 ) -> Result<ChatMessage, String> {
     log::trace!("Sending chat message for session: {session_id}, worktree: {worktree_id}, model: {model:?}, execution_mode: {execution_mode:?}, thinking: {thinking_level:?}, disable_thinking_for_mode: {disable_thinking_for_mode:?}, allowed_tools: {allowed_tools:?}");
 
@@ -855,6 +860,15 @@ pub async fn send_chat_message(
         return Err("Worktree path cannot be empty".to_string());
     }
 
+    // Refuse the run if a hard usage budget is exhausted (unless overridden)
+    super::budget::enforce_budgets(
+        &app,
+        &session_id,
+        &worktree_id,
+        override_budget.unwrap_or(false),
+    )
+    .await?;
+
     // Load sessions
     let mut sessions = load_sessions(&app, &worktree_path, &worktree_id)?;
 
@@ -1005,10 +1019,23 @@ pub async fn send_chat_message(
     let context = ClaudeContext::new(worktree_path.clone());
 
     // Get the Claude session ID for resumption
-    let claude_session_id = sessions
+    let mut claude_session_id = sessions
         .find_session(&session_id)
         .and_then(|s| s.claude_session_id.clone());
 
+    // Forked sessions start from the source's history until they have their own Claude session
+    let fork_start = if claude_session_id.is_none() {
+        load_metadata(&app, &session_id)?.and_then(|m| m.fork_start)
+    } else {
+        None
+    };
+    if let Some(super::types::ForkStart::Resume {
+        claude_session_id: source_claude_session_id,
+    }) = &fork_start
+    {
+        claude_session_id = Some(source_claude_session_id.clone());
+    }
+
     // Start NDJSON run log for crash recovery
     let mut run_log_writer = run_log::start_run(
         &app,
@@ -1061,6 +1088,7 @@ pub async fn send_chat_message(
             disable_thinking_in_non_plan_modes,
             parallel_execution_prompt,
             ai_language.as_deref(),
+            fork_start.as_ref(),
         ) {
             Ok((pid, response)) => {
                 log::trace!("execute_claude_detached succeeded (PID: {pid})");
@@ -1212,6 +1240,8 @@ pub async fn send_chat_message(
         log::trace!("Chat message cancelled but partial response saved for session: {session_id}");
     } else {
         log::trace!("Chat message sent and response received for session: {session_id}");
+        // Send the next queued follow-up (cancellation pauses the queue)
+        super::queue::dispatch_next(&app, &session_id, &worktree_id, &worktree_path);
     }
     Ok(assistant_msg)
 }
@@ -1232,6 +1262,7 @@ pub async fn clear_session_history(
     if let Err(e) = delete_session_data(&app, &session_id) {
         log::warn!("Failed to delete session data: {e}");
     }
+    super::search::remove_session_from_index(&app, &session_id);
 
     with_sessions_mut(&app, &worktree_path, &worktree_id, |sessions| {
         if let Some(session) = sessions.find_session_mut(&session_id) {
@@ -2789,6 +2820,15 @@ pub async fn resume_session(
                             log::trace!("Could not delete input file (may not exist): {e}");
                         }
                     }
+
+                    // Send whatever was queued behind the recovered run
+                    if !response.cancelled {
+                        super::queue::dispatch_next_after_recovery(
+                            &app_clone,
+                            &session_id_clone,
+                            &worktree_id_clone,
+                        );
+                    }
                 }
                 Err(e) => {
                     log::error!("Resume failed for run: {run_id_clone}, error: {e}");
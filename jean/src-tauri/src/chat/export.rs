@@ -0,0 +1,642 @@
+//! Session export to Markdown, HTML and JSON transcripts
+//!
+//! Messages are first converted into a stable, versioned [`SessionExport`]
+//! (the JSON format), which the Markdown and HTML renderers then format.
+//! Thinking blocks, tool outputs and token usage can be left out.
+
+use std::fmt::Write as _;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::run_log::load_session_messages;
+use super::storage::load_metadata;
+use super::types::{ChatMessage, ContentBlock, MessageRole, SessionMetadata, UsageData};
+use super::usage::utc_day;
+
+/// Version of the JSON export schema; bump on breaking changes
+pub const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Output format of an export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Json,
+}
+
+/// What to include in an export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    pub include_thinking: bool,
+    pub include_tool_outputs: bool,
+    pub include_usage: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            include_thinking: false,
+            include_tool_outputs: true,
+            include_usage: true,
+        }
+    }
+}
+
+/// Session details included in an export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedSession {
+    pub id: String,
+    pub name: String,
+    pub worktree_id: String,
+    pub created_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claude_session_id: Option<String>,
+}
+
+/// A piece of message content, in the order Claude produced it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExportedBlock {
+    Text {
+        text: String,
+    },
+    Thinking {
+        thinking: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        parent_tool_use_id: Option<String>,
+    },
+}
+
+/// A single exported message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedMessage {
+    pub id: String,
+    pub role: MessageRole,
+    pub timestamp: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking_level: Option<String>,
+    #[serde(default)]
+    pub cancelled: bool,
+    pub blocks: Vec<ExportedBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<UsageData>,
+}
+
+/// A full session transcript (the JSON export format)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionExport {
+    pub schema_version: u32,
+    pub exported_at: u64,
+    pub session: ExportedSession,
+    pub messages: Vec<ExportedMessage>,
+    /// Total token usage across all messages (when usage is included)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<UsageData>,
+}
+
+// ============================================================================
+// Building
+// ============================================================================
+
+/// Convert a message's content blocks (or legacy content/tool calls) to export blocks
+fn export_blocks(message: &ChatMessage, options: &ExportOptions) -> Vec<ExportedBlock> {
+    let tool_block = |id: &str| {
+        message
+            .tool_calls
+            .iter()
+            .find(|t| t.id == id)
+            .map(|tool| ExportedBlock::ToolUse {
+                id: tool.id.clone(),
+                name: tool.name.clone(),
+                input: tool.input.clone(),
+                output: tool.output.clone().filter(|_| options.include_tool_outputs),
+                parent_tool_use_id: tool.parent_tool_use_id.clone(),
+            })
+    };
+
+    let mut blocks = Vec::new();
+
+    if message.content_blocks.is_empty() {
+        // Legacy messages (and user messages) only have flat content + tool calls
+        if !message.content.is_empty() {
+            blocks.push(ExportedBlock::Text {
+                text: message.content.clone(),
+            });
+        }
+        blocks.extend(message.tool_calls.iter().filter_map(|t| tool_block(&t.id)));
+        return blocks;
+    }
+
+    let mut referenced = Vec::new();
+    for block in &message.content_blocks {
+        match block {
+            ContentBlock::Text { text } => blocks.push(ExportedBlock::Text { text: text.clone() }),
+            ContentBlock::Thinking { thinking } => {
+                if options.include_thinking {
+                    blocks.push(ExportedBlock::Thinking {
+                        thinking: thinking.clone(),
+                    });
+                }
+            }
+            ContentBlock::ToolUse { tool_call_id } => {
+                referenced.push(tool_call_id.as_str());
+                blocks.extend(tool_block(tool_call_id));
+            }
+        }
+    }
+
+    // Tool calls without a block (e.g. sub-agent calls) go at the end
+    for tool in &message.tool_calls {
+        if !referenced.contains(&tool.id.as_str()) {
+            blocks.extend(tool_block(&tool.id));
+        }
+    }
+
+    blocks
+}
+
+/// Build the export document for a session's messages
+pub fn build_export(
+    metadata: &SessionMetadata,
+    messages: &[ChatMessage],
+    options: &ExportOptions,
+    exported_at: u64,
+) -> SessionExport {
+    let mut total: Option<UsageData> = None;
+
+    let messages = messages
+        .iter()
+        .map(|message| {
+            let usage = message.usage.clone().filter(|_| options.include_usage);
+            if let Some(usage) = &usage {
+                let total = total.get_or_insert_with(UsageData::default);
+                total.input_tokens += usage.input_tokens;
+                total.output_tokens += usage.output_tokens;
+                total.cache_read_input_tokens += usage.cache_read_input_tokens;
+                total.cache_creation_input_tokens += usage.cache_creation_input_tokens;
+            }
+
+            ExportedMessage {
+                id: message.id.clone(),
+                role: message.role.clone(),
+                timestamp: message.timestamp,
+                model: message.model.clone(),
+                execution_mode: message.execution_mode.clone(),
+                thinking_level: message.thinking_level.clone(),
+                cancelled: message.cancelled,
+                blocks: export_blocks(message, options),
+                usage,
+            }
+        })
+        .collect();
+
+    SessionExport {
+        schema_version: EXPORT_SCHEMA_VERSION,
+        exported_at,
+        session: ExportedSession {
+            id: metadata.id.clone(),
+            name: metadata.name.clone(),
+            worktree_id: metadata.worktree_id.clone(),
+            created_at: metadata.created_at,
+            claude_session_id: metadata.claude_session_id.clone(),
+        },
+        messages,
+        usage: total,
+    }
+}
+
+// ============================================================================
+// Rendering
+// ============================================================================
+
+/// Format a Unix timestamp as `YYYY-MM-DD HH:MM UTC`
+fn format_time(timestamp: u64) -> String {
+    let secs = timestamp % 86_400;
+    format!(
+        "{} {:02}:{:02} UTC",
+        utc_day(timestamp),
+        secs / 3_600,
+        (secs % 3_600) / 60
+    )
+}
+
+fn format_usage(usage: &UsageData) -> String {
+    let mut out = format!(
+        "{} input · {} output",
+        usage.input_tokens, usage.output_tokens
+    );
+    if usage.cache_read_input_tokens > 0 || usage.cache_creation_input_tokens > 0 {
+        let _ = write!(
+            out,
+            " · {} cache read · {} cache write",
+            usage.cache_read_input_tokens, usage.cache_creation_input_tokens
+        );
+    }
+    out
+}
+
+fn role_label(role: &MessageRole) -> &'static str {
+    match role {
+        MessageRole::User => "User",
+        MessageRole::Assistant => "Assistant",
+    }
+}
+
+/// Details shown under a message heading (model, mode, usage)
+fn message_details(message: &ExportedMessage) -> Vec<String> {
+    let mut details = Vec::new();
+    if let Some(model) = &message.model {
+        details.push(format!("model: {model}"));
+    }
+    if let Some(mode) = &message.execution_mode {
+        details.push(format!("mode: {mode}"));
+    }
+    if let Some(level) = &message.thinking_level {
+        details.push(format!("thinking: {level}"));
+    }
+    if let Some(usage) = &message.usage {
+        details.push(format!("tokens: {}", format_usage(usage)));
+    }
+    if message.cancelled {
+        details.push("cancelled".to_string());
+    }
+    details
+}
+
+fn pretty_json(value: &serde_json::Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+/// A backtick fence longer than any backtick run inside `content`
+fn code_fence(content: &str) -> String {
+    let longest = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn push_code_block(out: &mut String, lang: &str, content: &str) {
+    let fence = code_fence(content);
+    let _ = writeln!(out, "{fence}{lang}\n{}\n{fence}", content.trim_end());
+}
+
+/// Render an export as Markdown
+pub fn render_markdown(export: &SessionExport) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# {}\n", export.session.name);
+    let _ = writeln!(out, "- **Session:** `{}`", export.session.id);
+    let _ = writeln!(
+        out,
+        "- **Created:** {}",
+        format_time(export.session.created_at)
+    );
+    let _ = writeln!(out, "- **Exported:** {}", format_time(export.exported_at));
+    if let Some(usage) = &export.usage {
+        let _ = writeln!(out, "- **Tokens:** {}", format_usage(usage));
+    }
+
+    for message in &export.messages {
+        let _ = writeln!(
+            out,
+            "\n---\n\n## {} · {}\n",
+            role_label(&message.role),
+            format_time(message.timestamp)
+        );
+        let details = message_details(message);
+        if !details.is_empty() {
+            let _ = writeln!(out, "_{}_\n", details.join(" · "));
+        }
+
+        for block in &message.blocks {
+            match block {
+                ExportedBlock::Text { text } => {
+                    let _ = writeln!(out, "{}\n", text.trim_end());
+                }
+                ExportedBlock::Thinking { thinking } => {
+                    let _ = writeln!(
+                        out,
+                        "<details>\n<summary>Thinking</summary>\n\n{}\n\n</details>\n",
+                        thinking.trim_end()
+                    );
+                }
+                ExportedBlock::ToolUse {
+                    name,
+                    input,
+                    output,
+                    ..
+                } => {
+                    let _ = writeln!(out, "**Tool:** `{name}`\n");
+                    push_code_block(&mut out, "json", &pretty_json(input));
+                    if let Some(output) = output {
+                        out.push_str("\n<details>\n<summary>Output</summary>\n\n");
+                        push_code_block(&mut out, "", output);
+                        out.push_str("\n</details>\n");
+                    }
+                    out.push('\n');
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+const HTML_STYLE: &str = "\
+body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;max-width:860px;margin:2rem auto;padding:0 1rem;color:#1f2328;line-height:1.5}
+header{border-bottom:1px solid #d0d7de;margin-bottom:1.5rem}
+.meta{color:#59636e;font-size:.875rem}
+.message{border:1px solid #d0d7de;border-radius:8px;padding:.75rem 1rem;margin:1rem 0}
+.message.user{background:#f6f8fa}
+.message h2{font-size:1rem;margin:0 0 .5rem}
+.text{white-space:pre-wrap;word-wrap:break-word}
+pre{background:#f6f8fa;border:1px solid #d0d7de;border-radius:6px;padding:.5rem;overflow-x:auto;font-size:.8125rem}
+details{margin:.5rem 0}
+summary{cursor:pointer;color:#59636e}
+.thinking{color:#59636e;font-style:italic}
+";
+
+/// Render an export as a standalone HTML document
+pub fn render_html(export: &SessionExport) -> String {
+    let mut out = String::new();
+    let title = escape_html(&export.session.name);
+
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>\n{HTML_STYLE}</style>\n</head>\n<body>\n<header>\n\
+         <h1>{title}</h1>\n<p class=\"meta\">Session <code>{}</code> · created {} · exported {}",
+        escape_html(&export.session.id),
+        format_time(export.session.created_at),
+        format_time(export.exported_at)
+    );
+    if let Some(usage) = &export.usage {
+        let _ = write!(out, " · {}", escape_html(&format_usage(usage)));
+    }
+    out.push_str("</p>\n</header>\n");
+
+    for message in &export.messages {
+        let role = role_label(&message.role);
+        let _ = write!(
+            out,
+            "<section class=\"message {}\">\n<h2>{role} <span class=\"meta\">{}</span></h2>\n",
+            role.to_lowercase(),
+            format_time(message.timestamp)
+        );
+        let details = message_details(message);
+        if !details.is_empty() {
+            let _ = writeln!(
+                out,
+                "<p class=\"meta\">{}</p>",
+                escape_html(&details.join(" · "))
+            );
+        }
+
+        for block in &message.blocks {
+            match block {
+                ExportedBlock::Text { text } => {
+                    let _ = writeln!(
+                        out,
+                        "<div class=\"text\">{}</div>",
+                        escape_html(text.trim_end())
+                    );
+                }
+                ExportedBlock::Thinking { thinking } => {
+                    let _ = writeln!(
+                        out,
+                        "<details><summary>Thinking</summary><div class=\"text thinking\">{}</div></details>",
+                        escape_html(thinking.trim_end())
+                    );
+                }
+                ExportedBlock::ToolUse {
+                    name,
+                    input,
+                    output,
+                    ..
+                } => {
+                    let _ = write!(
+                        out,
+                        "<details><summary>Tool: <code>{}</code></summary>\n<pre>{}</pre>\n",
+                        escape_html(name),
+                        escape_html(&pretty_json(input))
+                    );
+                    if let Some(output) = output {
+                        let _ = writeln!(out, "<pre>{}</pre>", escape_html(output.trim_end()));
+                    }
+                    out.push_str("</details>\n");
+                }
+            }
+        }
+
+        out.push_str("</section>\n");
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Export a session's history as Markdown, standalone HTML or JSON
+///
+/// Returns the rendered document; saving it is up to the caller.
+#[tauri::command]
+pub async fn export_session(
+    app: AppHandle,
+    session_id: String,
+    format: ExportFormat,
+    options: Option<ExportOptions>,
+) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    log::trace!("Exporting session {session_id} as {format:?} ({options:?})");
+
+    let metadata = load_metadata(&app, &session_id)?
+        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+    let messages = load_session_messages(&app, &session_id)?;
+
+    let exported_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let export = build_export(&metadata, &messages, &options, exported_at);
+
+    match format {
+        ExportFormat::Markdown => Ok(render_markdown(&export)),
+        ExportFormat::Html => Ok(render_html(&export)),
+        ExportFormat::Json => serde_json::to_string_pretty(&export)
+            .map_err(|e| format!("Failed to serialize export: {e}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::types::ToolCall;
+
+    fn metadata() -> SessionMetadata {
+        let mut metadata = SessionMetadata::new(
+            "s1".to_string(),
+            "wt-1".to_string(),
+            "Fix <migration>".to_string(),
+            0,
+        );
+        metadata.created_at = 1_760_000_000;
+        metadata
+    }
+
+    fn messages() -> Vec<ChatMessage> {
+        vec![
+            ChatMessage {
+                id: "u1".to_string(),
+                session_id: "s1".to_string(),
+                role: MessageRole::User,
+                content: "Why does the migration fail?".to_string(),
+                timestamp: 1_760_000_100,
+                model: Some("opus".to_string()),
+                ..Default::default()
+            },
+            ChatMessage {
+                id: "a1".to_string(),
+                session_id: "s1".to_string(),
+                role: MessageRole::Assistant,
+                content: "Found it.".to_string(),
+                timestamp: 1_760_000_200,
+                tool_calls: vec![ToolCall {
+                    id: "t1".to_string(),
+                    name: "Bash".to_string(),
+                    input: serde_json::json!({ "command": "cargo test" }),
+                    output: Some("```\ntest result: FAILED".to_string()),
+                    parent_tool_use_id: None,
+                }],
+                content_blocks: vec![
+                    ContentBlock::Thinking {
+                        thinking: "Let me run the tests".to_string(),
+                    },
+                    ContentBlock::ToolUse {
+                        tool_call_id: "t1".to_string(),
+                    },
+                    ContentBlock::Text {
+                        text: "Found it.".to_string(),
+                    },
+                ],
+                usage: Some(UsageData {
+                    input_tokens: 100,
+                    output_tokens: 20,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_build_export_respects_options() {
+        let export = build_export(&metadata(), &messages(), &ExportOptions::default(), 0);
+        assert_eq!(export.schema_version, EXPORT_SCHEMA_VERSION);
+        assert_eq!(export.messages.len(), 2);
+        // Thinking excluded by default; tool use and text kept in order
+        let blocks = &export.messages[1].blocks;
+        assert_eq!(blocks.len(), 2);
+        assert!(matches!(
+            &blocks[0],
+            ExportedBlock::ToolUse {
+                output: Some(_),
+                ..
+            }
+        ));
+        assert!(matches!(&blocks[1], ExportedBlock::Text { .. }));
+        assert_eq!(export.usage.as_ref().unwrap().input_tokens, 100);
+
+        let options = ExportOptions {
+            include_thinking: true,
+            include_tool_outputs: false,
+            include_usage: false,
+        };
+        let export = build_export(&metadata(), &messages(), &options, 0);
+        let blocks = &export.messages[1].blocks;
+        assert_eq!(blocks.len(), 3);
+        assert!(matches!(&blocks[0], ExportedBlock::Thinking { .. }));
+        assert!(matches!(
+            &blocks[1],
+            ExportedBlock::ToolUse { output: None, .. }
+        ));
+        assert!(export.usage.is_none());
+        assert!(export.messages[1].usage.is_none());
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let export = build_export(&metadata(), &messages(), &ExportOptions::default(), 42);
+        let json = serde_json::to_string(&export).unwrap();
+        assert!(json.contains("\"type\":\"tool_use\""));
+        let parsed: SessionExport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.exported_at, 42);
+        assert_eq!(parsed.messages[1].blocks.len(), 2);
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let export = build_export(&metadata(), &messages(), &ExportOptions::default(), 0);
+        let md = render_markdown(&export);
+        assert!(md.starts_with("# Fix <migration>\n"));
+        assert!(md.contains("## User · 2025-10-09"));
+        assert!(md.contains("_model: opus_"));
+        assert!(md.contains("**Tool:** `Bash`"));
+        // Output containing a fence is wrapped in a longer one
+        assert!(md.contains("````\n```\ntest result: FAILED\n````"));
+        assert!(md.contains("tokens: 100 input · 20 output"));
+        assert!(!md.contains("Let me run the tests"));
+    }
+
+    #[test]
+    fn test_render_html_escapes() {
+        let export = build_export(&metadata(), &messages(), &ExportOptions::default(), 0);
+        let html = render_html(&export);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Fix &lt;migration&gt;</title>"));
+        assert!(html.contains("<section class=\"message assistant\">"));
+        assert!(html.contains("&quot;command&quot;: &quot;cargo test&quot;"));
+        assert!(html.trim_end().ends_with("</html>"));
+    }
+
+    #[test]
+    fn test_code_fence() {
+        assert_eq!(code_fence("plain"), "```");
+        assert_eq!(code_fence("has ``` inside"), "````");
+        assert_eq!(code_fence("has ````` inside"), "``````");
+    }
+}
@@ -0,0 +1,261 @@
+//! Session forking ("fork from here")
+//!
+//! Copies a session's history up to a chosen message into a new session in
+//! the same or another worktree. When the fork point is the end of the source
+//! conversation (and the worktree is the same), the first run resumes the
+//! source's Claude CLI session with `--fork-session`; otherwise the copied
+//! history is replayed to Claude as a Markdown transcript.
+
+use std::collections::HashSet;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tauri::AppHandle;
+
+use super::export::{build_export, render_markdown, ExportOptions};
+use super::import::{remap_bundle, worktree_import_state, write_imported_session, SessionBundle};
+use super::run_log::{load_session_messages, read_run_log};
+use super::storage::{get_session_dir, load_metadata};
+use super::types::{
+    ChatMessage, ForkOrigin, ForkStart, RunEntry, RunStatus, Session, SessionMetadata,
+};
+
+/// File (in the forked session's directory) holding the replayed history
+const FORK_CONTEXT_FILE: &str = "fork-context.md";
+
+/// Runs instantly cancelled before any response (hidden from history)
+fn is_undo_send(run: &RunEntry) -> bool {
+    run.status == RunStatus::Cancelled && run.assistant_message_id.is_none()
+}
+
+/// Number of leading runs to copy when forking at `message_id`
+///
+/// Forking at an assistant message keeps its run; forking at a user message
+/// keeps only the runs before it, so the prompt can be edited and re-sent.
+pub fn fork_run_count(runs: &[RunEntry], message_id: &str) -> Result<usize, String> {
+    let count = runs
+        .iter()
+        .enumerate()
+        .find_map(|(i, run)| {
+            if run.assistant_message_id.as_deref() == Some(message_id) {
+                Some(i + 1)
+            } else if run.user_message_id == message_id {
+                Some(i)
+            } else {
+                None
+            }
+        })
+        .ok_or_else(|| format!("Message not found: {message_id}"))?;
+
+    if runs[..count]
+        .iter()
+        .any(|r| matches!(r.status, RunStatus::Running | RunStatus::Resumable))
+    {
+        return Err("Cannot fork while a response is still running".to_string());
+    }
+
+    Ok(count)
+}
+
+/// Whether the fork point is the end of the source's Claude conversation
+pub fn forks_at_end(runs: &[RunEntry], count: usize) -> bool {
+    count > 0 && runs[count..].iter().all(is_undo_send)
+}
+
+/// Write `messages` of `source` as a Markdown transcript into a session's
+/// directory, for its first run to pick up as context
+pub fn write_replay_context(
+    app: &AppHandle,
+    session_id: &str,
+    source: &SessionMetadata,
+    messages: &[ChatMessage],
+    intro: &str,
+) -> Result<ForkStart, String> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let options = ExportOptions {
+        include_thinking: false,
+        include_tool_outputs: false,
+        include_usage: false,
+    };
+    let transcript = render_markdown(&build_export(source, messages, &options, now));
+
+    let context_file = get_session_dir(app, session_id)?.join(FORK_CONTEXT_FILE);
+    fs::write(
+        &context_file,
+        format!("# Conversation So Far\n\n{intro}\n\n{transcript}"),
+    )
+    .map_err(|e| format!("Failed to write fork context: {e}"))?;
+
+    Ok(ForkStart::Replay {
+        context_file: context_file.to_string_lossy().to_string(),
+    })
+}
+
+/// Create a new session containing the history up to `message_id`
+///
+/// The fork goes into `target_worktree_id` if given (e.g. a worktree created
+/// for the alternative approach), otherwise next to the source session.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn fork_session(
+    app: AppHandle,
+    worktree_id: String,
+    worktree_path: String,
+    session_id: String,
+    message_id: String,
+    target_worktree_id: Option<String>,
+    name: Option<String>,
+) -> Result<Session, String> {
+    log::trace!("Forking session {session_id} at message {message_id} ({worktree_path})");
+
+    let source = load_metadata(&app, &session_id)?
+        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+    let count = fork_run_count(&source.runs, &message_id)?;
+    let runs: Vec<RunEntry> = source.runs[..count].to_vec();
+    let target_worktree_id = target_worktree_id.unwrap_or_else(|| worktree_id.clone());
+
+    let mut run_logs = std::collections::HashMap::new();
+    for run in &runs {
+        let lines = read_run_log(&app, &session_id, &run.run_id)?;
+        if !lines.is_empty() {
+            run_logs.insert(run.run_id.clone(), lines.join("\n"));
+        }
+    }
+
+    let mut source_copy = source.clone();
+    source_copy.runs = runs.clone();
+    let (order, _) = worktree_import_state(&app, &target_worktree_id)?;
+    let mut bundle = remap_bundle(
+        SessionBundle {
+            metadata: source_copy,
+            run_logs,
+        },
+        &target_worktree_id,
+        order,
+    );
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let metadata = &mut bundle.metadata;
+    metadata.name = name.unwrap_or_else(|| format!("{} (fork)", source.name));
+    metadata.created_at = now;
+    metadata.claude_session_id = None;
+    metadata.session_naming_completed = true;
+    metadata.digest = None;
+    metadata.forked_from = Some(ForkOrigin {
+        session_id: source.id.clone(),
+        worktree_id: source.worktree_id.clone(),
+        message_id: message_id.clone(),
+        forked_at: now,
+    });
+
+    let can_resume = forks_at_end(&source.runs, count) && target_worktree_id == worktree_id;
+    metadata.fork_start = match &source.claude_session_id {
+        Some(claude_session_id) if can_resume => Some(ForkStart::Resume {
+            claude_session_id: claude_session_id.clone(),
+        }),
+        _ if !runs.is_empty() => {
+            // Replay the copied history as a transcript
+            let kept: HashSet<&str> = runs
+                .iter()
+                .flat_map(|r| {
+                    [
+                        Some(r.user_message_id.as_str()),
+                        r.assistant_message_id.as_deref(),
+                    ]
+                })
+                .flatten()
+                .collect();
+            let messages: Vec<_> = load_session_messages(&app, &session_id)?
+                .into_iter()
+                .filter(|m| kept.contains(m.id.as_str()))
+                .collect();
+            Some(write_replay_context(
+                &app,
+                &metadata.id,
+                &source,
+                &messages,
+                "This session was forked from an earlier conversation. The transcript \
+                 below is the history up to the fork point; continue from where it ends.",
+            )?)
+        }
+        _ => None,
+    };
+
+    log::trace!(
+        "Forked session {session_id} into {} ({} runs, start: {:?})",
+        metadata.id,
+        runs.len(),
+        metadata.fork_start
+    );
+    write_imported_session(&app, bundle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(n: u32, status: RunStatus, answered: bool) -> RunEntry {
+        RunEntry {
+            run_id: format!("run-{n}"),
+            user_message_id: format!("user-{n}"),
+            user_message: format!("prompt {n}"),
+            model: None,
+            execution_mode: None,
+            thinking_level: None,
+            started_at: n as u64,
+            ended_at: None,
+            status,
+            assistant_message_id: answered.then(|| format!("assistant-{n}")),
+            cancelled: false,
+            recovered: false,
+            claude_session_id: None,
+            pid: None,
+            usage: None,
+        }
+    }
+
+    #[test]
+    fn test_fork_run_count() {
+        let runs = vec![
+            run(1, RunStatus::Completed, true),
+            run(2, RunStatus::Completed, true),
+            run(3, RunStatus::Completed, true),
+        ];
+        assert_eq!(fork_run_count(&runs, "assistant-2"), Ok(2));
+        assert_eq!(fork_run_count(&runs, "user-2"), Ok(1));
+        assert_eq!(fork_run_count(&runs, "user-1"), Ok(0));
+        assert!(fork_run_count(&runs, "missing").is_err());
+    }
+
+    #[test]
+    fn test_fork_run_count_rejects_running() {
+        let runs = vec![
+            run(1, RunStatus::Completed, true),
+            run(2, RunStatus::Running, false),
+        ];
+        assert!(fork_run_count(&runs, "user-2").is_ok());
+        assert!(fork_run_count(&runs, "assistant-1").is_ok());
+
+        let runs = vec![run(1, RunStatus::Resumable, true)];
+        assert!(fork_run_count(&runs, "assistant-1").is_err());
+    }
+
+    #[test]
+    fn test_forks_at_end() {
+        let runs = vec![
+            run(1, RunStatus::Completed, true),
+            run(2, RunStatus::Completed, true),
+            run(3, RunStatus::Cancelled, false),
+        ];
+        assert!(forks_at_end(&runs, 2));
+        assert!(forks_at_end(&runs, 3));
+        assert!(!forks_at_end(&runs, 1));
+        assert!(!forks_at_end(&runs, 0));
+    }
+}
@@ -0,0 +1,1009 @@
+//! Session import from bundles and from Claude CLI project logs
+//!
+//! A session bundle is a zip (or a plain directory) with the same layout as a
+//! session's data directory: `metadata.json` plus one `{run_id}.jsonl` run log
+//! per run. Imported sessions get fresh session, run and message IDs so they
+//! can live next to the original, e.g. in another worktree on the same machine.
+//! The original's Claude CLI session belongs to the original's directory (or
+//! another machine), so it is dropped; the first message replays the imported
+//! history as a transcript instead.
+//!
+//! Conversations started with the Claude CLI outside Jean
+//! (`~/.claude/projects/<project>/<session-id>.jsonl`) can be imported too; they
+//! are converted to run logs and keep their `claude_session_id` so they resume.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use super::fork::write_replay_context;
+use super::run_log::load_session_messages;
+use super::storage::{get_session_dir, load_index, load_metadata, save_metadata, with_index_mut};
+use super::types::{RunEntry, RunStatus, Session, SessionMetadata, UsageData};
+
+/// Name of the metadata file inside a bundle
+const BUNDLE_METADATA_FILE: &str = "metadata.json";
+
+/// Characters of the first user message used to name an imported CLI session
+const CLI_SESSION_NAME_CHARS: usize = 40;
+
+/// Largest single file (bundle entry or CLI log) read during an import
+const MAX_IMPORT_FILE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Largest total size of the files read from one bundle
+const MAX_BUNDLE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// A session read from a bundle or CLI log, before it is written to storage
+#[derive(Debug, Clone)]
+pub struct SessionBundle {
+    pub metadata: SessionMetadata,
+    /// run_id -> run log contents (NDJSON)
+    pub run_logs: HashMap<String, String>,
+}
+
+/// A Claude CLI conversation found in `~/.claude/projects`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudeCliSessionInfo {
+    pub claude_session_id: String,
+    pub path: String,
+    pub modified_at: u64,
+    /// CLI-generated summary, if any
+    pub summary: Option<String>,
+    pub first_message: Option<String>,
+    /// Whether a session with this claude_session_id already exists in the worktree
+    pub imported: bool,
+}
+
+// ============================================================================
+// Bundles
+// ============================================================================
+
+/// Read `reader` to a string, failing instead of reading past `MAX_IMPORT_FILE_BYTES`
+fn read_capped(reader: impl Read, name: &str) -> Result<String, String> {
+    let mut contents = String::new();
+    reader
+        .take(MAX_IMPORT_FILE_BYTES + 1)
+        .read_to_string(&mut contents)
+        .map_err(|e| format!("Failed to read {name}: {e}"))?;
+    if contents.len() as u64 > MAX_IMPORT_FILE_BYTES {
+        return Err(too_large(name));
+    }
+    Ok(contents)
+}
+
+fn too_large(name: &str) -> String {
+    format!(
+        "{name} is larger than the {} MB import limit",
+        MAX_IMPORT_FILE_BYTES / (1024 * 1024)
+    )
+}
+
+/// Add a file's size to a bundle's running total, failing past `MAX_BUNDLE_BYTES`
+fn add_bundle_bytes(total: &mut u64, size: u64) -> Result<(), String> {
+    *total += size;
+    if *total > MAX_BUNDLE_BYTES {
+        return Err(format!(
+            "Bundle is larger than the {} MB import limit",
+            MAX_BUNDLE_BYTES / (1024 * 1024)
+        ));
+    }
+    Ok(())
+}
+
+fn parse_bundle(
+    metadata_json: &str,
+    mut files: HashMap<String, String>,
+) -> Result<SessionBundle, String> {
+    let metadata: SessionMetadata =
+        serde_json::from_str(metadata_json).map_err(|e| format!("Invalid bundle metadata: {e}"))?;
+
+    let mut run_logs = HashMap::new();
+    for run in &metadata.runs {
+        if let Some(contents) = files.remove(&format!("{}.jsonl", run.run_id)) {
+            run_logs.insert(run.run_id.clone(), contents);
+        }
+    }
+
+    Ok(SessionBundle { metadata, run_logs })
+}
+
+fn read_bundle_dir(dir: &Path) -> Result<SessionBundle, String> {
+    let metadata_file = File::open(dir.join(BUNDLE_METADATA_FILE))
+        .map_err(|e| format!("Failed to read bundle metadata: {e}"))?;
+    let metadata_json = read_capped(metadata_file, "bundle metadata")?;
+
+    let mut total = metadata_json.len() as u64;
+    let mut files = HashMap::new();
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read bundle: {e}"))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+            continue;
+        }
+        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+            let file =
+                File::open(&path).map_err(|e| format!("Failed to read run log {name}: {e}"))?;
+            let contents = read_capped(file, &format!("run log {name}"))?;
+            add_bundle_bytes(&mut total, contents.len() as u64)?;
+            files.insert(name.to_string(), contents);
+        }
+    }
+
+    parse_bundle(&metadata_json, files)
+}
+
+fn read_bundle_zip(path: &Path) -> Result<SessionBundle, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open bundle: {e}"))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to open zip archive: {e}"))?;
+
+    let mut metadata_json = None;
+    let mut files = HashMap::new();
+    let mut total = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read zip entry: {e}"))?;
+        if entry.is_dir() {
+            continue;
+        }
+        // Only top-level files are part of a bundle
+        let Some(name) = entry
+            .enclosed_name()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        else {
+            continue;
+        };
+
+        if name != BUNDLE_METADATA_FILE && !name.ends_with(".jsonl") {
+            continue;
+        }
+
+        // Check the declared size up front; `read_capped` guards against it lying
+        if entry.size() > MAX_IMPORT_FILE_BYTES {
+            return Err(too_large(&name));
+        }
+        add_bundle_bytes(&mut total, entry.size())?;
+        let contents = read_capped(&mut entry, &name)?;
+
+        if name == BUNDLE_METADATA_FILE {
+            metadata_json = Some(contents);
+        } else {
+            files.insert(name, contents);
+        }
+    }
+
+    let metadata_json =
+        metadata_json.ok_or_else(|| format!("Bundle has no {BUNDLE_METADATA_FILE}"))?;
+    parse_bundle(&metadata_json, files)
+}
+
+/// Read a bundle from a zip file or a directory
+pub fn read_bundle(path: &Path) -> Result<SessionBundle, String> {
+    if path.is_dir() {
+        read_bundle_dir(path)
+    } else {
+        read_bundle_zip(path)
+    }
+}
+
+/// Rewrite the `_run_meta` header of a run log for new IDs
+fn rewrite_run_log_header(contents: &str, metadata: &SessionMetadata, run: &RunEntry) -> String {
+    let mut lines = contents.lines();
+    let Some(first) = lines.next() else {
+        return String::new();
+    };
+
+    let header = match serde_json::from_str::<serde_json::Value>(first) {
+        Ok(mut meta) if meta.get("_run_meta").and_then(|v| v.as_bool()) == Some(true) => {
+            meta["run_id"] = run.run_id.clone().into();
+            meta["session_id"] = metadata.id.clone().into();
+            meta["worktree_id"] = metadata.worktree_id.clone().into();
+            meta["user_message_id"] = run.user_message_id.clone().into();
+            meta.to_string()
+        }
+        _ => first.to_string(),
+    };
+
+    let mut out = header;
+    for line in lines {
+        out.push('\n');
+        out.push_str(line);
+    }
+    out.push('\n');
+    out
+}
+
+/// Give a bundle fresh session, run and message IDs for the target worktree
+///
+/// Runs that were still in flight when the bundle was made are marked crashed,
+/// and per-machine state (archive flag, pending prompts, Claude CLI session
+/// IDs) is reset.
+pub fn remap_bundle(bundle: SessionBundle, worktree_id: &str, order: u32) -> SessionBundle {
+    let SessionBundle {
+        mut metadata,
+        run_logs,
+    } = bundle;
+
+    let mut message_ids: HashMap<String, String> = HashMap::new();
+    let mut remap_message = |id: &str| {
+        message_ids
+            .entry(id.to_string())
+            .or_insert_with(|| Uuid::new_v4().to_string())
+            .clone()
+    };
+
+    metadata.id = Uuid::new_v4().to_string();
+    metadata.worktree_id = worktree_id.to_string();
+    metadata.order = order;
+    metadata.claude_session_id = None;
+    metadata.archived_at = None;
+    metadata.pending_permission_denials.clear();
+    metadata.denied_message_context = None;
+    metadata.is_reviewing = false;
+    metadata.waiting_for_input = false;
+    metadata.waiting_for_input_type = None;
+    metadata.fork_start = None;
+    metadata.message_queue.clear();
+
+    let mut new_logs = HashMap::new();
+    for run in &mut metadata.runs {
+        let old_run_id = std::mem::replace(&mut run.run_id, Uuid::new_v4().to_string());
+        run.user_message_id = remap_message(&run.user_message_id);
+        run.assistant_message_id = run.assistant_message_id.as_deref().map(&mut remap_message);
+        run.pid = None;
+        run.claude_session_id = None;
+        if matches!(run.status, RunStatus::Running | RunStatus::Resumable) {
+            run.status = RunStatus::Crashed;
+        }
+
+        if let Some(contents) = run_logs.get(&old_run_id) {
+            new_logs.insert(run.run_id.clone(), contents.clone());
+        }
+    }
+
+    metadata.approved_plan_message_ids = metadata
+        .approved_plan_message_ids
+        .iter()
+        .filter_map(|id| message_ids.get(id).cloned())
+        .collect();
+    metadata.pending_plan_message_id = metadata
+        .pending_plan_message_id
+        .as_ref()
+        .and_then(|id| message_ids.get(id).cloned());
+
+    let run_logs = metadata
+        .runs
+        .iter()
+        .filter_map(|run| {
+            new_logs.remove(&run.run_id).map(|log| {
+                (
+                    run.run_id.clone(),
+                    rewrite_run_log_header(&log, &metadata, run),
+                )
+            })
+        })
+        .collect();
+
+    SessionBundle { metadata, run_logs }
+}
+
+/// Write an imported session to storage and add it to its worktree's index
+pub fn write_imported_session(app: &AppHandle, bundle: SessionBundle) -> Result<Session, String> {
+    let SessionBundle { metadata, run_logs } = bundle;
+
+    let session_dir = get_session_dir(app, &metadata.id)?;
+    for (run_id, contents) in &run_logs {
+        fs::write(session_dir.join(format!("{run_id}.jsonl")), contents)
+            .map_err(|e| format!("Failed to write run log: {e}"))?;
+    }
+    save_metadata(app, &metadata)?;
+
+    with_index_mut(app, &metadata.worktree_id, |index| {
+        index.sessions.push(metadata.to_index_entry());
+        index.active_session_id = Some(metadata.id.clone());
+        Ok(())
+    })?;
+
+    super::search::index_session_in_background(app, &metadata.id);
+
+    log::trace!(
+        "Imported session {} ({} runs) into worktree {}",
+        metadata.id,
+        metadata.runs.len(),
+        metadata.worktree_id
+    );
+    Ok(metadata.to_session())
+}
+
+/// Next tab order and the claude_session_ids already present in a worktree
+pub fn worktree_import_state(
+    app: &AppHandle,
+    worktree_id: &str,
+) -> Result<(u32, HashSet<String>), String> {
+    let index = load_index(app, worktree_id)?;
+    let order = index
+        .sessions
+        .iter()
+        .map(|s| s.order + 1)
+        .max()
+        .unwrap_or(0);
+    let claude_ids = index
+        .sessions
+        .iter()
+        .filter_map(|s| load_metadata(app, &s.id).ok().flatten())
+        .filter_map(|m| m.claude_session_id)
+        .collect();
+    Ok((order, claude_ids))
+}
+
+// ============================================================================
+// Claude CLI project logs
+// ============================================================================
+
+/// Parse an RFC 3339 UTC timestamp (`2025-10-09T08:53:20.123Z`) to Unix seconds
+fn parse_iso_timestamp(value: &str) -> Option<u64> {
+    let (date, time) = value.split_once('T')?;
+    let mut date_parts = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (
+        date_parts.next()??,
+        date_parts.next()??,
+        date_parts.next()??,
+    );
+    let time = time.trim_end_matches('Z');
+    let time = time.split(['+', '.']).next()?;
+    let mut time_parts = time.splitn(3, ':').map(|p| p.parse::<i64>().ok());
+    let (hour, minute, second) = (
+        time_parts.next()??,
+        time_parts.next()??,
+        time_parts.next()??,
+    );
+
+    // Days-from-civil (Howard Hinnant's algorithm)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    u64::try_from(days * 86_400 + hour * 3_600 + minute * 60 + second).ok()
+}
+
+/// Text of a CLI user message, or None if it only carries tool results
+fn cli_user_text(content: &serde_json::Value) -> Option<String> {
+    match content {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Array(blocks) => {
+            let texts: Vec<&str> = blocks
+                .iter()
+                .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+                .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                .collect();
+            if texts.is_empty() {
+                None
+            } else {
+                Some(texts.join("\n"))
+            }
+        }
+        _ => None,
+    }
+}
+
+/// A run being assembled from CLI log lines
+struct CliRun {
+    entry: RunEntry,
+    lines: Vec<String>,
+    has_response: bool,
+    seen_message_ids: HashSet<String>,
+}
+
+/// Convert a Claude CLI conversation log into a session bundle
+///
+/// Every user prompt starts a new run; assistant messages and tool results
+/// that follow it become that run's log. Sidechain (sub-agent) and meta lines
+/// are skipped.
+pub fn convert_cli_transcript(
+    lines: &[String],
+    fallback_session_id: &str,
+    worktree_id: &str,
+    order: u32,
+) -> Result<SessionBundle, String> {
+    let mut claude_session_id: Option<String> = None;
+    let mut summary: Option<String> = None;
+    let mut runs: Vec<CliRun> = Vec::new();
+
+    for line in lines {
+        let Ok(msg) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        let flag = |key: &str| msg.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+        if flag("isSidechain") || flag("isMeta") {
+            continue;
+        }
+        if claude_session_id.is_none() {
+            claude_session_id = msg
+                .get("sessionId")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+        }
+        let timestamp = msg
+            .get("timestamp")
+            .and_then(|v| v.as_str())
+            .and_then(parse_iso_timestamp);
+
+        match msg.get("type").and_then(|v| v.as_str()).unwrap_or("") {
+            "summary" => {
+                if summary.is_none() {
+                    summary = msg
+                        .get("summary")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string());
+                }
+            }
+            "user" => {
+                let content = msg
+                    .get("message")
+                    .and_then(|m| m.get("content"))
+                    .unwrap_or(&serde_json::Value::Null);
+                if let Some(text) = cli_user_text(content) {
+                    let started_at = timestamp.unwrap_or(0);
+                    runs.push(CliRun {
+                        entry: RunEntry {
+                            run_id: Uuid::new_v4().to_string(),
+                            user_message_id: Uuid::new_v4().to_string(),
+                            user_message: text,
+                            model: None,
+                            execution_mode: None,
+                            thinking_level: None,
+                            started_at,
+                            ended_at: Some(started_at),
+                            status: RunStatus::Completed,
+                            assistant_message_id: Some(Uuid::new_v4().to_string()),
+                            cancelled: false,
+                            recovered: false,
+                            claude_session_id: None,
+                            pid: None,
+                            usage: None,
+                        },
+                        lines: Vec::new(),
+                        has_response: false,
+                        seen_message_ids: HashSet::new(),
+                    });
+                } else if let Some(run) = runs.last_mut() {
+                    // Tool results for the current run
+                    run.lines.push(line.clone());
+                }
+            }
+            "assistant" => {
+                let Some(run) = runs.last_mut() else {
+                    continue;
+                };
+                run.lines.push(line.clone());
+                run.has_response = true;
+                if let Some(ts) = timestamp {
+                    run.entry.ended_at = Some(ts);
+                }
+
+                let Some(message) = msg.get("message") else {
+                    continue;
+                };
+                if let Some(model) = message.get("model").and_then(|v| v.as_str()) {
+                    run.entry.model = Some(model.to_string());
+                }
+                // Streamed content blocks of one API message repeat its usage
+                let message_id = message.get("id").and_then(|v| v.as_str()).unwrap_or("");
+                if !run.seen_message_ids.insert(message_id.to_string()) {
+                    continue;
+                }
+                if let Some(usage) = message
+                    .get("usage")
+                    .and_then(|u| serde_json::from_value::<UsageData>(u.clone()).ok())
+                {
+                    let total = run.entry.usage.get_or_insert_with(UsageData::default);
+                    total.input_tokens += usage.input_tokens;
+                    total.output_tokens += usage.output_tokens;
+                    total.cache_read_input_tokens += usage.cache_read_input_tokens;
+                    total.cache_creation_input_tokens += usage.cache_creation_input_tokens;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if runs.is_empty() {
+        return Err("No conversation found in Claude CLI log".to_string());
+    }
+
+    let claude_session_id = claude_session_id.unwrap_or_else(|| fallback_session_id.to_string());
+    let name = summary.unwrap_or_else(|| {
+        let first = runs[0]
+            .entry
+            .user_message
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if first.chars().count() > CLI_SESSION_NAME_CHARS {
+            let truncated: String = first.chars().take(CLI_SESSION_NAME_CHARS).collect();
+            format!("{}…", truncated.trim_end())
+        } else if first.is_empty() {
+            "Imported session".to_string()
+        } else {
+            first
+        }
+    });
+
+    let mut metadata = SessionMetadata::new(
+        Uuid::new_v4().to_string(),
+        worktree_id.to_string(),
+        name,
+        order,
+    );
+    metadata.created_at = runs[0].entry.started_at;
+    metadata.claude_session_id = Some(claude_session_id.clone());
+    metadata.session_naming_completed = true;
+
+    let mut run_logs = HashMap::new();
+    for mut run in runs {
+        run.entry.claude_session_id = Some(claude_session_id.clone());
+        if !run.has_response {
+            run.entry.status = RunStatus::Cancelled;
+            run.entry.cancelled = true;
+        }
+
+        let header = serde_json::json!({
+            "_run_meta": true,
+            "run_id": run.entry.run_id,
+            "session_id": metadata.id,
+            "worktree_id": worktree_id,
+            "user_message_id": run.entry.user_message_id,
+            "model": run.entry.model,
+            "execution_mode": null,
+            "thinking_level": null,
+            "started_at": run.entry.started_at,
+            "imported_from": "claude_cli",
+        });
+        let mut contents = header.to_string();
+        contents.push('\n');
+        for line in &run.lines {
+            contents.push_str(line);
+            contents.push('\n');
+        }
+
+        run_logs.insert(run.entry.run_id.clone(), contents);
+        metadata.runs.push(run.entry);
+    }
+
+    Ok(SessionBundle { metadata, run_logs })
+}
+
+/// Claude CLI's directory name for a project path (non-alphanumerics become `-`)
+fn cli_project_dir_name(worktree_path: &str) -> String {
+    worktree_path
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+fn cli_projects_dir() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+    Ok(home.join(".claude").join("projects"))
+}
+
+/// Resolve a CLI log path, requiring it to be a `.jsonl` file directly inside `project_dir`
+fn resolve_cli_transcript(project_dir: &Path, jsonl_path: &str) -> Result<PathBuf, String> {
+    let not_in_project =
+        || format!("{jsonl_path} is not a Claude CLI session recorded for this worktree");
+    let project_dir = project_dir.canonicalize().map_err(|_| not_in_project())?;
+    let path = Path::new(jsonl_path)
+        .canonicalize()
+        .map_err(|e| format!("Failed to open {jsonl_path}: {e}"))?;
+    if path.parent() != Some(project_dir.as_path())
+        || path.extension().and_then(|e| e.to_str()) != Some("jsonl")
+        || !path.is_file()
+    {
+        return Err(not_in_project());
+    }
+    Ok(path)
+}
+
+fn read_lines(path: &Path) -> Result<Vec<String>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {path:?}: {e}"))?;
+    let contents = read_capped(file, &format!("{path:?}"))?;
+    Ok(contents.lines().map(|l| l.to_string()).collect())
+}
+
+/// Summary and first prompt of a CLI log, read without converting it
+fn peek_cli_transcript(path: &Path) -> (Option<String>, Option<String>) {
+    let Ok(file) = File::open(path) else {
+        return (None, None);
+    };
+    let mut summary = None;
+    let mut first_message = None;
+    let lines = BufReader::new(file.take(MAX_IMPORT_FILE_BYTES)).lines();
+    for line in lines.map_while(Result::ok) {
+        let Ok(msg) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        if msg.get("isSidechain").and_then(|v| v.as_bool()) == Some(true)
+            || msg.get("isMeta").and_then(|v| v.as_bool()) == Some(true)
+        {
+            continue;
+        }
+        match msg.get("type").and_then(|v| v.as_str()) {
+            Some("summary") if summary.is_none() => {
+                summary = msg
+                    .get("summary")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string());
+            }
+            Some("user") if first_message.is_none() => {
+                first_message = msg
+                    .get("message")
+                    .and_then(|m| m.get("content"))
+                    .and_then(cli_user_text);
+            }
+            _ => {}
+        }
+        if summary.is_some() && first_message.is_some() {
+            break;
+        }
+    }
+    (summary, first_message)
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Write a session as a zip bundle (metadata.json + run logs) to `dest_path`
+#[tauri::command]
+pub async fn export_session_bundle(
+    app: AppHandle,
+    session_id: String,
+    dest_path: String,
+) -> Result<(), String> {
+    log::trace!("Exporting session bundle for {session_id} to {dest_path}");
+
+    let metadata = load_metadata(&app, &session_id)?
+        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+    let session_dir = get_session_dir(&app, &session_id)?;
+
+    let file = File::create(&dest_path).map_err(|e| format!("Failed to create bundle: {e}"))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+
+    let metadata_json = serde_json::to_string_pretty(&metadata)
+        .map_err(|e| format!("Failed to serialize metadata: {e}"))?;
+    zip.start_file(BUNDLE_METADATA_FILE, options)
+        .map_err(|e| format!("Failed to write bundle metadata: {e}"))?;
+    zip.write_all(metadata_json.as_bytes())
+        .map_err(|e| format!("Failed to write bundle metadata: {e}"))?;
+
+    for run in &metadata.runs {
+        let name = format!("{}.jsonl", run.run_id);
+        let Ok(contents) = fs::read(session_dir.join(&name)) else {
+            continue;
+        };
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to write run log to bundle: {e}"))?;
+        zip.write_all(&contents)
+            .map_err(|e| format!("Failed to write run log to bundle: {e}"))?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finalize bundle: {e}"))?;
+    Ok(())
+}
+
+/// Import a session bundle (zip or directory) into a worktree
+#[tauri::command]
+pub async fn import_session_bundle(
+    app: AppHandle,
+    worktree_id: String,
+    worktree_path: String,
+    bundle_path: String,
+) -> Result<Session, String> {
+    log::trace!("Importing session bundle {bundle_path} into {worktree_path}");
+
+    let bundle = read_bundle(Path::new(&bundle_path))?;
+    let (order, _) = worktree_import_state(&app, &worktree_id)?;
+    let bundle = remap_bundle(bundle, &worktree_id, order);
+    let session = write_imported_session(&app, bundle)?;
+
+    // Without a Claude CLI session to resume, start from the imported history
+    let messages = load_session_messages(&app, &session.id)?;
+    if !messages.is_empty() {
+        let mut metadata = load_metadata(&app, &session.id)?
+            .ok_or_else(|| format!("Session not found: {}", session.id))?;
+        metadata.fork_start = Some(write_replay_context(
+            &app,
+            &metadata.id,
+            &metadata,
+            &messages,
+            "This session was imported from an earlier conversation. The transcript \
+             below is its history so far; continue from where it ends.",
+        )?);
+        save_metadata(&app, &metadata)?;
+    }
+
+    Ok(session)
+}
+
+/// List Claude CLI conversations recorded for a worktree's path
+#[tauri::command]
+pub async fn list_claude_cli_sessions(
+    app: AppHandle,
+    worktree_id: String,
+    worktree_path: String,
+) -> Result<Vec<ClaudeCliSessionInfo>, String> {
+    let project_dir = cli_projects_dir()?.join(cli_project_dir_name(&worktree_path));
+    if !project_dir.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let (_, imported_ids) = worktree_import_state(&app, &worktree_id)?;
+
+    let entries =
+        fs::read_dir(&project_dir).map_err(|e| format!("Failed to read {project_dir:?}: {e}"))?;
+    let mut sessions = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+            continue;
+        }
+        let Some(claude_session_id) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let modified_at = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let (summary, first_message) = peek_cli_transcript(&path);
+
+        sessions.push(ClaudeCliSessionInfo {
+            claude_session_id: claude_session_id.to_string(),
+            path: path.to_string_lossy().to_string(),
+            modified_at,
+            summary,
+            first_message,
+            imported: imported_ids.contains(claude_session_id),
+        });
+    }
+
+    sessions.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
+    Ok(sessions)
+}
+
+/// Import a Claude CLI conversation log as a resumable session
+#[tauri::command]
+pub async fn import_claude_cli_session(
+    app: AppHandle,
+    worktree_id: String,
+    worktree_path: String,
+    jsonl_path: String,
+) -> Result<Session, String> {
+    log::trace!("Importing Claude CLI session {jsonl_path} into {worktree_path}");
+
+    let project_dir = cli_projects_dir()?.join(cli_project_dir_name(&worktree_path));
+    let path = resolve_cli_transcript(&project_dir, &jsonl_path)?;
+    let lines = read_lines(&path)?;
+    let fallback_id = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default()
+        .to_string();
+
+    let (order, imported_ids) = worktree_import_state(&app, &worktree_id)?;
+    let bundle = convert_cli_transcript(&lines, &fallback_id, &worktree_id, order)?;
+    if let Some(id) = &bundle.metadata.claude_session_id {
+        if imported_ids.contains(id) {
+            return Err(format!(
+                "Claude session {id} is already imported in this worktree"
+            ));
+        }
+    }
+
+    write_imported_session(&app, bundle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::run_log::parse_run_to_message;
+
+    fn cli_lines() -> Vec<String> {
+        [
+            r#"{"type":"summary","summary":"Fix migration bug","leafUuid":"x"}"#,
+            r#"{"type":"user","sessionId":"cli-1","isMeta":true,"message":{"role":"user","content":"<caveat>"},"timestamp":"2025-10-09T08:53:00.000Z"}"#,
+            r#"{"type":"user","sessionId":"cli-1","message":{"role":"user","content":"Why does the migration fail?"},"timestamp":"2025-10-09T08:53:20.000Z"}"#,
+            r#"{"type":"assistant","sessionId":"cli-1","message":{"id":"msg_1","model":"claude-opus-4","role":"assistant","content":[{"type":"text","text":"Let me check."}],"usage":{"input_tokens":10,"output_tokens":5}},"timestamp":"2025-10-09T08:53:25.000Z"}"#,
+            r#"{"type":"assistant","sessionId":"cli-1","message":{"id":"msg_1","model":"claude-opus-4","role":"assistant","content":[{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"ls"}}],"usage":{"input_tokens":10,"output_tokens":5}},"timestamp":"2025-10-09T08:53:26.000Z"}"#,
+            r#"{"type":"user","sessionId":"cli-1","isSidechain":true,"message":{"role":"user","content":"sub-agent prompt"}}"#,
+            r#"{"type":"user","sessionId":"cli-1","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","content":"schema.rs"}]},"timestamp":"2025-10-09T08:53:27.000Z"}"#,
+            r#"{"type":"user","sessionId":"cli-1","message":{"role":"user","content":[{"type":"text","text":"Thanks"}]},"timestamp":"2025-10-09T08:54:00.000Z"}"#,
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn test_parse_iso_timestamp() {
+        assert_eq!(parse_iso_timestamp("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(
+            parse_iso_timestamp("2025-10-09T08:53:20.123Z"),
+            Some(1_760_000_000)
+        );
+        assert_eq!(
+            parse_iso_timestamp("2024-02-29T12:00:00+00:00"),
+            Some(1_709_208_000)
+        );
+        assert_eq!(parse_iso_timestamp("not a date"), None);
+    }
+
+    #[test]
+    fn test_convert_cli_transcript() {
+        let bundle = convert_cli_transcript(&cli_lines(), "fallback", "wt-1", 3).unwrap();
+        let metadata = &bundle.metadata;
+
+        assert_eq!(metadata.name, "Fix migration bug");
+        assert_eq!(metadata.worktree_id, "wt-1");
+        assert_eq!(metadata.order, 3);
+        assert_eq!(metadata.claude_session_id.as_deref(), Some("cli-1"));
+        assert_eq!(metadata.created_at, 1_760_000_000);
+        assert_eq!(metadata.runs.len(), 2);
+
+        let first = &metadata.runs[0];
+        assert_eq!(first.user_message, "Why does the migration fail?");
+        assert_eq!(first.status, RunStatus::Completed);
+        assert_eq!(first.model.as_deref(), Some("claude-opus-4"));
+        // Usage of the repeated API message is only counted once
+        assert_eq!(first.usage.as_ref().unwrap().input_tokens, 10);
+        assert_eq!(first.ended_at, Some(1_760_000_006));
+
+        // The run log parses back into the assistant message with tool output
+        let lines: Vec<String> = bundle.run_logs[&first.run_id]
+            .lines()
+            .map(|s| s.to_string())
+            .collect();
+        let message = parse_run_to_message(&lines, first).unwrap();
+        assert_eq!(message.content, "Let me check.");
+        assert_eq!(message.tool_calls.len(), 1);
+        assert_eq!(message.tool_calls[0].output.as_deref(), Some("schema.rs"));
+
+        // A trailing prompt without a response is kept as a cancelled run
+        let second = &metadata.runs[1];
+        assert_eq!(second.user_message, "Thanks");
+        assert_eq!(second.status, RunStatus::Cancelled);
+    }
+
+    #[test]
+    fn test_convert_cli_transcript_empty() {
+        assert!(convert_cli_transcript(&[], "x", "wt-1", 0).is_err());
+    }
+
+    #[test]
+    fn test_remap_bundle() {
+        let original = convert_cli_transcript(&cli_lines(), "fallback", "wt-1", 0).unwrap();
+        let mut metadata = original.metadata.clone();
+        metadata.archived_at = Some(5);
+        metadata.runs[1].status = RunStatus::Running;
+        metadata.runs[1].pid = Some(42);
+        let first_assistant = metadata.runs[0].assistant_message_id.clone().unwrap();
+        metadata.approved_plan_message_ids = vec![first_assistant.clone()];
+
+        let bundle = SessionBundle {
+            metadata: metadata.clone(),
+            run_logs: original.run_logs.clone(),
+        };
+        let remapped = remap_bundle(bundle, "wt-2", 7);
+        let new = &remapped.metadata;
+
+        assert_ne!(new.id, metadata.id);
+        assert_eq!(new.worktree_id, "wt-2");
+        assert_eq!(new.order, 7);
+        assert_eq!(new.archived_at, None);
+        assert!(metadata.claude_session_id.is_some());
+        assert_eq!(new.claude_session_id, None);
+        assert!(new.runs.iter().all(|r| r.claude_session_id.is_none()));
+        assert_eq!(new.runs[1].status, RunStatus::Crashed);
+        assert_eq!(new.runs[1].pid, None);
+
+        for (old_run, new_run) in metadata.runs.iter().zip(&new.runs) {
+            assert_ne!(old_run.run_id, new_run.run_id);
+            assert_ne!(old_run.user_message_id, new_run.user_message_id);
+            assert_ne!(old_run.assistant_message_id, new_run.assistant_message_id);
+
+            let log = &remapped.run_logs[&new_run.run_id];
+            let header: serde_json::Value =
+                serde_json::from_str(log.lines().next().unwrap()).unwrap();
+            assert_eq!(header["run_id"], new_run.run_id.as_str());
+            assert_eq!(header["session_id"], new.id.as_str());
+            assert_eq!(header["worktree_id"], "wt-2");
+        }
+
+        assert_eq!(
+            new.approved_plan_message_ids,
+            vec![new.runs[0].assistant_message_id.clone().unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_read_bundle_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = convert_cli_transcript(&cli_lines(), "fallback", "wt-1", 0).unwrap();
+        fs::write(
+            dir.path().join(BUNDLE_METADATA_FILE),
+            serde_json::to_string(&original.metadata).unwrap(),
+        )
+        .unwrap();
+        for (run_id, contents) in &original.run_logs {
+            fs::write(dir.path().join(format!("{run_id}.jsonl")), contents).unwrap();
+        }
+        fs::write(dir.path().join("stray.jsonl"), "{}").unwrap();
+
+        let bundle = read_bundle(dir.path()).unwrap();
+        assert_eq!(bundle.metadata.id, original.metadata.id);
+        assert_eq!(bundle.run_logs.len(), 2);
+    }
+
+    #[test]
+    fn test_read_bundle_dir_rejects_oversized_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = convert_cli_transcript(&cli_lines(), "fallback", "wt-1", 0).unwrap();
+        fs::write(
+            dir.path().join(BUNDLE_METADATA_FILE),
+            serde_json::to_string(&original.metadata).unwrap(),
+        )
+        .unwrap();
+        let file = File::create(dir.path().join("huge.jsonl")).unwrap();
+        file.set_len(MAX_IMPORT_FILE_BYTES + 1).unwrap();
+
+        let err = read_bundle(dir.path()).unwrap_err();
+        assert!(err.contains("import limit"), "{err}");
+    }
+
+    #[test]
+    fn test_resolve_cli_transcript() {
+        let root = tempfile::tempdir().unwrap();
+        let project_dir = root.path().join("-repo");
+        let other_dir = root.path().join("-other");
+        fs::create_dir_all(project_dir.join("nested")).unwrap();
+        fs::create_dir_all(&other_dir).unwrap();
+        for path in [
+            project_dir.join("abc.jsonl"),
+            project_dir.join("notes.txt"),
+            project_dir.join("nested").join("def.jsonl"),
+            other_dir.join("ghi.jsonl"),
+        ] {
+            fs::write(path, "{}").unwrap();
+        }
+        let resolve = |path: PathBuf| resolve_cli_transcript(&project_dir, path.to_str().unwrap());
+
+        assert!(resolve(project_dir.join("abc.jsonl")).is_ok());
+        assert!(resolve(project_dir.join("..").join("-repo").join("abc.jsonl")).is_ok());
+        assert!(resolve(project_dir.join("notes.txt")).is_err());
+        assert!(resolve(project_dir.join("nested").join("def.jsonl")).is_err());
+        assert!(resolve(project_dir.join("..").join("-other").join("ghi.jsonl")).is_err());
+        assert!(resolve(project_dir.join("missing.jsonl")).is_err());
+    }
+
+    #[test]
+    fn test_cli_project_dir_name() {
+        assert_eq!(
+            cli_project_dir_name("/Users/me/code/my.app"),
+            "-Users-me-code-my-app"
+        );
+    }
+}
@@ -1,12 +1,19 @@
+pub mod budget;
 mod claude;
 mod commands;
 pub mod detached;
+pub mod export;
+pub mod fork;
+pub mod import;
 mod naming;
+pub mod queue;
 pub mod registry;
 pub mod run_log;
+pub mod search;
 pub mod storage;
 pub mod tail;
 pub mod types;
+pub mod usage;
 
 pub use commands::*;
 pub use storage::{preserve_base_sessions, restore_base_sessions, with_sessions_mut};
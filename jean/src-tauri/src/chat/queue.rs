@@ -0,0 +1,504 @@
+//! Queued follow-up messages
+//!
+//! Each session keeps a FIFO of user messages (persisted in its metadata) that
+//! are sent one at a time: when a run completes successfully the next queued
+//! message is dispatched through `send_chat_message`, exactly as if the user
+//! had sent it. Cancelled or failed runs leave the queue untouched so the user
+//! can decide whether to continue.
+//!
+//! The chat input, API clients and the scheduler all queue here; attachments
+//! are already inlined into the message text as file references. When a
+//! queued message's run ends (or the message is removed) the scheduler is told,
+//! so runs it queued are finished too.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use super::registry::is_process_running;
+use super::storage::{load_metadata, with_metadata_mut};
+use super::types::{QueuedMessage, RunStatus, SessionMetadata, ThinkingLevel};
+use crate::http_server::EmitExt;
+use crate::projects::storage::load_projects_data;
+use crate::scheduler::{finish_queued_run, ScheduleOutcome};
+
+/// Sessions with a queued message being sent, from the pop until its run ends
+static DISPATCHING: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Payload for `chat:queue_updated`, emitted whenever a session's queue changes
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueUpdatedEvent {
+    pub session_id: String,
+    pub worktree_id: String,
+    pub queue: Vec<QueuedMessage>,
+}
+
+/// Payload for `chat:queue_dispatched`, emitted when a queued item is sent
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueDispatchedEvent {
+    pub session_id: String,
+    pub worktree_id: String,
+    pub item: QueuedMessage,
+}
+
+/// Whether a run is in flight for the session (process alive, run log open or
+/// a queued message being sent)
+pub fn is_session_busy(session_id: &str, metadata: Option<&SessionMetadata>) -> bool {
+    DISPATCHING.lock().unwrap().contains(session_id)
+        || is_process_running(session_id)
+        || metadata
+            .and_then(|m| m.runs.last())
+            .is_some_and(|r| matches!(r.status, RunStatus::Running | RunStatus::Resumable))
+}
+
+/// Mutate a session's queue under the metadata lock and notify listeners
+fn with_queue_mut<F, T>(app: &AppHandle, session_id: &str, f: F) -> Result<T, String>
+where
+    F: FnOnce(&mut Vec<QueuedMessage>) -> Result<T, String>,
+{
+    with_queued_metadata_mut(app, session_id, |metadata| f(&mut metadata.message_queue))
+}
+
+/// Like [`with_queue_mut`], with the rest of the metadata in view
+fn with_queued_metadata_mut<F, T>(app: &AppHandle, session_id: &str, f: F) -> Result<T, String>
+where
+    F: FnOnce(&mut SessionMetadata) -> Result<T, String>,
+{
+    let metadata = load_metadata(app, session_id)?
+        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+
+    let (result, worktree_id, queue) = with_metadata_mut(
+        app,
+        session_id,
+        &metadata.worktree_id,
+        &metadata.name,
+        metadata.order,
+        |metadata| {
+            let result = f(metadata)?;
+            Ok((
+                result,
+                metadata.worktree_id.clone(),
+                metadata.message_queue.clone(),
+            ))
+        },
+    )?;
+
+    emit_queue_updated(app, session_id, &worktree_id, queue);
+    Ok(result)
+}
+
+fn emit_queue_updated(
+    app: &AppHandle,
+    session_id: &str,
+    worktree_id: &str,
+    queue: Vec<QueuedMessage>,
+) {
+    let event = QueueUpdatedEvent {
+        session_id: session_id.to_string(),
+        worktree_id: worktree_id.to_string(),
+        queue,
+    };
+    if let Err(e) = app.emit_all("chat:queue_updated", &event) {
+        log::error!("Failed to emit queue updated event: {e}");
+    }
+}
+
+/// Reorder `queue` to match `item_ids`, which must list every item exactly once
+fn reorder_queue(queue: &mut Vec<QueuedMessage>, item_ids: &[String]) -> Result<(), String> {
+    let unique: HashSet<&str> = item_ids.iter().map(String::as_str).collect();
+    if item_ids.len() != queue.len()
+        || unique.len() != item_ids.len()
+        || queue.iter().any(|item| !unique.contains(item.id.as_str()))
+    {
+        return Err("Reorder must list every queued message exactly once".to_string());
+    }
+
+    let mut reordered = Vec::with_capacity(queue.len());
+    for id in item_ids {
+        if let Some(pos) = queue.iter().position(|item| &item.id == id) {
+            reordered.push(queue.remove(pos));
+        }
+    }
+    *queue = reordered;
+    Ok(())
+}
+
+/// Send the next queued message for a session, if any and the session is idle
+///
+/// Pops the head of the queue and spawns `send_chat_message` with its stored
+/// options. The session is claimed in the same critical section as the pop, so
+/// concurrent calls can't start two runs; the claim is released when the run
+/// ends, and the queue advances if it completed. If the send is refused before
+/// a run starts (e.g. a budget is exhausted) the item is put back at the front
+/// of the queue. Returns whether a message was dispatched.
+pub fn dispatch_next(
+    app: &AppHandle,
+    session_id: &str,
+    worktree_id: &str,
+    worktree_path: &str,
+) -> bool {
+    // Cheap check first so idle sessions don't rewrite their metadata
+    let has_queued = load_metadata(app, session_id)
+        .ok()
+        .flatten()
+        .is_some_and(|m| !m.message_queue.is_empty());
+    if !has_queued {
+        return false;
+    }
+
+    let popped = with_queued_metadata_mut(app, session_id, |metadata| {
+        if metadata.message_queue.is_empty() || is_session_busy(session_id, Some(metadata)) {
+            return Ok(None);
+        }
+        DISPATCHING.lock().unwrap().insert(session_id.to_string());
+        Ok(Some((
+            metadata.message_queue.remove(0),
+            metadata.runs.len(),
+        )))
+    });
+    let (item, runs_before) = match popped {
+        Ok(Some(popped)) => popped,
+        Ok(None) => return false,
+        Err(e) => {
+            log::warn!("Failed to pop queued message for session {session_id}: {e}");
+            return false;
+        }
+    };
+
+    log::trace!(
+        "Dispatching queued message {} for session {session_id}",
+        item.id
+    );
+    let event = QueueDispatchedEvent {
+        session_id: session_id.to_string(),
+        worktree_id: worktree_id.to_string(),
+        item: item.clone(),
+    };
+    if let Err(e) = app.emit_all("chat:queue_dispatched", &event) {
+        log::error!("Failed to emit queue dispatched event: {e}");
+    }
+
+    let app = app.clone();
+    let session_id = session_id.to_string();
+    let worktree_id = worktree_id.to_string();
+    let worktree_path = worktree_path.to_string();
+    tauri::async_runtime::spawn(async move {
+        let result = super::send_chat_message(
+            app.clone(),
+            session_id.clone(),
+            worktree_id.clone(),
+            worktree_path.clone(),
+            item.message.clone(),
+            item.model.clone(),
+            item.execution_mode.clone(),
+            item.thinking_level.clone(),
+            item.disable_thinking_for_mode,
+            item.parallel_execution_prompt_enabled,
+            item.ai_language.clone(),
+            item.allowed_tools.clone(),
+            None,
+        )
+        .await;
+        DISPATCHING.lock().unwrap().remove(&session_id);
+
+        // `send_chat_message` didn't advance the queue while the claim was held
+        match result {
+            Ok(message) => {
+                let outcome = if message.cancelled {
+                    ScheduleOutcome::Cancelled
+                } else {
+                    ScheduleOutcome::Completed
+                };
+                finish_queued_run(&app, &item.id, Ok((outcome, Some(message.id))));
+                if !message.cancelled {
+                    dispatch_next(&app, &session_id, &worktree_id, &worktree_path);
+                }
+            }
+            Err(e) => {
+                log::warn!(
+                    "Queued message {} failed for session {session_id}: {e}",
+                    item.id
+                );
+                let run_started = load_metadata(&app, &session_id)
+                    .ok()
+                    .flatten()
+                    .is_some_and(|m| m.runs.len() > runs_before);
+                if run_started {
+                    finish_queued_run(&app, &item.id, Err(e));
+                } else {
+                    restore_item(&app, &session_id, item);
+                }
+            }
+        }
+    });
+    true
+}
+
+/// Put a message that couldn't be sent back at the front of the queue
+fn restore_item(app: &AppHandle, session_id: &str, item: QueuedMessage) {
+    let result = with_queue_mut(app, session_id, |queue| {
+        if !queue.iter().any(|q| q.id == item.id) {
+            queue.insert(0, item);
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to restore queued message: {e}");
+    }
+}
+
+/// Advance a session's queue once a run recovered after a restart completes
+pub fn dispatch_next_after_recovery(app: &AppHandle, session_id: &str, worktree_id: &str) {
+    let worktree_path = match load_projects_data(app) {
+        Ok(data) => data.find_worktree(worktree_id).map(|w| w.path.clone()),
+        Err(e) => {
+            log::warn!("Failed to load projects for queued dispatch: {e}");
+            return;
+        }
+    };
+    match worktree_path {
+        Some(worktree_path) => {
+            dispatch_next(app, session_id, worktree_id, &worktree_path);
+        }
+        None => log::warn!("Worktree {worktree_id} not found for queued dispatch"),
+    }
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Queue a follow-up message for a session
+///
+/// If the session is idle the message is sent right away; otherwise it waits
+/// until the current run (and any messages queued before it) complete.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn enqueue_message(
+    app: AppHandle,
+    session_id: String,
+    worktree_id: String,
+    worktree_path: String,
+    message: String,
+    model: Option<String>,
+    execution_mode: Option<String>,
+    thinking_level: Option<ThinkingLevel>,
+    disable_thinking_for_mode: Option<bool>,
+    parallel_execution_prompt_enabled: Option<bool>,
+    ai_language: Option<String>,
+    allowed_tools: Option<Vec<String>>,
+) -> Result<QueuedMessage, String> {
+    log::trace!("Queueing message for session: {session_id}");
+
+    if message.trim().is_empty() {
+        return Err("Message cannot be empty".to_string());
+    }
+
+    let item = QueuedMessage {
+        id: Uuid::new_v4().to_string(),
+        message,
+        model,
+        execution_mode,
+        thinking_level,
+        disable_thinking_for_mode,
+        parallel_execution_prompt_enabled,
+        ai_language,
+        allowed_tools,
+        queued_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+    };
+
+    enqueue(
+        &app,
+        &session_id,
+        &worktree_id,
+        &worktree_path,
+        item.clone(),
+    )?;
+    Ok(item)
+}
+
+/// Append a message to a session's queue, sending it right away if idle
+pub fn enqueue(
+    app: &AppHandle,
+    session_id: &str,
+    worktree_id: &str,
+    worktree_path: &str,
+    item: QueuedMessage,
+) -> Result<(), String> {
+    with_queue_mut(app, session_id, |queue| {
+        queue.push(item);
+        Ok(())
+    })?;
+
+    dispatch_next(app, session_id, worktree_id, worktree_path);
+    Ok(())
+}
+
+/// List the queued messages for a session, in send order
+#[tauri::command]
+pub async fn list_queued_messages(
+    app: AppHandle,
+    session_id: String,
+) -> Result<Vec<QueuedMessage>, String> {
+    Ok(load_metadata(&app, &session_id)?
+        .map(|m| m.message_queue)
+        .unwrap_or_default())
+}
+
+/// Edit the text of a queued message
+#[tauri::command]
+pub async fn update_queued_message(
+    app: AppHandle,
+    session_id: String,
+    item_id: String,
+    message: String,
+) -> Result<QueuedMessage, String> {
+    if message.trim().is_empty() {
+        return Err("Message cannot be empty".to_string());
+    }
+
+    with_queue_mut(&app, &session_id, |queue| {
+        let item = queue
+            .iter_mut()
+            .find(|item| item.id == item_id)
+            .ok_or_else(|| format!("Queued message not found: {item_id}"))?;
+        item.message = message;
+        Ok(item.clone())
+    })
+}
+
+/// Reorder a session's queue; `item_ids` must contain every queued item once
+#[tauri::command]
+pub async fn reorder_queued_messages(
+    app: AppHandle,
+    session_id: String,
+    item_ids: Vec<String>,
+) -> Result<(), String> {
+    with_queue_mut(&app, &session_id, |queue| reorder_queue(queue, &item_ids))
+}
+
+/// Drop a single queued message
+#[tauri::command]
+pub async fn remove_queued_message(
+    app: AppHandle,
+    session_id: String,
+    item_id: String,
+) -> Result<(), String> {
+    with_queue_mut(&app, &session_id, |queue| {
+        let before = queue.len();
+        queue.retain(|item| item.id != item_id);
+        if queue.len() == before {
+            return Err(format!("Queued message not found: {item_id}"));
+        }
+        Ok(())
+    })?;
+
+    finish_queued_run(&app, &item_id, Ok((ScheduleOutcome::Cancelled, None)));
+    Ok(())
+}
+
+/// Drop every queued message for a session
+#[tauri::command]
+pub async fn clear_message_queue(app: AppHandle, session_id: String) -> Result<(), String> {
+    let removed = with_queue_mut(&app, &session_id, |queue| {
+        Ok(queue.drain(..).map(|item| item.id).collect::<Vec<_>>())
+    })?;
+
+    for item_id in removed {
+        finish_queued_run(&app, &item_id, Ok((ScheduleOutcome::Cancelled, None)));
+    }
+    Ok(())
+}
+
+/// Resume a paused queue (e.g. after the previous run was cancelled)
+///
+/// Returns false if the queue is empty or a run is still in progress.
+#[tauri::command]
+pub async fn send_next_queued_message(
+    app: AppHandle,
+    session_id: String,
+    worktree_id: String,
+    worktree_path: String,
+) -> Result<bool, String> {
+    Ok(dispatch_next(
+        &app,
+        &session_id,
+        &worktree_id,
+        &worktree_path,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str) -> QueuedMessage {
+        QueuedMessage {
+            id: id.to_string(),
+            message: format!("message {id}"),
+            model: None,
+            execution_mode: None,
+            thinking_level: None,
+            disable_thinking_for_mode: None,
+            parallel_execution_prompt_enabled: None,
+            ai_language: None,
+            allowed_tools: None,
+            queued_at: 0,
+        }
+    }
+
+    fn ids(queue: &[QueuedMessage]) -> Vec<&str> {
+        queue.iter().map(|item| item.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_reorder_queue() {
+        let mut queue = vec![item("a"), item("b"), item("c")];
+        let order = vec!["c".to_string(), "a".to_string(), "b".to_string()];
+        reorder_queue(&mut queue, &order).unwrap();
+        assert_eq!(ids(&queue), vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_reorder_queue_rejects_partial_lists() {
+        let mut queue = vec![item("a"), item("b")];
+        assert!(reorder_queue(&mut queue, &["a".to_string()]).is_err());
+        assert!(reorder_queue(&mut queue, &["a".to_string(), "a".to_string()]).is_err());
+        assert!(reorder_queue(&mut queue, &["a".to_string(), "x".to_string()]).is_err());
+        assert_eq!(ids(&queue), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_queue_round_trips_through_metadata() {
+        let mut metadata = SessionMetadata::new(
+            "session".to_string(),
+            "worktree".to_string(),
+            "Session 1".to_string(),
+            0,
+        );
+        metadata.message_queue.push(item("a"));
+        let json = serde_json::to_string(&metadata).unwrap();
+        let parsed: SessionMetadata = serde_json::from_str(&json).unwrap();
+        assert_eq!(ids(&parsed.message_queue), vec!["a"]);
+
+        // Metadata written before queues existed has no field at all
+        let empty = SessionMetadata::new(
+            "session".to_string(),
+            "worktree".to_string(),
+            "Session 1".to_string(),
+            0,
+        );
+        let json = serde_json::to_string(&empty).unwrap();
+        assert!(!json.contains("message_queue"));
+        let parsed: SessionMetadata = serde_json::from_str(&json).unwrap();
+        assert!(parsed.message_queue.is_empty());
+    }
+}
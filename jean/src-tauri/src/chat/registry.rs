@@ -35,7 +35,6 @@ pub fn unregister_process(session_id: &str) {
 }
 
 /// Check if a session has a running process
-#[allow(dead_code)]
 pub fn is_process_running(session_id: &str) -> bool {
     PROCESS_REGISTRY.lock().unwrap().contains_key(session_id)
 }
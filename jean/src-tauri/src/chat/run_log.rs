@@ -94,6 +94,10 @@ impl RunLogWriter {
         )?;
 
         log::trace!("Run completed: {}", self.run_id);
+
+        // Keep the full-text search index up to date (best-effort, off-thread)
+        super::search::index_run_in_background(&self.app, &self.session_id, &self.run_id);
+
         Ok(())
     }
 
@@ -784,6 +788,47 @@ pub fn recover_incomplete_runs(app: &tauri::AppHandle) -> Result<Vec<RecoveredRu
     Ok(recovered)
 }
 
+/// Mark runs whose detached process is still alive as resumable
+/// Called when Jean shuts down cleanly, so the CLI keeps running and the
+/// next start tails its output instead of waiting on a dead tailer
+pub fn mark_running_runs_resumable(app: &tauri::AppHandle) -> Result<Vec<RecoveredRun>, String> {
+    use super::detached::is_process_alive;
+
+    let session_ids = list_all_session_ids(app)?;
+    let mut resumable = Vec::new();
+
+    for session_id in session_ids {
+        let mut metadata = match load_metadata(app, &session_id)? {
+            Some(m) => m,
+            None => continue,
+        };
+
+        let mut modified = false;
+
+        for run in &mut metadata.runs {
+            // Runs whose process is gone are left for recover_incomplete_runs
+            if run.status == RunStatus::Running && run.pid.map(is_process_alive).unwrap_or(false) {
+                run.status = RunStatus::Resumable;
+                modified = true;
+
+                resumable.push(RecoveredRun {
+                    session_id: session_id.clone(),
+                    worktree_id: metadata.worktree_id.clone(),
+                    run_id: run.run_id.clone(),
+                    user_message: run.user_message.clone(),
+                    resumable: true,
+                });
+            }
+        }
+
+        if modified {
+            save_metadata(app, &metadata)?;
+        }
+    }
+
+    Ok(resumable)
+}
+
 /// Find all runs with status = Running (incomplete runs that need recovery)
 #[allow(dead_code)]
 pub fn find_incomplete_runs(
@@ -0,0 +1,1055 @@
+//! Full-text search across all session histories
+//!
+//! Maintains a persistent inverted index (`sessions/search_index.json`) over
+//! user messages, assistant text, tool names and tool inputs of every
+//! completed run, archived sessions included. Runs are added to the index when
+//! [`RunLogWriter::complete`](super::run_log::RunLogWriter::complete) finishes;
+//! anything missing (e.g. runs from before the index existed) is picked up by a
+//! one-time catch-up pass on the first search.
+//!
+//! Changes are saved on a debounce, so a burst of completed runs rewrites the
+//! file once. Changes lost to a quit before the save are redone by the
+//! catch-up pass.
+//!
+//! Sessions whose worktree was deleted, and archived sessions that were
+//! permanently deleted, keep their data on disk but are dropped from the
+//! index (and skipped by the catch-up pass).
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::run_log::{parse_run_to_message, read_run_log};
+use super::storage::{get_sessions_dir, list_all_session_ids, load_metadata};
+use super::types::{ChatMessage, RunEntry, RunStatus, SessionMetadata};
+use crate::projects::storage::load_projects_data;
+
+/// Bump when the on-disk format or tokenizer changes to force a rebuild
+const INDEX_VERSION: u32 = 1;
+
+/// Maximum characters of a single field kept in the index (and used for snippets)
+const MAX_FIELD_CHARS: usize = 16_000;
+
+/// Tokens longer than this (hashes, base64 blobs) are not indexed
+const MAX_TOKEN_CHARS: usize = 64;
+
+/// Characters of context shown on each side of the first match in a snippet
+const SNIPPET_RADIUS: usize = 80;
+
+/// Default number of hits returned by `search_sessions`
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+/// Delay between a change to the index and saving it
+const SAVE_DEBOUNCE: Duration = Duration::from_secs(5);
+
+// BM25 parameters
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+
+/// In-memory copy of the index, loaded lazily from disk
+static SEARCH_INDEX: Lazy<Mutex<Option<SearchIndex>>> = Lazy::new(|| Mutex::new(None));
+
+/// Whether the catch-up pass over existing sessions has run in this process
+static INDEX_SYNCED: AtomicBool = AtomicBool::new(false);
+
+/// Whether a save of the index is pending
+static SAVE_SCHEDULED: AtomicBool = AtomicBool::new(false);
+
+/// Held while writing the index file, so saves don't interleave
+static SAVE_LOCK: Mutex<()> = Mutex::new(());
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Which part of a run an indexed document came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchField {
+    UserMessage,
+    AssistantText,
+    ToolName,
+    ToolInput,
+}
+
+impl SearchField {
+    /// Relative weight of a match in this field
+    fn weight(self) -> f64 {
+        match self {
+            SearchField::UserMessage => 1.5,
+            SearchField::AssistantText => 1.0,
+            SearchField::ToolInput => 0.7,
+            SearchField::ToolName => 0.5,
+        }
+    }
+}
+
+/// A single indexed piece of text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedDoc {
+    pub session_id: String,
+    pub worktree_id: String,
+    pub run_id: String,
+    pub message_id: String,
+    pub field: SearchField,
+    pub text: String,
+    pub timestamp: u64,
+    /// Number of tokens in `text`
+    pub len: u32,
+}
+
+/// Inverted index over all indexed documents
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SearchIndex {
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    next_doc_id: u64,
+    #[serde(default)]
+    docs: HashMap<u64, IndexedDoc>,
+    /// term -> (doc id -> term frequency), ordered for prefix lookups
+    #[serde(default)]
+    postings: BTreeMap<String, HashMap<u64, u32>>,
+    /// "{session_id}/{run_id}" -> doc ids, for re-indexing and removal
+    #[serde(default)]
+    runs: BTreeMap<String, Vec<u64>>,
+    /// Permanently deleted sessions, so the catch-up pass doesn't re-add them
+    #[serde(default)]
+    deleted_sessions: HashSet<String>,
+}
+
+/// Optional restrictions for `search_sessions`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchFilter {
+    #[serde(default)]
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub worktree_id: Option<String>,
+    /// Include archived sessions (default: true)
+    #[serde(default)]
+    pub include_archived: Option<bool>,
+}
+
+/// A ranked search result (one per matching message)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub session_id: String,
+    pub session_name: String,
+    pub worktree_id: String,
+    pub worktree_name: Option<String>,
+    /// Path of the worktree (None once the worktree is deleted)
+    pub worktree_path: Option<String>,
+    pub project_id: Option<String>,
+    pub project_name: Option<String>,
+    pub run_id: String,
+    pub message_id: String,
+    pub field: SearchField,
+    pub snippet: String,
+    pub score: f64,
+    pub timestamp: u64,
+    pub archived: bool,
+}
+
+// ============================================================================
+// Tokenizer
+// ============================================================================
+
+/// Split text into lowercase alphanumeric tokens with their byte ranges
+fn tokenize_with_offsets(text: &str) -> Vec<(usize, usize, String)> {
+    let mut tokens = Vec::new();
+    let mut start: Option<usize> = None;
+
+    let push = |s: usize, e: usize, tokens: &mut Vec<(usize, usize, String)>| {
+        let token = &text[s..e];
+        let chars = token.chars().count();
+        if (2..=MAX_TOKEN_CHARS).contains(&chars) {
+            tokens.push((s, e, token.to_lowercase()));
+        }
+    };
+
+    for (i, c) in text.char_indices() {
+        if c.is_alphanumeric() {
+            if start.is_none() {
+                start = Some(i);
+            }
+        } else if let Some(s) = start.take() {
+            push(s, i, &mut tokens);
+        }
+    }
+    if let Some(s) = start {
+        push(s, text.len(), &mut tokens);
+    }
+
+    tokens
+}
+
+/// Split text into lowercase alphanumeric tokens
+pub fn tokenize(text: &str) -> Vec<String> {
+    tokenize_with_offsets(text)
+        .into_iter()
+        .map(|(_, _, t)| t)
+        .collect()
+}
+
+/// Truncate to at most `max` characters on a char boundary
+fn truncate_chars(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((i, _)) => &text[..i],
+        None => text,
+    }
+}
+
+/// Flatten the string values of a tool input into searchable text
+fn tool_input_text(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::String(s) => {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(s);
+        }
+        serde_json::Value::Array(items) => items.iter().for_each(|v| tool_input_text(v, out)),
+        serde_json::Value::Object(map) => map.values().for_each(|v| tool_input_text(v, out)),
+        serde_json::Value::Number(n) => {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&n.to_string());
+        }
+        _ => {}
+    }
+}
+
+/// Build a short excerpt around the first token matching any query term
+pub fn make_snippet(text: &str, terms: &[String]) -> String {
+    let match_start = tokenize_with_offsets(text)
+        .into_iter()
+        .find(|(_, _, token)| terms.iter().any(|t| token.starts_with(t.as_str())))
+        .map(|(s, _, _)| s)
+        .unwrap_or(0);
+
+    let before: Vec<usize> = text[..match_start].char_indices().map(|(i, _)| i).collect();
+    let start = if before.len() > SNIPPET_RADIUS {
+        before[before.len() - SNIPPET_RADIUS]
+    } else {
+        0
+    };
+    let end = text[match_start..]
+        .char_indices()
+        .nth(SNIPPET_RADIUS * 2)
+        .map(|(i, _)| match_start + i)
+        .unwrap_or(text.len());
+
+    let mut snippet = text[start..end]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if start > 0 {
+        snippet.insert_str(0, "…");
+    }
+    if end < text.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+// ============================================================================
+// Index
+// ============================================================================
+
+fn run_key(session_id: &str, run_id: &str) -> String {
+    format!("{session_id}/{run_id}")
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self {
+            version: INDEX_VERSION,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_run_indexed(&self, session_id: &str, run_id: &str) -> bool {
+        self.runs.contains_key(&run_key(session_id, run_id))
+    }
+
+    pub fn doc_count(&self) -> usize {
+        self.docs.len()
+    }
+
+    fn add_doc(&mut self, doc: IndexedDoc) -> Option<u64> {
+        let tokens = tokenize(&doc.text);
+        if tokens.is_empty() {
+            return None;
+        }
+
+        let id = self.next_doc_id;
+        self.next_doc_id += 1;
+
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for token in &tokens {
+            *counts.entry(token.clone()).or_default() += 1;
+        }
+        for (term, tf) in counts {
+            self.postings.entry(term).or_default().insert(id, tf);
+        }
+
+        self.docs.insert(
+            id,
+            IndexedDoc {
+                len: tokens.len() as u32,
+                ..doc
+            },
+        );
+        Some(id)
+    }
+
+    fn remove_doc(&mut self, id: u64) {
+        let Some(doc) = self.docs.remove(&id) else {
+            return;
+        };
+        let terms: HashSet<String> = tokenize(&doc.text).into_iter().collect();
+        for term in terms {
+            if let Some(postings) = self.postings.get_mut(&term) {
+                postings.remove(&id);
+                if postings.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    /// Index (or re-index) a run and the assistant message parsed from its log
+    pub fn add_run(&mut self, worktree_id: &str, run: &RunEntry, message: &ChatMessage) {
+        let session_id = message.session_id.as_str();
+        self.remove_run(session_id, &run.run_id);
+
+        let base = |message_id: &str, field: SearchField, text: &str, timestamp: u64| IndexedDoc {
+            session_id: session_id.to_string(),
+            worktree_id: worktree_id.to_string(),
+            run_id: run.run_id.clone(),
+            message_id: message_id.to_string(),
+            field,
+            text: truncate_chars(text, MAX_FIELD_CHARS).to_string(),
+            timestamp,
+            len: 0,
+        };
+
+        let mut docs = vec![
+            base(
+                &run.user_message_id,
+                SearchField::UserMessage,
+                &run.user_message,
+                run.started_at,
+            ),
+            base(
+                &message.id,
+                SearchField::AssistantText,
+                &message.content,
+                message.timestamp,
+            ),
+        ];
+        for tool_call in &message.tool_calls {
+            docs.push(base(
+                &message.id,
+                SearchField::ToolName,
+                &tool_call.name,
+                message.timestamp,
+            ));
+            let mut input = String::new();
+            tool_input_text(&tool_call.input, &mut input);
+            docs.push(base(
+                &message.id,
+                SearchField::ToolInput,
+                &input,
+                message.timestamp,
+            ));
+        }
+
+        let ids: Vec<u64> = docs.into_iter().filter_map(|d| self.add_doc(d)).collect();
+        // Record the run even when it produced no text so catch-up skips it
+        self.runs.insert(run_key(session_id, &run.run_id), ids);
+    }
+
+    pub fn remove_run(&mut self, session_id: &str, run_id: &str) {
+        if let Some(ids) = self.runs.remove(&run_key(session_id, run_id)) {
+            for id in ids {
+                self.remove_doc(id);
+            }
+        }
+    }
+
+    pub fn remove_session(&mut self, session_id: &str) {
+        let prefix = format!("{session_id}/");
+        let keys: Vec<String> = self
+            .runs
+            .range(prefix.clone()..)
+            .take_while(|(k, _)| k.starts_with(&prefix))
+            .map(|(k, _)| k.clone())
+            .collect();
+        for key in keys {
+            if let Some(ids) = self.runs.remove(&key) {
+                for id in ids {
+                    self.remove_doc(id);
+                }
+            }
+        }
+    }
+
+    /// Remove a session and keep it out of future catch-up passes
+    pub fn delete_session(&mut self, session_id: &str) {
+        self.remove_session(session_id);
+        self.deleted_sessions.insert(session_id.to_string());
+    }
+
+    /// Remove every session with documents from a worktree
+    pub fn remove_worktree(&mut self, worktree_id: &str) {
+        let sessions: HashSet<String> = self
+            .docs
+            .values()
+            .filter(|d| d.worktree_id == worktree_id)
+            .map(|d| d.session_id.clone())
+            .collect();
+        for session_id in sessions {
+            self.remove_session(&session_id);
+        }
+    }
+
+    /// Session IDs that have at least one indexed run
+    fn indexed_sessions(&self) -> HashSet<String> {
+        self.runs
+            .keys()
+            .filter_map(|k| k.split_once('/').map(|(s, _)| s.to_string()))
+            .collect()
+    }
+
+    /// Rank documents with BM25 (weighted per field)
+    ///
+    /// Every query term must match; each term matches index terms it is a
+    /// prefix of, so `migrat` finds `migration`. Returns `(doc, score)` pairs
+    /// sorted by descending score, at most one per message.
+    pub fn search(&self, query: &str) -> Vec<(&IndexedDoc, f64)> {
+        let terms = tokenize(query);
+        if terms.is_empty() || self.docs.is_empty() {
+            return Vec::new();
+        }
+
+        let n = self.docs.len() as f64;
+        let avg_len = self.docs.values().map(|d| d.len as f64).sum::<f64>() / n.max(1.0);
+
+        let mut scores: HashMap<u64, f64> = HashMap::new();
+        for (i, term) in terms.iter().enumerate() {
+            // doc -> summed tf over all index terms this query term prefixes
+            let mut tfs: HashMap<u64, u32> = HashMap::new();
+            let matching = self
+                .postings
+                .range(term.clone()..)
+                .take_while(|(indexed, _)| indexed.starts_with(term.as_str()));
+            for (_, postings) in matching {
+                for (&id, &tf) in postings {
+                    *tfs.entry(id).or_default() += tf;
+                }
+            }
+
+            let df = tfs.len() as f64;
+            let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+
+            if i == 0 {
+                for (&id, &tf) in &tfs {
+                    scores.insert(id, self.bm25(id, tf, idf, avg_len));
+                }
+            } else {
+                scores.retain(|id, _| tfs.contains_key(id));
+                for (id, score) in scores.iter_mut() {
+                    *score += self.bm25(*id, tfs[id], idf, avg_len);
+                }
+            }
+            if scores.is_empty() {
+                return Vec::new();
+            }
+        }
+
+        // Keep the best-scoring document per message
+        let mut best: HashMap<(&str, &str), (&IndexedDoc, f64)> = HashMap::new();
+        for (id, score) in scores {
+            let Some(doc) = self.docs.get(&id) else {
+                continue;
+            };
+            let key = (doc.session_id.as_str(), doc.message_id.as_str());
+            match best.get(&key) {
+                Some((_, s)) if *s >= score => {}
+                _ => {
+                    best.insert(key, (doc, score));
+                }
+            }
+        }
+
+        let mut hits: Vec<(&IndexedDoc, f64)> = best.into_values().collect();
+        hits.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.0.timestamp.cmp(&a.0.timestamp))
+        });
+        hits
+    }
+
+    fn bm25(&self, id: u64, tf: u32, idf: f64, avg_len: f64) -> f64 {
+        let Some(doc) = self.docs.get(&id) else {
+            return 0.0;
+        };
+        let tf = tf as f64;
+        let norm = 1.0 - BM25_B + BM25_B * (doc.len as f64 / avg_len.max(1.0));
+        doc.field.weight() * idf * (tf * (BM25_K1 + 1.0)) / (tf + BM25_K1 * norm)
+    }
+}
+
+// ============================================================================
+// Persistence
+// ============================================================================
+
+fn get_search_index_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_sessions_dir(app)?.join("search_index.json"))
+}
+
+fn load_index_from_disk(app: &AppHandle) -> SearchIndex {
+    let Ok(path) = get_search_index_path(app) else {
+        return SearchIndex::new();
+    };
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return SearchIndex::new();
+    };
+    match serde_json::from_str::<SearchIndex>(&contents) {
+        Ok(index) if index.version == INDEX_VERSION => index,
+        Ok(_) => {
+            log::trace!("Search index version changed, rebuilding");
+            SearchIndex::new()
+        }
+        Err(e) => {
+            log::warn!("Failed to parse search index, rebuilding: {e}");
+            SearchIndex::new()
+        }
+    }
+}
+
+fn save_index_to_disk(app: &AppHandle) -> Result<(), String> {
+    let _save = SAVE_LOCK.lock().unwrap();
+    // Serialize under the index lock, write after releasing it
+    let json = {
+        let guard = SEARCH_INDEX.lock().unwrap();
+        let Some(index) = guard.as_ref() else {
+            return Ok(());
+        };
+        serde_json::to_string(index)
+            .map_err(|e| format!("Failed to serialize search index: {e}"))?
+    };
+
+    let path = get_search_index_path(app)?;
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, json).map_err(|e| format!("Failed to write search index: {e}"))?;
+    fs::rename(&temp_path, &path).map_err(|e| format!("Failed to finalize search index: {e}"))?;
+
+    Ok(())
+}
+
+/// Save the index after [`SAVE_DEBOUNCE`], unless a save is already pending
+fn schedule_save(app: &AppHandle) {
+    if SAVE_SCHEDULED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    thread::spawn(move || {
+        thread::sleep(SAVE_DEBOUNCE);
+        // Cleared before saving, so later changes schedule another save
+        SAVE_SCHEDULED.store(false, Ordering::SeqCst);
+        if let Err(e) = save_index_to_disk(&app) {
+            log::warn!("Failed to save search index: {e}");
+        }
+    });
+}
+
+/// Run `f` against the loaded index; schedules a save if `f` returns true
+fn with_index<R>(app: &AppHandle, f: impl FnOnce(&mut SearchIndex) -> (R, bool)) -> R {
+    let mut guard = SEARCH_INDEX.lock().unwrap();
+    let index = guard.get_or_insert_with(|| load_index_from_disk(app));
+    let (result, changed) = f(index);
+    drop(guard);
+    if changed {
+        schedule_save(app);
+    }
+    result
+}
+
+/// Parse a completed run from its log and add it to an index
+fn index_run_into(
+    app: &AppHandle,
+    index: &mut SearchIndex,
+    metadata: &SessionMetadata,
+    run: &RunEntry,
+) -> Result<(), String> {
+    let lines = read_run_log(app, &metadata.id, &run.run_id)?;
+    let mut message = parse_run_to_message(&lines, run)?;
+    message.session_id = metadata.id.clone();
+    index.add_run(&metadata.worktree_id, run, &message);
+    Ok(())
+}
+
+/// Add a completed run to the search index
+pub fn index_run(app: &AppHandle, session_id: &str, run_id: &str) -> Result<(), String> {
+    let metadata = load_metadata(app, session_id)?
+        .ok_or_else(|| format!("Session not found: {session_id}"))?;
+    let run = metadata
+        .find_run(run_id)
+        .ok_or_else(|| format!("Run not found: {run_id}"))?
+        .clone();
+
+    with_index(app, |index| {
+        let result = index_run_into(app, index, &metadata, &run);
+        let changed = result.is_ok();
+        (result, changed)
+    })
+}
+
+/// Index a run on a background thread (called when a run completes)
+pub fn index_run_in_background(app: &AppHandle, session_id: &str, run_id: &str) {
+    let app = app.clone();
+    let session_id = session_id.to_string();
+    let run_id = run_id.to_string();
+    std::thread::spawn(move || {
+        if let Err(e) = index_run(&app, &session_id, &run_id) {
+            log::warn!("Failed to index run {run_id} for search: {e}");
+        }
+    });
+}
+
+/// Index all completed runs of a session on a background thread (after import or fork)
+pub fn index_session_in_background(app: &AppHandle, session_id: &str) {
+    let app = app.clone();
+    let session_id = session_id.to_string();
+    std::thread::spawn(move || {
+        let result = load_metadata(&app, &session_id).and_then(|metadata| {
+            let Some(metadata) = metadata else {
+                return Ok(());
+            };
+            with_index(&app, |index| {
+                for run in &metadata.runs {
+                    if run.status != RunStatus::Completed {
+                        continue;
+                    }
+                    if let Err(e) = index_run_into(&app, index, &metadata, run) {
+                        log::warn!("Skipping run {} in search index: {e}", run.run_id);
+                    }
+                }
+                ((), true)
+            });
+            Ok(())
+        });
+        if let Err(e) = result {
+            log::warn!("Failed to index session {session_id} for search: {e}");
+        }
+    });
+}
+
+/// Drop a session from the search index on a background thread (when its
+/// data is deleted)
+pub fn remove_session_from_index(app: &AppHandle, session_id: &str) {
+    let app = app.clone();
+    let session_id = session_id.to_string();
+    thread::spawn(move || {
+        with_index(&app, |index| {
+            let had = index.indexed_sessions().contains(&session_id);
+            index.remove_session(&session_id);
+            ((), had)
+        });
+    });
+}
+
+/// Drop permanently deleted (archived) sessions from the search index on a
+/// background thread
+pub fn delete_sessions_from_index(app: &AppHandle, session_ids: Vec<String>) {
+    if session_ids.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    thread::spawn(move || {
+        with_index(&app, |index| {
+            for session_id in &session_ids {
+                index.delete_session(session_id);
+            }
+            ((), true)
+        });
+    });
+}
+
+/// Drop every session of a deleted worktree from the search index on a
+/// background thread
+pub fn remove_worktree_from_index(app: &AppHandle, worktree_id: &str) {
+    let app = app.clone();
+    let worktree_id = worktree_id.to_string();
+    thread::spawn(move || {
+        with_index(&app, |index| {
+            let before = index.doc_count();
+            index.remove_worktree(&worktree_id);
+            ((), index.doc_count() != before)
+        });
+    });
+}
+
+/// Index every completed run not yet in the index and drop deleted sessions
+fn sync_index(app: &AppHandle) -> Result<(), String> {
+    let projects = load_projects_data(app)?;
+    let session_ids = list_all_session_ids(app)?;
+    let mut sessions = Vec::new();
+    for session_id in &session_ids {
+        if let Ok(Some(metadata)) = load_metadata(app, session_id) {
+            sessions.push(metadata);
+        }
+    }
+
+    with_index(app, |index| {
+        let mut changed = false;
+
+        // Tombstones are only needed while the session's data is on disk
+        let on_disk: HashSet<&str> = session_ids.iter().map(String::as_str).collect();
+        let tombstones = index.deleted_sessions.len();
+        index
+            .deleted_sessions
+            .retain(|id| on_disk.contains(id.as_str()));
+        changed |= index.deleted_sessions.len() != tombstones;
+
+        sessions.retain(|m| {
+            projects.find_worktree(&m.worktree_id).is_some()
+                && !index.deleted_sessions.contains(&m.id)
+        });
+        let live: HashSet<&str> = sessions.iter().map(|m| m.id.as_str()).collect();
+        for stale in index.indexed_sessions() {
+            if !live.contains(stale.as_str()) {
+                index.remove_session(&stale);
+                changed = true;
+            }
+        }
+
+        for metadata in &sessions {
+            for run in &metadata.runs {
+                if run.status != RunStatus::Completed
+                    || index.is_run_indexed(&metadata.id, &run.run_id)
+                {
+                    continue;
+                }
+                match index_run_into(app, index, metadata, run) {
+                    Ok(()) => changed = true,
+                    Err(e) => log::warn!("Skipping run {} in search index: {e}", run.run_id),
+                }
+            }
+        }
+
+        log::trace!("Search index synced: {} documents", index.doc_count());
+        ((), changed)
+    });
+    Ok(())
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Search all session histories (including archived sessions)
+///
+/// Returns hits ranked by relevance, at most one per message.
+#[tauri::command]
+pub async fn search_sessions(
+    app: AppHandle,
+    query: String,
+    filter: Option<SearchFilter>,
+    limit: Option<usize>,
+) -> Result<Vec<SearchHit>, String> {
+    let filter = filter.unwrap_or_default();
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    log::trace!("Searching sessions for {query:?} ({filter:?})");
+
+    // Loading and syncing the index reads session files
+    tauri::async_runtime::spawn_blocking(move || search_index(&app, &query, &filter, limit))
+        .await
+        .map_err(|e| format!("Search failed: {e}"))?
+}
+
+/// Body of [`search_sessions`], run on a blocking thread
+fn search_index(
+    app: &AppHandle,
+    query: &str,
+    filter: &SearchFilter,
+    limit: usize,
+) -> Result<Vec<SearchHit>, String> {
+    if !INDEX_SYNCED.swap(true, Ordering::SeqCst) {
+        if let Err(e) = sync_index(app) {
+            INDEX_SYNCED.store(false, Ordering::SeqCst);
+            return Err(e);
+        }
+    }
+
+    let projects = load_projects_data(app)?;
+    let terms = tokenize(query);
+    let include_archived = filter.include_archived.unwrap_or(true);
+    let mut metadata_cache: HashMap<String, Option<SessionMetadata>> = HashMap::new();
+
+    let hits = with_index(app, |index| {
+        let mut hits = Vec::new();
+
+        for (doc, score) in index.search(query) {
+            if hits.len() >= limit {
+                break;
+            }
+            if filter
+                .worktree_id
+                .as_deref()
+                .is_some_and(|id| id != doc.worktree_id)
+            {
+                continue;
+            }
+
+            let worktree = projects.find_worktree(&doc.worktree_id);
+            let project_id = worktree.map(|w| w.project_id.clone());
+            if filter.project_id.is_some() && filter.project_id != project_id {
+                continue;
+            }
+
+            let metadata = metadata_cache
+                .entry(doc.session_id.clone())
+                .or_insert_with(|| load_metadata(app, &doc.session_id).ok().flatten());
+            let Some(metadata) = metadata else {
+                continue;
+            };
+            let archived = metadata.archived_at.is_some();
+            if archived && !include_archived {
+                continue;
+            }
+
+            hits.push(SearchHit {
+                session_id: doc.session_id.clone(),
+                session_name: metadata.name.clone(),
+                worktree_id: doc.worktree_id.clone(),
+                worktree_name: worktree.map(|w| w.name.clone()),
+                worktree_path: worktree.map(|w| w.path.clone()),
+                project_name: project_id
+                    .as_deref()
+                    .and_then(|id| projects.find_project(id))
+                    .map(|p| p.name.clone()),
+                project_id,
+                run_id: doc.run_id.clone(),
+                message_id: doc.message_id.clone(),
+                field: doc.field,
+                snippet: make_snippet(&doc.text, &terms),
+                score,
+                timestamp: doc.timestamp,
+                archived,
+            });
+        }
+
+        (hits, false)
+    });
+    Ok(hits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::types::{MessageRole, ToolCall};
+
+    fn run(run_id: &str, user_message: &str) -> RunEntry {
+        RunEntry {
+            run_id: run_id.to_string(),
+            user_message_id: format!("{run_id}-user"),
+            user_message: user_message.to_string(),
+            model: None,
+            execution_mode: None,
+            thinking_level: None,
+            started_at: 100,
+            ended_at: Some(200),
+            status: RunStatus::Completed,
+            assistant_message_id: Some(format!("{run_id}-assistant")),
+            cancelled: false,
+            recovered: false,
+            claude_session_id: None,
+            pid: None,
+            usage: None,
+        }
+    }
+
+    fn message(session_id: &str, run_id: &str, content: &str, tools: Vec<ToolCall>) -> ChatMessage {
+        ChatMessage {
+            id: format!("{run_id}-assistant"),
+            session_id: session_id.to_string(),
+            role: MessageRole::Assistant,
+            content: content.to_string(),
+            timestamp: 200,
+            tool_calls: tools,
+            ..Default::default()
+        }
+    }
+
+    fn tool(name: &str, input: serde_json::Value) -> ToolCall {
+        ToolCall {
+            id: format!("tool-{name}"),
+            name: name.to_string(),
+            input,
+            output: None,
+            parent_tool_use_id: None,
+        }
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("Fix the load_session_messages() bug, a x"),
+            vec!["fix", "the", "load", "session", "messages", "bug"]
+        );
+        assert_eq!(tokenize("Ünïcode Straße"), vec!["ünïcode", "straße"]);
+        assert!(tokenize(&"a".repeat(100)).is_empty());
+    }
+
+    #[test]
+    fn test_search_ranks_and_requires_all_terms() {
+        let mut index = SearchIndex::new();
+        index.add_run(
+            "wt-1",
+            &run("r1", "the migration is failing"),
+            &message("s1", "r1", "Fixed the migration bug in schema.rs", vec![]),
+        );
+        index.add_run(
+            "wt-2",
+            &run("r2", "add a button"),
+            &message("s2", "r2", "Added the button. No migration needed.", vec![]),
+        );
+
+        let hits = index.search("migration bug");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0.session_id, "s1");
+
+        // Prefix match, and user messages outrank incidental assistant mentions
+        let hits = index.search("migrat");
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].0.session_id, "s1");
+
+        assert!(index.search("nonexistent").is_empty());
+        assert!(index.search("  ").is_empty());
+    }
+
+    #[test]
+    fn test_indexes_tool_names_and_inputs() {
+        let mut index = SearchIndex::new();
+        index.add_run(
+            "wt-1",
+            &run("r1", "look around"),
+            &message(
+                "s1",
+                "r1",
+                "Done",
+                vec![tool(
+                    "Grep",
+                    serde_json::json!({ "pattern": "parse_config", "path": "src/" }),
+                )],
+            ),
+        );
+
+        let hits = index.search("grep");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0.field, SearchField::ToolName);
+
+        let hits = index.search("parse config");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0.field, SearchField::ToolInput);
+        assert_eq!(hits[0].0.message_id, "r1-assistant");
+    }
+
+    #[test]
+    fn test_reindex_and_remove() {
+        let mut index = SearchIndex::new();
+        index.add_run(
+            "wt-1",
+            &run("r1", "first"),
+            &message("s1", "r1", "alpha", vec![]),
+        );
+        index.add_run(
+            "wt-1",
+            &run("r1", "first"),
+            &message("s1", "r1", "beta", vec![]),
+        );
+        assert!(index.search("alpha").is_empty());
+        assert_eq!(index.search("beta").len(), 1);
+        assert!(index.is_run_indexed("s1", "r1"));
+
+        index.remove_session("s1");
+        assert!(!index.is_run_indexed("s1", "r1"));
+        assert_eq!(index.doc_count(), 0);
+        assert!(index.postings.is_empty());
+    }
+
+    #[test]
+    fn test_remove_worktree_and_prefix_keys() {
+        let mut index = SearchIndex::new();
+        index.add_run("wt-1", &run("r1", "one"), &message("s1", "r1", "a", vec![]));
+        index.add_run(
+            "wt-1",
+            &run("r2", "two"),
+            &message("s10", "r2", "b", vec![]),
+        );
+        index.add_run(
+            "wt-2",
+            &run("r3", "three"),
+            &message("s2", "r3", "c", vec![]),
+        );
+
+        // "s1/" must not match runs of "s10"
+        index.remove_session("s1");
+        assert!(index.is_run_indexed("s10", "r2"));
+
+        index.remove_worktree("wt-1");
+        assert!(!index.is_run_indexed("s10", "r2"));
+        assert!(index.is_run_indexed("s2", "r3"));
+        assert_eq!(index.search("three").len(), 1);
+        assert!(index.search("two").is_empty());
+
+        index.delete_session("s2");
+        assert_eq!(index.doc_count(), 0);
+        assert!(index.deleted_sessions.contains("s2"));
+    }
+
+    #[test]
+    fn test_index_roundtrip() {
+        let mut index = SearchIndex::new();
+        index.add_run(
+            "wt-1",
+            &run("r1", "persist me"),
+            &message("s1", "r1", "ok", vec![]),
+        );
+        let json = serde_json::to_string(&index).unwrap();
+        let loaded: SearchIndex = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.version, INDEX_VERSION);
+        assert_eq!(loaded.search("persist").len(), 1);
+    }
+
+    #[test]
+    fn test_make_snippet() {
+        let text = format!(
+            "{} the migration failed {}",
+            "x ".repeat(100),
+            "y ".repeat(100)
+        );
+        let snippet = make_snippet(&text, &["migration".to_string()]);
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("the migration failed"));
+
+        assert_eq!(
+            make_snippet("short text", &["short".to_string()]),
+            "short text"
+        );
+    }
+}
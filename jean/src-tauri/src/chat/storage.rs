@@ -267,6 +267,7 @@ fn save_metadata_internal(app: &AppHandle, metadata: &SessionMetadata) -> Result
         .map_err(|e| format!("Failed to write metadata: {e}"))?;
 
     fs::rename(&temp_path, &path).map_err(|e| format!("Failed to rename metadata file: {e}"))?;
+    super::budget::record_session_usage(metadata);
 
     log::trace!("Saved metadata for session: {}", metadata.id);
     Ok(())
@@ -328,6 +329,7 @@ pub fn delete_session_data(app: &AppHandle, session_id: &str) -> Result<(), Stri
     if session_dir.exists() {
         fs::remove_dir_all(&session_dir)
             .map_err(|e| format!("Failed to delete session directory: {e}"))?;
+        super::budget::forget_session_usage(session_id);
         log::trace!("Deleted session data for: {session_id}");
     }
 
@@ -403,6 +405,7 @@ pub fn load_sessions(
                 plan_file_path: None,
                 pending_plan_message_id: None,
                 digest: None,
+                forked_from: None,
                 last_run_status: None,
                 last_run_execution_mode: None,
             }
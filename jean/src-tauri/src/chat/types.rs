@@ -14,6 +14,63 @@ pub struct SessionDigest {
     pub last_action: String,
 }
 
+// ============================================================================
+// Session Fork Types
+// ============================================================================
+
+/// Where a forked session was branched off
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForkOrigin {
+    /// Session the history was copied from
+    pub session_id: String,
+    /// Worktree of the source session
+    pub worktree_id: String,
+    /// Message the fork was made at
+    pub message_id: String,
+    /// Unix timestamp of the fork
+    pub forked_at: u64,
+}
+
+/// How a forked session's first run picks up the copied history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ForkStart {
+    /// Resume the source's Claude CLI session with `--fork-session`
+    Resume { claude_session_id: String },
+    /// Replay the copied history as context (Markdown transcript file)
+    Replay { context_file: String },
+}
+
+// ============================================================================
+// Message Queue Types
+// ============================================================================
+
+/// A follow-up message waiting for the session's current run to finish.
+/// Carries the send options so it can be dispatched without the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedMessage {
+    /// Unique queue item identifier (UUID v4)
+    pub id: String,
+    /// Message text to send
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking_level: Option<ThinkingLevel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disable_thinking_for_mode: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallel_execution_prompt_enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai_language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
+    /// Unix timestamp when the message was queued
+    pub queued_at: u64,
+}
+
 // ============================================================================
 // Compaction Types
 // ============================================================================
@@ -325,6 +382,9 @@ pub struct Session {
     /// Persisted session digest (recap summary)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub digest: Option<SessionDigest>,
+    /// Source of this session if it was forked from another one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forked_from: Option<ForkOrigin>,
 
     // ========================================================================
     // Run recovery state (for showing correct status on app restart)
@@ -368,6 +428,7 @@ impl Session {
             plan_file_path: None,
             pending_plan_message_id: None,
             digest: None,
+            forked_from: None,
             last_run_status: None,
             last_run_execution_mode: None,
         }
@@ -513,6 +574,7 @@ impl SessionMetadata {
             plan_file_path: self.plan_file_path.clone(),
             pending_plan_message_id: self.pending_plan_message_id.clone(),
             digest: self.digest.clone(),
+            forked_from: self.forked_from.clone(),
             // Populate from last run for status recovery on app restart
             last_run_status: last_run.map(|r| r.status.clone()),
             last_run_execution_mode: last_run.and_then(|r| r.execution_mode.clone()),
@@ -808,6 +870,16 @@ pub struct SessionMetadata {
     /// Persisted session digest (recap summary)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub digest: Option<SessionDigest>,
+    /// Source of this session if it was forked from another one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forked_from: Option<ForkOrigin>,
+    /// How the first run of a forked session continues the copied history
+    /// (only used while the session has no Claude session of its own)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fork_start: Option<ForkStart>,
+    /// Follow-up messages to send when the current run completes (FIFO)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub message_queue: Vec<QueuedMessage>,
 
     /// Run history - each entry corresponds to one Claude CLI execution
     #[serde(default)]
@@ -893,6 +965,9 @@ impl SessionMetadata {
             plan_file_path: None,
             pending_plan_message_id: None,
             digest: None,
+            forked_from: None,
+            fork_start: None,
+            message_queue: vec![],
             runs: vec![],
             version: 1,
         }
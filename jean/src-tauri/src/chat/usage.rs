@@ -0,0 +1,519 @@
+//! Token usage and cost accounting
+//!
+//! Aggregates the [`UsageData`] recorded on every [`RunEntry`] into totals per
+//! session, worktree, project and (UTC) day. Costs are computed from the
+//! per-model price table in preferences (`model_pricing`).
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::storage::{list_all_session_ids, load_metadata};
+use super::types::{RunEntry, SessionMetadata, UsageData};
+use crate::projects::storage::load_projects_data;
+use crate::projects::types::ProjectsData;
+
+// ============================================================================
+// Pricing
+// ============================================================================
+
+/// Price of a model in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    #[serde(default)]
+    pub cache_read_per_mtok: f64,
+    #[serde(default)]
+    pub cache_write_per_mtok: f64,
+}
+
+impl ModelPricing {
+    /// Cost in USD of the given usage at this price
+    pub fn cost(&self, usage: &UsageData) -> f64 {
+        (usage.input_tokens as f64 * self.input_per_mtok
+            + usage.output_tokens as f64 * self.output_per_mtok
+            + usage.cache_read_input_tokens as f64 * self.cache_read_per_mtok
+            + usage.cache_creation_input_tokens as f64 * self.cache_write_per_mtok)
+            / 1_000_000.0
+    }
+}
+
+/// Default price table, keyed by the model aliases passed to the Claude CLI
+pub fn default_model_pricing() -> HashMap<String, ModelPricing> {
+    HashMap::from([
+        (
+            "opus".to_string(),
+            ModelPricing {
+                input_per_mtok: 5.0,
+                output_per_mtok: 25.0,
+                cache_read_per_mtok: 0.5,
+                cache_write_per_mtok: 6.25,
+            },
+        ),
+        (
+            "sonnet".to_string(),
+            ModelPricing {
+                input_per_mtok: 3.0,
+                output_per_mtok: 15.0,
+                cache_read_per_mtok: 0.3,
+                cache_write_per_mtok: 3.75,
+            },
+        ),
+        (
+            "haiku".to_string(),
+            ModelPricing {
+                input_per_mtok: 1.0,
+                output_per_mtok: 5.0,
+                cache_read_per_mtok: 0.1,
+                cache_write_per_mtok: 1.25,
+            },
+        ),
+    ])
+}
+
+/// Find the price for a model
+///
+/// Tries an exact match first, then any alias contained in the model name
+/// (so `claude-sonnet-4-5` uses the `sonnet` price).
+pub fn find_pricing<'a>(
+    pricing: &'a HashMap<String, ModelPricing>,
+    model: &str,
+) -> Option<&'a ModelPricing> {
+    let model = model.to_lowercase();
+    pricing.get(&model).or_else(|| {
+        pricing
+            .iter()
+            .filter(|(alias, _)| model.contains(alias.to_lowercase().as_str()))
+            .max_by_key(|(alias, _)| alias.len())
+            .map(|(_, price)| price)
+    })
+}
+
+// ============================================================================
+// Report Types
+// ============================================================================
+
+/// Aggregated token usage and cost
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageTotals {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_input_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    /// Estimated cost in USD
+    pub cost_usd: f64,
+    /// Number of runs with usage data
+    pub run_count: u32,
+    /// Runs whose model had no price (their tokens count, their cost does not)
+    pub unpriced_run_count: u32,
+}
+
+impl UsageTotals {
+    fn add(&mut self, usage: &UsageData, cost: Option<f64>) {
+        self.input_tokens += usage.input_tokens;
+        self.output_tokens += usage.output_tokens;
+        self.cache_read_input_tokens += usage.cache_read_input_tokens;
+        self.cache_creation_input_tokens += usage.cache_creation_input_tokens;
+        self.run_count += 1;
+        match cost {
+            Some(cost) => self.cost_usd += cost,
+            None => self.unpriced_run_count += 1,
+        }
+    }
+}
+
+/// Usage for one session, worktree, project, model or day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageBucket {
+    /// Session/worktree/project ID, model name, or `YYYY-MM-DD` for days
+    pub key: String,
+    /// Display name (session, worktree or project name when known)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Owning worktree (session buckets only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree_id: Option<String>,
+    /// Owning project (session and worktree buckets only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    pub totals: UsageTotals,
+}
+
+/// Usage roll-up returned by `get_usage_report`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageReport {
+    pub total: UsageTotals,
+    pub by_project: Vec<UsageBucket>,
+    pub by_worktree: Vec<UsageBucket>,
+    pub by_session: Vec<UsageBucket>,
+    pub by_model: Vec<UsageBucket>,
+    /// Sorted chronologically (oldest first)
+    pub by_day: Vec<UsageBucket>,
+}
+
+/// Restricts which runs are counted in a report
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UsageFilter {
+    #[serde(default)]
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub worktree_id: Option<String>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Only runs started at or after this Unix timestamp
+    #[serde(default)]
+    pub since: Option<u64>,
+    /// Only runs started before this Unix timestamp
+    #[serde(default)]
+    pub until: Option<u64>,
+}
+
+impl UsageFilter {
+    fn matches_run(&self, run: &RunEntry) -> bool {
+        self.since.is_none_or(|since| run.started_at >= since)
+            && self.until.is_none_or(|until| run.started_at < until)
+    }
+}
+
+// ============================================================================
+// Aggregation
+// ============================================================================
+
+/// Format a Unix timestamp as a UTC `YYYY-MM-DD` date
+pub fn utc_day(timestamp: u64) -> String {
+    // Civil-from-days (Howard Hinnant's algorithm)
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Aggregate usage over a set of sessions
+///
+/// `projects` is used to map worktrees to projects and to fill in display
+/// names. Sessions of deleted worktrees are reported without a project.
+pub fn aggregate_usage(
+    sessions: &[SessionMetadata],
+    projects: &ProjectsData,
+    pricing: &HashMap<String, ModelPricing>,
+    filter: &UsageFilter,
+) -> UsageReport {
+    let mut total = UsageTotals::default();
+    let mut by_project: HashMap<String, UsageBucket> = HashMap::new();
+    let mut by_worktree: HashMap<String, UsageBucket> = HashMap::new();
+    let mut by_session: HashMap<String, UsageBucket> = HashMap::new();
+    let mut by_model: HashMap<String, UsageBucket> = HashMap::new();
+    let mut by_day: HashMap<String, UsageBucket> = HashMap::new();
+
+    for session in sessions {
+        let worktree = projects.find_worktree(&session.worktree_id);
+        let project_id = worktree.map(|w| w.project_id.clone());
+
+        if filter
+            .session_id
+            .as_ref()
+            .is_some_and(|id| *id != session.id)
+            || filter
+                .worktree_id
+                .as_ref()
+                .is_some_and(|id| *id != session.worktree_id)
+            || filter
+                .project_id
+                .as_ref()
+                .is_some_and(|id| Some(id) != project_id.as_ref())
+        {
+            continue;
+        }
+
+        for run in &session.runs {
+            let Some(usage) = &run.usage else {
+                continue;
+            };
+            if !filter.matches_run(run) {
+                continue;
+            }
+
+            let model = run.model.as_deref().unwrap_or("unknown");
+            let cost = find_pricing(pricing, model).map(|p| p.cost(usage));
+
+            total.add(usage, cost);
+
+            by_session
+                .entry(session.id.clone())
+                .or_insert_with(|| UsageBucket {
+                    key: session.id.clone(),
+                    name: Some(session.name.clone()),
+                    worktree_id: Some(session.worktree_id.clone()),
+                    project_id: project_id.clone(),
+                    totals: UsageTotals::default(),
+                })
+                .totals
+                .add(usage, cost);
+
+            by_worktree
+                .entry(session.worktree_id.clone())
+                .or_insert_with(|| UsageBucket {
+                    key: session.worktree_id.clone(),
+                    name: worktree.map(|w| w.name.clone()),
+                    worktree_id: None,
+                    project_id: project_id.clone(),
+                    totals: UsageTotals::default(),
+                })
+                .totals
+                .add(usage, cost);
+
+            if let Some(project_id) = &project_id {
+                by_project
+                    .entry(project_id.clone())
+                    .or_insert_with(|| UsageBucket {
+                        key: project_id.clone(),
+                        name: projects.find_project(project_id).map(|p| p.name.clone()),
+                        worktree_id: None,
+                        project_id: None,
+                        totals: UsageTotals::default(),
+                    })
+                    .totals
+                    .add(usage, cost);
+            }
+
+            by_model
+                .entry(model.to_string())
+                .or_insert_with(|| simple_bucket(model.to_string()))
+                .totals
+                .add(usage, cost);
+
+            let day = utc_day(run.started_at);
+            by_day
+                .entry(day.clone())
+                .or_insert_with(|| simple_bucket(day))
+                .totals
+                .add(usage, cost);
+        }
+    }
+
+    let mut by_day: Vec<UsageBucket> = by_day.into_values().collect();
+    by_day.sort_by(|a, b| a.key.cmp(&b.key));
+
+    UsageReport {
+        total,
+        by_project: sorted_by_cost(by_project),
+        by_worktree: sorted_by_cost(by_worktree),
+        by_session: sorted_by_cost(by_session),
+        by_model: sorted_by_cost(by_model),
+        by_day,
+    }
+}
+
+fn simple_bucket(key: String) -> UsageBucket {
+    UsageBucket {
+        key,
+        name: None,
+        worktree_id: None,
+        project_id: None,
+        totals: UsageTotals::default(),
+    }
+}
+
+/// Most expensive first, then most output tokens
+fn sorted_by_cost(buckets: HashMap<String, UsageBucket>) -> Vec<UsageBucket> {
+    let mut buckets: Vec<UsageBucket> = buckets.into_values().collect();
+    buckets.sort_by(|a, b| {
+        b.totals
+            .cost_usd
+            .total_cmp(&a.totals.cost_usd)
+            .then(b.totals.output_tokens.cmp(&a.totals.output_tokens))
+    });
+    buckets
+}
+
+/// Load every session's metadata (including archived sessions)
+pub fn load_all_session_metadata(app: &AppHandle) -> Result<Vec<SessionMetadata>, String> {
+    let mut sessions = Vec::new();
+    for session_id in list_all_session_ids(app)? {
+        match load_metadata(app, &session_id) {
+            Ok(Some(metadata)) => sessions.push(metadata),
+            Ok(None) => {}
+            Err(e) => log::warn!("Failed to load metadata for session {session_id}: {e}"),
+        }
+    }
+    Ok(sessions)
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Roll up token usage and estimated cost across sessions
+///
+/// All filter fields are optional; an empty filter covers every session in
+/// every project, archived ones included.
+#[tauri::command]
+pub async fn get_usage_report(
+    app: AppHandle,
+    filter: Option<UsageFilter>,
+) -> Result<UsageReport, String> {
+    let filter = filter.unwrap_or_default();
+    log::trace!("Building usage report: {filter:?}");
+
+    let prefs = crate::load_preferences(app.clone()).await?;
+    let projects = load_projects_data(&app)?;
+    let sessions = load_all_session_metadata(&app)?;
+
+    Ok(aggregate_usage(
+        &sessions,
+        &projects,
+        &prefs.model_pricing,
+        &filter,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::types::RunStatus;
+    use crate::projects::types::{Project, Worktree};
+
+    fn run(model: &str, started_at: u64, input: u64, output: u64) -> RunEntry {
+        RunEntry {
+            run_id: format!("run-{started_at}"),
+            user_message_id: "msg".to_string(),
+            user_message: "hello".to_string(),
+            model: Some(model.to_string()),
+            execution_mode: None,
+            thinking_level: None,
+            started_at,
+            ended_at: Some(started_at + 10),
+            status: RunStatus::Completed,
+            assistant_message_id: None,
+            cancelled: false,
+            recovered: false,
+            claude_session_id: None,
+            pid: None,
+            usage: Some(UsageData {
+                input_tokens: input,
+                output_tokens: output,
+                cache_read_input_tokens: 0,
+                cache_creation_input_tokens: 0,
+            }),
+        }
+    }
+
+    fn projects() -> ProjectsData {
+        let project: Project = serde_json::from_value(serde_json::json!({
+            "id": "p1",
+            "name": "Project One",
+            "path": "/tmp/p1",
+            "default_branch": "main",
+            "added_at": 0
+        }))
+        .unwrap();
+        let worktree: Worktree = serde_json::from_value(serde_json::json!({
+            "id": "w1",
+            "project_id": "p1",
+            "name": "fuzzy-tiger",
+            "path": "/tmp/p1-fuzzy-tiger",
+            "branch": "fuzzy-tiger",
+            "created_at": 0
+        }))
+        .unwrap();
+        ProjectsData {
+            projects: vec![project],
+            worktrees: vec![worktree],
+        }
+    }
+
+    #[test]
+    fn test_utc_day() {
+        assert_eq!(utc_day(0), "1970-01-01");
+        assert_eq!(utc_day(951_782_400), "2000-02-29");
+        assert_eq!(utc_day(1_700_000_000), "2023-11-14");
+    }
+
+    #[test]
+    fn test_find_pricing_matches_aliases() {
+        let pricing = default_model_pricing();
+        assert_eq!(find_pricing(&pricing, "opus"), pricing.get("opus"));
+        assert_eq!(
+            find_pricing(&pricing, "claude-sonnet-4-5"),
+            pricing.get("sonnet")
+        );
+        assert!(find_pricing(&pricing, "gpt-4").is_none());
+    }
+
+    #[test]
+    fn test_aggregate_usage() {
+        let mut session = SessionMetadata::new(
+            "s1".to_string(),
+            "w1".to_string(),
+            "Session 1".to_string(),
+            0,
+        );
+        session.runs = vec![
+            run("opus", 1_700_000_000, 1_000_000, 0),
+            run("haiku", 1_700_100_000, 0, 1_000_000),
+            run("mystery", 1_700_100_000, 10, 10),
+        ];
+        let orphan = {
+            let mut s = SessionMetadata::new(
+                "s2".to_string(),
+                "deleted".to_string(),
+                "Session 2".to_string(),
+                0,
+            );
+            s.runs = vec![run("sonnet", 1_700_000_000, 0, 0)];
+            s
+        };
+
+        let report = aggregate_usage(
+            &[session, orphan],
+            &projects(),
+            &default_model_pricing(),
+            &UsageFilter::default(),
+        );
+
+        assert_eq!(report.total.run_count, 4);
+        assert_eq!(report.total.unpriced_run_count, 1);
+        assert!((report.total.cost_usd - 10.0).abs() < 1e-9);
+        assert_eq!(report.by_project.len(), 1);
+        assert_eq!(report.by_project[0].name.as_deref(), Some("Project One"));
+        assert_eq!(report.by_project[0].totals.run_count, 3);
+        assert_eq!(report.by_worktree.len(), 2);
+        assert_eq!(report.by_session.len(), 2);
+        let days: Vec<&str> = report.by_day.iter().map(|d| d.key.as_str()).collect();
+        assert_eq!(days, vec!["2023-11-14", "2023-11-16"]);
+    }
+
+    #[test]
+    fn test_aggregate_usage_filters() {
+        let mut session = SessionMetadata::new(
+            "s1".to_string(),
+            "w1".to_string(),
+            "Session 1".to_string(),
+            0,
+        );
+        session.runs = vec![run("opus", 100, 10, 10), run("opus", 200, 10, 10)];
+
+        let filter = UsageFilter {
+            project_id: Some("p1".to_string()),
+            since: Some(150),
+            ..Default::default()
+        };
+        let report = aggregate_usage(&[session.clone()], &projects(), &HashMap::new(), &filter);
+        assert_eq!(report.total.run_count, 1);
+
+        let filter = UsageFilter {
+            project_id: Some("other".to_string()),
+            ..Default::default()
+        };
+        let report = aggregate_usage(&[session], &projects(), &HashMap::new(), &filter);
+        assert_eq!(report.total.run_count, 0);
+    }
+}
@@ -0,0 +1,187 @@
+//! Headless mode
+//!
+//! `jean --headless [--port <port>] [--bind-all]` runs the backend without
+//! creating a window: preferences and projects are loaded, the HTTP server,
+//! background polling and the scheduler start as usual, and remote clients
+//! use the web UI. Without `--bind-all` the server keeps the "localhost only"
+//! preference, so a build box can be reached through an SSH tunnel. Earlier
+//! versions always listened on all interfaces in headless mode; the startup
+//! banner says which applies, along with the URL and access token.
+//!
+//! On Linux the Tauri runtime still initialises GTK, so a machine without a
+//! display needs one (e.g. `xvfb-run jean --headless`) even though nothing is
+//! shown; [`check_display`] refuses to start with that hint rather than let
+//! GTK abort. A display-free server would have to run the backend without
+//! `tauri::Builder`, which every command and store is built on (they resolve
+//! paths and emit events through the `AppHandle`), so it isn't offered.
+//!
+//! SIGTERM and Ctrl+C stop the HTTP server, mark chat runs whose CLI process
+//! is still alive as resumable, kill in-process terminals and exit. Terminals
+//! owned by a terminal host keep running.
+
+use tauri::AppHandle;
+
+/// Options for running without a window
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HeadlessOptions {
+    /// Overrides the `http_server_port` preference
+    pub port: Option<u16>,
+    /// Listen on all interfaces instead of honouring "localhost only"
+    pub bind_all: bool,
+}
+
+/// Parse headless options from the command line
+///
+/// Returns `None` unless `--headless` is present. `--port` and `--bind-all`
+/// are only accepted together with `--headless`; other arguments are left
+/// for Tauri and the OS (e.g. `-psn_*` on macOS).
+pub fn parse_args(args: &[String]) -> Result<Option<HeadlessOptions>, String> {
+    let mut headless = false;
+    let mut options = HeadlessOptions::default();
+    let mut iter = args.iter().skip(1);
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--headless" => headless = true,
+            "--bind-all" => options.bind_all = true,
+            "--port" => {
+                let value = iter.next().ok_or("--port requires a value")?;
+                options.port = Some(parse_port(value)?);
+            }
+            _ => {
+                if let Some(value) = arg.strip_prefix("--port=") {
+                    options.port = Some(parse_port(value)?);
+                }
+            }
+        }
+    }
+
+    if !headless {
+        if options != HeadlessOptions::default() {
+            return Err("--port and --bind-all require --headless".to_string());
+        }
+        return Ok(None);
+    }
+    Ok(Some(options))
+}
+
+fn parse_port(value: &str) -> Result<u16, String> {
+    match value.parse::<u16>() {
+        Ok(port) if port != 0 => Ok(port),
+        _ => Err(format!("Invalid port: {value}")),
+    }
+}
+
+/// Fail early when GTK has no display to connect to
+#[cfg(target_os = "linux")]
+pub fn check_display() -> Result<(), String> {
+    let set = |name: &str| std::env::var_os(name).is_some_and(|v| !v.is_empty());
+    if set("DISPLAY") || set("WAYLAND_DISPLAY") {
+        return Ok(());
+    }
+    Err("No display found (DISPLAY and WAYLAND_DISPLAY are unset). \
+         Headless mode still needs one on Linux; run it under a virtual \
+         display, e.g. `xvfb-run jean --headless`."
+        .to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn check_display() -> Result<(), String> {
+    Ok(())
+}
+
+/// Shut down cleanly when the process receives SIGTERM or Ctrl+C
+pub fn spawn_shutdown_handler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        wait_for_shutdown_signal().await;
+        log::info!("Shutdown signal received, stopping Jean");
+        shutdown(&app).await;
+        app.exit(0);
+    });
+}
+
+#[cfg(unix)]
+async fn wait_for_shutdown_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    match signal(SignalKind::terminate()) {
+        Ok(mut sigterm) => {
+            tokio::select! {
+                _ = sigterm.recv() => {}
+                _ = tokio::signal::ctrl_c() => {}
+            }
+        }
+        Err(e) => {
+            log::warn!("Failed to listen for SIGTERM: {e}");
+            let _ = tokio::signal::ctrl_c().await;
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_shutdown_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+async fn shutdown(app: &AppHandle) {
+    if let Err(e) = crate::stop_http_server(app.clone()).await {
+        log::warn!("Failed to stop HTTP server: {e}");
+    }
+
+    match crate::chat::run_log::mark_running_runs_resumable(app) {
+        Ok(runs) if !runs.is_empty() => {
+            log::info!("Marked {} running session(s) as resumable", runs.len());
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("Failed to mark running sessions resumable: {e}"),
+    }
+
+    let killed = crate::terminal::cleanup_all_terminals();
+    log::info!("Killed {killed} terminal(s)");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<HeadlessOptions>, String> {
+        let args: Vec<String> = std::iter::once("jean")
+            .chain(args.iter().copied())
+            .map(String::from)
+            .collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn test_gui_mode_without_headless_flag() {
+        assert_eq!(parse(&[]), Ok(None));
+        assert_eq!(parse(&["-psn_0_12345"]), Ok(None));
+    }
+
+    #[test]
+    fn test_headless_options() {
+        assert_eq!(parse(&["--headless"]), Ok(Some(HeadlessOptions::default())));
+        assert_eq!(
+            parse(&["--headless", "--port", "3456", "--bind-all"]),
+            Ok(Some(HeadlessOptions {
+                port: Some(3456),
+                bind_all: true,
+            }))
+        );
+        assert_eq!(
+            parse(&["--port=8080", "--headless"]),
+            Ok(Some(HeadlessOptions {
+                port: Some(8080),
+                bind_all: false,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        assert!(parse(&["--headless", "--port"]).is_err());
+        assert!(parse(&["--headless", "--port", "0"]).is_err());
+        assert!(parse(&["--headless", "--port=http"]).is_err());
+        assert!(parse(&["--bind-all"]).is_err());
+    }
+}
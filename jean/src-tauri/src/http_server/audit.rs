@@ -0,0 +1,445 @@
+//! Audit log of what remote clients do through the HTTP server
+//!
+//! Every command run via `dispatch_command` for a WebSocket or REST client is
+//! appended to `audit_log.jsonl` in the app data directory, along with
+//! WebSocket connects and disconnects. Local UI actions go through Tauri IPC
+//! and are not logged. Entries are never rewritten; when the file grows past
+//! [`MAX_LOG_BYTES`] it is moved to `audit_log.1.jsonl` (replacing the previous
+//! one) and a new file is started. Entries are written in order by a
+//! dedicated thread so file I/O never blocks the async runtime.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use super::auth::Grant;
+use super::dispatch::{dispatch_command, DispatchError};
+
+/// Size after which the log is rotated
+const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Longest string argument kept as is; longer ones are truncated
+const MAX_ARG_CHARS: usize = 256;
+
+/// Entries returned by `query_audit_log` when no limit is given
+const DEFAULT_QUERY_LIMIT: usize = 500;
+
+/// Commands whose arguments carry raw terminal input; only its length is kept
+const KEYSTROKE_COMMANDS: &[&str] = &["terminal_write"];
+
+/// Serializes writes and rotation of the log file
+static AUDIT_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Queue of entries to append, drained by the writer thread
+static AUDIT_WRITER: Lazy<Sender<(AppHandle, AuditEntry)>> = Lazy::new(|| {
+    let (tx, rx) = mpsc::channel::<(AppHandle, AuditEntry)>();
+    thread::spawn(move || {
+        for (app, entry) in rx {
+            if let Err(e) = append_entry(&app, &entry) {
+                log::warn!("Failed to record audit entry: {e}");
+            }
+        }
+    });
+    tx
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditKind {
+    Command,
+    Connect,
+    Disconnect,
+}
+
+/// How the client reached the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditTransport {
+    WebSocket,
+    Rest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Unix timestamp in milliseconds
+    pub timestamp: u64,
+    pub kind: AuditKind,
+    pub transport: AuditTransport,
+    pub client_addr: String,
+    /// API token used (`None` for the shared token or when no token is required)
+    #[serde(default)]
+    pub token_id: Option<String>,
+    #[serde(default)]
+    pub token_name: Option<String>,
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Arguments with secrets redacted and long values truncated
+    #[serde(default)]
+    pub args: Option<Value>,
+    #[serde(default)]
+    pub ok: Option<bool>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+}
+
+/// Who is making a request, for audit entries
+#[derive(Debug, Clone)]
+pub struct AuditClient {
+    pub addr: SocketAddr,
+    pub transport: AuditTransport,
+}
+
+impl AuditClient {
+    fn entry(&self, kind: AuditKind, grant: &Grant) -> AuditEntry {
+        AuditEntry {
+            timestamp: now_millis(),
+            kind,
+            transport: self.transport,
+            client_addr: self.addr.to_string(),
+            token_id: grant.token_id.clone(),
+            token_name: grant.token_name.clone(),
+            command: None,
+            args: None,
+            ok: None,
+            error: None,
+            duration_ms: None,
+        }
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+// ============================================================================
+// Sanitizing
+// ============================================================================
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    // Ids of tokens etc. aren't secret
+    if key.ends_with("id") || key.ends_with("ids") {
+        return false;
+    }
+    [
+        "token",
+        "secret",
+        "password",
+        "api_key",
+        "apikey",
+        "authorization",
+    ]
+    .iter()
+    .any(|s| key.contains(s))
+}
+
+/// Copy of `args` safe to store: secrets redacted, long strings truncated
+pub fn sanitize_args(args: &Value) -> Value {
+    match args {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let value = if is_secret_key(key) && value.is_string() {
+                        Value::String("[redacted]".to_string())
+                    } else {
+                        sanitize_args(value)
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(sanitize_args).collect()),
+        Value::String(s) if s.chars().count() > MAX_ARG_CHARS => {
+            let kept: String = s.chars().take(MAX_ARG_CHARS).collect();
+            Value::String(format!("{kept}… ({} chars)", s.chars().count()))
+        }
+        other => other.clone(),
+    }
+}
+
+/// Arguments of `command` as stored in the log: keystrokes sent to a terminal
+/// are replaced by their length, everything else goes through [`sanitize_args`]
+fn command_args(command: &str, args: &Value) -> Value {
+    let mut sanitized = sanitize_args(args);
+    if KEYSTROKE_COMMANDS.contains(&command) {
+        if let Some(data) = args.get("data").and_then(Value::as_str) {
+            sanitized["data"] = Value::String(format!("[{} bytes]", data.len()));
+        }
+    }
+    sanitized
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+fn get_audit_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    std::fs::create_dir_all(&app_data_dir)
+        .map_err(|e| format!("Failed to create app data directory: {e}"))?;
+    Ok(app_data_dir)
+}
+
+fn append_entry(app: &AppHandle, entry: &AuditEntry) -> Result<(), String> {
+    let dir = get_audit_dir(app)?;
+    let path = dir.join("audit_log.jsonl");
+    let mut line = serde_json::to_string(entry)
+        .map_err(|e| format!("Failed to serialize audit entry: {e}"))?;
+    line.push('\n');
+
+    let _lock = AUDIT_LOCK.lock().unwrap();
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() >= MAX_LOG_BYTES) {
+        std::fs::rename(&path, dir.join("audit_log.1.jsonl"))
+            .map_err(|e| format!("Failed to rotate audit log: {e}"))?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open audit log: {e}"))?;
+    file.write_all(line.as_bytes())
+        .map_err(|e| format!("Failed to write audit log: {e}"))
+}
+
+/// Queue an entry for the writer thread; failures are logged but never fail
+/// the request
+pub fn record(app: &AppHandle, entry: AuditEntry) {
+    if AUDIT_WRITER.send((app.clone(), entry)).is_err() {
+        log::warn!("Failed to record audit entry: writer thread stopped");
+    }
+}
+
+pub fn record_connection(app: &AppHandle, client: &AuditClient, grant: &Grant, kind: AuditKind) {
+    record(app, client.entry(kind, grant));
+}
+
+/// Audit entry of a running command; if the request is dropped before it
+/// finishes (cancelled or timed out), the entry is recorded as aborted.
+struct PendingCommand<'a> {
+    app: &'a AppHandle,
+    entry: Option<AuditEntry>,
+    started: Instant,
+}
+
+impl PendingCommand<'_> {
+    fn finish(mut self, result: &Result<Value, DispatchError>) {
+        if let Some(mut entry) = self.entry.take() {
+            entry.duration_ms = Some(self.started.elapsed().as_millis() as u64);
+            entry.ok = Some(result.is_ok());
+            entry.error = result.as_ref().err().map(ToString::to_string);
+            record(self.app, entry);
+        }
+    }
+}
+
+impl Drop for PendingCommand<'_> {
+    fn drop(&mut self) {
+        if let Some(mut entry) = self.entry.take() {
+            entry.duration_ms = Some(self.started.elapsed().as_millis() as u64);
+            entry.ok = Some(false);
+            entry.error = Some("Aborted before completion".to_string());
+            record(self.app, entry);
+        }
+    }
+}
+
+/// Run a command for a remote client and record it in the audit log
+pub async fn dispatch_audited(
+    app: &AppHandle,
+    client: &AuditClient,
+    grant: &Grant,
+    command: &str,
+    args: Value,
+) -> Result<Value, DispatchError> {
+    let mut entry = client.entry(AuditKind::Command, grant);
+    entry.command = Some(command.to_string());
+    entry.args = Some(command_args(command, &args));
+    let pending = PendingCommand {
+        app,
+        entry: Some(entry),
+        started: Instant::now(),
+    };
+
+    let result = dispatch_command(app, grant, command, args).await;
+    pending.finish(&result);
+    result
+}
+
+// ============================================================================
+// Querying
+// ============================================================================
+
+/// Filter for `query_audit_log`
+#[derive(Debug, Default)]
+struct AuditQuery {
+    since: Option<u64>,
+    until: Option<u64>,
+    command: Option<String>,
+}
+
+impl AuditQuery {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        self.since.is_none_or(|since| entry.timestamp >= since)
+            && self.until.is_none_or(|until| entry.timestamp <= until)
+            && self
+                .command
+                .as_deref()
+                .is_none_or(|command| entry.command.as_deref() == Some(command))
+    }
+}
+
+fn read_entries(path: &PathBuf, query: &AuditQuery, out: &mut Vec<AuditEntry>) {
+    let Ok(file) = std::fs::File::open(path) else {
+        return;
+    };
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        // Skip a partially written last line
+        if let Ok(entry) = serde_json::from_str::<AuditEntry>(&line) {
+            if query.matches(&entry) {
+                out.push(entry);
+            }
+        }
+    }
+}
+
+/// Audit entries between `since` and `until` (Unix ms, inclusive), optionally
+/// only those of one command. Newest first, at most `limit`.
+#[tauri::command]
+pub async fn query_audit_log(
+    app: AppHandle,
+    since: Option<u64>,
+    until: Option<u64>,
+    command: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<AuditEntry>, String> {
+    let query = AuditQuery {
+        since,
+        until,
+        command: command.filter(|c| !c.is_empty()),
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let dir = get_audit_dir(&app)?;
+        let mut entries = Vec::new();
+        {
+            let _lock = AUDIT_LOCK.lock().unwrap();
+            read_entries(&dir.join("audit_log.1.jsonl"), &query, &mut entries);
+            read_entries(&dir.join("audit_log.jsonl"), &query, &mut entries);
+        }
+        entries.reverse();
+        entries.truncate(limit.unwrap_or(DEFAULT_QUERY_LIMIT));
+        Ok(entries)
+    })
+    .await
+    .map_err(|e| format!("Failed to read audit log: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_sanitize_redacts_secrets() {
+        let args = json!({
+            "tokenId": "t1",
+            "preferences": {
+                "http_server_token": "abc",
+                "http_server_token_required": true,
+                "theme": "dark",
+            },
+            "password": null,
+            "worktreeId": "w1",
+        });
+        let sanitized = sanitize_args(&args);
+        assert_eq!(sanitized["tokenId"], "t1");
+        assert_eq!(sanitized["preferences"]["http_server_token"], "[redacted]");
+        assert_eq!(sanitized["preferences"]["http_server_token_required"], true);
+        assert_eq!(sanitized["preferences"]["theme"], "dark");
+        assert_eq!(sanitized["password"], Value::Null);
+        assert_eq!(sanitized["worktreeId"], "w1");
+    }
+
+    #[test]
+    fn test_sanitize_truncates_long_strings() {
+        let message = "x".repeat(MAX_ARG_CHARS + 10);
+        let sanitized = sanitize_args(&json!({ "messages": [message] }));
+        let kept = sanitized["messages"][0].as_str().unwrap();
+        assert!(kept.starts_with(&"x".repeat(MAX_ARG_CHARS)));
+        assert!(kept.ends_with(&format!("({} chars)", MAX_ARG_CHARS + 10)));
+    }
+
+    #[test]
+    fn test_terminal_input_is_not_logged() {
+        let args = json!({ "terminalId": "t1", "data": "hunter2\r" });
+        let logged = command_args("terminal_write", &args);
+        assert_eq!(logged["terminalId"], "t1");
+        assert_eq!(logged["data"], "[8 bytes]");
+        // Other commands keep their (sanitized) data
+        assert_eq!(
+            command_args("write_file_content", &args)["data"],
+            "hunter2\r"
+        );
+    }
+
+    fn entry(timestamp: u64, command: Option<&str>) -> AuditEntry {
+        AuditEntry {
+            timestamp,
+            kind: if command.is_some() {
+                AuditKind::Command
+            } else {
+                AuditKind::Connect
+            },
+            transport: AuditTransport::WebSocket,
+            client_addr: "192.168.1.5:50000".to_string(),
+            token_id: None,
+            token_name: None,
+            command: command.map(str::to_string),
+            args: None,
+            ok: None,
+            error: None,
+            duration_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let query = AuditQuery {
+            since: Some(100),
+            until: Some(200),
+            command: Some("send_chat_message".to_string()),
+        };
+        assert!(query.matches(&entry(150, Some("send_chat_message"))));
+        assert!(!query.matches(&entry(150, Some("list_projects"))));
+        assert!(!query.matches(&entry(99, Some("send_chat_message"))));
+        assert!(!query.matches(&entry(201, Some("send_chat_message"))));
+        assert!(!query.matches(&entry(150, None)));
+        assert!(AuditQuery::default().matches(&entry(0, None)));
+    }
+
+    #[test]
+    fn test_entry_roundtrip() {
+        let json = serde_json::to_string(&entry(1, Some("list_projects"))).unwrap();
+        assert!(json.contains(r#""kind":"command""#));
+        assert!(json.contains(r#""transport":"websocket""#));
+        let parsed: AuditEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.command.as_deref(), Some("list_projects"));
+    }
+}
@@ -1,4 +1,13 @@
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+
+use super::subscriptions::EventTarget;
+use super::tokens;
+use super::WsEvent;
+use crate::projects::types::Worktree;
 
 /// Generate a cryptographically random token (32 bytes, base64url-encoded).
 pub fn generate_token() -> String {
@@ -20,3 +29,689 @@ pub fn validate_token(provided: &str, expected: &str) -> bool {
         .fold(0u8, |acc, (a, b)| acc | (a ^ b))
         == 0
 }
+
+/// SHA-256 of a token (hex); API tokens are only ever stored hashed.
+pub fn hash_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Permission carried by an API token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenScope {
+    /// View projects, sessions, diffs and files (implied by every other scope)
+    ReadOnly,
+    /// Manage sessions and send messages (except in yolo mode or past a budget)
+    Chat,
+    /// Commit, push, merge and manage worktrees
+    GitWrite,
+    /// Run worktree terminals and see their output
+    Terminal,
+    /// Everything, including yolo mode, budget overrides, preferences, tokens
+    /// and commands that take arbitrary filesystem paths
+    Admin,
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::ReadOnly => "read_only",
+            TokenScope::Chat => "chat",
+            TokenScope::GitWrite => "git_write",
+            TokenScope::Terminal => "terminal",
+            TokenScope::Admin => "admin",
+        }
+    }
+}
+
+/// What an authenticated client is allowed to do.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grant {
+    /// API token the client authenticated with (`None` for the shared token)
+    pub token_id: Option<String>,
+    /// Name of that API token, for logs
+    pub token_name: Option<String>,
+    pub scopes: Vec<TokenScope>,
+    /// Projects the client may access (empty = all)
+    pub project_ids: Vec<String>,
+}
+
+impl Grant {
+    /// Unrestricted access (shared token, or no token required)
+    pub fn full() -> Self {
+        Self {
+            token_id: None,
+            token_name: None,
+            scopes: vec![TokenScope::Admin],
+            project_ids: vec![],
+        }
+    }
+
+    pub fn has_scope(&self, scope: TokenScope) -> bool {
+        self.scopes.contains(&TokenScope::Admin)
+            || self.scopes.contains(&scope)
+            || (scope == TokenScope::ReadOnly && !self.scopes.is_empty())
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.has_scope(TokenScope::Admin)
+    }
+
+    pub fn allows_project(&self, project_id: &str) -> bool {
+        self.project_ids.is_empty() || self.project_ids.iter().any(|id| id == project_id)
+    }
+
+    /// Whether a broadcast event may reach this client; project-restricted
+    /// grants only see events of their projects (and events with no project).
+    /// Terminal events need the terminal scope.
+    pub fn allows_event(
+        &self,
+        event: &WsEvent,
+        project_of: &mut impl FnMut(&str) -> Option<String>,
+    ) -> bool {
+        if event.event.starts_with("terminal:") && !self.has_scope(TokenScope::Terminal) {
+            return false;
+        }
+        self.project_ids.is_empty()
+            || EventTarget::of(&event.payload)
+                .project(project_of)
+                .is_none_or(|project_id| self.allows_project(&project_id))
+    }
+
+    /// Check that `command` may run with `args`, returning a `Forbidden: ...` error if not.
+    pub fn authorize(&self, app: &AppHandle, command: &str, args: &Value) -> Result<(), String> {
+        let required = required_scope(command, args);
+        if !self.has_scope(required) {
+            return Err(format!(
+                "Forbidden: {command} requires the '{}' scope",
+                required.as_str()
+            ));
+        }
+
+        // Re-checked per command so revocation and expiry apply to open connections
+        if let Some(token_id) = &self.token_id {
+            tokens::check_token_active(app, token_id)?;
+        }
+
+        if self.project_ids.is_empty() && self.is_admin() {
+            return Ok(());
+        }
+        let projects = target_projects(app, args).map_err(|e| format!("Forbidden: {e}"))?;
+        if self.project_ids.is_empty() {
+            return Ok(());
+        }
+        if projects.is_empty() {
+            return if PROJECT_AGNOSTIC_COMMANDS.contains(&command) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Forbidden: {command} is not available to project-restricted tokens"
+                ))
+            };
+        }
+        if projects.iter().all(|p| self.allows_project(p)) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Forbidden: this token cannot access the project targeted by {command}"
+            ))
+        }
+    }
+}
+
+/// Resolve the access granted by a presented token.
+///
+/// The shared preferences token grants full access; named API tokens grant
+/// their scopes. When tokens aren't required, unauthenticated clients get
+/// full access too.
+pub fn authenticate(
+    app: &AppHandle,
+    provided: &str,
+    shared_token: &str,
+    token_required: bool,
+) -> Option<Grant> {
+    if !provided.is_empty() && validate_token(provided, shared_token) {
+        return Some(Grant::full());
+    }
+    if let Some(grant) = tokens::authenticate_api_token(app, provided) {
+        return Some(grant);
+    }
+    (!token_required).then(Grant::full)
+}
+
+/// Hide the shared server token from clients without the admin scope.
+pub fn redact_preferences(grant: &Grant, preferences: &mut Value) {
+    if !grant.is_admin() {
+        if let Some(token) = preferences.get_mut("http_server_token") {
+            *token = Value::Null;
+        }
+    }
+}
+
+// =============================================================================
+// Command classification
+// =============================================================================
+
+/// Commands that only change what this client is looking at
+const VIEW_STATE_COMMANDS: &[&str] = &[
+    "save_ui_state",
+    "set_active_session",
+    "set_app_focus_state",
+    "set_active_worktree_for_polling",
+    "set_polled_worktrees",
+    "trigger_immediate_git_poll",
+    "trigger_immediate_remote_poll",
+    "fetch_worktrees_status",
+    "update_worktree_cached_status",
+    "reorder_projects",
+    "reorder_worktrees",
+    "reorder_items",
+    "reorder_sessions",
+    "greet",
+];
+
+const CHAT_COMMANDS: &[&str] = &[
+    "create_session",
+    "rename_session",
+    "close_session",
+    "send_chat_message",
+    "cancel_chat_message",
+    "clear_session_history",
+    "set_session_model",
+    "set_session_thinking_level",
+    "mark_plan_approved",
+    "save_cancelled_message",
+    "update_session_state",
+    "archive_session",
+    "unarchive_session",
+    "restore_session_with_base",
+    "delete_archived_session",
+    "create_base_session",
+    "close_base_session",
+    "close_base_session_clean",
+    "resume_session",
+    "broadcast_session_setting",
+    "generate_session_digest",
+    "update_session_digest",
+    "run_review_with_ai",
+    "fork_session",
+    "enqueue_message",
+    "update_queued_message",
+    "reorder_queued_messages",
+    "remove_queued_message",
+    "clear_message_queue",
+    "send_next_queued_message",
+    "create_schedule",
+    "update_schedule",
+    "set_schedule_enabled",
+    "delete_schedule",
+    "run_schedule_now",
+    "load_issue_context",
+    "remove_issue_context",
+    "load_pr_context",
+    "remove_pr_context",
+    "attach_saved_context",
+    "remove_saved_context",
+    "save_context_file",
+    "delete_context_file",
+    "rename_saved_context",
+    "generate_context_from_session",
+    "save_pasted_image",
+    "save_dropped_image",
+    "delete_pasted_image",
+    "save_pasted_text",
+    "delete_pasted_text",
+];
+
+const GIT_WRITE_COMMANDS: &[&str] = &[
+    "create_worktree",
+    "create_worktree_from_existing_branch",
+    "checkout_pr",
+    "delete_worktree",
+    "archive_worktree",
+    "unarchive_worktree",
+    "rename_worktree",
+    "permanently_delete_worktree",
+    "git_pull",
+    "git_push",
+    "commit_changes",
+    "create_commit_with_ai",
+    "create_pr_with_ai_content",
+    "save_worktree_pr",
+    "clear_worktree_pr",
+    "rebase_worktree",
+    "merge_worktree_to_base",
+    "fetch_and_merge_base",
+];
+
+/// Commands that run or read worktree terminals
+const TERMINAL_COMMANDS: &[&str] = &[
+    "start_terminal",
+    "terminal_write",
+    "terminal_resize",
+    "stop_terminal",
+    "get_terminal_scrollback",
+    "list_terminals",
+    "start_terminal_recording",
+    "stop_terminal_recording",
+    "list_terminal_recordings",
+    "read_terminal_recording",
+    "delete_terminal_recording",
+    "attach_terminal_recording",
+];
+
+/// Commands a project-restricted token may run without naming a project
+const PROJECT_AGNOSTIC_COMMANDS: &[&str] = &[
+    "load_preferences",
+    "load_ui_state",
+    "save_ui_state",
+    "list_projects",
+    "set_app_focus_state",
+    "set_polled_worktrees",
+    "has_running_sessions",
+    "list_claude_skills",
+    "list_claude_commands",
+    "check_claude_cli_installed",
+    "check_claude_cli_auth",
+    "check_gh_cli_installed",
+    "check_gh_cli_auth",
+    "get_git_poll_interval",
+    "get_remote_poll_interval",
+    "greet",
+];
+
+/// Commands that only read. Anything unlisted needs admin, including reads
+/// and writes that expose the shared server token or take arbitrary
+/// filesystem paths (`read_file_content`, `write_file_content`,
+/// `export_session_bundle`, `get_app_data_dir`).
+///
+/// Commands that take a `worktreePath` are safe here because `authorize`
+/// only accepts recorded worktree paths from non-admin clients.
+const READ_COMMANDS: &[&str] = &[
+    "load_preferences",
+    "load_ui_state",
+    "list_projects",
+    "list_worktrees",
+    "get_worktree",
+    "get_project_branches",
+    "has_uncommitted_changes",
+    "get_git_diff",
+    "list_worktree_files",
+    "list_github_issues",
+    "get_github_issue",
+    "list_github_prs",
+    "get_github_pr",
+    "list_loaded_issue_contexts",
+    "list_loaded_pr_contexts",
+    "get_issue_context_content",
+    "get_pr_context_content",
+    "list_attached_saved_contexts",
+    "get_saved_context_content",
+    "get_sessions",
+    "list_all_sessions",
+    "get_session",
+    "has_running_sessions",
+    "list_saved_contexts",
+    "read_context_file",
+    "read_plan_file",
+    "get_git_poll_interval",
+    "get_remote_poll_interval",
+    "check_resumable_sessions",
+    "load_emergency_data",
+    "list_archived_worktrees",
+    "get_github_branch_url",
+    "get_github_repo_url",
+    "get_pr_prompt",
+    "get_review_prompt",
+    "get_merge_conflicts",
+    "list_claude_skills",
+    "list_claude_commands",
+    "search_github_issues",
+    "search_github_prs",
+    "get_active_terminals",
+    "has_active_terminal",
+    "get_run_script",
+    "list_archived_sessions",
+    "list_all_archived_sessions",
+    "read_pasted_text",
+    "get_session_debug_info",
+    "get_usage_report",
+    "get_budget_status",
+    "search_sessions",
+    "export_session",
+    "list_claude_cli_sessions",
+    "list_queued_messages",
+    "list_schedules",
+    "preview_schedule",
+    "check_claude_cli_installed",
+    "check_claude_cli_auth",
+    "get_available_cli_versions",
+    "check_gh_cli_installed",
+    "check_gh_cli_auth",
+    "get_available_gh_versions",
+];
+
+/// Scope needed to run `command` with `args`. Unknown commands need admin.
+pub fn required_scope(command: &str, args: &Value) -> TokenScope {
+    if CHAT_COMMANDS.contains(&command) {
+        if requests_elevation(args) {
+            TokenScope::Admin
+        } else {
+            TokenScope::Chat
+        }
+    } else if GIT_WRITE_COMMANDS.contains(&command) {
+        TokenScope::GitWrite
+    } else if TERMINAL_COMMANDS.contains(&command) {
+        TokenScope::Terminal
+    } else if VIEW_STATE_COMMANDS.contains(&command) || READ_COMMANDS.contains(&command) {
+        TokenScope::ReadOnly
+    } else {
+        TokenScope::Admin
+    }
+}
+
+/// Whether the args ask for yolo mode (no permission prompts) or to send
+/// past an exhausted budget
+fn requests_elevation(args: &Value) -> bool {
+    let yolo = [
+        "/executionMode",
+        "/execution_mode",
+        "/schedule/execution_mode",
+    ]
+    .iter()
+    .any(|p| args.pointer(p).and_then(Value::as_str) == Some("yolo"));
+    let override_budget = ["/overrideBudget", "/override_budget"]
+        .iter()
+        .any(|p| args.pointer(p).and_then(Value::as_bool) == Some(true));
+    yolo || override_budget
+}
+
+fn str_arg<'a>(args: &'a Value, camel: &str, snake: &str) -> Option<&'a str> {
+    args.get(camel)
+        .or_else(|| args.get(snake))
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+}
+
+/// Projects a command operates on, one per id or path in its args
+///
+/// Every id and path is resolved on its own, so an allowed id can't be paired
+/// with a path or id of another project. Ids that don't exist (yet) add
+/// nothing; a session must belong to a known worktree.
+fn target_projects(app: &AppHandle, args: &Value) -> Result<Vec<String>, String> {
+    let mut projects = Vec::new();
+    for project_id in [
+        str_arg(args, "projectId", "project_id"),
+        args.pointer("/schedule/target/project_id")
+            .and_then(Value::as_str),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        projects.push(project_id.to_string());
+    }
+
+    if let Some(schedule_id) = str_arg(args, "scheduleId", "schedule_id") {
+        let schedules = crate::scheduler::load_schedules(app)?;
+        if let Some(schedule) = schedules.into_iter().find(|s| s.id == schedule_id) {
+            projects.push(schedule.target.project_id);
+        }
+    }
+
+    let data = crate::projects::storage::load_projects_data(app)?;
+    let mut worktree_ids: Vec<String> = [
+        str_arg(args, "worktreeId", "worktree_id"),
+        args.pointer("/schedule/target/worktree_id")
+            .and_then(Value::as_str),
+    ]
+    .into_iter()
+    .flatten()
+    .map(str::to_string)
+    .collect();
+    if let Some(session_id) = str_arg(args, "sessionId", "session_id") {
+        if let Some(metadata) = crate::chat::storage::load_metadata(app, session_id)? {
+            if data.find_worktree(&metadata.worktree_id).is_none() {
+                return Err(format!("session {session_id} belongs to no worktree"));
+            }
+            worktree_ids.push(metadata.worktree_id);
+        }
+    }
+    if let Some(terminal_id) = str_arg(args, "terminalId", "terminal_id") {
+        worktree_ids.extend(crate::terminal::terminal_worktree_id(terminal_id));
+    }
+    projects.extend(
+        worktree_ids
+            .iter()
+            .filter_map(|id| data.find_worktree(id))
+            .map(|w| w.project_id.clone()),
+    );
+
+    if let Some(path) = str_arg(args, "worktreePath", "worktree_path") {
+        let worktree_id = str_arg(args, "worktreeId", "worktree_id");
+        let worktree = worktree_at_path(&data.worktrees, path, worktree_id)?;
+        projects.push(worktree.project_id.clone());
+    }
+    Ok(projects)
+}
+
+/// The worktree recorded at `path`; it must be `worktree_id` when one is given
+fn worktree_at_path<'a>(
+    worktrees: &'a [Worktree],
+    path: &str,
+    worktree_id: Option<&str>,
+) -> Result<&'a Worktree, String> {
+    let mut at_path = worktrees.iter().filter(|w| w.path == path);
+    match worktree_id {
+        Some(id) => at_path
+            .find(|w| w.id == id)
+            .ok_or_else(|| format!("{path} is not the path of worktree {id}")),
+        None => at_path
+            .next()
+            .ok_or_else(|| format!("{path} is not a worktree path")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_hash_token_is_stable_hex() {
+        let hash = hash_token("secret");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_token("secret"));
+        assert_ne!(hash, hash_token("secret2"));
+    }
+
+    #[test]
+    fn test_required_scope() {
+        let none = json!({});
+        assert_eq!(required_scope("list_projects", &none), TokenScope::ReadOnly);
+        assert_eq!(required_scope("get_git_diff", &none), TokenScope::ReadOnly);
+        assert_eq!(required_scope("save_ui_state", &none), TokenScope::ReadOnly);
+        assert_eq!(
+            required_scope("load_issue_context", &none),
+            TokenScope::Chat
+        );
+        assert_eq!(required_scope("send_chat_message", &none), TokenScope::Chat);
+        assert_eq!(required_scope("git_push", &none), TokenScope::GitWrite);
+        assert_eq!(
+            required_scope("write_file_content", &none),
+            TokenScope::Admin
+        );
+        assert_eq!(required_scope("save_preferences", &none), TokenScope::Admin);
+        assert_eq!(
+            required_scope("start_terminal", &none),
+            TokenScope::Terminal
+        );
+        assert_eq!(
+            required_scope("get_terminal_scrollback", &none),
+            TokenScope::Terminal
+        );
+        assert_eq!(
+            required_scope("get_active_terminals", &none),
+            TokenScope::ReadOnly
+        );
+        assert_eq!(
+            required_scope("kill_all_terminals", &none),
+            TokenScope::Admin
+        );
+        assert_eq!(
+            required_scope("get_http_server_status", &none),
+            TokenScope::Admin
+        );
+        assert_eq!(required_scope("something_new", &none), TokenScope::Admin);
+        assert_eq!(
+            required_scope("get_something_new", &none),
+            TokenScope::Admin
+        );
+    }
+
+    #[test]
+    fn test_read_only_cannot_reach_arbitrary_paths() {
+        let read_only = Grant {
+            token_id: Some("t".to_string()),
+            token_name: None,
+            scopes: vec![TokenScope::ReadOnly],
+            project_ids: vec![],
+        };
+        let none = json!({});
+        for command in [
+            "read_file_content",
+            "write_file_content",
+            "export_session_bundle",
+            "get_app_data_dir",
+            "import_session_bundle",
+            "import_claude_cli_session",
+            "import_worktree",
+        ] {
+            let required = required_scope(command, &none);
+            assert_eq!(required, TokenScope::Admin, "{command}");
+            assert!(!read_only.has_scope(required), "{command}");
+        }
+        assert!(read_only.has_scope(required_scope("get_session", &none)));
+    }
+
+    #[test]
+    fn test_yolo_requires_admin() {
+        let yolo = json!({ "executionMode": "yolo" });
+        assert_eq!(
+            required_scope("send_chat_message", &yolo),
+            TokenScope::Admin
+        );
+        assert_eq!(required_scope("enqueue_message", &yolo), TokenScope::Admin);
+        let scheduled = json!({ "schedule": { "execution_mode": "yolo" } });
+        assert_eq!(
+            required_scope("create_schedule", &scheduled),
+            TokenScope::Admin
+        );
+        let build = json!({ "executionMode": "build" });
+        assert_eq!(
+            required_scope("send_chat_message", &build),
+            TokenScope::Chat
+        );
+    }
+
+    #[test]
+    fn test_budget_override_requires_admin() {
+        let overridden = json!({ "overrideBudget": true });
+        assert_eq!(
+            required_scope("send_chat_message", &overridden),
+            TokenScope::Admin
+        );
+        let not_overridden = json!({ "override_budget": false });
+        assert_eq!(
+            required_scope("send_chat_message", &not_overridden),
+            TokenScope::Chat
+        );
+    }
+
+    #[test]
+    fn test_worktree_path_must_be_recorded() {
+        let worktree = |id: &str, project_id: &str, path: &str| -> Worktree {
+            serde_json::from_value(json!({
+                "id": id,
+                "project_id": project_id,
+                "name": id,
+                "path": path,
+                "branch": id,
+                "created_at": 0,
+            }))
+            .unwrap()
+        };
+        let worktrees = vec![
+            worktree("w1", "p1", "/repos/a/w1"),
+            worktree("w2", "p2", "/repos/b/w2"),
+        ];
+
+        let found = worktree_at_path(&worktrees, "/repos/a/w1", None).unwrap();
+        assert_eq!(found.project_id, "p1");
+        assert!(worktree_at_path(&worktrees, "/repos/a/w1", Some("w1")).is_ok());
+        // An allowed id can't vouch for another worktree's path
+        assert!(worktree_at_path(&worktrees, "/repos/b/w2", Some("w1")).is_err());
+        assert!(worktree_at_path(&worktrees, "/etc", None).is_err());
+    }
+
+    #[test]
+    fn test_grant_scopes() {
+        let chat = Grant {
+            token_id: Some("t".to_string()),
+            token_name: Some("CI".to_string()),
+            scopes: vec![TokenScope::Chat],
+            project_ids: vec!["p1".to_string()],
+        };
+        assert!(chat.has_scope(TokenScope::ReadOnly));
+        assert!(chat.has_scope(TokenScope::Chat));
+        assert!(!chat.has_scope(TokenScope::GitWrite));
+        assert!(!chat.is_admin());
+        assert!(chat.allows_project("p1"));
+        assert!(!chat.allows_project("p2"));
+
+        let full = Grant::full();
+        assert!(full.has_scope(TokenScope::GitWrite));
+        assert!(full.allows_project("anything"));
+    }
+
+    #[test]
+    fn test_terminal_events_require_terminal_scope() {
+        let event = |name: &str| WsEvent {
+            seq: 1,
+            event: name.to_string(),
+            payload: json!({ "terminal_id": "t1" }),
+        };
+        let grant = |scopes: Vec<TokenScope>| Grant {
+            token_id: Some("t".to_string()),
+            token_name: None,
+            scopes,
+            project_ids: vec![],
+        };
+        let mut no_projects = |_: &str| -> Option<String> { None };
+
+        let chat = grant(vec![TokenScope::Chat]);
+        assert!(!chat.allows_event(&event("terminal:output"), &mut no_projects));
+        assert!(chat.allows_event(&event("chat:chunk"), &mut no_projects));
+
+        let terminal = grant(vec![TokenScope::Terminal]);
+        assert!(terminal.allows_event(&event("terminal:output"), &mut no_projects));
+        assert!(Grant::full().allows_event(&event("terminal:stopped"), &mut no_projects));
+    }
+
+    #[test]
+    fn test_redact_preferences() {
+        let mut prefs = json!({ "http_server_token": "shh", "theme": "dark" });
+        let read_only = Grant {
+            token_id: Some("t".to_string()),
+            token_name: Some("CI".to_string()),
+            scopes: vec![TokenScope::ReadOnly],
+            project_ids: vec![],
+        };
+        redact_preferences(&Grant::full(), &mut prefs);
+        assert_eq!(prefs["http_server_token"], "shh");
+        redact_preferences(&read_only, &mut prefs);
+        assert_eq!(prefs["http_server_token"], Value::Null);
+        assert_eq!(prefs["theme"], "dark");
+    }
+}
@@ -2,1362 +2,1748 @@ use serde_json::Value;
 use tauri::AppHandle;
 use tauri::Manager;
 
+use super::auth::Grant;
 use super::EmitExt;
 
+/// Why a dispatched command failed
+///
+/// The REST API maps each kind to an HTTP status; WebSocket clients only see
+/// the message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DispatchError {
+    /// No command with this name
+    UnknownCommand(String),
+    /// The client's grant doesn't allow the command
+    Forbidden(String),
+    /// A required arg is missing or an arg has the wrong type
+    InvalidArgs(String),
+    /// The command ran and returned an error
+    Failed(String),
+}
+
+impl std::fmt::Display for DispatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownCommand(command) => write!(f, "Unknown command: {command}"),
+            Self::Forbidden(message) | Self::InvalidArgs(message) | Self::Failed(message) => {
+                f.write_str(message)
+            }
+        }
+    }
+}
+
+/// Command functions return `String` errors; `?` turns them into `Failed`
+impl From<String> for DispatchError {
+    fn from(message: String) -> Self {
+        Self::Failed(message)
+    }
+}
+
 /// Dispatch a command by name to the corresponding Rust handler.
 /// This mirrors Tauri's invoke system but routes through WebSocket.
 ///
 /// Each arm deserializes args from the JSON Value and calls the
 /// existing command function directly, then serializes the result.
+/// The command must be allowed by the client's `grant` (see `auth`).
 pub async fn dispatch_command(
     app: &AppHandle,
+    grant: &Grant,
     command: &str,
     args: Value,
-) -> Result<Value, String> {
-    match command {
-        // =====================================================================
-        // Preferences & UI State
-        // =====================================================================
-        "load_preferences" => {
-            let result = crate::load_preferences(app.clone()).await?;
-            to_value(result)
-        }
-        "save_preferences" => {
-            let preferences = from_field(&args, "preferences")?;
-            crate::save_preferences(app.clone(), preferences).await?;
-            emit_cache_invalidation(app, &["preferences"]);
-            Ok(Value::Null)
-        }
-        "load_ui_state" => {
-            let result = crate::load_ui_state(app.clone()).await?;
-            to_value(result)
-        }
-        "save_ui_state" => {
-            let ui_state = field(&args, "uiState", "ui_state")?;
-            crate::save_ui_state(app.clone(), ui_state).await?;
-            emit_cache_invalidation(app, &["ui-state"]);
-            Ok(Value::Null)
-        }
+) -> Result<Value, DispatchError> {
+    grant
+        .authorize(app, command, &args)
+        .map_err(DispatchError::Forbidden)?;
+    run_command(app, grant, command, args).await
+}
 
-        // =====================================================================
-        // Projects
-        // =====================================================================
-        "list_projects" => {
-            let result = crate::projects::list_projects(app.clone()).await?;
-            to_value(result)
-        }
-        "add_project" => {
-            let path: String = from_field(&args, "path")?;
-            let parent_id: Option<String> = field_opt(&args, "parentId", "parent_id")?;
-            let result = crate::projects::add_project(app.clone(), path, parent_id).await?;
-            to_value(result)
-        }
-        "remove_project" => {
-            let project_id: String = field(&args, "projectId", "project_id")?;
-            crate::projects::remove_project(app.clone(), project_id).await?;
-            emit_cache_invalidation(app, &["projects"]);
-            Ok(Value::Null)
-        }
-        "list_worktrees" => {
-            let project_id: String = field(&args, "projectId", "project_id")?;
-            let result = crate::projects::list_worktrees(app.clone(), project_id).await?;
-            to_value(result)
-        }
-        "get_worktree" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let result = crate::projects::get_worktree(app.clone(), worktree_id).await?;
-            to_value(result)
-        }
-        "create_worktree" => {
-            let project_id: String = field(&args, "projectId", "project_id")?;
-            let base_branch: Option<String> = field_opt(&args, "baseBranch", "base_branch")?;
-            let issue_context = field_opt(&args, "issueContext", "issue_context")?;
-            let pr_context = field_opt(&args, "prContext", "pr_context")?;
-            let custom_name = field_opt(&args, "customName", "custom_name")?;
-            let result = crate::projects::create_worktree(
-                app.clone(),
-                project_id,
-                base_branch,
-                issue_context,
-                pr_context,
-                custom_name,
-            )
-            .await?;
-            emit_cache_invalidation(app, &["projects"]);
-            to_value(result)
-        }
-        "delete_worktree" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            crate::projects::delete_worktree(app.clone(), worktree_id).await?;
-            emit_cache_invalidation(app, &["projects"]);
-            Ok(Value::Null)
-        }
-        "get_project_branches" => {
-            let project_id: String = field(&args, "projectId", "project_id")?;
-            let result = crate::projects::get_project_branches(app.clone(), project_id).await?;
-            to_value(result)
-        }
-        "update_project_settings" => {
-            let project_id: String = field(&args, "projectId", "project_id")?;
-            let default_branch: Option<String> =
-                field_opt(&args, "defaultBranch", "default_branch")?;
-            let result =
-                crate::projects::update_project_settings(app.clone(), project_id, default_branch)
-                    .await?;
-            to_value(result)
-        }
-        "reorder_projects" => {
-            let project_ids: Vec<String> = field(&args, "projectIds", "project_ids")?;
-            crate::projects::reorder_projects(app.clone(), project_ids).await?;
-            emit_cache_invalidation(app, &["projects"]);
-            Ok(Value::Null)
-        }
-        "reorder_worktrees" => {
-            let project_id: String = field(&args, "projectId", "project_id")?;
-            let worktree_ids: Vec<String> = field(&args, "worktreeIds", "worktree_ids")?;
-            crate::projects::reorder_worktrees(app.clone(), project_id, worktree_ids).await?;
-            emit_cache_invalidation(app, &["projects"]);
-            Ok(Value::Null)
-        }
-        "fetch_worktrees_status" => {
-            let project_id: String = field(&args, "projectId", "project_id")?;
-            let result = crate::projects::fetch_worktrees_status(app.clone(), project_id).await?;
-            to_value(result)
-        }
-        "archive_worktree" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            crate::projects::archive_worktree(app.clone(), worktree_id).await?;
-            Ok(Value::Null)
-        }
-        "unarchive_worktree" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let result = crate::projects::unarchive_worktree(app.clone(), worktree_id).await?;
-            to_value(result)
-        }
-        "rename_worktree" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let new_name: String = field(&args, "newName", "new_name")?;
-            let result =
-                crate::projects::rename_worktree(app.clone(), worktree_id, new_name).await?;
-            to_value(result)
-        }
-        "has_uncommitted_changes" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let result = crate::projects::has_uncommitted_changes(app.clone(), worktree_id).await?;
-            to_value(result)
-        }
-        "get_git_diff" => {
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let diff_type: String = field(&args, "diffType", "diff_type")?;
-            let base_branch: Option<String> = field_opt(&args, "baseBranch", "base_branch")?;
-            let result =
-                crate::projects::get_git_diff(worktree_path, diff_type, base_branch).await?;
-            to_value(result)
-        }
-        "git_pull" => {
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let base_branch: String = field(&args, "baseBranch", "base_branch")?;
-            let result = crate::projects::git_pull(worktree_path, base_branch).await?;
-            to_value(result)
-        }
-        "git_push" => {
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let pr_number: Option<u32> = field_opt(&args, "prNumber", "pr_number")?;
-            let result = crate::projects::git_push(app.clone(), worktree_path, pr_number).await?;
-            to_value(result)
-        }
-        "commit_changes" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let message: String = from_field(&args, "message")?;
-            let stage_all: Option<bool> = field_opt(&args, "stageAll", "stage_all")?;
-            let result =
-                crate::projects::commit_changes(app.clone(), worktree_id, message, stage_all)
-                    .await?;
-            to_value(result)
-        }
-        "save_worktree_pr" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let pr_number: u32 = field(&args, "prNumber", "pr_number")?;
-            let pr_url: String = field(&args, "prUrl", "pr_url")?;
-            crate::projects::save_worktree_pr(app.clone(), worktree_id, pr_number, pr_url).await?;
-            emit_cache_invalidation(app, &["projects"]);
-            Ok(Value::Null)
-        }
-        "clear_worktree_pr" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            crate::projects::clear_worktree_pr(app.clone(), worktree_id).await?;
-            emit_cache_invalidation(app, &["projects"]);
-            Ok(Value::Null)
-        }
-        "create_pr_with_ai_content" => {
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let magic_prompt: Option<String> = field_opt(&args, "magicPrompt", "magic_prompt")?;
-            let model: Option<String> = from_field_opt(&args, "model")?;
-            let result = crate::projects::create_pr_with_ai_content(
-                app.clone(),
-                worktree_path,
-                magic_prompt,
-                model,
-            )
-            .await?;
-            to_value(result)
-        }
-        "create_commit_with_ai" => {
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let custom_prompt: Option<String> = field_opt(&args, "magicPrompt", "magic_prompt")?;
-            let push: bool = from_field_opt(&args, "push")?.unwrap_or(false);
-            let model: Option<String> = from_field_opt(&args, "model")?;
-            let result = crate::projects::create_commit_with_ai(
-                app.clone(),
-                worktree_path,
-                custom_prompt,
-                push,
-                model,
-            )
-            .await?;
-            to_value(result)
-        }
-        "run_review_with_ai" => {
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let magic_prompt: Option<String> = field_opt(&args, "magicPrompt", "magic_prompt")?;
-            let model: Option<String> = from_field_opt(&args, "model")?;
-            let result = crate::projects::run_review_with_ai(
-                app.clone(),
-                worktree_path,
-                magic_prompt,
-                model,
-            )
-            .await?;
-            to_value(result)
-        }
-        "update_worktree_cached_status" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let pr_status: Option<String> = field_opt(&args, "prStatus", "pr_status")?;
-            let check_status: Option<String> = field_opt(&args, "checkStatus", "check_status")?;
-            let behind_count: Option<u32> = field_opt(&args, "behindCount", "behind_count")?;
-            let ahead_count: Option<u32> = field_opt(&args, "aheadCount", "ahead_count")?;
-            let uncommitted_added: Option<u32> =
-                field_opt(&args, "uncommittedAdded", "uncommitted_added")?;
-            let uncommitted_removed: Option<u32> =
-                field_opt(&args, "uncommittedRemoved", "uncommitted_removed")?;
-            let branch_diff_added: Option<u32> =
-                field_opt(&args, "branchDiffAdded", "branch_diff_added")?;
-            let branch_diff_removed: Option<u32> =
-                field_opt(&args, "branchDiffRemoved", "branch_diff_removed")?;
-            let base_branch_ahead_count: Option<u32> =
-                field_opt(&args, "baseBranchAheadCount", "base_branch_ahead_count")?;
-            let base_branch_behind_count: Option<u32> =
-                field_opt(&args, "baseBranchBehindCount", "base_branch_behind_count")?;
-            let worktree_ahead_count: Option<u32> =
-                field_opt(&args, "worktreeAheadCount", "worktree_ahead_count")?;
-            let unpushed_count: Option<u32> = field_opt(&args, "unpushedCount", "unpushed_count")?;
-            crate::projects::update_worktree_cached_status(
-                app.clone(),
-                worktree_id,
-                pr_status,
-                check_status,
-                behind_count,
-                ahead_count,
-                uncommitted_added,
-                uncommitted_removed,
-                branch_diff_added,
-                branch_diff_removed,
-                base_branch_ahead_count,
-                base_branch_behind_count,
-                worktree_ahead_count,
-                unpushed_count,
-            )
-            .await?;
-            emit_cache_invalidation(app, &["projects"]);
-            Ok(Value::Null)
-        }
-        "list_worktree_files" => {
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let max_files: Option<usize> = field_opt(&args, "maxFiles", "max_files")?;
-            let result = crate::projects::list_worktree_files(worktree_path, max_files).await?;
-            to_value(result)
-        }
+/// Defines [`COMMANDS`] and `run_command`'s match from one list of
+/// `"name" => { ... }` arms, so the two can't drift apart
+macro_rules! commands {
+    (|$app:ident, $grant:ident, $command:ident, $args:ident| $($name:literal => $body:block)*) => {
+        /// Every command the dispatcher handles
+        pub const COMMANDS: &[&str] = &[$($name),*];
 
-        // =====================================================================
-        // GitHub Issues & PRs
-        // =====================================================================
-        "list_github_issues" => {
-            let project_path: String = field(&args, "projectPath", "project_path")?;
-            let state: Option<String> = from_field_opt(&args, "state")?;
-            let result =
-                crate::projects::list_github_issues(app.clone(), project_path, state).await?;
-            to_value(result)
-        }
-        "get_github_issue" => {
-            let project_path: String = field(&args, "projectPath", "project_path")?;
-            let issue_number: u32 = field(&args, "issueNumber", "issue_number")?;
-            let result =
-                crate::projects::get_github_issue(app.clone(), project_path, issue_number).await?;
-            to_value(result)
-        }
-        "list_github_prs" => {
-            let project_path: String = field(&args, "projectPath", "project_path")?;
-            let state: Option<String> = from_field_opt(&args, "state")?;
-            let result = crate::projects::list_github_prs(app.clone(), project_path, state).await?;
-            to_value(result)
-        }
-        "get_github_pr" => {
-            let project_path: String = field(&args, "projectPath", "project_path")?;
-            let pr_number: u32 = field(&args, "prNumber", "pr_number")?;
-            let result =
-                crate::projects::get_github_pr(app.clone(), project_path, pr_number).await?;
-            to_value(result)
-        }
-        "load_issue_context" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let issue_number: u32 = field(&args, "issueNumber", "issue_number")?;
-            let project_path: String = field(&args, "projectPath", "project_path")?;
-            let result = crate::projects::load_issue_context(
-                app.clone(),
-                worktree_id,
-                issue_number,
-                project_path,
-            )
-            .await?;
-            to_value(result)
-        }
-        "list_loaded_issue_contexts" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let result =
-                crate::projects::list_loaded_issue_contexts(app.clone(), worktree_id).await?;
-            to_value(result)
-        }
-        "remove_issue_context" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let issue_number: u32 = field(&args, "issueNumber", "issue_number")?;
-            let project_path: String = field(&args, "projectPath", "project_path")?;
-            crate::projects::remove_issue_context(
-                app.clone(),
-                worktree_id,
-                issue_number,
-                project_path,
-            )
-            .await?;
-            emit_cache_invalidation(app, &["contexts"]);
-            Ok(Value::Null)
-        }
-        "load_pr_context" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let pr_number: u32 = field(&args, "prNumber", "pr_number")?;
-            let project_path: String = field(&args, "projectPath", "project_path")?;
-            let result =
-                crate::projects::load_pr_context(app.clone(), worktree_id, pr_number, project_path)
-                    .await?;
-            to_value(result)
-        }
-        "list_loaded_pr_contexts" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let result = crate::projects::list_loaded_pr_contexts(app.clone(), worktree_id).await?;
-            to_value(result)
-        }
-        "remove_pr_context" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let pr_number: u32 = field(&args, "prNumber", "pr_number")?;
-            let project_path: String = field(&args, "projectPath", "project_path")?;
-            crate::projects::remove_pr_context(app.clone(), worktree_id, pr_number, project_path)
-                .await?;
-            emit_cache_invalidation(app, &["contexts"]);
-            Ok(Value::Null)
-        }
-        "get_issue_context_content" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let issue_number: u32 = field(&args, "issueNumber", "issue_number")?;
-            let project_path: String = field(&args, "projectPath", "project_path")?;
-            let result = crate::projects::get_issue_context_content(
-                app.clone(),
-                worktree_id,
-                issue_number,
-                project_path,
-            )
-            .await?;
-            to_value(result)
-        }
-        "get_pr_context_content" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let pr_number: u32 = field(&args, "prNumber", "pr_number")?;
-            let project_path: String = field(&args, "projectPath", "project_path")?;
-            let result = crate::projects::get_pr_context_content(
-                app.clone(),
-                worktree_id,
-                pr_number,
-                project_path,
-            )
-            .await?;
-            to_value(result)
-        }
+        async fn run_command(
+            $app: &AppHandle,
+            $grant: &Grant,
+            $command: &str,
+            $args: Value,
+        ) -> Result<Value, DispatchError> {
+            match $command {
+                $($name => $body)*
+                _ => Err(DispatchError::UnknownCommand($command.to_string())),
+            }
+        }
+    };
+}
 
-        // =====================================================================
-        // Saved Contexts
-        // =====================================================================
-        "attach_saved_context" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let context_slug: String = field(&args, "contextSlug", "context_slug")?;
-            crate::projects::attach_saved_context(
-                app.clone(),
-                worktree_id,
-                worktree_path,
-                context_slug,
-            )
-            .await?;
-            emit_cache_invalidation(app, &["contexts"]);
-            Ok(Value::Null)
-        }
-        "remove_saved_context" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let context_slug: String = field(&args, "contextSlug", "context_slug")?;
-            crate::projects::remove_saved_context(app.clone(), worktree_id, context_slug).await?;
-            emit_cache_invalidation(app, &["contexts"]);
-            Ok(Value::Null)
-        }
-        "list_attached_saved_contexts" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let result =
-                crate::projects::list_attached_saved_contexts(app.clone(), worktree_id).await?;
-            to_value(result)
-        }
-        "get_saved_context_content" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let context_slug: String = field(&args, "contextSlug", "context_slug")?;
-            let result =
-                crate::projects::get_saved_context_content(app.clone(), worktree_id, context_slug)
-                    .await?;
-            to_value(result)
-        }
+commands! {
+    |app, grant, command, args|
+    // =====================================================================
+    // Preferences & UI State
+    // =====================================================================
+    "load_preferences" => {
+        let result = crate::load_preferences(app.clone()).await?;
+        let mut value = to_value(result)?;
+        super::auth::redact_preferences(grant, &mut value);
+        Ok(value)
+    }
+    "save_preferences" => {
+        let preferences = from_field(&args, "preferences")?;
+        crate::save_preferences(app.clone(), preferences).await?;
+        emit_cache_invalidation(app, &["preferences"]);
+        Ok(Value::Null)
+    }
+    "load_ui_state" => {
+        let result = crate::load_ui_state(app.clone()).await?;
+        to_value(result)
+    }
+    "save_ui_state" => {
+        let ui_state = field(&args, "uiState", "ui_state")?;
+        crate::save_ui_state(app.clone(), ui_state).await?;
+        emit_cache_invalidation(app, &["ui-state"]);
+        Ok(Value::Null)
+    }
 
-        // =====================================================================
-        // Chat Sessions
-        // =====================================================================
-        "get_sessions" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let include_archived: Option<bool> =
-                field_opt(&args, "includeArchived", "include_archived")?;
-            let include_message_counts: Option<bool> =
-                field_opt(&args, "includeMessageCounts", "include_message_counts")?;
-            let result = crate::chat::get_sessions(
-                app.clone(),
-                worktree_id,
-                worktree_path,
-                include_archived,
-                include_message_counts,
-            )
-            .await?;
-            to_value(result)
-        }
-        "list_all_sessions" => {
-            let result = crate::chat::list_all_sessions(app.clone()).await?;
-            to_value(result)
-        }
-        "get_session" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let result =
-                crate::chat::get_session(app.clone(), worktree_id, worktree_path, session_id)
-                    .await?;
-            to_value(result)
-        }
-        "create_session" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let name: Option<String> = from_field_opt(&args, "name")?;
-            let result =
-                crate::chat::create_session(app.clone(), worktree_id, worktree_path, name).await?;
-            to_value(result)
-        }
-        "rename_session" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let new_name: String = field(&args, "newName", "new_name")?;
-            crate::chat::rename_session(
-                app.clone(),
-                worktree_id,
-                worktree_path,
-                session_id,
-                new_name,
-            )
-            .await?;
-            emit_cache_invalidation(app, &["sessions"]);
-            Ok(Value::Null)
-        }
-        "close_session" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            crate::chat::close_session(app.clone(), worktree_id, worktree_path, session_id).await?;
-            emit_cache_invalidation(app, &["sessions"]);
-            Ok(Value::Null)
-        }
-        "reorder_sessions" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let session_ids: Vec<String> = field(&args, "sessionIds", "session_ids")?;
-            crate::chat::reorder_sessions(app.clone(), worktree_id, worktree_path, session_ids)
+    // =====================================================================
+    // Projects
+    // =====================================================================
+    "list_projects" => {
+        let mut result = crate::projects::list_projects(app.clone()).await?;
+        result.retain(|p| p.is_folder || grant.allows_project(&p.id));
+        to_value(result)
+    }
+    "add_project" => {
+        let path: String = from_field(&args, "path")?;
+        let parent_id: Option<String> = field_opt(&args, "parentId", "parent_id")?;
+        let result = crate::projects::add_project(app.clone(), path, parent_id).await?;
+        to_value(result)
+    }
+    "remove_project" => {
+        let project_id: String = field(&args, "projectId", "project_id")?;
+        crate::projects::remove_project(app.clone(), project_id).await?;
+        emit_cache_invalidation(app, &["projects"]);
+        Ok(Value::Null)
+    }
+    "list_worktrees" => {
+        let project_id: String = field(&args, "projectId", "project_id")?;
+        let result = crate::projects::list_worktrees(app.clone(), project_id).await?;
+        to_value(result)
+    }
+    "get_worktree" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let result = crate::projects::get_worktree(app.clone(), worktree_id).await?;
+        to_value(result)
+    }
+    "create_worktree" => {
+        let project_id: String = field(&args, "projectId", "project_id")?;
+        let base_branch: Option<String> = field_opt(&args, "baseBranch", "base_branch")?;
+        let issue_context = field_opt(&args, "issueContext", "issue_context")?;
+        let pr_context = field_opt(&args, "prContext", "pr_context")?;
+        let custom_name = field_opt(&args, "customName", "custom_name")?;
+        let result = crate::projects::create_worktree(
+            app.clone(),
+            project_id,
+            base_branch,
+            issue_context,
+            pr_context,
+            custom_name,
+        )
+        .await?;
+        emit_cache_invalidation(app, &["projects"]);
+        to_value(result)
+    }
+    "delete_worktree" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        crate::projects::delete_worktree(app.clone(), worktree_id).await?;
+        emit_cache_invalidation(app, &["projects"]);
+        Ok(Value::Null)
+    }
+    "get_project_branches" => {
+        let project_id: String = field(&args, "projectId", "project_id")?;
+        let result = crate::projects::get_project_branches(app.clone(), project_id).await?;
+        to_value(result)
+    }
+    "update_project_settings" => {
+        let project_id: String = field(&args, "projectId", "project_id")?;
+        let default_branch: Option<String> =
+            field_opt(&args, "defaultBranch", "default_branch")?;
+        let result =
+            crate::projects::update_project_settings(app.clone(), project_id, default_branch)
                 .await?;
-            emit_cache_invalidation(app, &["sessions"]);
-            Ok(Value::Null)
-        }
-        "set_active_session" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            crate::chat::set_active_session(app.clone(), worktree_id, worktree_path, session_id)
+        to_value(result)
+    }
+    "reorder_projects" => {
+        let project_ids: Vec<String> = field(&args, "projectIds", "project_ids")?;
+        crate::projects::reorder_projects(app.clone(), project_ids).await?;
+        emit_cache_invalidation(app, &["projects"]);
+        Ok(Value::Null)
+    }
+    "reorder_worktrees" => {
+        let project_id: String = field(&args, "projectId", "project_id")?;
+        let worktree_ids: Vec<String> = field(&args, "worktreeIds", "worktree_ids")?;
+        crate::projects::reorder_worktrees(app.clone(), project_id, worktree_ids).await?;
+        emit_cache_invalidation(app, &["projects"]);
+        Ok(Value::Null)
+    }
+    "fetch_worktrees_status" => {
+        let project_id: String = field(&args, "projectId", "project_id")?;
+        let result = crate::projects::fetch_worktrees_status(app.clone(), project_id).await?;
+        to_value(result)
+    }
+    "archive_worktree" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        crate::projects::archive_worktree(app.clone(), worktree_id).await?;
+        Ok(Value::Null)
+    }
+    "unarchive_worktree" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let result = crate::projects::unarchive_worktree(app.clone(), worktree_id).await?;
+        to_value(result)
+    }
+    "rename_worktree" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let new_name: String = field(&args, "newName", "new_name")?;
+        let result =
+            crate::projects::rename_worktree(app.clone(), worktree_id, new_name).await?;
+        to_value(result)
+    }
+    "has_uncommitted_changes" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let result = crate::projects::has_uncommitted_changes(app.clone(), worktree_id).await?;
+        to_value(result)
+    }
+    "get_git_diff" => {
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let diff_type: String = field(&args, "diffType", "diff_type")?;
+        let base_branch: Option<String> = field_opt(&args, "baseBranch", "base_branch")?;
+        let result =
+            crate::projects::get_git_diff(worktree_path, diff_type, base_branch).await?;
+        to_value(result)
+    }
+    "git_pull" => {
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let base_branch: String = field(&args, "baseBranch", "base_branch")?;
+        let result = crate::projects::git_pull(worktree_path, base_branch).await?;
+        to_value(result)
+    }
+    "git_push" => {
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let pr_number: Option<u32> = field_opt(&args, "prNumber", "pr_number")?;
+        let result = crate::projects::git_push(app.clone(), worktree_path, pr_number).await?;
+        to_value(result)
+    }
+    "commit_changes" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let message: String = from_field(&args, "message")?;
+        let stage_all: Option<bool> = field_opt(&args, "stageAll", "stage_all")?;
+        let result =
+            crate::projects::commit_changes(app.clone(), worktree_id, message, stage_all)
                 .await?;
-            emit_cache_invalidation(app, &["sessions"]);
-            Ok(Value::Null)
-        }
+        to_value(result)
+    }
+    "save_worktree_pr" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let pr_number: u32 = field(&args, "prNumber", "pr_number")?;
+        let pr_url: String = field(&args, "prUrl", "pr_url")?;
+        crate::projects::save_worktree_pr(app.clone(), worktree_id, pr_number, pr_url).await?;
+        emit_cache_invalidation(app, &["projects"]);
+        Ok(Value::Null)
+    }
+    "clear_worktree_pr" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        crate::projects::clear_worktree_pr(app.clone(), worktree_id).await?;
+        emit_cache_invalidation(app, &["projects"]);
+        Ok(Value::Null)
+    }
+    "create_pr_with_ai_content" => {
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let magic_prompt: Option<String> = field_opt(&args, "magicPrompt", "magic_prompt")?;
+        let model: Option<String> = from_field_opt(&args, "model")?;
+        let result = crate::projects::create_pr_with_ai_content(
+            app.clone(),
+            worktree_path,
+            magic_prompt,
+            model,
+        )
+        .await?;
+        to_value(result)
+    }
+    "create_commit_with_ai" => {
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let custom_prompt: Option<String> = field_opt(&args, "magicPrompt", "magic_prompt")?;
+        let push: bool = from_field_opt(&args, "push")?.unwrap_or(false);
+        let model: Option<String> = from_field_opt(&args, "model")?;
+        let result = crate::projects::create_commit_with_ai(
+            app.clone(),
+            worktree_path,
+            custom_prompt,
+            push,
+            model,
+        )
+        .await?;
+        to_value(result)
+    }
+    "run_review_with_ai" => {
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let magic_prompt: Option<String> = field_opt(&args, "magicPrompt", "magic_prompt")?;
+        let model: Option<String> = from_field_opt(&args, "model")?;
+        let result = crate::projects::run_review_with_ai(
+            app.clone(),
+            worktree_path,
+            magic_prompt,
+            model,
+        )
+        .await?;
+        to_value(result)
+    }
+    "update_worktree_cached_status" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let pr_status: Option<String> = field_opt(&args, "prStatus", "pr_status")?;
+        let check_status: Option<String> = field_opt(&args, "checkStatus", "check_status")?;
+        let behind_count: Option<u32> = field_opt(&args, "behindCount", "behind_count")?;
+        let ahead_count: Option<u32> = field_opt(&args, "aheadCount", "ahead_count")?;
+        let uncommitted_added: Option<u32> =
+            field_opt(&args, "uncommittedAdded", "uncommitted_added")?;
+        let uncommitted_removed: Option<u32> =
+            field_opt(&args, "uncommittedRemoved", "uncommitted_removed")?;
+        let branch_diff_added: Option<u32> =
+            field_opt(&args, "branchDiffAdded", "branch_diff_added")?;
+        let branch_diff_removed: Option<u32> =
+            field_opt(&args, "branchDiffRemoved", "branch_diff_removed")?;
+        let base_branch_ahead_count: Option<u32> =
+            field_opt(&args, "baseBranchAheadCount", "base_branch_ahead_count")?;
+        let base_branch_behind_count: Option<u32> =
+            field_opt(&args, "baseBranchBehindCount", "base_branch_behind_count")?;
+        let worktree_ahead_count: Option<u32> =
+            field_opt(&args, "worktreeAheadCount", "worktree_ahead_count")?;
+        let unpushed_count: Option<u32> = field_opt(&args, "unpushedCount", "unpushed_count")?;
+        crate::projects::update_worktree_cached_status(
+            app.clone(),
+            worktree_id,
+            pr_status,
+            check_status,
+            behind_count,
+            ahead_count,
+            uncommitted_added,
+            uncommitted_removed,
+            branch_diff_added,
+            branch_diff_removed,
+            base_branch_ahead_count,
+            base_branch_behind_count,
+            worktree_ahead_count,
+            unpushed_count,
+        )
+        .await?;
+        emit_cache_invalidation(app, &["projects"]);
+        Ok(Value::Null)
+    }
+    "list_worktree_files" => {
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let max_files: Option<usize> = field_opt(&args, "maxFiles", "max_files")?;
+        let result = crate::projects::list_worktree_files(worktree_path, max_files).await?;
+        to_value(result)
+    }
 
-        // =====================================================================
-        // Chat Messaging
-        // =====================================================================
-        "send_chat_message" => {
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let message: String = from_field(&args, "message")?;
-            let model: Option<String> = from_field_opt(&args, "model")?;
-            let execution_mode: Option<String> =
-                field_opt(&args, "executionMode", "execution_mode")?;
-            let thinking_level = field_opt(&args, "thinkingLevel", "thinking_level")?;
-            let disable_thinking_for_mode: Option<bool> =
-                field_opt(&args, "disableThinkingForMode", "disable_thinking_for_mode")?;
-            let parallel_execution_prompt_enabled: Option<bool> = field_opt(
-                &args,
-                "parallelExecutionPromptEnabled",
-                "parallel_execution_prompt_enabled",
-            )?;
-            let ai_language: Option<String> = field_opt(&args, "aiLanguage", "ai_language")?;
-            let allowed_tools: Option<Vec<String>> =
-                field_opt(&args, "allowedTools", "allowed_tools")?;
-            let result = crate::chat::send_chat_message(
-                app.clone(),
-                session_id,
-                worktree_id,
-                worktree_path,
-                message,
-                model,
-                execution_mode,
-                thinking_level,
-                disable_thinking_for_mode,
-                parallel_execution_prompt_enabled,
-                ai_language,
-                allowed_tools,
-            )
-            .await?;
-            to_value(result)
-        }
-        "cancel_chat_message" => {
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            crate::chat::cancel_chat_message(app.clone(), session_id, worktree_id).await?;
-            Ok(Value::Null)
-        }
-        "clear_session_history" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            crate::chat::clear_session_history(app.clone(), worktree_id, worktree_path, session_id)
+    // =====================================================================
+    // GitHub Issues & PRs
+    // =====================================================================
+    "list_github_issues" => {
+        let project_path: String = field(&args, "projectPath", "project_path")?;
+        let state: Option<String> = from_field_opt(&args, "state")?;
+        let result =
+            crate::projects::list_github_issues(app.clone(), project_path, state).await?;
+        to_value(result)
+    }
+    "get_github_issue" => {
+        let project_path: String = field(&args, "projectPath", "project_path")?;
+        let issue_number: u32 = field(&args, "issueNumber", "issue_number")?;
+        let result =
+            crate::projects::get_github_issue(app.clone(), project_path, issue_number).await?;
+        to_value(result)
+    }
+    "list_github_prs" => {
+        let project_path: String = field(&args, "projectPath", "project_path")?;
+        let state: Option<String> = from_field_opt(&args, "state")?;
+        let result = crate::projects::list_github_prs(app.clone(), project_path, state).await?;
+        to_value(result)
+    }
+    "get_github_pr" => {
+        let project_path: String = field(&args, "projectPath", "project_path")?;
+        let pr_number: u32 = field(&args, "prNumber", "pr_number")?;
+        let result =
+            crate::projects::get_github_pr(app.clone(), project_path, pr_number).await?;
+        to_value(result)
+    }
+    "load_issue_context" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let issue_number: u32 = field(&args, "issueNumber", "issue_number")?;
+        let project_path: String = field(&args, "projectPath", "project_path")?;
+        let result = crate::projects::load_issue_context(
+            app.clone(),
+            worktree_id,
+            issue_number,
+            project_path,
+        )
+        .await?;
+        to_value(result)
+    }
+    "list_loaded_issue_contexts" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let result =
+            crate::projects::list_loaded_issue_contexts(app.clone(), worktree_id).await?;
+        to_value(result)
+    }
+    "remove_issue_context" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let issue_number: u32 = field(&args, "issueNumber", "issue_number")?;
+        let project_path: String = field(&args, "projectPath", "project_path")?;
+        crate::projects::remove_issue_context(
+            app.clone(),
+            worktree_id,
+            issue_number,
+            project_path,
+        )
+        .await?;
+        emit_cache_invalidation(app, &["contexts"]);
+        Ok(Value::Null)
+    }
+    "load_pr_context" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let pr_number: u32 = field(&args, "prNumber", "pr_number")?;
+        let project_path: String = field(&args, "projectPath", "project_path")?;
+        let result =
+            crate::projects::load_pr_context(app.clone(), worktree_id, pr_number, project_path)
                 .await?;
-            emit_cache_invalidation(app, &["sessions"]);
-            Ok(Value::Null)
-        }
-        "set_session_model" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let model: String = from_field(&args, "model")?;
-            crate::chat::set_session_model(
-                app.clone(),
-                worktree_id,
-                worktree_path,
-                session_id,
-                model,
-            )
-            .await?;
-            Ok(Value::Null)
-        }
-        "set_session_thinking_level" => {
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let thinking_level: crate::chat::types::ThinkingLevel =
-                field(&args, "thinkingLevel", "thinking_level")?;
-            crate::chat::set_session_thinking_level(
-                app.clone(),
-                worktree_id,
-                worktree_path,
-                session_id,
-                thinking_level,
-            )
+        to_value(result)
+    }
+    "list_loaded_pr_contexts" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let result = crate::projects::list_loaded_pr_contexts(app.clone(), worktree_id).await?;
+        to_value(result)
+    }
+    "remove_pr_context" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let pr_number: u32 = field(&args, "prNumber", "pr_number")?;
+        let project_path: String = field(&args, "projectPath", "project_path")?;
+        crate::projects::remove_pr_context(app.clone(), worktree_id, pr_number, project_path)
             .await?;
-            Ok(Value::Null)
-        }
-        "mark_plan_approved" => {
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let message_id: String = field(&args, "messageId", "message_id")?;
-            crate::chat::mark_plan_approved(
-                app.clone(),
-                worktree_id,
-                worktree_path,
-                session_id,
-                message_id,
-            )
+        emit_cache_invalidation(app, &["contexts"]);
+        Ok(Value::Null)
+    }
+    "get_issue_context_content" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let issue_number: u32 = field(&args, "issueNumber", "issue_number")?;
+        let project_path: String = field(&args, "projectPath", "project_path")?;
+        let result = crate::projects::get_issue_context_content(
+            app.clone(),
+            worktree_id,
+            issue_number,
+            project_path,
+        )
+        .await?;
+        to_value(result)
+    }
+    "get_pr_context_content" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let pr_number: u32 = field(&args, "prNumber", "pr_number")?;
+        let project_path: String = field(&args, "projectPath", "project_path")?;
+        let result = crate::projects::get_pr_context_content(
+            app.clone(),
+            worktree_id,
+            pr_number,
+            project_path,
+        )
+        .await?;
+        to_value(result)
+    }
+
+    // =====================================================================
+    // Saved Contexts
+    // =====================================================================
+    "attach_saved_context" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let context_slug: String = field(&args, "contextSlug", "context_slug")?;
+        crate::projects::attach_saved_context(
+            app.clone(),
+            worktree_id,
+            worktree_path,
+            context_slug,
+        )
+        .await?;
+        emit_cache_invalidation(app, &["contexts"]);
+        Ok(Value::Null)
+    }
+    "remove_saved_context" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let context_slug: String = field(&args, "contextSlug", "context_slug")?;
+        crate::projects::remove_saved_context(app.clone(), worktree_id, context_slug).await?;
+        emit_cache_invalidation(app, &["contexts"]);
+        Ok(Value::Null)
+    }
+    "list_attached_saved_contexts" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let result =
+            crate::projects::list_attached_saved_contexts(app.clone(), worktree_id).await?;
+        to_value(result)
+    }
+    "get_saved_context_content" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let context_slug: String = field(&args, "contextSlug", "context_slug")?;
+        let result =
+            crate::projects::get_saved_context_content(app.clone(), worktree_id, context_slug)
+                .await?;
+        to_value(result)
+    }
+
+    // =====================================================================
+    // Chat Sessions
+    // =====================================================================
+    "get_sessions" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let include_archived: Option<bool> =
+            field_opt(&args, "includeArchived", "include_archived")?;
+        let include_message_counts: Option<bool> =
+            field_opt(&args, "includeMessageCounts", "include_message_counts")?;
+        let result = crate::chat::get_sessions(
+            app.clone(),
+            worktree_id,
+            worktree_path,
+            include_archived,
+            include_message_counts,
+        )
+        .await?;
+        to_value(result)
+    }
+    "list_all_sessions" => {
+        let result = crate::chat::list_all_sessions(app.clone()).await?;
+        to_value(result)
+    }
+    "get_session" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let result =
+            crate::chat::get_session(app.clone(), worktree_id, worktree_path, session_id)
+                .await?;
+        to_value(result)
+    }
+    "create_session" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let name: Option<String> = from_field_opt(&args, "name")?;
+        let result =
+            crate::chat::create_session(app.clone(), worktree_id, worktree_path, name).await?;
+        to_value(result)
+    }
+    "rename_session" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let new_name: String = field(&args, "newName", "new_name")?;
+        crate::chat::rename_session(
+            app.clone(),
+            worktree_id,
+            worktree_path,
+            session_id,
+            new_name,
+        )
+        .await?;
+        emit_cache_invalidation(app, &["sessions"]);
+        Ok(Value::Null)
+    }
+    "close_session" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        crate::chat::close_session(app.clone(), worktree_id, worktree_path, session_id).await?;
+        emit_cache_invalidation(app, &["sessions"]);
+        Ok(Value::Null)
+    }
+    "reorder_sessions" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let session_ids: Vec<String> = field(&args, "sessionIds", "session_ids")?;
+        crate::chat::reorder_sessions(app.clone(), worktree_id, worktree_path, session_ids)
             .await?;
-            Ok(Value::Null)
-        }
-        "save_cancelled_message" => {
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let content: String = from_field(&args, "content")?;
-            let tool_calls: Vec<crate::chat::types::ToolCall> =
-                from_field_opt(&args, "toolCalls")?.unwrap_or_default();
-            let content_blocks: Vec<crate::chat::types::ContentBlock> =
-                from_field_opt(&args, "contentBlocks")?.unwrap_or_default();
-            crate::chat::save_cancelled_message(
-                app.clone(),
-                worktree_id,
-                worktree_path,
-                session_id,
-                content,
-                tool_calls,
-                content_blocks,
-            )
+        emit_cache_invalidation(app, &["sessions"]);
+        Ok(Value::Null)
+    }
+    "set_active_session" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        crate::chat::set_active_session(app.clone(), worktree_id, worktree_path, session_id)
             .await?;
-            emit_cache_invalidation(app, &["sessions"]);
-            Ok(Value::Null)
-        }
-        "has_running_sessions" => {
-            let result = crate::chat::has_running_sessions();
-            to_value(result)
-        }
+        emit_cache_invalidation(app, &["sessions"]);
+        Ok(Value::Null)
+    }
 
-        // =====================================================================
-        // Chat - Saved Contexts
-        // =====================================================================
-        "list_saved_contexts" => {
-            let result = crate::chat::list_saved_contexts(app.clone()).await?;
-            to_value(result)
-        }
-        "save_context_file" => {
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let slug: String = from_field(&args, "slug")?;
-            let content: String = from_field(&args, "content")?;
-            crate::chat::save_context_file(app.clone(), worktree_path, slug, content).await?;
-            emit_cache_invalidation(app, &["contexts"]);
-            Ok(Value::Null)
-        }
-        "read_context_file" => {
-            let path: String = from_field(&args, "path")?;
-            let result = crate::chat::read_context_file(app.clone(), path).await?;
-            to_value(result)
-        }
-        "delete_context_file" => {
-            let path: String = from_field(&args, "path")?;
-            crate::chat::delete_context_file(app.clone(), path).await?;
-            emit_cache_invalidation(app, &["contexts"]);
-            Ok(Value::Null)
-        }
-        "generate_context_from_session" => {
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let project_name: String = field(&args, "projectName", "project_name")?;
-            let custom_prompt: Option<String> = field_opt(&args, "magicPrompt", "magic_prompt")?;
-            let model: Option<String> = from_field_opt(&args, "model")?;
-            let result = crate::chat::generate_context_from_session(
-                app.clone(),
-                worktree_path,
-                worktree_id,
-                session_id,
-                project_name,
-                custom_prompt,
-                model,
-            )
+    // =====================================================================
+    // Chat Messaging
+    // =====================================================================
+    "send_chat_message" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let message: String = from_field(&args, "message")?;
+        let model: Option<String> = from_field_opt(&args, "model")?;
+        let execution_mode: Option<String> =
+            field_opt(&args, "executionMode", "execution_mode")?;
+        let thinking_level = field_opt(&args, "thinkingLevel", "thinking_level")?;
+        let disable_thinking_for_mode: Option<bool> =
+            field_opt(&args, "disableThinkingForMode", "disable_thinking_for_mode")?;
+        let parallel_execution_prompt_enabled: Option<bool> = field_opt(
+            &args,
+            "parallelExecutionPromptEnabled",
+            "parallel_execution_prompt_enabled",
+        )?;
+        let ai_language: Option<String> = field_opt(&args, "aiLanguage", "ai_language")?;
+        let allowed_tools: Option<Vec<String>> =
+            field_opt(&args, "allowedTools", "allowed_tools")?;
+        let override_budget: Option<bool> =
+            field_opt(&args, "overrideBudget", "override_budget")?;
+        let result = crate::chat::send_chat_message(
+            app.clone(),
+            session_id,
+            worktree_id,
+            worktree_path,
+            message,
+            model,
+            execution_mode,
+            thinking_level,
+            disable_thinking_for_mode,
+            parallel_execution_prompt_enabled,
+            ai_language,
+            allowed_tools,
+            override_budget,
+        )
+        .await?;
+        to_value(result)
+    }
+    "cancel_chat_message" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        crate::chat::cancel_chat_message(app.clone(), session_id, worktree_id).await?;
+        Ok(Value::Null)
+    }
+    "clear_session_history" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        crate::chat::clear_session_history(app.clone(), worktree_id, worktree_path, session_id)
             .await?;
-            to_value(result)
-        }
+        emit_cache_invalidation(app, &["sessions"]);
+        Ok(Value::Null)
+    }
+    "set_session_model" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let model: String = from_field(&args, "model")?;
+        crate::chat::set_session_model(
+            app.clone(),
+            worktree_id,
+            worktree_path,
+            session_id,
+            model,
+        )
+        .await?;
+        Ok(Value::Null)
+    }
+    "set_session_thinking_level" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let thinking_level: crate::chat::types::ThinkingLevel =
+            field(&args, "thinkingLevel", "thinking_level")?;
+        crate::chat::set_session_thinking_level(
+            app.clone(),
+            worktree_id,
+            worktree_path,
+            session_id,
+            thinking_level,
+        )
+        .await?;
+        Ok(Value::Null)
+    }
+    "mark_plan_approved" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let message_id: String = field(&args, "messageId", "message_id")?;
+        crate::chat::mark_plan_approved(
+            app.clone(),
+            worktree_id,
+            worktree_path,
+            session_id,
+            message_id,
+        )
+        .await?;
+        Ok(Value::Null)
+    }
+    "save_cancelled_message" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let content: String = from_field(&args, "content")?;
+        let tool_calls: Vec<crate::chat::types::ToolCall> =
+            from_field_opt(&args, "toolCalls")?.unwrap_or_default();
+        let content_blocks: Vec<crate::chat::types::ContentBlock> =
+            from_field_opt(&args, "contentBlocks")?.unwrap_or_default();
+        crate::chat::save_cancelled_message(
+            app.clone(),
+            worktree_id,
+            worktree_path,
+            session_id,
+            content,
+            tool_calls,
+            content_blocks,
+        )
+        .await?;
+        emit_cache_invalidation(app, &["sessions"]);
+        Ok(Value::Null)
+    }
+    "has_running_sessions" => {
+        let result = crate::chat::has_running_sessions();
+        to_value(result)
+    }
 
-        // =====================================================================
-        // Chat - File operations
-        // =====================================================================
-        "read_file_content" => {
-            let file_path: String = field(&args, "filePath", "file_path")?;
-            let result = crate::chat::read_file_content(file_path).await?;
-            to_value(result)
-        }
-        "read_plan_file" => {
-            let path: String = from_field(&args, "path")?;
-            let result = crate::chat::read_plan_file(path).await?;
-            to_value(result)
-        }
+    // =====================================================================
+    // Chat - Saved Contexts
+    // =====================================================================
+    "list_saved_contexts" => {
+        let result = crate::chat::list_saved_contexts(app.clone()).await?;
+        to_value(result)
+    }
+    "save_context_file" => {
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let slug: String = from_field(&args, "slug")?;
+        let content: String = from_field(&args, "content")?;
+        crate::chat::save_context_file(app.clone(), worktree_path, slug, content).await?;
+        emit_cache_invalidation(app, &["contexts"]);
+        Ok(Value::Null)
+    }
+    "read_context_file" => {
+        let path: String = from_field(&args, "path")?;
+        let result = crate::chat::read_context_file(app.clone(), path).await?;
+        to_value(result)
+    }
+    "delete_context_file" => {
+        let path: String = from_field(&args, "path")?;
+        crate::chat::delete_context_file(app.clone(), path).await?;
+        emit_cache_invalidation(app, &["contexts"]);
+        Ok(Value::Null)
+    }
+    "generate_context_from_session" => {
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let project_name: String = field(&args, "projectName", "project_name")?;
+        let custom_prompt: Option<String> = field_opt(&args, "magicPrompt", "magic_prompt")?;
+        let model: Option<String> = from_field_opt(&args, "model")?;
+        let result = crate::chat::generate_context_from_session(
+            app.clone(),
+            worktree_path,
+            worktree_id,
+            session_id,
+            project_name,
+            custom_prompt,
+            model,
+        )
+        .await?;
+        to_value(result)
+    }
 
-        // =====================================================================
-        // Background Tasks (polling control)
-        // =====================================================================
-        "set_app_focus_state" => {
-            let focused: bool = from_field(&args, "focused")?;
-            let state = app.state::<crate::background_tasks::BackgroundTaskManager>();
-            crate::background_tasks::commands::set_app_focus_state(state, focused)?;
-            Ok(Value::Null)
-        }
-        "set_active_worktree_for_polling" => {
-            let worktree_id: Option<String> = field_opt(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: Option<String> = field_opt(&args, "worktreePath", "worktree_path")?;
-            let base_branch: Option<String> = field_opt(&args, "baseBranch", "base_branch")?;
-            let pr_number: Option<u32> = field_opt(&args, "prNumber", "pr_number")?;
-            let pr_url: Option<String> = field_opt(&args, "prUrl", "pr_url")?;
-            let state = app.state::<crate::background_tasks::BackgroundTaskManager>();
-            crate::background_tasks::commands::set_active_worktree_for_polling(
-                state,
-                worktree_id,
-                worktree_path,
-                base_branch,
-                pr_number,
-                pr_url,
-            )?;
-            Ok(Value::Null)
-        }
-        "trigger_immediate_git_poll" => {
-            let state = app.state::<crate::background_tasks::BackgroundTaskManager>();
-            crate::background_tasks::commands::trigger_immediate_git_poll(state)?;
-            Ok(Value::Null)
-        }
-        "trigger_immediate_remote_poll" => {
-            let state = app.state::<crate::background_tasks::BackgroundTaskManager>();
-            crate::background_tasks::commands::trigger_immediate_remote_poll(state)?;
-            Ok(Value::Null)
-        }
-        "set_git_poll_interval" => {
-            let seconds: u64 = from_field(&args, "seconds")?;
-            let state = app.state::<crate::background_tasks::BackgroundTaskManager>();
-            crate::background_tasks::commands::set_git_poll_interval(state, seconds)?;
-            Ok(Value::Null)
-        }
-        "get_git_poll_interval" => {
-            let state = app.state::<crate::background_tasks::BackgroundTaskManager>();
-            let result = crate::background_tasks::commands::get_git_poll_interval(state)?;
-            to_value(result)
-        }
-        "set_remote_poll_interval" => {
-            let seconds: u64 = from_field(&args, "seconds")?;
-            let state = app.state::<crate::background_tasks::BackgroundTaskManager>();
-            crate::background_tasks::commands::set_remote_poll_interval(state, seconds)?;
-            Ok(Value::Null)
-        }
-        "get_remote_poll_interval" => {
-            let state = app.state::<crate::background_tasks::BackgroundTaskManager>();
-            let result = crate::background_tasks::commands::get_remote_poll_interval(state)?;
-            to_value(result)
-        }
+    // =====================================================================
+    // Chat - File operations
+    // =====================================================================
+    "read_file_content" => {
+        let file_path: String = field(&args, "filePath", "file_path")?;
+        let result = crate::chat::read_file_content(file_path).await?;
+        to_value(result)
+    }
+    "read_plan_file" => {
+        let path: String = from_field(&args, "path")?;
+        let result = crate::chat::read_plan_file(path).await?;
+        to_value(result)
+    }
 
-        // =====================================================================
-        // Terminal
-        // =====================================================================
-        "kill_all_terminals" => {
-            let result = crate::terminal::kill_all_terminals();
-            to_value(result)
-        }
+    // =====================================================================
+    // Background Tasks (polling control)
+    // =====================================================================
+    "set_app_focus_state" => {
+        let focused: bool = from_field(&args, "focused")?;
+        let state = app.state::<crate::background_tasks::BackgroundTaskManager>();
+        crate::background_tasks::commands::set_app_focus_state(state, focused)?;
+        Ok(Value::Null)
+    }
+    "set_active_worktree_for_polling" => {
+        let worktree_id: Option<String> = field_opt(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: Option<String> = field_opt(&args, "worktreePath", "worktree_path")?;
+        let base_branch: Option<String> = field_opt(&args, "baseBranch", "base_branch")?;
+        let pr_number: Option<u32> = field_opt(&args, "prNumber", "pr_number")?;
+        let pr_url: Option<String> = field_opt(&args, "prUrl", "pr_url")?;
+        let state = app.state::<crate::background_tasks::BackgroundTaskManager>();
+        crate::background_tasks::commands::set_active_worktree_for_polling(
+            state,
+            worktree_id,
+            worktree_path,
+            base_branch,
+            pr_number,
+            pr_url,
+        )?;
+        Ok(Value::Null)
+    }
+    "set_polled_worktrees" => {
+        let worktrees: Vec<crate::background_tasks::commands::PolledWorktreeInput> =
+            from_field(&args, "worktrees")?;
+        let state = app.state::<crate::background_tasks::BackgroundTaskManager>();
+        crate::background_tasks::commands::set_polled_worktrees(state, worktrees)?;
+        Ok(Value::Null)
+    }
+    "trigger_immediate_git_poll" => {
+        let state = app.state::<crate::background_tasks::BackgroundTaskManager>();
+        crate::background_tasks::commands::trigger_immediate_git_poll(state)?;
+        Ok(Value::Null)
+    }
+    "trigger_immediate_remote_poll" => {
+        let state = app.state::<crate::background_tasks::BackgroundTaskManager>();
+        crate::background_tasks::commands::trigger_immediate_remote_poll(state)?;
+        Ok(Value::Null)
+    }
+    "set_git_poll_interval" => {
+        let seconds: u64 = from_field(&args, "seconds")?;
+        let state = app.state::<crate::background_tasks::BackgroundTaskManager>();
+        crate::background_tasks::commands::set_git_poll_interval(state, seconds)?;
+        Ok(Value::Null)
+    }
+    "get_git_poll_interval" => {
+        let state = app.state::<crate::background_tasks::BackgroundTaskManager>();
+        let result = crate::background_tasks::commands::get_git_poll_interval(state)?;
+        to_value(result)
+    }
+    "set_remote_poll_interval" => {
+        let seconds: u64 = from_field(&args, "seconds")?;
+        let state = app.state::<crate::background_tasks::BackgroundTaskManager>();
+        crate::background_tasks::commands::set_remote_poll_interval(state, seconds)?;
+        Ok(Value::Null)
+    }
+    "get_remote_poll_interval" => {
+        let state = app.state::<crate::background_tasks::BackgroundTaskManager>();
+        let result = crate::background_tasks::commands::get_remote_poll_interval(state)?;
+        to_value(result)
+    }
 
-        // =====================================================================
-        // Recovery & Cleanup
-        // =====================================================================
-        "cleanup_old_recovery_files" => {
-            let result = crate::cleanup_old_recovery_files(app.clone()).await?;
-            to_value(result)
-        }
-        "check_resumable_sessions" => {
-            let result = crate::chat::check_resumable_sessions(app.clone()).await?;
-            to_value(result)
-        }
-        "cleanup_old_archives" => {
-            let retention_days: u32 = field(&args, "retentionDays", "retention_days")?;
-            let result = crate::projects::cleanup_old_archives(app.clone(), retention_days).await?;
-            to_value(result)
-        }
+    // =====================================================================
+    // Terminal
+    // =====================================================================
+    "kill_all_terminals" => {
+        let result = crate::terminal::kill_all_terminals();
+        to_value(result)
+    }
 
-        // =====================================================================
-        // HTTP Server control (exposed so web clients can check status)
-        // =====================================================================
-        "get_http_server_status" => {
-            let result = crate::http_server::server::get_server_status(app.clone()).await;
-            to_value(result)
-        }
+    // =====================================================================
+    // Recovery & Cleanup
+    // =====================================================================
+    "cleanup_old_recovery_files" => {
+        let result = crate::cleanup_old_recovery_files(app.clone()).await?;
+        to_value(result)
+    }
+    "check_resumable_sessions" => {
+        let result = crate::chat::check_resumable_sessions(app.clone()).await?;
+        to_value(result)
+    }
+    "cleanup_old_archives" => {
+        let retention_days: u32 = field(&args, "retentionDays", "retention_days")?;
+        let result = crate::projects::cleanup_old_archives(app.clone(), retention_days).await?;
+        to_value(result)
+    }
 
-        // =====================================================================
-        // Core / Utility
-        // =====================================================================
-        "greet" => {
-            let name: String = from_field(&args, "name")?;
-            let result = format!("Hello, {name}! You've been greeted from Rust!");
-            to_value(result)
-        }
-        "send_native_notification" => {
-            let title: String = from_field(&args, "title")?;
-            let body: Option<String> = from_field_opt(&args, "body")?;
-            crate::send_native_notification(app.clone(), title, body).await?;
-            Ok(Value::Null)
-        }
-        "save_emergency_data" => {
-            let filename: String = from_field(&args, "filename")?;
-            let data: Value = from_field(&args, "data")?;
-            crate::save_emergency_data(app.clone(), filename, data).await?;
-            Ok(Value::Null)
-        }
-        "load_emergency_data" => {
-            let filename: String = from_field(&args, "filename")?;
-            let result = crate::load_emergency_data(app.clone(), filename).await?;
-            to_value(result)
-        }
+    // =====================================================================
+    // HTTP Server control (exposed so web clients can check status)
+    // =====================================================================
+    "get_http_server_status" => {
+        let result = crate::http_server::server::get_server_status(app.clone()).await;
+        to_value(result)
+    }
 
-        // =====================================================================
-        // Project Management (additional)
-        // =====================================================================
-        "init_git_in_folder" => {
-            let path: String = from_field(&args, "path")?;
-            let result = crate::projects::init_git_in_folder(path).await?;
-            to_value(result)
-        }
-        "init_project" => {
-            let path: String = from_field(&args, "path")?;
-            let parent_id: Option<String> = field_opt(&args, "parentId", "parent_id")?;
-            let result = crate::projects::init_project(app.clone(), path, parent_id).await?;
-            to_value(result)
-        }
-        "create_worktree_from_existing_branch" => {
-            let project_id: String = field(&args, "projectId", "project_id")?;
-            let branch_name: String = field(&args, "branchName", "branch_name")?;
-            let issue_context = field_opt(&args, "issueContext", "issue_context")?;
-            let pr_context = field_opt(&args, "prContext", "pr_context")?;
-            let result = crate::projects::create_worktree_from_existing_branch(
-                app.clone(),
-                project_id,
-                branch_name,
-                issue_context,
-                pr_context,
-            )
-            .await?;
-            to_value(result)
-        }
-        "checkout_pr" => {
-            let project_id: String = field(&args, "projectId", "project_id")?;
-            let pr_number: u32 = field(&args, "prNumber", "pr_number")?;
-            let result = crate::projects::checkout_pr(app.clone(), project_id, pr_number).await?;
-            to_value(result)
-        }
-        "create_base_session" => {
-            let project_id: String = field(&args, "projectId", "project_id")?;
-            let result = crate::projects::create_base_session(app.clone(), project_id).await?;
-            emit_cache_invalidation(app, &["projects"]);
-            to_value(result)
-        }
-        "close_base_session" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            crate::projects::close_base_session(app.clone(), worktree_id).await?;
-            emit_cache_invalidation(app, &["projects"]);
-            Ok(Value::Null)
-        }
-        "close_base_session_clean" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            crate::projects::close_base_session_clean(app.clone(), worktree_id).await?;
-            emit_cache_invalidation(app, &["projects"]);
-            Ok(Value::Null)
-        }
-        "list_archived_worktrees" => {
-            let result = crate::projects::list_archived_worktrees(app.clone()).await?;
-            to_value(result)
-        }
-        "import_worktree" => {
-            let project_id: String = field(&args, "projectId", "project_id")?;
-            let path: String = from_field(&args, "path")?;
-            let result = crate::projects::import_worktree(app.clone(), project_id, path).await?;
-            to_value(result)
-        }
-        "permanently_delete_worktree" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            crate::projects::permanently_delete_worktree(app.clone(), worktree_id).await?;
-            Ok(Value::Null)
-        }
-        "delete_all_archives" => {
-            let result = crate::projects::delete_all_archives(app.clone()).await?;
-            to_value(result)
-        }
-        "open_worktree_in_finder" => {
-            // NATIVE ONLY: Finder doesn't exist in browser mode
-            Ok(Value::Null)
-        }
-        "open_project_worktrees_folder" => {
-            // NATIVE ONLY: Finder doesn't exist in browser mode
-            Ok(Value::Null)
-        }
-        "open_worktree_in_terminal" => {
-            // NATIVE ONLY: Cannot open native terminal from browser
-            Ok(Value::Null)
-        }
-        "open_worktree_in_editor" => {
-            // NATIVE ONLY: Cannot open native editor from browser
-            Ok(Value::Null)
-        }
-        "open_pull_request" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let title: Option<String> = from_field_opt(&args, "title")?;
-            let body: Option<String> = from_field_opt(&args, "body")?;
-            let draft: Option<bool> = from_field_opt(&args, "draft")?;
-            let result =
-                crate::projects::open_pull_request(app.clone(), worktree_id, title, body, draft)
-                    .await?;
-            to_value(result)
-        }
-        "open_project_on_github" => {
-            let project_id: String = field(&args, "projectId", "project_id")?;
-            crate::projects::open_project_on_github(app.clone(), project_id).await?;
-            Ok(Value::Null)
-        }
-        "get_github_branch_url" => {
-            let repo_path: String = field(&args, "repoPath", "repo_path")?;
-            let branch: String = from_field(&args, "branch")?;
-            let result = crate::projects::get_github_branch_url(repo_path, branch).await?;
-            to_value(result)
-        }
-        "get_github_repo_url" => {
-            let repo_path: String = field(&args, "repoPath", "repo_path")?;
-            let result = crate::projects::get_github_repo_url(repo_path).await?;
-            to_value(result)
-        }
-        "get_pr_prompt" => {
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let result = crate::projects::get_pr_prompt(app.clone(), worktree_path).await?;
-            to_value(result)
-        }
-        "get_review_prompt" => {
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let result = crate::projects::get_review_prompt(app.clone(), worktree_path).await?;
-            to_value(result)
-        }
-        "rebase_worktree" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let commit_message: Option<String> =
-                field_opt(&args, "commitMessage", "commit_message")?;
-            let result =
-                crate::projects::rebase_worktree(app.clone(), worktree_id, commit_message).await?;
-            to_value(result)
-        }
+    // =====================================================================
+    // Core / Utility
+    // =====================================================================
+    "greet" => {
+        let name: String = from_field(&args, "name")?;
+        let result = format!("Hello, {name}! You've been greeted from Rust!");
+        to_value(result)
+    }
+    "send_native_notification" => {
+        let title: String = from_field(&args, "title")?;
+        let body: Option<String> = from_field_opt(&args, "body")?;
+        crate::send_native_notification(app.clone(), title, body).await?;
+        Ok(Value::Null)
+    }
+    "save_emergency_data" => {
+        let filename: String = from_field(&args, "filename")?;
+        let data: Value = from_field(&args, "data")?;
+        crate::save_emergency_data(app.clone(), filename, data).await?;
+        Ok(Value::Null)
+    }
+    "load_emergency_data" => {
+        let filename: String = from_field(&args, "filename")?;
+        let result = crate::load_emergency_data(app.clone(), filename).await?;
+        to_value(result)
+    }
 
-        // =====================================================================
-        // Git Operations (additional)
-        // =====================================================================
-        "merge_worktree_to_base" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let merge_type: crate::projects::types::MergeType =
-                field(&args, "mergeType", "merge_type")?;
-            let result =
-                crate::projects::merge_worktree_to_base(app.clone(), worktree_id, merge_type)
-                    .await?;
-            to_value(result)
-        }
-        "get_merge_conflicts" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let result = crate::projects::get_merge_conflicts(app.clone(), worktree_id).await?;
-            to_value(result)
-        }
-        "fetch_and_merge_base" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let result = crate::projects::fetch_and_merge_base(app.clone(), worktree_id).await?;
-            to_value(result)
-        }
+    // =====================================================================
+    // Project Management (additional)
+    // =====================================================================
+    "init_git_in_folder" => {
+        let path: String = from_field(&args, "path")?;
+        let result = crate::projects::init_git_in_folder(path).await?;
+        to_value(result)
+    }
+    "init_project" => {
+        let path: String = from_field(&args, "path")?;
+        let parent_id: Option<String> = field_opt(&args, "parentId", "parent_id")?;
+        let result = crate::projects::init_project(app.clone(), path, parent_id).await?;
+        to_value(result)
+    }
+    "create_worktree_from_existing_branch" => {
+        let project_id: String = field(&args, "projectId", "project_id")?;
+        let branch_name: String = field(&args, "branchName", "branch_name")?;
+        let issue_context = field_opt(&args, "issueContext", "issue_context")?;
+        let pr_context = field_opt(&args, "prContext", "pr_context")?;
+        let result = crate::projects::create_worktree_from_existing_branch(
+            app.clone(),
+            project_id,
+            branch_name,
+            issue_context,
+            pr_context,
+        )
+        .await?;
+        to_value(result)
+    }
+    "checkout_pr" => {
+        let project_id: String = field(&args, "projectId", "project_id")?;
+        let pr_number: u32 = field(&args, "prNumber", "pr_number")?;
+        let result = crate::projects::checkout_pr(app.clone(), project_id, pr_number).await?;
+        to_value(result)
+    }
+    "create_base_session" => {
+        let project_id: String = field(&args, "projectId", "project_id")?;
+        let result = crate::projects::create_base_session(app.clone(), project_id).await?;
+        emit_cache_invalidation(app, &["projects"]);
+        to_value(result)
+    }
+    "close_base_session" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        crate::projects::close_base_session(app.clone(), worktree_id).await?;
+        emit_cache_invalidation(app, &["projects"]);
+        Ok(Value::Null)
+    }
+    "close_base_session_clean" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        crate::projects::close_base_session_clean(app.clone(), worktree_id).await?;
+        emit_cache_invalidation(app, &["projects"]);
+        Ok(Value::Null)
+    }
+    "list_archived_worktrees" => {
+        let result = crate::projects::list_archived_worktrees(app.clone()).await?;
+        to_value(result)
+    }
+    "import_worktree" => {
+        let project_id: String = field(&args, "projectId", "project_id")?;
+        let path: String = from_field(&args, "path")?;
+        let result = crate::projects::import_worktree(app.clone(), project_id, path).await?;
+        to_value(result)
+    }
+    "permanently_delete_worktree" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        crate::projects::permanently_delete_worktree(app.clone(), worktree_id).await?;
+        Ok(Value::Null)
+    }
+    "delete_all_archives" => {
+        let result = crate::projects::delete_all_archives(app.clone()).await?;
+        to_value(result)
+    }
+    "open_worktree_in_finder" => {
+        // NATIVE ONLY: Finder doesn't exist in browser mode
+        Ok(Value::Null)
+    }
+    "open_project_worktrees_folder" => {
+        // NATIVE ONLY: Finder doesn't exist in browser mode
+        Ok(Value::Null)
+    }
+    "open_worktree_in_terminal" => {
+        // NATIVE ONLY: Cannot open native terminal from browser
+        Ok(Value::Null)
+    }
+    "open_worktree_in_editor" => {
+        // NATIVE ONLY: Cannot open native editor from browser
+        Ok(Value::Null)
+    }
+    "open_pull_request" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let title: Option<String> = from_field_opt(&args, "title")?;
+        let body: Option<String> = from_field_opt(&args, "body")?;
+        let draft: Option<bool> = from_field_opt(&args, "draft")?;
+        let result =
+            crate::projects::open_pull_request(app.clone(), worktree_id, title, body, draft)
+                .await?;
+        to_value(result)
+    }
+    "open_project_on_github" => {
+        let project_id: String = field(&args, "projectId", "project_id")?;
+        crate::projects::open_project_on_github(app.clone(), project_id).await?;
+        Ok(Value::Null)
+    }
+    "get_github_branch_url" => {
+        let repo_path: String = field(&args, "repoPath", "repo_path")?;
+        let branch: String = from_field(&args, "branch")?;
+        let result = crate::projects::get_github_branch_url(repo_path, branch).await?;
+        to_value(result)
+    }
+    "get_github_repo_url" => {
+        let repo_path: String = field(&args, "repoPath", "repo_path")?;
+        let result = crate::projects::get_github_repo_url(repo_path).await?;
+        to_value(result)
+    }
+    "get_pr_prompt" => {
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let result = crate::projects::get_pr_prompt(app.clone(), worktree_path).await?;
+        to_value(result)
+    }
+    "get_review_prompt" => {
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let result = crate::projects::get_review_prompt(app.clone(), worktree_path).await?;
+        to_value(result)
+    }
+    "rebase_worktree" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let commit_message: Option<String> =
+            field_opt(&args, "commitMessage", "commit_message")?;
+        let result =
+            crate::projects::rebase_worktree(app.clone(), worktree_id, commit_message).await?;
+        to_value(result)
+    }
 
-        // =====================================================================
-        // Skills & Search
-        // =====================================================================
-        "list_claude_skills" => {
-            let result = crate::projects::list_claude_skills().await?;
-            to_value(result)
-        }
-        "list_claude_commands" => {
-            let result = crate::projects::list_claude_commands().await?;
-            to_value(result)
-        }
-        "search_github_issues" => {
-            let project_path: String = field(&args, "projectPath", "project_path")?;
-            let query: String = from_field(&args, "query")?;
-            let result =
-                crate::projects::search_github_issues(app.clone(), project_path, query).await?;
-            to_value(result)
-        }
-        "search_github_prs" => {
-            let project_path: String = field(&args, "projectPath", "project_path")?;
-            let query: String = from_field(&args, "query")?;
-            let result =
-                crate::projects::search_github_prs(app.clone(), project_path, query).await?;
-            to_value(result)
-        }
+    // =====================================================================
+    // Git Operations (additional)
+    // =====================================================================
+    "merge_worktree_to_base" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let merge_type: crate::projects::types::MergeType =
+            field(&args, "mergeType", "merge_type")?;
+        let result =
+            crate::projects::merge_worktree_to_base(app.clone(), worktree_id, merge_type)
+                .await?;
+        to_value(result)
+    }
+    "get_merge_conflicts" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let result = crate::projects::get_merge_conflicts(app.clone(), worktree_id).await?;
+        to_value(result)
+    }
+    "fetch_and_merge_base" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let result = crate::projects::fetch_and_merge_base(app.clone(), worktree_id).await?;
+        to_value(result)
+    }
 
-        // =====================================================================
-        // Folder Management
-        // =====================================================================
-        "create_folder" => {
-            let name: String = from_field(&args, "name")?;
-            let parent_id: Option<String> = field_opt(&args, "parentId", "parent_id")?;
-            let result = crate::projects::create_folder(app.clone(), name, parent_id).await?;
-            to_value(result)
-        }
-        "rename_folder" => {
-            let folder_id: String = field(&args, "folderId", "folder_id")?;
-            let name: String = from_field(&args, "name")?;
-            let result = crate::projects::rename_folder(app.clone(), folder_id, name).await?;
-            to_value(result)
-        }
-        "delete_folder" => {
-            let folder_id: String = field(&args, "folderId", "folder_id")?;
-            crate::projects::delete_folder(app.clone(), folder_id).await?;
-            emit_cache_invalidation(app, &["projects"]);
-            Ok(Value::Null)
-        }
-        "move_item" => {
-            let item_id: String = field(&args, "itemId", "item_id")?;
-            let new_parent_id: Option<String> = field_opt(&args, "newParentId", "new_parent_id")?;
-            let target_index: Option<u32> = field_opt(&args, "targetIndex", "target_index")?;
-            let result =
-                crate::projects::move_item(app.clone(), item_id, new_parent_id, target_index)
-                    .await?;
-            to_value(result)
-        }
-        "reorder_items" => {
-            let item_ids: Vec<String> = field(&args, "itemIds", "item_ids")?;
-            let parent_id: Option<String> = field_opt(&args, "parentId", "parent_id")?;
-            crate::projects::reorder_items(app.clone(), item_ids, parent_id).await?;
-            emit_cache_invalidation(app, &["projects"]);
-            Ok(Value::Null)
-        }
+    // =====================================================================
+    // Skills & Search
+    // =====================================================================
+    "list_claude_skills" => {
+        let result = crate::projects::list_claude_skills().await?;
+        to_value(result)
+    }
+    "list_claude_commands" => {
+        let result = crate::projects::list_claude_commands().await?;
+        to_value(result)
+    }
+    "search_github_issues" => {
+        let project_path: String = field(&args, "projectPath", "project_path")?;
+        let query: String = from_field(&args, "query")?;
+        let result =
+            crate::projects::search_github_issues(app.clone(), project_path, query).await?;
+        to_value(result)
+    }
+    "search_github_prs" => {
+        let project_path: String = field(&args, "projectPath", "project_path")?;
+        let query: String = from_field(&args, "query")?;
+        let result =
+            crate::projects::search_github_prs(app.clone(), project_path, query).await?;
+        to_value(result)
+    }
 
-        // =====================================================================
-        // Avatar Management
-        // =====================================================================
-        "set_project_avatar" => {
-            let project_id: String = field(&args, "projectId", "project_id")?;
-            let result = crate::projects::set_project_avatar(app.clone(), project_id).await?;
-            to_value(result)
-        }
-        "remove_project_avatar" => {
-            let project_id: String = field(&args, "projectId", "project_id")?;
-            let result = crate::projects::remove_project_avatar(app.clone(), project_id).await?;
-            to_value(result)
-        }
-        "get_app_data_dir" => {
-            let result = crate::projects::get_app_data_dir(app.clone()).await?;
-            to_value(result)
-        }
+    // =====================================================================
+    // Folder Management
+    // =====================================================================
+    "create_folder" => {
+        let name: String = from_field(&args, "name")?;
+        let parent_id: Option<String> = field_opt(&args, "parentId", "parent_id")?;
+        let result = crate::projects::create_folder(app.clone(), name, parent_id).await?;
+        to_value(result)
+    }
+    "rename_folder" => {
+        let folder_id: String = field(&args, "folderId", "folder_id")?;
+        let name: String = from_field(&args, "name")?;
+        let result = crate::projects::rename_folder(app.clone(), folder_id, name).await?;
+        to_value(result)
+    }
+    "delete_folder" => {
+        let folder_id: String = field(&args, "folderId", "folder_id")?;
+        crate::projects::delete_folder(app.clone(), folder_id).await?;
+        emit_cache_invalidation(app, &["projects"]);
+        Ok(Value::Null)
+    }
+    "move_item" => {
+        let item_id: String = field(&args, "itemId", "item_id")?;
+        let new_parent_id: Option<String> = field_opt(&args, "newParentId", "new_parent_id")?;
+        let target_index: Option<u32> = field_opt(&args, "targetIndex", "target_index")?;
+        let result =
+            crate::projects::move_item(app.clone(), item_id, new_parent_id, target_index)
+                .await?;
+        to_value(result)
+    }
+    "reorder_items" => {
+        let item_ids: Vec<String> = field(&args, "itemIds", "item_ids")?;
+        let parent_id: Option<String> = field_opt(&args, "parentId", "parent_id")?;
+        crate::projects::reorder_items(app.clone(), item_ids, parent_id).await?;
+        emit_cache_invalidation(app, &["projects"]);
+        Ok(Value::Null)
+    }
 
-        // =====================================================================
-        // Terminal (NATIVE ONLY — return empty/null in browser mode)
-        // =====================================================================
-        "start_terminal" => {
-            // NATIVE ONLY: Terminals don't work in browser mode
-            Ok(Value::Null)
-        }
-        "terminal_write" => {
-            // NATIVE ONLY: Terminals don't work in browser mode
-            Ok(Value::Null)
-        }
-        "terminal_resize" => {
-            // NATIVE ONLY: Terminals don't work in browser mode
-            Ok(Value::Null)
-        }
-        "stop_terminal" => {
-            // NATIVE ONLY: Terminals don't work in browser mode
-            Ok(Value::Null)
-        }
-        "get_active_terminals" => {
-            // NATIVE ONLY: Return empty array
-            Ok(Value::Array(vec![]))
-        }
-        "has_active_terminal" => {
-            // NATIVE ONLY: No terminals in browser mode
-            to_value(false)
-        }
-        "get_run_script" => {
-            // NATIVE ONLY: Terminals don't work in browser mode
-            Ok(Value::Null)
-        }
+    // =====================================================================
+    // Avatar Management
+    // =====================================================================
+    "set_project_avatar" => {
+        let project_id: String = field(&args, "projectId", "project_id")?;
+        let result = crate::projects::set_project_avatar(app.clone(), project_id).await?;
+        to_value(result)
+    }
+    "remove_project_avatar" => {
+        let project_id: String = field(&args, "projectId", "project_id")?;
+        let result = crate::projects::remove_project_avatar(app.clone(), project_id).await?;
+        to_value(result)
+    }
+    "get_app_data_dir" => {
+        let result = crate::projects::get_app_data_dir(app.clone()).await?;
+        to_value(result)
+    }
 
-        // =====================================================================
-        // Session Management (additional)
-        // =====================================================================
-        "update_session_state" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let answered_questions: Option<Vec<String>> =
-                field_opt(&args, "answeredQuestions", "answered_questions")?;
-            let submitted_answers: Option<std::collections::HashMap<String, serde_json::Value>> =
-                field_opt(&args, "submittedAnswers", "submitted_answers")?;
-            let fixed_findings: Option<Vec<String>> =
-                field_opt(&args, "fixedFindings", "fixed_findings")?;
-            let pending_permission_denials: Option<Vec<crate::chat::types::PermissionDenial>> =
-                field_opt(
-                    &args,
-                    "pendingPermissionDenials",
-                    "pending_permission_denials",
-                )?;
-            let denied_message_context: Option<Option<crate::chat::types::DeniedMessageContext>> =
-                field_opt(&args, "deniedMessageContext", "denied_message_context")?;
-            let is_reviewing: Option<bool> = field_opt(&args, "isReviewing", "is_reviewing")?;
-            let waiting_for_input: Option<bool> =
-                field_opt(&args, "waitingForInput", "waiting_for_input")?;
-            let waiting_for_input_type: Option<Option<String>> =
-                field_opt(&args, "waitingForInputType", "waiting_for_input_type")?;
-            let plan_file_path: Option<Option<String>> =
-                field_opt(&args, "planFilePath", "plan_file_path")?;
-            let pending_plan_message_id: Option<Option<String>> =
-                field_opt(&args, "pendingPlanMessageId", "pending_plan_message_id")?;
-            crate::chat::update_session_state(
-                app.clone(),
-                worktree_id,
-                worktree_path,
-                session_id,
-                answered_questions,
-                submitted_answers,
-                fixed_findings,
-                pending_permission_denials,
-                denied_message_context,
-                is_reviewing,
-                waiting_for_input,
-                waiting_for_input_type,
-                plan_file_path,
-                pending_plan_message_id,
-            )
-            .await?;
-            emit_cache_invalidation(app, &["sessions"]);
-            Ok(Value::Null)
-        }
-        "archive_session" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let result =
-                crate::chat::archive_session(app.clone(), worktree_id, worktree_path, session_id)
-                    .await?;
-            emit_cache_invalidation(app, &["sessions"]);
-            to_value(result)
-        }
-        "unarchive_session" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let result =
-                crate::chat::unarchive_session(app.clone(), worktree_id, worktree_path, session_id)
-                    .await?;
-            emit_cache_invalidation(app, &["sessions"]);
-            to_value(result)
-        }
-        "restore_session_with_base" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let project_id: String = field(&args, "projectId", "project_id")?;
-            let result = crate::chat::restore_session_with_base(
-                app.clone(),
-                worktree_id,
-                worktree_path,
-                session_id,
-                project_id,
-            )
-            .await?;
-            emit_cache_invalidation(app, &["sessions", "projects"]);
-            to_value(result)
-        }
-        "delete_archived_session" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            crate::chat::delete_archived_session(
-                app.clone(),
-                worktree_id,
-                worktree_path,
-                session_id,
-            )
-            .await?;
-            emit_cache_invalidation(app, &["sessions"]);
-            Ok(Value::Null)
-        }
-        "list_archived_sessions" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let result =
-                crate::chat::list_archived_sessions(app.clone(), worktree_id, worktree_path)
-                    .await?;
-            to_value(result)
-        }
-        "list_all_archived_sessions" => {
-            let result = crate::chat::list_all_archived_sessions(app.clone()).await?;
-            to_value(result)
-        }
+    // =====================================================================
+    // Terminal (output reaches clients subscribed to the terminal)
+    // =====================================================================
+    "start_terminal" => {
+        let terminal_id: String = field(&args, "terminalId", "terminal_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let cols: u16 = from_field(&args, "cols")?;
+        let rows: u16 = from_field(&args, "rows")?;
+        let command: Option<String> = from_field_opt(&args, "command")?;
+        crate::terminal::start_terminal(
+            app.clone(),
+            terminal_id,
+            worktree_path,
+            cols,
+            rows,
+            command,
+        )
+        .await?;
+        Ok(Value::Null)
+    }
+    "terminal_write" => {
+        let terminal_id: String = field(&args, "terminalId", "terminal_id")?;
+        let data: String = from_field(&args, "data")?;
+        crate::terminal::terminal_write(terminal_id, data).await?;
+        Ok(Value::Null)
+    }
+    "terminal_resize" => {
+        let terminal_id: String = field(&args, "terminalId", "terminal_id")?;
+        let cols: u16 = from_field(&args, "cols")?;
+        let rows: u16 = from_field(&args, "rows")?;
+        crate::terminal::terminal_resize(terminal_id, cols, rows).await?;
+        Ok(Value::Null)
+    }
+    "stop_terminal" => {
+        let terminal_id: String = field(&args, "terminalId", "terminal_id")?;
+        let result = crate::terminal::stop_terminal(app.clone(), terminal_id).await?;
+        to_value(result)
+    }
+    "get_active_terminals" => {
+        let result = crate::terminal::get_active_terminals().await;
+        to_value(result)
+    }
+    "has_active_terminal" => {
+        let terminal_id: String = field(&args, "terminalId", "terminal_id")?;
+        let result = crate::terminal::has_active_terminal(terminal_id).await;
+        to_value(result)
+    }
+    "list_terminals" => {
+        let result = crate::terminal::list_terminals().await;
+        to_value(result)
+    }
+    "get_terminal_scrollback" => {
+        let terminal_id: String = field(&args, "terminalId", "terminal_id")?;
+        let result = crate::terminal::get_terminal_scrollback(terminal_id).await?;
+        to_value(result)
+    }
+    "start_terminal_recording" => {
+        let terminal_id: String = field(&args, "terminalId", "terminal_id")?;
+        let include_input: Option<bool> = field_opt(&args, "includeInput", "include_input")?;
+        let result =
+            crate::terminal::start_terminal_recording(app.clone(), terminal_id, include_input)
+                .await?;
+        to_value(result)
+    }
+    "stop_terminal_recording" => {
+        let terminal_id: String = field(&args, "terminalId", "terminal_id")?;
+        let result = crate::terminal::stop_terminal_recording(terminal_id).await;
+        to_value(result)
+    }
+    "list_terminal_recordings" => {
+        let result = crate::terminal::list_terminal_recordings(app.clone()).await?;
+        to_value(result)
+    }
+    "read_terminal_recording" => {
+        let recording_id: String = field(&args, "recordingId", "recording_id")?;
+        let result =
+            crate::terminal::read_terminal_recording(app.clone(), recording_id).await?;
+        to_value(result)
+    }
+    "delete_terminal_recording" => {
+        let recording_id: String = field(&args, "recordingId", "recording_id")?;
+        crate::terminal::delete_terminal_recording(app.clone(), recording_id).await?;
+        Ok(Value::Null)
+    }
+    "attach_terminal_recording" => {
+        let recording_id: String = field(&args, "recordingId", "recording_id")?;
+        let result =
+            crate::terminal::attach_terminal_recording(app.clone(), recording_id).await?;
+        to_value(result)
+    }
+    "get_run_script" => {
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let result = crate::terminal::get_run_script(worktree_path).await;
+        to_value(result)
+    }
 
-        // =====================================================================
-        // Images & Pasted Text
-        // =====================================================================
-        "save_pasted_image" => {
-            let data: String = from_field(&args, "data")?;
-            let mime_type: String = field(&args, "mimeType", "mime_type")?;
-            let result = crate::chat::save_pasted_image(app.clone(), data, mime_type).await?;
-            to_value(result)
-        }
-        "save_dropped_image" => {
-            // NATIVE ONLY: Drag-drop from native file paths doesn't work in browser
-            Ok(Value::Null)
-        }
-        "delete_pasted_image" => {
-            let path: String = from_field(&args, "path")?;
-            crate::chat::delete_pasted_image(app.clone(), path).await?;
-            Ok(Value::Null)
-        }
-        "save_pasted_text" => {
-            let content: String = from_field(&args, "content")?;
-            let result = crate::chat::save_pasted_text(app.clone(), content).await?;
-            to_value(result)
-        }
-        "delete_pasted_text" => {
-            let path: String = from_field(&args, "path")?;
-            crate::chat::delete_pasted_text(app.clone(), path).await?;
-            Ok(Value::Null)
-        }
-        "read_pasted_text" => {
-            let path: String = from_field(&args, "path")?;
-            let result = crate::chat::read_pasted_text(app.clone(), path).await?;
-            to_value(result)
-        }
+    // =====================================================================
+    // Session Management (additional)
+    // =====================================================================
+    "update_session_state" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let answered_questions: Option<Vec<String>> =
+            field_opt(&args, "answeredQuestions", "answered_questions")?;
+        let submitted_answers: Option<std::collections::HashMap<String, serde_json::Value>> =
+            field_opt(&args, "submittedAnswers", "submitted_answers")?;
+        let fixed_findings: Option<Vec<String>> =
+            field_opt(&args, "fixedFindings", "fixed_findings")?;
+        let pending_permission_denials: Option<Vec<crate::chat::types::PermissionDenial>> =
+            field_opt(
+                &args,
+                "pendingPermissionDenials",
+                "pending_permission_denials",
+            )?;
+        let denied_message_context: Option<Option<crate::chat::types::DeniedMessageContext>> =
+            field_opt(&args, "deniedMessageContext", "denied_message_context")?;
+        let is_reviewing: Option<bool> = field_opt(&args, "isReviewing", "is_reviewing")?;
+        let waiting_for_input: Option<bool> =
+            field_opt(&args, "waitingForInput", "waiting_for_input")?;
+        let waiting_for_input_type: Option<Option<String>> =
+            field_opt(&args, "waitingForInputType", "waiting_for_input_type")?;
+        let plan_file_path: Option<Option<String>> =
+            field_opt(&args, "planFilePath", "plan_file_path")?;
+        let pending_plan_message_id: Option<Option<String>> =
+            field_opt(&args, "pendingPlanMessageId", "pending_plan_message_id")?;
+        crate::chat::update_session_state(
+            app.clone(),
+            worktree_id,
+            worktree_path,
+            session_id,
+            answered_questions,
+            submitted_answers,
+            fixed_findings,
+            pending_permission_denials,
+            denied_message_context,
+            is_reviewing,
+            waiting_for_input,
+            waiting_for_input_type,
+            plan_file_path,
+            pending_plan_message_id,
+        )
+        .await?;
+        emit_cache_invalidation(app, &["sessions"]);
+        Ok(Value::Null)
+    }
+    "archive_session" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let result =
+            crate::chat::archive_session(app.clone(), worktree_id, worktree_path, session_id)
+                .await?;
+        emit_cache_invalidation(app, &["sessions"]);
+        to_value(result)
+    }
+    "unarchive_session" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let result =
+            crate::chat::unarchive_session(app.clone(), worktree_id, worktree_path, session_id)
+                .await?;
+        emit_cache_invalidation(app, &["sessions"]);
+        to_value(result)
+    }
+    "restore_session_with_base" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let project_id: String = field(&args, "projectId", "project_id")?;
+        let result = crate::chat::restore_session_with_base(
+            app.clone(),
+            worktree_id,
+            worktree_path,
+            session_id,
+            project_id,
+        )
+        .await?;
+        emit_cache_invalidation(app, &["sessions", "projects"]);
+        to_value(result)
+    }
+    "delete_archived_session" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        crate::chat::delete_archived_session(
+            app.clone(),
+            worktree_id,
+            worktree_path,
+            session_id,
+        )
+        .await?;
+        emit_cache_invalidation(app, &["sessions"]);
+        Ok(Value::Null)
+    }
+    "list_archived_sessions" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let result =
+            crate::chat::list_archived_sessions(app.clone(), worktree_id, worktree_path)
+                .await?;
+        to_value(result)
+    }
+    "list_all_archived_sessions" => {
+        let result = crate::chat::list_all_archived_sessions(app.clone()).await?;
+        to_value(result)
+    }
 
-        // =====================================================================
-        // File Operations (additional)
-        // =====================================================================
-        "write_file_content" => {
-            let path: String = from_field(&args, "path")?;
-            let content: String = from_field(&args, "content")?;
-            crate::chat::write_file_content(path, content).await?;
-            Ok(Value::Null)
-        }
-        "open_file_in_default_app" => {
-            // NATIVE ONLY: Cannot open native apps from browser
-            Ok(Value::Null)
-        }
+    // =====================================================================
+    // Images & Pasted Text
+    // =====================================================================
+    "save_pasted_image" => {
+        let data: String = from_field(&args, "data")?;
+        let mime_type: String = field(&args, "mimeType", "mime_type")?;
+        let result = crate::chat::save_pasted_image(app.clone(), data, mime_type).await?;
+        to_value(result)
+    }
+    "save_dropped_image" => {
+        // NATIVE ONLY: Drag-drop from native file paths doesn't work in browser
+        Ok(Value::Null)
+    }
+    "delete_pasted_image" => {
+        let path: String = from_field(&args, "path")?;
+        crate::chat::delete_pasted_image(app.clone(), path).await?;
+        Ok(Value::Null)
+    }
+    "save_pasted_text" => {
+        let content: String = from_field(&args, "content")?;
+        let result = crate::chat::save_pasted_text(app.clone(), content).await?;
+        to_value(result)
+    }
+    "delete_pasted_text" => {
+        let path: String = from_field(&args, "path")?;
+        crate::chat::delete_pasted_text(app.clone(), path).await?;
+        Ok(Value::Null)
+    }
+    "read_pasted_text" => {
+        let path: String = from_field(&args, "path")?;
+        let result = crate::chat::read_pasted_text(app.clone(), path).await?;
+        to_value(result)
+    }
 
-        // =====================================================================
-        // Context & Debug (additional)
-        // =====================================================================
-        "rename_saved_context" => {
-            let filename: String = from_field(&args, "filename")?;
-            let new_name: String = field(&args, "newName", "new_name")?;
-            crate::chat::rename_saved_context(app.clone(), filename, new_name).await?;
-            emit_cache_invalidation(app, &["contexts"]);
-            Ok(Value::Null)
-        }
-        "generate_session_digest" => {
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let result = crate::chat::generate_session_digest(app.clone(), session_id).await?;
-            to_value(result)
-        }
-        "update_session_digest" => {
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let digest: crate::chat::types::SessionDigest = from_field(&args, "digest")?;
-            crate::chat::update_session_digest(app.clone(), session_id, digest).await?;
-            Ok(Value::Null)
-        }
-        "get_session_debug_info" => {
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let result = crate::chat::get_session_debug_info(
-                app.clone(),
-                worktree_id,
-                worktree_path,
-                session_id,
-            )
-            .await?;
-            to_value(result)
-        }
-        "resume_session" => {
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
-            let result = crate::chat::resume_session(app.clone(), session_id, worktree_id).await?;
-            to_value(result)
-        }
-        "broadcast_session_setting" => {
-            let session_id: String = field(&args, "sessionId", "session_id")?;
-            let key: String = field(&args, "key", "key")?;
-            let value: String = field(&args, "value", "value")?;
-            crate::chat::broadcast_session_setting(app.clone(), session_id, key, value).await?;
-            Ok(Value::Null)
-        }
+    // =====================================================================
+    // File Operations (additional)
+    // =====================================================================
+    "write_file_content" => {
+        let path: String = from_field(&args, "path")?;
+        let content: String = from_field(&args, "content")?;
+        crate::chat::write_file_content(path, content).await?;
+        Ok(Value::Null)
+    }
+    "open_file_in_default_app" => {
+        // NATIVE ONLY: Cannot open native apps from browser
+        Ok(Value::Null)
+    }
 
-        // =====================================================================
-        // CLI Management
-        // =====================================================================
-        "check_claude_cli_installed" => {
-            let result = crate::claude_cli::check_claude_cli_installed(app.clone()).await?;
-            to_value(result)
-        }
-        "check_claude_cli_auth" => {
-            let result = crate::claude_cli::check_claude_cli_auth(app.clone()).await?;
-            to_value(result)
-        }
-        "get_available_cli_versions" => {
-            let result = crate::claude_cli::get_available_cli_versions().await?;
-            to_value(result)
-        }
-        "install_claude_cli" => {
-            let version: Option<String> = from_field_opt(&args, "version")?;
-            crate::claude_cli::install_claude_cli(app.clone(), version).await?;
-            Ok(Value::Null)
-        }
-        "check_gh_cli_installed" => {
-            let result = crate::gh_cli::check_gh_cli_installed(app.clone()).await?;
-            to_value(result)
-        }
-        "check_gh_cli_auth" => {
-            let result = crate::gh_cli::check_gh_cli_auth(app.clone()).await?;
-            to_value(result)
-        }
-        "get_available_gh_versions" => {
-            let result = crate::gh_cli::get_available_gh_versions().await?;
-            to_value(result)
-        }
-        "install_gh_cli" => {
-            let version: Option<String> = from_field_opt(&args, "version")?;
-            crate::gh_cli::install_gh_cli(app.clone(), version).await?;
-            Ok(Value::Null)
-        }
+    // =====================================================================
+    // Context & Debug (additional)
+    // =====================================================================
+    "rename_saved_context" => {
+        let filename: String = from_field(&args, "filename")?;
+        let new_name: String = field(&args, "newName", "new_name")?;
+        crate::chat::rename_saved_context(app.clone(), filename, new_name).await?;
+        emit_cache_invalidation(app, &["contexts"]);
+        Ok(Value::Null)
+    }
+    "generate_session_digest" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let result = crate::chat::generate_session_digest(app.clone(), session_id).await?;
+        to_value(result)
+    }
+    "update_session_digest" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let digest: crate::chat::types::SessionDigest = from_field(&args, "digest")?;
+        crate::chat::update_session_digest(app.clone(), session_id, digest).await?;
+        Ok(Value::Null)
+    }
+    "get_session_debug_info" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let result = crate::chat::get_session_debug_info(
+            app.clone(),
+            worktree_id,
+            worktree_path,
+            session_id,
+        )
+        .await?;
+        to_value(result)
+    }
+    "get_usage_report" => {
+        let filter: Option<crate::chat::usage::UsageFilter> = from_field_opt(&args, "filter")?;
+        let result = crate::chat::usage::get_usage_report(app.clone(), filter).await?;
+        to_value(result)
+    }
+    "get_budget_status" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let result = crate::chat::budget::get_budget_status(app.clone(), worktree_id).await?;
+        to_value(result)
+    }
+    "search_sessions" => {
+        let query: String = field(&args, "query", "query")?;
+        let filter: Option<crate::chat::search::SearchFilter> =
+            from_field_opt(&args, "filter")?;
+        let limit: Option<usize> = field_opt(&args, "limit", "limit")?;
+        let result =
+            crate::chat::search::search_sessions(app.clone(), query, filter, limit).await?;
+        to_value(result)
+    }
+    "export_session" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let format: crate::chat::export::ExportFormat = field(&args, "format", "format")?;
+        let options: Option<crate::chat::export::ExportOptions> =
+            from_field_opt(&args, "options")?;
+        let result =
+            crate::chat::export::export_session(app.clone(), session_id, format, options)
+                .await?;
+        to_value(result)
+    }
+    "export_session_bundle" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let dest_path: String = field(&args, "destPath", "dest_path")?;
+        crate::chat::import::export_session_bundle(app.clone(), session_id, dest_path).await?;
+        Ok(Value::Null)
+    }
+    "import_session_bundle" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let bundle_path: String = field(&args, "bundlePath", "bundle_path")?;
+        let result = crate::chat::import::import_session_bundle(
+            app.clone(),
+            worktree_id,
+            worktree_path,
+            bundle_path,
+        )
+        .await?;
+        to_value(result)
+    }
+    "list_claude_cli_sessions" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let result = crate::chat::import::list_claude_cli_sessions(
+            app.clone(),
+            worktree_id,
+            worktree_path,
+        )
+        .await?;
+        to_value(result)
+    }
+    "import_claude_cli_session" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let jsonl_path: String = field(&args, "jsonlPath", "jsonl_path")?;
+        let result = crate::chat::import::import_claude_cli_session(
+            app.clone(),
+            worktree_id,
+            worktree_path,
+            jsonl_path,
+        )
+        .await?;
+        to_value(result)
+    }
+    "fork_session" => {
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let message_id: String = field(&args, "messageId", "message_id")?;
+        let target_worktree_id: Option<String> =
+            field_opt(&args, "targetWorktreeId", "target_worktree_id")?;
+        let name: Option<String> = field_opt(&args, "name", "name")?;
+        let result = crate::chat::fork::fork_session(
+            app.clone(),
+            worktree_id,
+            worktree_path,
+            session_id,
+            message_id,
+            target_worktree_id,
+            name,
+        )
+        .await?;
+        to_value(result)
+    }
+    "enqueue_message" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let message: String = from_field(&args, "message")?;
+        let model: Option<String> = from_field_opt(&args, "model")?;
+        let execution_mode: Option<String> =
+            field_opt(&args, "executionMode", "execution_mode")?;
+        let thinking_level = field_opt(&args, "thinkingLevel", "thinking_level")?;
+        let disable_thinking_for_mode: Option<bool> =
+            field_opt(&args, "disableThinkingForMode", "disable_thinking_for_mode")?;
+        let parallel_execution_prompt_enabled: Option<bool> = field_opt(
+            &args,
+            "parallelExecutionPromptEnabled",
+            "parallel_execution_prompt_enabled",
+        )?;
+        let ai_language: Option<String> = field_opt(&args, "aiLanguage", "ai_language")?;
+        let allowed_tools: Option<Vec<String>> =
+            field_opt(&args, "allowedTools", "allowed_tools")?;
+        let result = crate::chat::queue::enqueue_message(
+            app.clone(),
+            session_id,
+            worktree_id,
+            worktree_path,
+            message,
+            model,
+            execution_mode,
+            thinking_level,
+            disable_thinking_for_mode,
+            parallel_execution_prompt_enabled,
+            ai_language,
+            allowed_tools,
+        )
+        .await?;
+        to_value(result)
+    }
+    "list_queued_messages" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let result = crate::chat::queue::list_queued_messages(app.clone(), session_id).await?;
+        to_value(result)
+    }
+    "update_queued_message" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let item_id: String = field(&args, "itemId", "item_id")?;
+        let message: String = from_field(&args, "message")?;
+        let result = crate::chat::queue::update_queued_message(
+            app.clone(),
+            session_id,
+            item_id,
+            message,
+        )
+        .await?;
+        to_value(result)
+    }
+    "reorder_queued_messages" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let item_ids: Vec<String> = field(&args, "itemIds", "item_ids")?;
+        crate::chat::queue::reorder_queued_messages(app.clone(), session_id, item_ids).await?;
+        Ok(Value::Null)
+    }
+    "remove_queued_message" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let item_id: String = field(&args, "itemId", "item_id")?;
+        crate::chat::queue::remove_queued_message(app.clone(), session_id, item_id).await?;
+        Ok(Value::Null)
+    }
+    "clear_message_queue" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        crate::chat::queue::clear_message_queue(app.clone(), session_id).await?;
+        Ok(Value::Null)
+    }
+    "send_next_queued_message" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let worktree_path: String = field(&args, "worktreePath", "worktree_path")?;
+        let result = crate::chat::queue::send_next_queued_message(
+            app.clone(),
+            session_id,
+            worktree_id,
+            worktree_path,
+        )
+        .await?;
+        to_value(result)
+    }
+    "list_schedules" => {
+        let result = crate::scheduler::commands::list_schedules(app.clone()).await?;
+        to_value(result)
+    }
+    "create_schedule" => {
+        let schedule: crate::scheduler::commands::ScheduleInput =
+            from_field(&args, "schedule")?;
+        let result = crate::scheduler::commands::create_schedule(app.clone(), schedule).await?;
+        to_value(result)
+    }
+    "update_schedule" => {
+        let schedule_id: String = field(&args, "scheduleId", "schedule_id")?;
+        let schedule: crate::scheduler::commands::ScheduleInput =
+            from_field(&args, "schedule")?;
+        let result =
+            crate::scheduler::commands::update_schedule(app.clone(), schedule_id, schedule)
+                .await?;
+        to_value(result)
+    }
+    "set_schedule_enabled" => {
+        let schedule_id: String = field(&args, "scheduleId", "schedule_id")?;
+        let enabled: bool = from_field(&args, "enabled")?;
+        let result =
+            crate::scheduler::commands::set_schedule_enabled(app.clone(), schedule_id, enabled)
+                .await?;
+        to_value(result)
+    }
+    "delete_schedule" => {
+        let schedule_id: String = field(&args, "scheduleId", "schedule_id")?;
+        crate::scheduler::commands::delete_schedule(app.clone(), schedule_id).await?;
+        Ok(Value::Null)
+    }
+    "run_schedule_now" => {
+        let schedule_id: String = field(&args, "scheduleId", "schedule_id")?;
+        crate::scheduler::commands::run_schedule_now(app.clone(), schedule_id).await?;
+        Ok(Value::Null)
+    }
+    "preview_schedule" => {
+        let trigger: crate::scheduler::ScheduleTrigger = from_field(&args, "trigger")?;
+        let count: Option<usize> = from_field_opt(&args, "count")?;
+        let result = crate::scheduler::commands::preview_schedule(trigger, count).await?;
+        to_value(result)
+    }
+    "resume_session" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let worktree_id: String = field(&args, "worktreeId", "worktree_id")?;
+        let result = crate::chat::resume_session(app.clone(), session_id, worktree_id).await?;
+        to_value(result)
+    }
+    "broadcast_session_setting" => {
+        let session_id: String = field(&args, "sessionId", "session_id")?;
+        let key: String = field(&args, "key", "key")?;
+        let value: String = field(&args, "value", "value")?;
+        crate::chat::broadcast_session_setting(app.clone(), session_id, key, value).await?;
+        Ok(Value::Null)
+    }
 
-        // =====================================================================
-        // HTTP Server control (additional)
-        // =====================================================================
-        "start_http_server" => {
-            // Server is already running if we're receiving this via WebSocket
-            let result = crate::http_server::server::get_server_status(app.clone()).await;
-            to_value(result)
-        }
-        "stop_http_server" => {
-            // Cannot stop the server from within the server — use native Tauri command
-            Err("Cannot stop HTTP server from a WebSocket connection".to_string())
-        }
-        "regenerate_http_token" => {
-            let result = crate::regenerate_http_token(app.clone()).await?;
-            to_value(result)
-        }
+    // =====================================================================
+    // CLI Management
+    // =====================================================================
+    "check_claude_cli_installed" => {
+        let result = crate::claude_cli::check_claude_cli_installed(app.clone()).await?;
+        to_value(result)
+    }
+    "check_claude_cli_auth" => {
+        let result = crate::claude_cli::check_claude_cli_auth(app.clone()).await?;
+        to_value(result)
+    }
+    "get_available_cli_versions" => {
+        let result = crate::claude_cli::get_available_cli_versions().await?;
+        to_value(result)
+    }
+    "install_claude_cli" => {
+        let version: Option<String> = from_field_opt(&args, "version")?;
+        crate::claude_cli::install_claude_cli(app.clone(), version).await?;
+        Ok(Value::Null)
+    }
+    "check_gh_cli_installed" => {
+        let result = crate::gh_cli::check_gh_cli_installed(app.clone()).await?;
+        to_value(result)
+    }
+    "check_gh_cli_auth" => {
+        let result = crate::gh_cli::check_gh_cli_auth(app.clone()).await?;
+        to_value(result)
+    }
+    "get_available_gh_versions" => {
+        let result = crate::gh_cli::get_available_gh_versions().await?;
+        to_value(result)
+    }
+    "install_gh_cli" => {
+        let version: Option<String> = from_field_opt(&args, "version")?;
+        crate::gh_cli::install_gh_cli(app.clone(), version).await?;
+        Ok(Value::Null)
+    }
 
-        // =====================================================================
-        // Unknown command
-        // =====================================================================
-        _ => Err(format!("Unknown command: {command}")),
+    // =====================================================================
+    // HTTP Server control (additional)
+    // =====================================================================
+    "start_http_server" => {
+        // Server is already running if we're receiving this via WebSocket
+        let result = crate::http_server::server::get_server_status(app.clone()).await;
+        to_value(result)
+    }
+    "stop_http_server" => {
+        // Cannot stop the server from within the server — use native Tauri command
+        Err(DispatchError::Failed(
+            "Cannot stop HTTP server from a WebSocket connection".to_string(),
+        ))
+    }
+    "regenerate_http_token" => {
+        let result = crate::regenerate_http_token(app.clone()).await?;
+        to_value(result)
+    }
+    "create_api_token" => {
+        let name: String = from_field(&args, "name")?;
+        let scopes = from_field(&args, "scopes")?;
+        let project_ids = field_opt(&args, "projectIds", "project_ids")?;
+        let expires_at = field_opt(&args, "expiresAt", "expires_at")?;
+        let result =
+            super::tokens::create_api_token(app.clone(), name, scopes, project_ids, expires_at)
+                .await?;
+        to_value(result)
+    }
+    "list_api_tokens" => {
+        let result = super::tokens::list_api_tokens(app.clone()).await?;
+        to_value(result)
+    }
+    "revoke_api_token" => {
+        let token_id: String = field(&args, "tokenId", "token_id")?;
+        super::tokens::revoke_api_token(app.clone(), token_id).await?;
+        Ok(Value::Null)
     }
+    "query_audit_log" => {
+        let since = from_field_opt(&args, "since")?;
+        let until = from_field_opt(&args, "until")?;
+        let command = from_field_opt(&args, "command")?;
+        let limit = from_field_opt(&args, "limit")?;
+        let result =
+            super::audit::query_audit_log(app.clone(), since, until, command, limit).await?;
+        to_value(result)
+    }
+
 }
 
 // =============================================================================
@@ -1375,27 +1761,33 @@ fn emit_cache_invalidation(app: &AppHandle, keys: &[&str]) {
 // Helper functions for JSON deserialization
 // =============================================================================
 
-fn to_value<T: serde::Serialize>(val: T) -> Result<Value, String> {
-    serde_json::to_value(val).map_err(|e| format!("Serialization error: {e}"))
+fn to_value<T: serde::Serialize>(val: T) -> Result<Value, DispatchError> {
+    serde_json::to_value(val)
+        .map_err(|e| DispatchError::Failed(format!("Serialization error: {e}")))
 }
 
-fn from_field<T: serde::de::DeserializeOwned>(args: &Value, field: &str) -> Result<T, String> {
+fn from_field<T: serde::de::DeserializeOwned>(
+    args: &Value,
+    field: &str,
+) -> Result<T, DispatchError> {
     args.get(field)
-        .ok_or_else(|| format!("Missing field: {field}"))
-        .and_then(|v| {
-            serde_json::from_value(v.clone()).map_err(|e| format!("Invalid field '{field}': {e}"))
-        })
+        .ok_or_else(|| DispatchError::InvalidArgs(format!("Missing field: {field}")))
+        .and_then(|v| serde_json::from_value(v.clone()).map_err(|e| invalid_field(field, e)))
+}
+
+fn invalid_field(field: &str, e: serde_json::Error) -> DispatchError {
+    DispatchError::InvalidArgs(format!("Invalid field '{field}': {e}"))
 }
 
 fn from_field_opt<T: serde::de::DeserializeOwned>(
     args: &Value,
     field: &str,
-) -> Result<Option<T>, String> {
+) -> Result<Option<T>, DispatchError> {
     match args.get(field) {
         None | Some(Value::Null) => Ok(None),
         Some(v) => serde_json::from_value(v.clone())
             .map(Some)
-            .map_err(|e| format!("Invalid field '{field}': {e}")),
+            .map_err(|e| invalid_field(field, e)),
     }
 }
 
@@ -1404,7 +1796,7 @@ fn field<T: serde::de::DeserializeOwned>(
     args: &Value,
     camel: &str,
     snake: &str,
-) -> Result<T, String> {
+) -> Result<T, DispatchError> {
     from_field(args, camel).or_else(|_| from_field(args, snake))
 }
 
@@ -1413,7 +1805,7 @@ fn field_opt<T: serde::de::DeserializeOwned>(
     args: &Value,
     camel: &str,
     snake: &str,
-) -> Result<Option<T>, String> {
+) -> Result<Option<T>, DispatchError> {
     let camel_result = from_field_opt(args, camel)?;
     if camel_result.is_some() {
         return Ok(camel_result);
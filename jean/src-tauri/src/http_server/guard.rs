@@ -0,0 +1,425 @@
+//! Brute-force protection for the HTTP server
+//!
+//! Tracks token checks per client IP:
+//! - connection endpoints (`/api/auth`, `/api/init`, `/ws`) allow at most
+//!   [`RATE_LIMIT_REQUESTS`] attempts per [`RATE_LIMIT_WINDOW`];
+//! - after [`FAILURES_BEFORE_LOCKOUT`] failed token checks in a row an IP is
+//!   locked out, for a period that doubles with every further failure;
+//! - with an allowlist in preferences, other addresses are refused outright
+//!   (loopback is always allowed).
+//!
+//! Rejections are emitted to the desktop UI as `http-server:auth-rejected`.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// Attempts allowed per IP within [`RATE_LIMIT_WINDOW`] on connection endpoints
+const RATE_LIMIT_REQUESTS: usize = 30;
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Consecutive failed token checks before an IP is locked out
+const FAILURES_BEFORE_LOCKOUT: u32 = 5;
+const BASE_LOCKOUT: Duration = Duration::from_secs(30);
+const MAX_LOCKOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Minimum time between UI events for rejections of the same IP
+const EVENT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Tracked IPs before idle entries are pruned
+const MAX_TRACKED_CLIENTS: usize = 1024;
+
+/// Why a request was refused
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rejection {
+    InvalidToken,
+    /// Too many attempts in the rate limit window
+    RateLimited {
+        retry_after: Duration,
+    },
+    /// Too many failed attempts
+    LockedOut {
+        retry_after: Duration,
+    },
+    /// Not in the allowlist
+    NotAllowed,
+}
+
+impl Rejection {
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::InvalidToken => "invalid_token",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::LockedOut { .. } => "locked_out",
+            Self::NotAllowed => "not_allowed",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Self::InvalidToken => "Invalid token".to_string(),
+            Self::RateLimited { retry_after } | Self::LockedOut { retry_after } => format!(
+                "Too many attempts, retry in {}s",
+                retry_after.as_secs().max(1)
+            ),
+            Self::NotAllowed => "Address not allowed".to_string(),
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::InvalidToken => StatusCode::UNAUTHORIZED,
+            Self::RateLimited { .. } | Self::LockedOut { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Self::NotAllowed => StatusCode::FORBIDDEN,
+        }
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimited { retry_after } | Self::LockedOut { retry_after } => {
+                Some(*retry_after)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Adds `Retry-After` to `response` when the client should wait
+pub fn with_retry_after(rejection: &Rejection, mut response: Response) -> Response {
+    if let Some(retry_after) = rejection.retry_after() {
+        let secs = retry_after.as_secs().max(1).to_string();
+        if let Ok(value) = secs.parse() {
+            response.headers_mut().insert(header::RETRY_AFTER, value);
+        }
+    }
+    response
+}
+
+impl IntoResponse for Rejection {
+    fn into_response(self) -> Response {
+        with_retry_after(&self, (self.status(), self.message()).into_response())
+    }
+}
+
+/// Payload of `http-server:auth-rejected`
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthRejectedEvent {
+    pub ip: String,
+    pub reason: &'static str,
+    pub failures: u32,
+    /// Seconds until the IP may try again (lockouts and rate limits)
+    pub retry_after_secs: Option<u64>,
+}
+
+/// An IP address or CIDR range, e.g. `192.168.1.0/24`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IpRange {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpRange {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|_| format!("Invalid IP address: {s}"))?;
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(p) => p
+                .parse::<u8>()
+                .ok()
+                .filter(|p| *p <= max)
+                .ok_or_else(|| format!("Invalid prefix length: {s}"))?,
+            None => max,
+        };
+        Ok(Self { addr, prefix })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+            v4 => v4,
+        };
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                prefix_matches(&net.octets(), &ip.octets(), self.prefix)
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_matches(&net.octets(), &ip.octets(), self.prefix)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn prefix_matches(net: &[u8], ip: &[u8], prefix: u8) -> bool {
+    let full_bytes = usize::from(prefix / 8);
+    if net[..full_bytes] != ip[..full_bytes] {
+        return false;
+    }
+    let rest = prefix % 8;
+    rest == 0 || {
+        let mask = 0xffu8 << (8 - rest);
+        net[full_bytes] & mask == ip[full_bytes] & mask
+    }
+}
+
+/// Parse allowlist entries from preferences
+pub fn parse_allowlist(entries: &[String]) -> Result<Vec<IpRange>, String> {
+    entries
+        .iter()
+        .filter(|e| !e.trim().is_empty())
+        .map(|e| IpRange::parse(e))
+        .collect()
+}
+
+#[derive(Debug)]
+struct ClientRecord {
+    /// Connection attempts within the current rate limit window
+    attempts: Vec<Instant>,
+    /// Failed token checks since the last success
+    failures: u32,
+    locked_until: Option<Instant>,
+    last_event: Option<Instant>,
+    last_seen: Instant,
+}
+
+impl ClientRecord {
+    fn new(now: Instant) -> Self {
+        Self {
+            attempts: Vec::new(),
+            failures: 0,
+            locked_until: None,
+            last_event: None,
+            last_seen: now,
+        }
+    }
+}
+
+fn lockout_duration(failures: u32) -> Duration {
+    let doublings = failures.saturating_sub(FAILURES_BEFORE_LOCKOUT).min(16);
+    (BASE_LOCKOUT * 2u32.pow(doublings)).min(MAX_LOCKOUT)
+}
+
+/// Per-server state of the brute-force protection
+pub struct AuthGuard {
+    allowlist: Vec<IpRange>,
+    clients: Mutex<HashMap<IpAddr, ClientRecord>>,
+}
+
+impl AuthGuard {
+    pub fn new(allowlist: Vec<IpRange>) -> Self {
+        Self {
+            allowlist,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_allowed(&self, ip: IpAddr) -> bool {
+        ip.is_loopback()
+            || self.allowlist.is_empty()
+            || self.allowlist.iter().any(|range| range.contains(ip))
+    }
+
+    /// Check an IP before verifying its token; `rate_limited` for connection endpoints
+    pub fn check(&self, ip: IpAddr, rate_limited: bool, now: Instant) -> Result<(), Rejection> {
+        if !self.is_allowed(ip) {
+            return Err(Rejection::NotAllowed);
+        }
+
+        let mut clients = self.clients.lock().unwrap();
+        if clients.len() >= MAX_TRACKED_CLIENTS {
+            clients.retain(|_, c| {
+                c.locked_until.is_some_and(|until| until > now)
+                    || now.duration_since(c.last_seen) < RATE_LIMIT_WINDOW
+            });
+        }
+        let client = clients.entry(ip).or_insert_with(|| ClientRecord::new(now));
+        client.last_seen = now;
+
+        if let Some(until) = client.locked_until.filter(|until| *until > now) {
+            return Err(Rejection::LockedOut {
+                retry_after: until - now,
+            });
+        }
+        if rate_limited {
+            client
+                .attempts
+                .retain(|at| now.duration_since(*at) < RATE_LIMIT_WINDOW);
+            if client.attempts.len() >= RATE_LIMIT_REQUESTS {
+                let oldest = client.attempts[0];
+                return Err(Rejection::RateLimited {
+                    retry_after: RATE_LIMIT_WINDOW - now.duration_since(oldest),
+                });
+            }
+            client.attempts.push(now);
+        }
+        Ok(())
+    }
+
+    pub fn record_success(&self, ip: IpAddr) {
+        if let Some(client) = self.clients.lock().unwrap().get_mut(&ip) {
+            client.failures = 0;
+            client.locked_until = None;
+        }
+    }
+
+    /// Count a failed token check, locking the IP out after too many.
+    /// Returns the rejection to report.
+    pub fn record_failure(&self, ip: IpAddr, now: Instant) -> Rejection {
+        let mut clients = self.clients.lock().unwrap();
+        let client = clients.entry(ip).or_insert_with(|| ClientRecord::new(now));
+        client.failures += 1;
+        if client.failures >= FAILURES_BEFORE_LOCKOUT {
+            let retry_after = lockout_duration(client.failures);
+            client.locked_until = Some(now + retry_after);
+            // Always report the start of a lockout
+            client.last_event = None;
+            Rejection::LockedOut { retry_after }
+        } else {
+            Rejection::InvalidToken
+        }
+    }
+
+    /// Tell the desktop UI about a rejection (throttled per IP)
+    pub fn report(&self, app: &AppHandle, ip: IpAddr, rejection: &Rejection, now: Instant) {
+        let failures = {
+            let mut clients = self.clients.lock().unwrap();
+            let client = clients.entry(ip).or_insert_with(|| ClientRecord::new(now));
+            if client
+                .last_event
+                .is_some_and(|at| now.duration_since(at) < EVENT_INTERVAL)
+            {
+                return;
+            }
+            client.last_event = Some(now);
+            client.failures
+        };
+
+        log::warn!("HTTP server rejected {ip}: {}", rejection.message());
+        let event = AuthRejectedEvent {
+            ip: ip.to_string(),
+            reason: rejection.reason(),
+            failures,
+            retry_after_secs: rejection.retry_after().map(|d| d.as_secs().max(1)),
+        };
+        // Desktop UI only; remote clients have no business seeing this
+        if let Err(e) = app.emit("http-server:auth-rejected", &event) {
+            log::error!("Failed to emit auth rejection: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_ip_ranges() {
+        let lan = IpRange::parse("192.168.1.0/24").unwrap();
+        assert!(lan.contains(ip("192.168.1.42")));
+        assert!(!lan.contains(ip("192.168.2.1")));
+        assert!(lan.contains(ip("::ffff:192.168.1.7")));
+
+        let odd = IpRange::parse("10.0.0.0/13").unwrap();
+        assert!(odd.contains(ip("10.7.255.255")));
+        assert!(!odd.contains(ip("10.8.0.0")));
+
+        let single = IpRange::parse(" 10.0.0.5 ").unwrap();
+        assert!(single.contains(ip("10.0.0.5")));
+        assert!(!single.contains(ip("10.0.0.6")));
+
+        let v6 = IpRange::parse("fd00::/8").unwrap();
+        assert!(v6.contains(ip("fd12::1")));
+        assert!(!v6.contains(ip("fe80::1")));
+
+        assert!(IpRange::parse("0.0.0.0/0").unwrap().contains(ip("8.8.8.8")));
+        assert!(IpRange::parse("10.0.0.0/33").is_err());
+        assert!(IpRange::parse("not-an-ip").is_err());
+    }
+
+    #[test]
+    fn test_allowlist_always_allows_loopback() {
+        let guard = AuthGuard::new(parse_allowlist(&["192.168.1.0/24".to_string()]).unwrap());
+        let now = Instant::now();
+        assert!(guard.check(ip("127.0.0.1"), true, now).is_ok());
+        assert!(guard.check(ip("192.168.1.3"), true, now).is_ok());
+        assert_eq!(
+            guard.check(ip("10.0.0.1"), true, now),
+            Err(Rejection::NotAllowed)
+        );
+        assert!(AuthGuard::new(vec![]).is_allowed(ip("10.0.0.1")));
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let guard = AuthGuard::new(vec![]);
+        let client = ip("192.168.1.3");
+        let now = Instant::now();
+        for _ in 0..RATE_LIMIT_REQUESTS {
+            assert!(guard.check(client, true, now).is_ok());
+        }
+        assert!(matches!(
+            guard.check(client, true, now),
+            Err(Rejection::RateLimited { .. })
+        ));
+        // Only connection endpoints are rate limited
+        assert!(guard.check(client, false, now).is_ok());
+        // The window slides
+        assert!(guard.check(client, true, now + RATE_LIMIT_WINDOW).is_ok());
+    }
+
+    #[test]
+    fn test_lockout_after_failures() {
+        let guard = AuthGuard::new(vec![]);
+        let client = ip("192.168.1.3");
+        let now = Instant::now();
+        for _ in 1..FAILURES_BEFORE_LOCKOUT {
+            assert_eq!(guard.record_failure(client, now), Rejection::InvalidToken);
+        }
+        assert_eq!(
+            guard.record_failure(client, now),
+            Rejection::LockedOut {
+                retry_after: BASE_LOCKOUT
+            }
+        );
+        assert!(matches!(
+            guard.check(client, false, now),
+            Err(Rejection::LockedOut { .. })
+        ));
+        assert!(guard.check(client, false, now + BASE_LOCKOUT).is_ok());
+
+        // Another failure doubles the lockout
+        assert_eq!(
+            guard.record_failure(client, now + BASE_LOCKOUT),
+            Rejection::LockedOut {
+                retry_after: BASE_LOCKOUT * 2
+            }
+        );
+
+        guard.record_success(client);
+        assert!(guard.check(client, false, now + BASE_LOCKOUT).is_ok());
+        assert_eq!(guard.record_failure(client, now), Rejection::InvalidToken);
+    }
+
+    #[test]
+    fn test_lockout_is_capped() {
+        assert_eq!(lockout_duration(FAILURES_BEFORE_LOCKOUT), BASE_LOCKOUT);
+        assert_eq!(lockout_duration(100), MAX_LOCKOUT);
+    }
+}
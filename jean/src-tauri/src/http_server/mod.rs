@@ -1,43 +1,66 @@
+pub mod audit;
 pub mod auth;
 pub mod dispatch;
+pub mod guard;
+pub mod replay;
+pub mod rest;
 pub mod server;
+pub mod sse;
+pub mod subscriptions;
+pub mod tls;
+pub mod tokens;
 pub mod websocket;
 
+use std::sync::Mutex;
+
 use serde::Serialize;
 use serde_json::Value;
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::broadcast;
 
+use replay::{Catchup, ReplayLog, ResumePoint};
+
 /// Broadcast channel for sending events to all connected WebSocket clients.
 /// Managed as Tauri state so any code with an AppHandle can broadcast.
+/// Events are numbered and recent ones kept for reconnecting clients (see `replay`).
 pub struct WsBroadcaster {
     tx: broadcast::Sender<WsEvent>,
+    replay: Mutex<ReplayLog>,
 }
 
 #[derive(Clone, Debug)]
 pub struct WsEvent {
+    /// Position in the event stream, increasing by one per event
+    pub seq: u64,
     pub event: String,
     pub payload: Value,
 }
 
 impl WsBroadcaster {
     pub fn new() -> (Self, broadcast::Sender<WsEvent>) {
-        // Buffer 1000 events — slow clients will miss old events
+        // Buffer 1000 events — slow clients will miss old events and must resync
         let (tx, _) = broadcast::channel(1000);
         let tx_clone = tx.clone();
-        (Self { tx }, tx_clone)
+        let replay = Mutex::new(ReplayLog::default());
+        (Self { tx, replay }, tx_clone)
     }
 
     pub fn broadcast(&self, event: &str, payload: &Value) {
+        // Send while holding the log so events reach receivers in sequence order
+        let mut replay = self.replay.lock().unwrap();
+        let ws_event = replay.record(event, payload);
         // Ignore send errors (no active receivers is fine)
-        let _ = self.tx.send(WsEvent {
-            event: event.to_string(),
-            payload: payload.clone(),
-        });
+        let _ = self.tx.send(ws_event);
     }
 
-    pub fn subscribe(&self) -> broadcast::Receiver<WsEvent> {
-        self.tx.subscribe()
+    /// Receiver for live events, plus the events missed since `resume`.
+    /// Both are taken together, so nothing falls between replay and live events.
+    pub fn subscribe(
+        &self,
+        resume: Option<&ResumePoint>,
+    ) -> (broadcast::Receiver<WsEvent>, Catchup) {
+        let replay = self.replay.lock().unwrap();
+        (self.tx.subscribe(), replay.catchup(resume))
     }
 }
 
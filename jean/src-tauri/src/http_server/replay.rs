@@ -0,0 +1,227 @@
+//! Replay log for WebSocket events
+//!
+//! Every broadcast event gets a sequence number, increasing by one per event
+//! for the lifetime of the server process. The most recent events are kept in
+//! memory so a client that reconnects with `/ws?resume_from=<seq>&stream_id=<id>`
+//! receives what it missed. When that isn't possible (events already dropped
+//! from the log, or the app restarted and the stream id changed) the client is
+//! told to resync, i.e. reload its state via `/api/init` or queries.
+//!
+//! Terminal output is numbered but not kept: it would quickly crowd everything
+//! else out of the log, and clients recover it from the terminal's scrollback.
+
+use std::collections::VecDeque;
+
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::WsEvent;
+
+/// Events kept for replay; older ones can only be recovered by a resync
+const REPLAY_LOG_CAPACITY: usize = 5000;
+
+/// Events that are never replayed
+const UNREPLAYED_EVENTS: &[&str] = &["terminal:output"];
+
+/// Where a reconnecting client left off
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResumePoint {
+    /// Stream the sequence number belongs to; `None` trusts the number as is
+    pub stream_id: Option<String>,
+    /// Last sequence number the client has seen
+    pub seq: u64,
+}
+
+/// What a newly connected client starts from
+#[derive(Debug)]
+pub struct Catchup {
+    pub stream_id: String,
+    /// Latest sequence number at the time of connecting
+    pub seq: u64,
+    /// Missed events to send before live ones, oldest first
+    pub events: Vec<WsEvent>,
+    /// The missed events are no longer available; the client must reload
+    pub resync: bool,
+}
+
+#[derive(Debug)]
+pub struct ReplayLog {
+    /// Changes every time the server process starts
+    stream_id: String,
+    next_seq: u64,
+    /// Last sequence number dropped from the log (0 if none)
+    evicted_seq: u64,
+    events: VecDeque<WsEvent>,
+    capacity: usize,
+}
+
+impl Default for ReplayLog {
+    fn default() -> Self {
+        Self::with_capacity(REPLAY_LOG_CAPACITY)
+    }
+}
+
+impl ReplayLog {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            stream_id: Uuid::new_v4().to_string(),
+            next_seq: 1,
+            evicted_seq: 0,
+            events: VecDeque::with_capacity(capacity.min(1024)),
+            capacity,
+        }
+    }
+
+    /// Sequence number of the last recorded event (0 before the first one)
+    pub fn last_seq(&self) -> u64 {
+        self.next_seq - 1
+    }
+
+    /// Number an event and keep it for replay
+    pub fn record(&mut self, event: &str, payload: &Value) -> WsEvent {
+        let ws_event = WsEvent {
+            seq: self.next_seq,
+            event: event.to_string(),
+            payload: payload.clone(),
+        };
+        self.next_seq += 1;
+        if !UNREPLAYED_EVENTS.contains(&event) {
+            if self.events.len() == self.capacity {
+                // With no room at all, the new event itself is lost
+                self.evicted_seq = self.events.pop_front().map_or(ws_event.seq, |e| e.seq);
+            }
+            if self.capacity > 0 {
+                self.events.push_back(ws_event.clone());
+            }
+        }
+        ws_event
+    }
+
+    /// Events after `resume`, or `None` if some of them are gone
+    fn events_after(&self, resume: &ResumePoint) -> Option<Vec<WsEvent>> {
+        if resume
+            .stream_id
+            .as_deref()
+            .is_some_and(|id| id != self.stream_id)
+        {
+            return None;
+        }
+        // From the future: the client saw a different stream
+        if resume.seq > self.last_seq() {
+            return None;
+        }
+        if resume.seq < self.evicted_seq {
+            return None;
+        }
+        Some(
+            self.events
+                .iter()
+                .filter(|e| e.seq > resume.seq)
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Starting point for a client, resuming from `resume` if given
+    pub fn catchup(&self, resume: Option<&ResumePoint>) -> Catchup {
+        let (events, resync) = match resume {
+            None => (vec![], false),
+            Some(resume) => match self.events_after(resume) {
+                Some(events) => (events, false),
+                None => (vec![], true),
+            },
+        };
+        Catchup {
+            stream_id: self.stream_id.clone(),
+            seq: self.last_seq(),
+            events,
+            resync,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn log_with(capacity: usize, count: usize) -> ReplayLog {
+        let mut log = ReplayLog::with_capacity(capacity);
+        for i in 0..count {
+            log.record("chat:chunk", &json!({ "i": i }));
+        }
+        log
+    }
+
+    fn resume(log: &ReplayLog, seq: u64) -> ResumePoint {
+        ResumePoint {
+            stream_id: Some(log.stream_id.clone()),
+            seq,
+        }
+    }
+
+    #[test]
+    fn test_sequence_numbers_increase() {
+        let mut log = ReplayLog::with_capacity(10);
+        assert_eq!(log.last_seq(), 0);
+        assert_eq!(log.record("a", &json!({})).seq, 1);
+        assert_eq!(log.record("b", &json!({})).seq, 2);
+        assert_eq!(log.last_seq(), 2);
+    }
+
+    #[test]
+    fn test_catchup_replays_missed_events() {
+        let log = log_with(10, 5);
+        let catchup = log.catchup(Some(&resume(&log, 3)));
+        assert!(!catchup.resync);
+        assert_eq!(catchup.seq, 5);
+        let seqs: Vec<u64> = catchup.events.iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, vec![4, 5]);
+
+        let catchup = log.catchup(Some(&resume(&log, 5)));
+        assert!(!catchup.resync);
+        assert!(catchup.events.is_empty());
+
+        // Fresh connection: nothing to replay
+        let catchup = log.catchup(None);
+        assert!(!catchup.resync && catchup.events.is_empty());
+    }
+
+    #[test]
+    fn test_terminal_output_is_not_replayed() {
+        let mut log = ReplayLog::with_capacity(10);
+        log.record("chat:chunk", &json!({}));
+        log.record("terminal:output", &json!({ "terminal_id": "t1" }));
+        log.record("chat:done", &json!({}));
+
+        let catchup = log.catchup(Some(&resume(&log, 0)));
+        assert!(!catchup.resync);
+        let seqs: Vec<u64> = catchup.events.iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_catchup_requires_resync_when_events_dropped() {
+        let log = log_with(3, 10);
+        // Log holds 8..=10; resuming from 7 is still complete
+        assert!(!log.catchup(Some(&resume(&log, 7))).resync);
+        assert!(log.catchup(Some(&resume(&log, 6))).resync);
+    }
+
+    #[test]
+    fn test_catchup_requires_resync_for_other_stream() {
+        let log = log_with(10, 5);
+        let other = ResumePoint {
+            stream_id: Some("old-process".to_string()),
+            seq: 2,
+        };
+        assert!(log.catchup(Some(&other)).resync);
+
+        // Without a stream id, only a sequence number ahead of ours is detectable
+        let ahead = ResumePoint {
+            stream_id: None,
+            seq: 9,
+        };
+        assert!(log.catchup(Some(&ahead)).resync);
+    }
+}
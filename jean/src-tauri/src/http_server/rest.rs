@@ -0,0 +1,1175 @@
+//! Versioned REST API over the command dispatcher
+//!
+//! Every route in [`ROUTES`] maps an HTTP method and path under `/api/v1` to
+//! a command of the dispatcher (`dispatch::dispatch_command`). Path parameters,
+//! query parameters and the JSON body are merged into the command's args, so a
+//! route accepts the same fields (camelCase or snake_case) as the WebSocket
+//! `invoke`. Any other command is reachable via `POST /api/v1/commands/{command}`.
+//! Commands are recorded in the audit log (see `audit`).
+//!
+//! Callers only need to name the resource: `worktreeId` is looked up from a
+//! `sessionId` and `worktreePath` from a `worktreeId` when omitted.
+//!
+//! `GET /api/v1/openapi.json` serves an OpenAPI 3.1 document generated from
+//! the same route table.
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+
+use axum::{
+    body::Bytes,
+    extract::{ConnectInfo, Query, RawPathParams, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, on, post, MethodFilter, MethodRouter},
+    Json, Router,
+};
+use serde_json::{json, Map, Value};
+use tauri::AppHandle;
+
+use super::audit::{dispatch_audited, AuditClient, AuditTransport};
+use super::dispatch::DispatchError;
+use super::guard::with_retry_after;
+use super::server::AppState;
+
+/// Prefix for all REST routes
+pub const API_PREFIX: &str = "/api/v1";
+
+/// JSON type of a documented parameter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamType {
+    String,
+    Integer,
+    Boolean,
+    Object,
+    Array,
+}
+
+impl ParamType {
+    fn schema(self) -> Value {
+        match self {
+            Self::String => json!({ "type": "string" }),
+            Self::Integer => json!({ "type": "integer" }),
+            Self::Boolean => json!({ "type": "boolean" }),
+            Self::Object => json!({ "type": "object" }),
+            Self::Array => json!({ "type": "array", "items": {} }),
+        }
+    }
+}
+
+/// A query or body parameter of a route (path parameters come from the path)
+#[derive(Debug, Clone, Copy)]
+pub struct Param {
+    pub name: &'static str,
+    pub ty: ParamType,
+    pub required: bool,
+}
+
+const fn req(name: &'static str, ty: ParamType) -> Param {
+    Param {
+        name,
+        ty,
+        required: true,
+    }
+}
+
+const fn opt(name: &'static str, ty: ParamType) -> Param {
+    Param {
+        name,
+        ty,
+        required: false,
+    }
+}
+
+/// A REST endpoint backed by a dispatcher command
+#[derive(Debug, Clone, Copy)]
+pub struct RestRoute {
+    pub method: RestMethod,
+    /// Path below [`API_PREFIX`], with `{param}` placeholders
+    pub path: &'static str,
+    pub command: &'static str,
+    pub tag: &'static str,
+    pub summary: &'static str,
+    /// Query parameters (GET/DELETE) or JSON body fields (POST/PUT/PATCH)
+    pub params: &'static [Param],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RestMethod {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+impl RestMethod {
+    fn filter(self) -> MethodFilter {
+        match self {
+            Self::Get => MethodFilter::GET,
+            Self::Post => MethodFilter::POST,
+            Self::Put => MethodFilter::PUT,
+            Self::Patch => MethodFilter::PATCH,
+            Self::Delete => MethodFilter::DELETE,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Get => "get",
+            Self::Post => "post",
+            Self::Put => "put",
+            Self::Patch => "patch",
+            Self::Delete => "delete",
+        }
+    }
+
+    /// Whether parameters travel in the query string rather than a JSON body
+    fn uses_query(self) -> bool {
+        matches!(self, Self::Get | Self::Delete)
+    }
+}
+
+use ParamType::{Array, Boolean, Integer, Object, String as Str};
+use RestMethod::{Delete, Get, Patch, Post, Put};
+
+/// Every REST endpoint. A dispatcher command added without a route must be
+/// listed in the tests' `UNROUTED_COMMANDS`.
+pub const ROUTES: &[RestRoute] = &[
+    // Projects & worktrees
+    RestRoute {
+        method: Get,
+        path: "/projects",
+        command: "list_projects",
+        tag: "projects",
+        summary: "List projects",
+        params: &[],
+    },
+    RestRoute {
+        method: Get,
+        path: "/worktrees",
+        command: "list_worktrees",
+        tag: "projects",
+        summary: "List a project's worktrees",
+        params: &[req("projectId", Str)],
+    },
+    RestRoute {
+        method: Get,
+        path: "/projects/{projectId}/worktrees",
+        command: "list_worktrees",
+        tag: "projects",
+        summary: "List a project's worktrees",
+        params: &[],
+    },
+    RestRoute {
+        method: Post,
+        path: "/projects/{projectId}/worktrees",
+        command: "create_worktree",
+        tag: "projects",
+        summary: "Create a worktree",
+        params: &[
+            opt("baseBranch", Str),
+            opt("customName", Str),
+            opt("issueContext", Object),
+            opt("prContext", Object),
+        ],
+    },
+    RestRoute {
+        method: Get,
+        path: "/worktrees/{worktreeId}",
+        command: "get_worktree",
+        tag: "projects",
+        summary: "Get a worktree",
+        params: &[],
+    },
+    RestRoute {
+        method: Delete,
+        path: "/worktrees/{worktreeId}",
+        command: "delete_worktree",
+        tag: "projects",
+        summary: "Delete a worktree",
+        params: &[],
+    },
+    // Sessions
+    RestRoute {
+        method: Get,
+        path: "/sessions",
+        command: "list_all_sessions",
+        tag: "sessions",
+        summary: "List sessions across all worktrees",
+        params: &[],
+    },
+    RestRoute {
+        method: Get,
+        path: "/worktrees/{worktreeId}/sessions",
+        command: "get_sessions",
+        tag: "sessions",
+        summary: "List a worktree's sessions",
+        params: &[
+            opt("includeArchived", Boolean),
+            opt("includeMessageCounts", Boolean),
+        ],
+    },
+    RestRoute {
+        method: Post,
+        path: "/worktrees/{worktreeId}/sessions",
+        command: "create_session",
+        tag: "sessions",
+        summary: "Create a session",
+        params: &[opt("name", Str)],
+    },
+    RestRoute {
+        method: Get,
+        path: "/sessions/{sessionId}",
+        command: "get_session",
+        tag: "sessions",
+        summary: "Get a session with its messages",
+        params: &[],
+    },
+    RestRoute {
+        method: Patch,
+        path: "/sessions/{sessionId}",
+        command: "rename_session",
+        tag: "sessions",
+        summary: "Rename a session",
+        params: &[req("newName", Str)],
+    },
+    RestRoute {
+        method: Post,
+        path: "/sessions/{sessionId}/archive",
+        command: "archive_session",
+        tag: "sessions",
+        summary: "Archive a session",
+        params: &[],
+    },
+    RestRoute {
+        method: Post,
+        path: "/sessions/{sessionId}/fork",
+        command: "fork_session",
+        tag: "sessions",
+        summary: "Fork a session at a message",
+        params: &[
+            req("messageId", Str),
+            opt("targetWorktreeId", Str),
+            opt("name", Str),
+        ],
+    },
+    RestRoute {
+        method: Get,
+        path: "/sessions/{sessionId}/export",
+        command: "export_session",
+        tag: "sessions",
+        summary: "Export a session as markdown, html or json",
+        params: &[req("format", Str)],
+    },
+    RestRoute {
+        method: Post,
+        path: "/sessions/search",
+        command: "search_sessions",
+        tag: "sessions",
+        summary: "Full-text search over session histories",
+        params: &[
+            req("query", Str),
+            opt("filter", Object),
+            opt("limit", Integer),
+        ],
+    },
+    // Messages
+    RestRoute {
+        method: Post,
+        path: "/sessions/{sessionId}/messages",
+        command: "send_chat_message",
+        tag: "messages",
+        summary: "Send a message and wait for the response",
+        params: &[
+            req("message", Str),
+            opt("model", Str),
+            opt("executionMode", Str),
+            opt("thinkingLevel", Str),
+            opt("allowedTools", Array),
+            opt("overrideBudget", Boolean),
+        ],
+    },
+    RestRoute {
+        method: Delete,
+        path: "/sessions/{sessionId}/messages",
+        command: "clear_session_history",
+        tag: "messages",
+        summary: "Clear a session's history",
+        params: &[],
+    },
+    RestRoute {
+        method: Post,
+        path: "/sessions/{sessionId}/cancel",
+        command: "cancel_chat_message",
+        tag: "messages",
+        summary: "Cancel the running response",
+        params: &[],
+    },
+    RestRoute {
+        method: Get,
+        path: "/sessions/{sessionId}/queue",
+        command: "list_queued_messages",
+        tag: "messages",
+        summary: "List queued follow-up messages",
+        params: &[],
+    },
+    RestRoute {
+        method: Post,
+        path: "/sessions/{sessionId}/queue",
+        command: "enqueue_message",
+        tag: "messages",
+        summary: "Queue a follow-up message",
+        params: &[
+            req("message", Str),
+            opt("model", Str),
+            opt("executionMode", Str),
+            opt("thinkingLevel", Str),
+            opt("allowedTools", Array),
+        ],
+    },
+    RestRoute {
+        method: Put,
+        path: "/sessions/{sessionId}/queue",
+        command: "reorder_queued_messages",
+        tag: "messages",
+        summary: "Reorder queued messages",
+        params: &[req("itemIds", Array)],
+    },
+    RestRoute {
+        method: Delete,
+        path: "/sessions/{sessionId}/queue",
+        command: "clear_message_queue",
+        tag: "messages",
+        summary: "Drop all queued messages",
+        params: &[],
+    },
+    RestRoute {
+        method: Patch,
+        path: "/sessions/{sessionId}/queue/{itemId}",
+        command: "update_queued_message",
+        tag: "messages",
+        summary: "Edit a queued message",
+        params: &[req("message", Str)],
+    },
+    RestRoute {
+        method: Delete,
+        path: "/sessions/{sessionId}/queue/{itemId}",
+        command: "remove_queued_message",
+        tag: "messages",
+        summary: "Drop a queued message",
+        params: &[],
+    },
+    // Usage
+    RestRoute {
+        method: Post,
+        path: "/usage/report",
+        command: "get_usage_report",
+        tag: "usage",
+        summary: "Token and cost usage report",
+        params: &[opt("filter", Object)],
+    },
+    RestRoute {
+        method: Get,
+        path: "/worktrees/{worktreeId}/budget",
+        command: "get_budget_status",
+        tag: "usage",
+        summary: "Budget status for a worktree",
+        params: &[],
+    },
+    // Schedules
+    RestRoute {
+        method: Get,
+        path: "/schedules",
+        command: "list_schedules",
+        tag: "schedules",
+        summary: "List schedules",
+        params: &[],
+    },
+    RestRoute {
+        method: Post,
+        path: "/schedules",
+        command: "create_schedule",
+        tag: "schedules",
+        summary: "Create a schedule",
+        params: &[req("schedule", Object)],
+    },
+    RestRoute {
+        method: Put,
+        path: "/schedules/{scheduleId}",
+        command: "update_schedule",
+        tag: "schedules",
+        summary: "Update a schedule",
+        params: &[req("schedule", Object)],
+    },
+    RestRoute {
+        method: Delete,
+        path: "/schedules/{scheduleId}",
+        command: "delete_schedule",
+        tag: "schedules",
+        summary: "Delete a schedule",
+        params: &[],
+    },
+    RestRoute {
+        method: Post,
+        path: "/schedules/{scheduleId}/enabled",
+        command: "set_schedule_enabled",
+        tag: "schedules",
+        summary: "Pause or resume a schedule",
+        params: &[req("enabled", Boolean)],
+    },
+    RestRoute {
+        method: Post,
+        path: "/schedules/{scheduleId}/run",
+        command: "run_schedule_now",
+        tag: "schedules",
+        summary: "Run a schedule now",
+        params: &[],
+    },
+    // Preferences
+    RestRoute {
+        method: Get,
+        path: "/preferences",
+        command: "load_preferences",
+        tag: "preferences",
+        summary: "Get preferences",
+        params: &[],
+    },
+    RestRoute {
+        method: Put,
+        path: "/preferences",
+        command: "save_preferences",
+        tag: "preferences",
+        summary: "Replace preferences",
+        params: &[req("preferences", Object)],
+    },
+    // API tokens
+    RestRoute {
+        method: Get,
+        path: "/tokens",
+        command: "list_api_tokens",
+        tag: "tokens",
+        summary: "List API tokens",
+        params: &[],
+    },
+    RestRoute {
+        method: Post,
+        path: "/tokens",
+        command: "create_api_token",
+        tag: "tokens",
+        summary: "Create an API token (the secret is only returned here)",
+        params: &[
+            req("name", Str),
+            req("scopes", Array),
+            opt("projectIds", Array),
+            opt("expiresAt", Integer),
+        ],
+    },
+    RestRoute {
+        method: Delete,
+        path: "/tokens/{tokenId}",
+        command: "revoke_api_token",
+        tag: "tokens",
+        summary: "Revoke an API token",
+        params: &[],
+    },
+    // Audit log
+    RestRoute {
+        method: Get,
+        path: "/audit",
+        command: "query_audit_log",
+        tag: "audit",
+        summary: "Remote commands and connections, newest first",
+        params: &[
+            opt("since", Integer),
+            opt("until", Integer),
+            opt("command", Str),
+            opt("limit", Integer),
+        ],
+    },
+];
+
+/// Path of the generic command endpoint
+const COMMAND_PATH: &str = "/commands/{command}";
+
+/// Router with every REST route plus the OpenAPI document
+pub fn router() -> Router<AppState> {
+    let mut by_path: BTreeMap<&'static str, MethodRouter<AppState>> = BTreeMap::new();
+    for route in ROUTES {
+        let handler = move |state: State<AppState>,
+                            connect_info: ConnectInfo<SocketAddr>,
+                            path: RawPathParams,
+                            query: Query<HashMap<String, String>>,
+                            headers: HeaderMap,
+                            body: Bytes| {
+            route_handler(
+                state,
+                connect_info,
+                route.command,
+                path,
+                query,
+                headers,
+                body,
+            )
+        };
+        let entry = by_path.remove(route.path);
+        let method_router = match entry {
+            Some(existing) => existing.on(route.method.filter(), handler),
+            None => on(route.method.filter(), handler),
+        };
+        by_path.insert(route.path, method_router);
+    }
+
+    let mut router = Router::new()
+        .route(&format!("{API_PREFIX}/openapi.json"), get(openapi_handler))
+        .route(
+            &format!("{API_PREFIX}{COMMAND_PATH}"),
+            post(command_handler),
+        );
+    for (path, method_router) in by_path {
+        router = router.route(&format!("{API_PREFIX}{path}"), method_router);
+    }
+    router
+}
+
+/// Token from `Authorization: Bearer ...` or the `token` query parameter
+pub(super) fn provided_token<'a>(
+    headers: &'a HeaderMap,
+    query: &'a HashMap<String, String>,
+) -> &'a str {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or_else(|| query.get("token").map(String::as_str))
+        .unwrap_or_default()
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(json!({ "error": message.into() }))).into_response()
+}
+
+/// HTTP status for a dispatcher error
+pub fn status_for_error(error: &DispatchError) -> StatusCode {
+    match error {
+        DispatchError::UnknownCommand(_) => StatusCode::NOT_FOUND,
+        DispatchError::Forbidden(_) => StatusCode::FORBIDDEN,
+        DispatchError::InvalidArgs(_) => StatusCode::BAD_REQUEST,
+        DispatchError::Failed(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Build command args from body, query and path (later sources win)
+pub fn build_args(
+    body: &[u8],
+    query: &HashMap<String, String>,
+    path: &[(String, String)],
+) -> Result<Map<String, Value>, String> {
+    let mut args = if body.iter().all(u8::is_ascii_whitespace) {
+        Map::new()
+    } else {
+        match serde_json::from_slice::<Value>(body) {
+            Ok(Value::Object(map)) => map,
+            Ok(_) => return Err("Request body must be a JSON object".to_string()),
+            Err(e) => return Err(format!("Invalid JSON body: {e}")),
+        }
+    };
+
+    for (key, value) in query {
+        if key == "token" {
+            continue;
+        }
+        // `?limit=5&includeArchived=true` → typed values; anything else is a string
+        let value = serde_json::from_str::<Value>(value)
+            .ok()
+            .filter(|v| v.is_number() || v.is_boolean())
+            .unwrap_or_else(|| Value::String(value.clone()));
+        args.insert(key.clone(), value);
+    }
+
+    for (key, value) in path {
+        args.insert(key.clone(), Value::String(value.clone()));
+    }
+    Ok(args)
+}
+
+fn has_arg(args: &Map<String, Value>, camel: &str, snake: &str) -> bool {
+    [camel, snake]
+        .iter()
+        .any(|k| args.get(*k).is_some_and(|v| !v.is_null()))
+}
+
+fn get_arg(args: &Map<String, Value>, camel: &str, snake: &str) -> Option<String> {
+    args.get(camel)
+        .or_else(|| args.get(snake))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Fill in `worktreeId`/`worktreePath` that can be derived from other args
+fn fill_context(app: &AppHandle, args: &mut Map<String, Value>) {
+    if !has_arg(args, "worktreeId", "worktree_id") {
+        if let Some(session_id) = get_arg(args, "sessionId", "session_id") {
+            if let Ok(Some(metadata)) = crate::chat::storage::load_metadata(app, &session_id) {
+                args.insert(
+                    "worktreeId".to_string(),
+                    Value::String(metadata.worktree_id),
+                );
+            }
+        }
+    }
+
+    if !has_arg(args, "worktreePath", "worktree_path") {
+        if let Some(worktree_id) = get_arg(args, "worktreeId", "worktree_id") {
+            if let Ok(data) = crate::projects::storage::load_projects_data(app) {
+                if let Some(worktree) = data.find_worktree(&worktree_id) {
+                    args.insert(
+                        "worktreePath".to_string(),
+                        Value::String(worktree.path.clone()),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Authenticate, build args and run `command`, mapping the result to HTTP
+async fn run_command(
+    state: AppState,
+    addr: SocketAddr,
+    command: &str,
+    path: Vec<(String, String)>,
+    query: HashMap<String, String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let grant = match state.authenticate(addr.ip(), provided_token(&headers, &query), false) {
+        Ok(grant) => grant,
+        Err(rejection) => {
+            let response = error_response(rejection.status(), rejection.message());
+            return with_retry_after(&rejection, response);
+        }
+    };
+
+    let mut args = match build_args(&body, &query, &path) {
+        Ok(args) => args,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+    fill_context(&state.app, &mut args);
+
+    log::trace!(
+        "REST {command} with args: {:?}",
+        args.keys().collect::<Vec<_>>()
+    );
+    let client = AuditClient {
+        addr,
+        transport: AuditTransport::Rest,
+    };
+    match dispatch_audited(&state.app, &client, &grant, command, Value::Object(args)).await {
+        Ok(Value::Null) => StatusCode::NO_CONTENT.into_response(),
+        Ok(value) if command.starts_with("create_") => {
+            (StatusCode::CREATED, Json(value)).into_response()
+        }
+        Ok(value) => Json(value).into_response(),
+        Err(e) => error_response(status_for_error(&e), e.to_string()),
+    }
+}
+
+async fn route_handler(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    command: &'static str,
+    path: RawPathParams,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let path = path
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    run_command(state, addr, command, path, query, headers, body).await
+}
+
+/// `POST /api/v1/commands/{command}`: any dispatcher command, body = args
+async fn command_handler(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    path: RawPathParams,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let command = path
+        .iter()
+        .find(|(k, _)| *k == "command")
+        .map(|(_, v)| v.to_string())
+        .unwrap_or_default();
+    run_command(state, addr, &command, vec![], query, headers, body).await
+}
+
+/// Served without auth so tooling can discover the API
+async fn openapi_handler() -> Response {
+    Json(openapi_document()).into_response()
+}
+
+/// Names of the `{param}` placeholders in a route path
+fn path_params(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter_map(|seg| seg.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+        .collect()
+}
+
+/// OpenAPI operationId: the command name, qualified by the route's path
+/// parameters when several routes share a command
+/// (`GET /projects/{projectId}/worktrees` is `list_worktrees_by_project_id`)
+fn operation_id(route: &RestRoute) -> String {
+    let shared = ROUTES.iter().filter(|r| r.command == route.command).count() > 1;
+    let params = path_params(route.path);
+    if !shared || params.is_empty() {
+        return route.command.to_string();
+    }
+    let params: Vec<String> = params.into_iter().map(snake_case).collect();
+    format!("{}_by_{}", route.command, params.join("_and_"))
+}
+
+fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('_');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn operation(route: &RestRoute) -> Value {
+    let mut parameters: Vec<Value> = path_params(route.path)
+        .into_iter()
+        .map(|name| {
+            json!({ "name": name, "in": "path", "required": true, "schema": { "type": "string" } })
+        })
+        .collect();
+
+    let mut op = json!({
+        "operationId": operation_id(route),
+        "summary": route.summary,
+        "tags": [route.tag],
+        "responses": {
+            "200": { "description": "Command result", "content": { "application/json": { "schema": {} } } },
+            "204": { "description": "Command succeeded without a result" },
+            "400": { "$ref": "#/components/responses/Error" },
+            "401": { "$ref": "#/components/responses/Error" },
+            "403": { "$ref": "#/components/responses/Error" },
+            "404": { "$ref": "#/components/responses/Error" },
+            "500": { "$ref": "#/components/responses/Error" },
+        },
+    });
+
+    if route.method.uses_query() {
+        parameters.extend(route.params.iter().map(|p| {
+            json!({ "name": p.name, "in": "query", "required": p.required, "schema": p.ty.schema() })
+        }));
+    } else {
+        let properties: Map<String, Value> = route
+            .params
+            .iter()
+            .map(|p| (p.name.to_string(), p.ty.schema()))
+            .collect();
+        let required: Vec<&str> = route
+            .params
+            .iter()
+            .filter(|p| p.required)
+            .map(|p| p.name)
+            .collect();
+        op["requestBody"] = json!({
+            "required": !required.is_empty(),
+            "content": { "application/json": { "schema": {
+                "type": "object",
+                "properties": properties,
+                "required": required,
+                "additionalProperties": true,
+            } } },
+        });
+    }
+    op["parameters"] = Value::Array(parameters);
+    op
+}
+
+/// OpenAPI 3.1 document describing [`ROUTES`] and the generic command endpoint
+pub fn openapi_document() -> Value {
+    let mut paths = Map::new();
+    for route in ROUTES {
+        let item = paths
+            .entry(format!("{API_PREFIX}{}", route.path))
+            .or_insert_with(|| json!({}));
+        item[route.method.as_str()] = operation(route);
+    }
+    paths.insert(
+        format!("{API_PREFIX}{COMMAND_PATH}"),
+        json!({ "post": {
+            "operationId": "invoke_command",
+            "summary": "Invoke any command available over the WebSocket API",
+            "tags": ["commands"],
+            "parameters": [
+                { "name": "command", "in": "path", "required": true, "schema": { "type": "string" } }
+            ],
+            "requestBody": { "content": { "application/json": { "schema": { "type": "object" } } } },
+            "responses": {
+                "200": { "description": "Command result", "content": { "application/json": { "schema": {} } } },
+                "204": { "description": "Command succeeded without a result" },
+                "400": { "$ref": "#/components/responses/Error" },
+                "401": { "$ref": "#/components/responses/Error" },
+                "403": { "$ref": "#/components/responses/Error" },
+                "404": { "$ref": "#/components/responses/Error" },
+                "500": { "$ref": "#/components/responses/Error" },
+            },
+        } }),
+    );
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "Jean API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Path, query and body parameters are merged into the command's arguments. \
+                            worktreeId and worktreePath are derived from sessionId/worktreeId when omitted.",
+        },
+        "paths": paths,
+        "components": {
+            "securitySchemes": { "bearer": { "type": "http", "scheme": "bearer" } },
+            "responses": { "Error": {
+                "description": "Command error",
+                "content": { "application/json": { "schema": {
+                    "type": "object",
+                    "properties": { "error": { "type": "string" } },
+                    "required": ["error"],
+                } } },
+            } },
+        },
+        "security": [{ "bearer": [] }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_routes_are_unique() {
+        let mut seen = HashSet::new();
+        for route in ROUTES {
+            assert!(
+                seen.insert((route.method.as_str(), route.path)),
+                "duplicate route {} {}",
+                route.method.as_str(),
+                route.path
+            );
+        }
+    }
+
+    /// Dispatcher commands deliberately left without a dedicated route. They
+    /// remain reachable through the generic command endpoint.
+    const UNROUTED_COMMANDS: &[&str] = &[
+        // Preferences & UI State
+        "load_ui_state",
+        "save_ui_state",
+        // Projects
+        "add_project",
+        "remove_project",
+        "get_project_branches",
+        "update_project_settings",
+        "reorder_projects",
+        "reorder_worktrees",
+        "fetch_worktrees_status",
+        "archive_worktree",
+        "unarchive_worktree",
+        "rename_worktree",
+        "has_uncommitted_changes",
+        "get_git_diff",
+        "git_pull",
+        "git_push",
+        "commit_changes",
+        "save_worktree_pr",
+        "clear_worktree_pr",
+        "create_pr_with_ai_content",
+        "create_commit_with_ai",
+        "run_review_with_ai",
+        "update_worktree_cached_status",
+        "list_worktree_files",
+        // GitHub Issues & PRs
+        "list_github_issues",
+        "get_github_issue",
+        "list_github_prs",
+        "get_github_pr",
+        "load_issue_context",
+        "list_loaded_issue_contexts",
+        "remove_issue_context",
+        "load_pr_context",
+        "list_loaded_pr_contexts",
+        "remove_pr_context",
+        "get_issue_context_content",
+        "get_pr_context_content",
+        // Saved Contexts
+        "attach_saved_context",
+        "remove_saved_context",
+        "list_attached_saved_contexts",
+        "get_saved_context_content",
+        // Chat Sessions
+        "close_session",
+        "reorder_sessions",
+        "set_active_session",
+        // Chat Messaging
+        "set_session_model",
+        "set_session_thinking_level",
+        "mark_plan_approved",
+        "save_cancelled_message",
+        "has_running_sessions",
+        // Chat - Saved Contexts
+        "list_saved_contexts",
+        "save_context_file",
+        "read_context_file",
+        "delete_context_file",
+        "generate_context_from_session",
+        // Chat - File operations
+        "read_file_content",
+        "read_plan_file",
+        // Background Tasks
+        "set_app_focus_state",
+        "set_active_worktree_for_polling",
+        "set_polled_worktrees",
+        "trigger_immediate_git_poll",
+        "trigger_immediate_remote_poll",
+        "set_git_poll_interval",
+        "get_git_poll_interval",
+        "set_remote_poll_interval",
+        "get_remote_poll_interval",
+        // Terminal
+        "kill_all_terminals",
+        // Recovery & Cleanup
+        "cleanup_old_recovery_files",
+        "check_resumable_sessions",
+        "cleanup_old_archives",
+        // HTTP Server control
+        "get_http_server_status",
+        // Core / Utility
+        "greet",
+        "send_native_notification",
+        "save_emergency_data",
+        "load_emergency_data",
+        // Project Management
+        "init_git_in_folder",
+        "init_project",
+        "create_worktree_from_existing_branch",
+        "checkout_pr",
+        "create_base_session",
+        "close_base_session",
+        "close_base_session_clean",
+        "list_archived_worktrees",
+        "import_worktree",
+        "permanently_delete_worktree",
+        "delete_all_archives",
+        "open_worktree_in_finder",
+        "open_project_worktrees_folder",
+        "open_worktree_in_terminal",
+        "open_worktree_in_editor",
+        "open_pull_request",
+        "open_project_on_github",
+        "get_github_branch_url",
+        "get_github_repo_url",
+        "get_pr_prompt",
+        "get_review_prompt",
+        "rebase_worktree",
+        // Git Operations
+        "merge_worktree_to_base",
+        "get_merge_conflicts",
+        "fetch_and_merge_base",
+        // Skills & Search
+        "list_claude_skills",
+        "list_claude_commands",
+        "search_github_issues",
+        "search_github_prs",
+        // Folder Management
+        "create_folder",
+        "rename_folder",
+        "delete_folder",
+        "move_item",
+        "reorder_items",
+        // Avatar Management
+        "set_project_avatar",
+        "remove_project_avatar",
+        "get_app_data_dir",
+        // Terminal
+        "start_terminal",
+        "terminal_write",
+        "terminal_resize",
+        "stop_terminal",
+        "get_active_terminals",
+        "has_active_terminal",
+        "list_terminals",
+        "get_terminal_scrollback",
+        "start_terminal_recording",
+        "stop_terminal_recording",
+        "list_terminal_recordings",
+        "read_terminal_recording",
+        "delete_terminal_recording",
+        "attach_terminal_recording",
+        "get_run_script",
+        // Session Management
+        "update_session_state",
+        "unarchive_session",
+        "restore_session_with_base",
+        "delete_archived_session",
+        "list_archived_sessions",
+        "list_all_archived_sessions",
+        // Images & Pasted Text
+        "save_pasted_image",
+        "save_dropped_image",
+        "delete_pasted_image",
+        "save_pasted_text",
+        "delete_pasted_text",
+        "read_pasted_text",
+        // File Operations
+        "write_file_content",
+        "open_file_in_default_app",
+        // Context & Debug
+        "rename_saved_context",
+        "generate_session_digest",
+        "update_session_digest",
+        "get_session_debug_info",
+        "export_session_bundle",
+        "import_session_bundle",
+        "list_claude_cli_sessions",
+        "import_claude_cli_session",
+        "send_next_queued_message",
+        "preview_schedule",
+        "resume_session",
+        "broadcast_session_setting",
+        // CLI Management
+        "check_claude_cli_installed",
+        "check_claude_cli_auth",
+        "get_available_cli_versions",
+        "install_claude_cli",
+        "check_gh_cli_installed",
+        "check_gh_cli_auth",
+        "get_available_gh_versions",
+        "install_gh_cli",
+        // HTTP Server control
+        "start_http_server",
+        "stop_http_server",
+        "regenerate_http_token",
+    ];
+
+    #[test]
+    fn test_every_command_is_routed_or_unrouted() {
+        let arms = super::super::dispatch::COMMANDS;
+        assert!(arms.contains(&"send_chat_message"));
+        let routed: HashSet<&str> = ROUTES.iter().map(|r| r.command).collect();
+        for command in &arms {
+            assert!(
+                routed.contains(command) != UNROUTED_COMMANDS.contains(command),
+                "{command} must be either routed or listed in UNROUTED_COMMANDS"
+            );
+        }
+        for command in routed.iter().chain(UNROUTED_COMMANDS) {
+            assert!(
+                arms.contains(command),
+                "{command} is not a dispatcher command"
+            );
+        }
+    }
+
+    #[test]
+    fn test_operation_ids_are_unique() {
+        let doc = openapi_document();
+        let mut seen = HashSet::new();
+        for item in doc["paths"].as_object().unwrap().values() {
+            for op in item.as_object().unwrap().values() {
+                let id = op["operationId"].as_str().unwrap();
+                assert!(seen.insert(id.to_string()), "duplicate operationId {id}");
+            }
+        }
+        assert!(seen.contains("list_worktrees"));
+        assert!(seen.contains("list_worktrees_by_project_id"));
+    }
+
+    #[test]
+    fn test_build_args_merges_sources() {
+        let query = HashMap::from([
+            ("limit".to_string(), "5".to_string()),
+            ("includeArchived".to_string(), "true".to_string()),
+            ("format".to_string(), "markdown".to_string()),
+            ("token".to_string(), "secret".to_string()),
+        ]);
+        let path = vec![("sessionId".to_string(), "abc".to_string())];
+        let args = build_args(
+            br#"{"message": "hi", "sessionId": "ignored"}"#,
+            &query,
+            &path,
+        )
+        .unwrap();
+
+        assert_eq!(args["message"], "hi");
+        assert_eq!(args["sessionId"], "abc");
+        assert_eq!(args["limit"], 5);
+        assert_eq!(args["includeArchived"], true);
+        assert_eq!(args["format"], "markdown");
+        assert!(!args.contains_key("token"));
+    }
+
+    #[test]
+    fn test_build_args_rejects_non_object_body() {
+        assert!(build_args(b"[1, 2]", &HashMap::new(), &[]).is_err());
+        assert!(build_args(b"{not json", &HashMap::new(), &[]).is_err());
+        assert!(build_args(b"  \n", &HashMap::new(), &[])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_status_for_error() {
+        let unknown = DispatchError::UnknownCommand("nope".to_string());
+        assert_eq!(status_for_error(&unknown), StatusCode::NOT_FOUND);
+        assert_eq!(unknown.to_string(), "Unknown command: nope");
+        assert_eq!(
+            status_for_error(&DispatchError::InvalidArgs(
+                "Missing field: message".to_string()
+            )),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            status_for_error(&DispatchError::Forbidden(
+                "Forbidden: git_push requires the 'git_write' scope".to_string()
+            )),
+            StatusCode::FORBIDDEN
+        );
+        // A command's own error message doesn't change the status
+        let failed = DispatchError::from("Session not found: x".to_string());
+        assert_eq!(status_for_error(&failed), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn test_openapi_document_covers_routes() {
+        let doc = openapi_document();
+        for route in ROUTES {
+            let op = &doc["paths"][format!("{API_PREFIX}{}", route.path)][route.method.as_str()];
+            assert_eq!(op["operationId"], operation_id(route));
+            for name in path_params(route.path) {
+                assert!(op["parameters"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .any(|p| p["name"] == name && p["in"] == "path"));
+            }
+        }
+        let send = &doc["paths"]["/api/v1/sessions/{sessionId}/messages"]["post"];
+        assert_eq!(
+            send["requestBody"]["content"]["application/json"]["schema"]["required"],
+            json!(["message"])
+        );
+    }
+}
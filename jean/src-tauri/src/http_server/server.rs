@@ -1,29 +1,79 @@
 use axum::{
-    extract::{ws::WebSocketUpgrade, Query, State},
+    extract::{ws::WebSocketUpgrade, ConnectInfo, Query, Request, State},
     http::StatusCode,
+    middleware::{self, Next},
     response::{IntoResponse, Response},
     routing::get,
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Semaphore};
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::services::{ServeDir, ServeFile};
 
+use super::audit::{AuditClient, AuditTransport};
 use super::auth;
+use super::guard::{parse_allowlist, with_retry_after, AuthGuard, Rejection};
+use super::replay::ResumePoint;
+use super::rest;
+use super::sse;
+use super::tls::TlsMaterial;
 use super::websocket::handle_ws_connection;
 use super::WsBroadcaster;
 
 /// Shared state for the Axum server.
 #[derive(Clone)]
-struct AppState {
-    app: AppHandle,
-    token: String,
-    token_required: bool,
+pub(super) struct AppState {
+    pub(super) app: AppHandle,
+    pub(super) token: String,
+    pub(super) token_required: bool,
+    pub(super) guard: Arc<AuthGuard>,
+    /// Open SSE streams (see [`sse::MAX_SSE_CLIENTS`])
+    pub(super) sse_clients: Arc<Semaphore>,
+}
+
+impl AppState {
+    /// Access granted by a presented token. Failed attempts count towards a
+    /// lockout of the client's IP; `connection` endpoints are also rate limited.
+    pub(super) fn authenticate(
+        &self,
+        ip: IpAddr,
+        provided: &str,
+        connection: bool,
+    ) -> Result<auth::Grant, Rejection> {
+        let now = Instant::now();
+        let result = self.guard.check(ip, connection, now).and_then(|()| {
+            auth::authenticate(&self.app, provided, &self.token, self.token_required)
+                .ok_or_else(|| self.guard.record_failure(ip, now))
+        });
+        match &result {
+            Ok(_) => self.guard.record_success(ip),
+            Err(rejection) => self.guard.report(&self.app, ip, rejection, now),
+        }
+        result
+    }
+}
+
+/// Refuse addresses outside the allowlist before any route runs
+async fn allowlist_middleware(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !state.guard.is_allowed(addr.ip()) {
+        let rejection = Rejection::NotAllowed;
+        state
+            .guard
+            .report(&state.app, addr.ip(), &rejection, Instant::now());
+        return rejection.into_response();
+    }
+    next.run(request).await
 }
 
 /// Server handle for shutdown coordination.
@@ -34,6 +84,22 @@ pub struct HttpServerHandle {
     pub url: String,
     pub localhost_only: bool,
     pub token_required: bool,
+    /// Set when serving HTTPS
+    pub tls_fingerprint: Option<String>,
+}
+
+impl HttpServerHandle {
+    pub fn status(&self) -> ServerStatus {
+        ServerStatus {
+            running: true,
+            url: Some(self.url.clone()),
+            token: Some(self.token.clone()),
+            port: Some(self.port),
+            localhost_only: Some(self.localhost_only),
+            tls: Some(self.tls_fingerprint.is_some()),
+            tls_fingerprint: self.tls_fingerprint.clone(),
+        }
+    }
 }
 
 /// Status response for the HTTP server.
@@ -44,6 +110,23 @@ pub struct ServerStatus {
     pub token: Option<String>,
     pub port: Option<u16>,
     pub localhost_only: Option<bool>,
+    pub tls: Option<bool>,
+    /// SHA-256 fingerprint of the served certificate, for verifying it in browsers
+    pub tls_fingerprint: Option<String>,
+}
+
+impl ServerStatus {
+    fn stopped() -> Self {
+        Self {
+            running: false,
+            url: None,
+            token: None,
+            port: None,
+            localhost_only: None,
+            tls: None,
+            tls_fingerprint: None,
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -51,6 +134,15 @@ struct WsAuth {
     token: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct WsParams {
+    token: Option<String>,
+    /// Last event sequence number the client saw before reconnecting
+    resume_from: Option<u64>,
+    /// Event stream that `resume_from` belongs to
+    stream_id: Option<String>,
+}
+
 /// Resolve the dist directory path at runtime.
 /// Checks multiple locations for development and production scenarios.
 fn resolve_dist_path(app: &AppHandle) -> std::path::PathBuf {
@@ -99,11 +191,16 @@ pub async fn start_server(
     token: String,
     localhost_only: bool,
     token_required: bool,
+    tls: Option<TlsMaterial>,
+    allowed_ips: &[String],
 ) -> Result<HttpServerHandle, String> {
+    let allowlist = parse_allowlist(allowed_ips)?;
     let state = AppState {
         app: app.clone(),
         token: token.clone(),
         token_required,
+        guard: Arc::new(AuthGuard::new(allowlist)),
+        sse_clients: Arc::new(Semaphore::new(sse::MAX_SSE_CLIENTS)),
     };
 
     let cors = CorsLayer::new()
@@ -123,7 +220,16 @@ pub async fn start_server(
         .route("/ws", get(ws_handler))
         .route("/api/auth", get(auth_handler))
         .route("/api/init", get(init_handler))
+        .route(
+            "/api/sessions/{id}/events",
+            get(sse::session_events_handler),
+        )
+        .merge(rest::router())
         .fallback_service(serve_dir)
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            allowlist_middleware,
+        ))
         .layer(cors)
         .with_state(state);
 
@@ -147,21 +253,57 @@ pub async fn start_server(
     } else {
         get_local_ip().unwrap_or_else(|| "127.0.0.1".to_string())
     };
-    let url = format!("http://{ip}:{}", local_addr.port());
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    let url = format!("{scheme}://{ip}:{}", local_addr.port());
 
     let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
 
-    // Spawn the server
-    tokio::spawn(async move {
-        log::info!("HTTP server listening on {local_addr} (localhost_only: {localhost_only})");
-        axum::serve(listener, router)
-            .with_graceful_shutdown(async {
+    let tls_fingerprint = match tls {
+        Some(tls) => {
+            let fingerprint = tls.fingerprint.clone();
+            let config = super::tls::rustls_config(tls).await?;
+            let listener = listener
+                .into_std()
+                .map_err(|e| format!("Failed to prepare TLS listener: {e}"))?;
+            let server_handle = axum_server::Handle::new();
+            let shutdown_handle = server_handle.clone();
+
+            tokio::spawn(async move {
                 let _ = shutdown_rx.await;
-                log::info!("HTTP server shutting down");
-            })
-            .await
-            .unwrap_or_else(|e| log::error!("HTTP server error: {e}"));
-    });
+                log::info!("HTTPS server shutting down");
+                shutdown_handle.graceful_shutdown(Some(Duration::from_secs(5)));
+            });
+            tokio::spawn(async move {
+                log::info!(
+                    "HTTPS server listening on {local_addr} (localhost_only: {localhost_only})"
+                );
+                axum_server::from_tcp_rustls(listener, config)
+                    .handle(server_handle)
+                    .serve(router.into_make_service_with_connect_info::<SocketAddr>())
+                    .await
+                    .unwrap_or_else(|e| log::error!("HTTPS server error: {e}"));
+            });
+            Some(fingerprint)
+        }
+        None => {
+            tokio::spawn(async move {
+                log::info!(
+                    "HTTP server listening on {local_addr} (localhost_only: {localhost_only})"
+                );
+                axum::serve(
+                    listener,
+                    router.into_make_service_with_connect_info::<SocketAddr>(),
+                )
+                .with_graceful_shutdown(async {
+                    let _ = shutdown_rx.await;
+                    log::info!("HTTP server shutting down");
+                })
+                .await
+                .unwrap_or_else(|e| log::error!("HTTP server error: {e}"));
+            });
+            None
+        }
+    };
 
     Ok(HttpServerHandle {
         shutdown_tx,
@@ -170,66 +312,88 @@ pub async fn start_server(
         url,
         localhost_only,
         token_required,
+        tls_fingerprint,
     })
 }
 
 /// WebSocket upgrade handler with token auth.
 async fn ws_handler(
     ws: WebSocketUpgrade,
-    Query(params): Query<WsAuth>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Query(params): Query<WsParams>,
     State(state): State<AppState>,
 ) -> Response {
-    // Validate token (skip if token not required)
-    if state.token_required {
-        let provided = params.token.unwrap_or_default();
-        if !auth::validate_token(&provided, &state.token) {
-            return (StatusCode::UNAUTHORIZED, "Invalid token").into_response();
-        }
-    }
+    let grant =
+        match state.authenticate(addr.ip(), params.token.as_deref().unwrap_or_default(), true) {
+            Ok(grant) => grant,
+            Err(rejection) => return rejection.into_response(),
+        };
 
-    // Get broadcast receiver for this client
+    // Get broadcast receiver for this client, with the events it missed
+    let resume = params.resume_from.map(|seq| ResumePoint {
+        stream_id: params.stream_id,
+        seq,
+    });
     let broadcaster = state.app.try_state::<WsBroadcaster>();
-    let event_rx = match broadcaster {
-        Some(b) => b.subscribe(),
+    let (event_rx, catchup) = match broadcaster {
+        Some(b) => b.subscribe(resume.as_ref()),
         None => {
             return (StatusCode::INTERNAL_SERVER_ERROR, "Server not initialized").into_response();
         }
     };
+    if catchup.resync {
+        log::debug!("WS client can't resume from {resume:?}, asking it to resync");
+    }
 
     let app = state.app.clone();
-    ws.on_upgrade(move |socket| handle_ws_connection(socket, app, event_rx))
+    let client = AuditClient {
+        addr,
+        transport: AuditTransport::WebSocket,
+    };
+    ws.on_upgrade(move |socket| handle_ws_connection(socket, app, grant, client, event_rx, catchup))
 }
 
 /// Token validation endpoint. Returns 200 with { ok: true } on success,
 /// or 401 with { ok: false, error: "..." } on failure.
-async fn auth_handler(Query(params): Query<WsAuth>, State(state): State<AppState>) -> Response {
+/// Too many attempts answer 429 with `Retry-After`.
+async fn auth_handler(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Query(params): Query<WsAuth>,
+    State(state): State<AppState>,
+) -> Response {
     // If token not required, always return success
     if !state.token_required {
         return Json(serde_json::json!({ "ok": true, "token_required": false })).into_response();
     }
 
     let provided = params.token.unwrap_or_default();
-    if auth::validate_token(&provided, &state.token) {
-        Json(serde_json::json!({ "ok": true })).into_response()
-    } else {
-        (
-            StatusCode::UNAUTHORIZED,
-            Json(serde_json::json!({ "ok": false, "error": "Invalid token" })),
-        )
-            .into_response()
+    match state.authenticate(addr.ip(), &provided, true) {
+        Ok(grant) => {
+            Json(serde_json::json!({ "ok": true, "scopes": grant.scopes })).into_response()
+        }
+        Err(rejection) => {
+            let body = serde_json::json!({
+                "ok": false,
+                "error": rejection.message(),
+                "reason": rejection.reason(),
+            });
+            with_retry_after(&rejection, (rejection.status(), Json(body)).into_response())
+        }
     }
 }
 
 /// Initial data endpoint. Returns all data needed to render the initial view.
 /// This is used by the web view to preload data before WebSocket connects.
-async fn init_handler(Query(params): Query<WsAuth>, State(state): State<AppState>) -> Response {
-    // Validate token (skip if token not required)
-    if state.token_required {
-        let provided = params.token.unwrap_or_default();
-        if !auth::validate_token(&provided, &state.token) {
-            return (StatusCode::UNAUTHORIZED, "Invalid token").into_response();
-        }
-    }
+async fn init_handler(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Query(params): Query<WsAuth>,
+    State(state): State<AppState>,
+) -> Response {
+    let grant =
+        match state.authenticate(addr.ip(), params.token.as_deref().unwrap_or_default(), true) {
+            Ok(grant) => grant,
+            Err(rejection) => return rejection.into_response(),
+        };
 
     // Fetch base data in parallel
     let (projects_result, preferences_result, ui_state_result) = tokio::join!(
@@ -242,13 +406,15 @@ async fn init_handler(Query(params): Query<WsAuth>, State(state): State<AppState
     let mut response = serde_json::json!({});
 
     // Extract projects and fetch worktrees for each
-    let projects = match projects_result {
+    let mut projects = match projects_result {
         Ok(projects) => projects,
         Err(e) => {
             log::error!("Failed to load projects for /api/init: {e}");
             vec![]
         }
     };
+    // Project-restricted tokens only see their projects
+    projects.retain(|p| p.is_folder || grant.allows_project(&p.id));
 
     // Fetch worktrees for all projects in parallel
     let worktrees_futures: Vec<_> = projects
@@ -389,7 +555,8 @@ async fn init_handler(Query(params): Query<WsAuth>, State(state): State<AppState
 
     match preferences_result {
         Ok(preferences) => {
-            if let Ok(val) = serde_json::to_value(&preferences) {
+            if let Ok(mut val) = serde_json::to_value(&preferences) {
+                auth::redact_preferences(&grant, &mut val);
                 response["preferences"] = val;
             }
         }
@@ -415,7 +582,7 @@ async fn init_handler(Query(params): Query<WsAuth>, State(state): State<AppState
 }
 
 /// Get the local LAN IP address.
-fn get_local_ip() -> Option<String> {
+pub(super) fn get_local_ip() -> Option<String> {
     use std::net::UdpSocket;
     let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
     socket.connect("8.8.8.8:80").ok()?;
@@ -429,28 +596,10 @@ pub async fn get_server_status(app: AppHandle) -> ServerStatus {
         Some(handle_state) => {
             let handle = handle_state.lock().await;
             match handle.as_ref() {
-                Some(h) => ServerStatus {
-                    running: true,
-                    url: Some(h.url.clone()),
-                    token: Some(h.token.clone()),
-                    port: Some(h.port),
-                    localhost_only: Some(h.localhost_only),
-                },
-                None => ServerStatus {
-                    running: false,
-                    url: None,
-                    token: None,
-                    port: None,
-                    localhost_only: None,
-                },
+                Some(h) => h.status(),
+                None => ServerStatus::stopped(),
             }
         }
-        None => ServerStatus {
-            running: false,
-            url: None,
-            token: None,
-            port: None,
-            localhost_only: None,
-        },
+        None => ServerStatus::stopped(),
     }
 }
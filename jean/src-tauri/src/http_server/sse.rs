@@ -0,0 +1,449 @@
+//! Server-Sent Events stream of a session's output
+//!
+//! `GET /api/sessions/{id}/events` streams `chat:chunk`, `chat:tool_use`,
+//! `chat:tool_result` and `chat:done` for one session. Events are read from
+//! the runs' NDJSON logs rather than the live broadcast, so every event has a
+//! stable id (`{run_id}:{line}.{n}` for the n-th event of a log line, or
+//! `{run_id}:end` for `chat:done`) and a client reconnecting with
+//! `Last-Event-ID` resumes exactly where it stopped.
+//!
+//! Without `Last-Event-ID` the stream starts at the beginning of the run in
+//! progress, or waits for the next run if the session is idle.
+//!
+//! Logs are polled on the blocking thread pool, and at most
+//! [`MAX_SSE_CLIENTS`] streams are open at once.
+
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{ConnectInfo, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+};
+use futures_util::stream::{self, Stream};
+use serde_json::{json, Value};
+use tauri::AppHandle;
+use tokio::sync::OwnedSemaphorePermit;
+
+use super::rest::provided_token;
+use super::server::AppState;
+use crate::chat::run_log::get_run_log_path;
+use crate::chat::storage::load_metadata;
+use crate::chat::tail::NdjsonTailer;
+use crate::chat::types::{RunStatus, SessionMetadata};
+
+/// Maximum number of SSE streams open at once, across all clients
+pub const MAX_SSE_CLIENTS: usize = 32;
+
+/// How often run logs are polled for new lines
+const SSE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often session metadata is reloaded to notice run status changes while
+/// the log is quiet
+const METADATA_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Convert one NDJSON line of Claude CLI output into session events
+///
+/// Payloads match the corresponding Tauri/WebSocket events.
+pub fn line_to_events(
+    session_id: &str,
+    worktree_id: &str,
+    line: &str,
+) -> Vec<(&'static str, Value)> {
+    let Ok(msg) = serde_json::from_str::<Value>(line) else {
+        return vec![];
+    };
+    let parent_tool_use_id = msg.get("parent_tool_use_id").and_then(Value::as_str);
+    let blocks = msg
+        .get("message")
+        .and_then(|m| m.get("content"))
+        .and_then(Value::as_array);
+    let Some(blocks) = blocks else {
+        return vec![];
+    };
+
+    let mut events = Vec::new();
+    match msg.get("type").and_then(Value::as_str) {
+        Some("assistant") => {
+            for block in blocks {
+                match block.get("type").and_then(Value::as_str) {
+                    Some("text") => {
+                        let text = block.get("text").and_then(Value::as_str).unwrap_or("");
+                        // CLI placeholder emitted before extended thinking
+                        if text.is_empty() || text == "(no content)" {
+                            continue;
+                        }
+                        events.push((
+                            "chat:chunk",
+                            json!({
+                                "session_id": session_id,
+                                "worktree_id": worktree_id,
+                                "content": text,
+                            }),
+                        ));
+                    }
+                    Some("tool_use") => events.push((
+                        "chat:tool_use",
+                        json!({
+                            "session_id": session_id,
+                            "worktree_id": worktree_id,
+                            "id": block.get("id").and_then(Value::as_str).unwrap_or(""),
+                            "name": block.get("name").and_then(Value::as_str).unwrap_or(""),
+                            "input": block.get("input").cloned().unwrap_or(Value::Null),
+                            "parent_tool_use_id": parent_tool_use_id,
+                        }),
+                    )),
+                    _ => {}
+                }
+            }
+        }
+        Some("user") => {
+            for block in blocks {
+                if block.get("type").and_then(Value::as_str) != Some("tool_result") {
+                    continue;
+                }
+                events.push((
+                    "chat:tool_result",
+                    json!({
+                        "session_id": session_id,
+                        "worktree_id": worktree_id,
+                        "tool_use_id": block.get("tool_use_id").and_then(Value::as_str).unwrap_or(""),
+                        "output": block.get("content").and_then(Value::as_str).unwrap_or(""),
+                    }),
+                ));
+            }
+        }
+        _ => {}
+    }
+    events
+}
+
+/// Parse a `Last-Event-ID` into (run_id, next (line, event) to send)
+///
+/// `None` for the position means the run was streamed to the end. A bare
+/// `{run_id}:{line}` counts the whole line as delivered.
+pub fn parse_event_id(id: &str) -> Option<(&str, Option<(usize, usize)>)> {
+    let (run_id, position) = id.rsplit_once(':')?;
+    if run_id.is_empty() {
+        return None;
+    }
+    if position == "end" {
+        return Some((run_id, None));
+    }
+    let next = match position.split_once('.') {
+        Some((line, n)) => (line.parse().ok()?, n.parse::<usize>().ok()? + 1),
+        None => (position.parse::<usize>().ok()? + 1, 0),
+    };
+    Some((run_id, Some(next)))
+}
+
+/// Run currently being streamed
+struct RunCursor {
+    run_id: String,
+    tailer: Option<NdjsonTailer>,
+    /// Index of the next line read from the log
+    line: usize,
+    /// Events before this (line, event) position were already delivered (resume)
+    skip: (usize, usize),
+}
+
+struct SessionStream {
+    app: AppHandle,
+    session_id: String,
+    worktree_id: String,
+    cursor: Option<RunCursor>,
+    /// Last run streamed to the end; the next run after it is picked up next
+    last_finished: Option<String>,
+    pending: VecDeque<Event>,
+    closed: bool,
+    metadata_checked_at: Option<Instant>,
+}
+
+impl SessionStream {
+    fn new(app: AppHandle, metadata: &SessionMetadata, last_event_id: Option<&str>) -> Self {
+        let mut stream = Self {
+            app,
+            session_id: metadata.id.clone(),
+            worktree_id: metadata.worktree_id.clone(),
+            cursor: None,
+            last_finished: None,
+            pending: VecDeque::new(),
+            closed: false,
+            metadata_checked_at: None,
+        };
+
+        let resume = last_event_id
+            .and_then(parse_event_id)
+            .filter(|(run_id, _)| metadata.runs.iter().any(|r| r.run_id == *run_id));
+        match resume {
+            Some((run_id, Some(skip))) => stream.cursor = Some(RunCursor::new(run_id, skip)),
+            Some((run_id, None)) => stream.last_finished = Some(run_id.to_string()),
+            None => match metadata.runs.last() {
+                Some(run) if is_active(&run.status) => {
+                    stream.cursor = Some(RunCursor::new(&run.run_id, (0, 0)))
+                }
+                Some(run) => stream.last_finished = Some(run.run_id.clone()),
+                None => {}
+            },
+        }
+        stream
+    }
+
+    /// Reload the session metadata, at most once per [`METADATA_POLL_INTERVAL`]
+    ///
+    /// Closes the stream if the session is gone.
+    fn reload_metadata(&mut self) -> Option<SessionMetadata> {
+        if self
+            .metadata_checked_at
+            .is_some_and(|at| at.elapsed() < METADATA_POLL_INTERVAL)
+        {
+            return None;
+        }
+        self.metadata_checked_at = Some(Instant::now());
+        match load_metadata(&self.app, &self.session_id) {
+            Ok(Some(metadata)) => Some(metadata),
+            Ok(None) | Err(_) => {
+                self.closed = true;
+                None
+            }
+        }
+    }
+
+    /// Queue events for new output and move on to the next run when one ends
+    ///
+    /// The metadata is only consulted to find the next run or, while the log
+    /// is quiet, to notice a run that ended without a `result` line.
+    fn poll(&mut self) {
+        if self.cursor.is_none() {
+            let Some(metadata) = self.reload_metadata() else {
+                return;
+            };
+            let next_index = match &self.last_finished {
+                Some(run_id) => metadata
+                    .runs
+                    .iter()
+                    .position(|r| &r.run_id == run_id)
+                    .map(|i| i + 1),
+                None => Some(0),
+            };
+            if let Some(run) = next_index.and_then(|i| metadata.runs.get(i)) {
+                self.cursor = Some(RunCursor::new(&run.run_id, (0, 0)));
+            }
+        }
+        let Some(cursor) = self.cursor.as_mut() else {
+            return;
+        };
+
+        if cursor.tailer.is_none() {
+            if let Ok(path) = get_run_log_path(&self.app, &self.session_id, &cursor.run_id) {
+                cursor.tailer = NdjsonTailer::new_from_start(&path).ok();
+            }
+        }
+
+        let mut saw_output = false;
+        let mut saw_result = false;
+        if let Some(tailer) = cursor.tailer.as_mut() {
+            for line in tailer.poll().unwrap_or_default() {
+                let index = cursor.line;
+                cursor.line += 1;
+                saw_output = true;
+                saw_result |= is_result_line(&line);
+                let events = line_to_events(&self.session_id, &self.worktree_id, &line);
+                for (n, (name, payload)) in events.into_iter().enumerate() {
+                    if (index, n) < cursor.skip {
+                        continue;
+                    }
+                    self.pending.push_back(
+                        Event::default()
+                            .id(format!("{}:{index}.{n}", cursor.run_id))
+                            .event(name)
+                            .data(payload.to_string()),
+                    );
+                }
+            }
+        }
+
+        let run_id = cursor.run_id.clone();
+        let finished = saw_result
+            || (!saw_output
+                && self.reload_metadata().is_some_and(|metadata| {
+                    !metadata
+                        .runs
+                        .iter()
+                        .find(|r| r.run_id == run_id)
+                        .is_some_and(|r| is_active(&r.status))
+                }));
+        if finished {
+            let done = json!({ "session_id": self.session_id, "worktree_id": self.worktree_id });
+            self.pending.push_back(
+                Event::default()
+                    .id(format!("{run_id}:end"))
+                    .event("chat:done")
+                    .data(done.to_string()),
+            );
+            self.last_finished = Some(run_id);
+            self.cursor = None;
+        }
+    }
+}
+
+impl RunCursor {
+    fn new(run_id: &str, skip: (usize, usize)) -> Self {
+        Self {
+            run_id: run_id.to_string(),
+            tailer: None,
+            line: 0,
+            skip,
+        }
+    }
+}
+
+/// The CLI's final `result` message ends a run
+fn is_result_line(line: &str) -> bool {
+    serde_json::from_str::<Value>(line)
+        .ok()
+        .is_some_and(|msg| msg.get("type").and_then(Value::as_str) == Some("result"))
+}
+
+fn is_active(status: &RunStatus) -> bool {
+    matches!(status, RunStatus::Running | RunStatus::Resumable)
+}
+
+/// Events of a session; `permit` is held until the client disconnects
+fn session_events(
+    state: SessionStream,
+    permit: OwnedSemaphorePermit,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    stream::unfold((state, permit), |(mut state, permit)| async move {
+        loop {
+            if let Some(event) = state.pending.pop_front() {
+                return Some((Ok(event), (state, permit)));
+            }
+            if state.closed {
+                return None;
+            }
+            // Reading logs and metadata is blocking file IO
+            state = tokio::task::spawn_blocking(move || {
+                state.poll();
+                state
+            })
+            .await
+            .ok()?;
+            if state.pending.is_empty() {
+                tokio::time::sleep(SSE_POLL_INTERVAL).await;
+            }
+        }
+    })
+}
+
+/// `GET /api/sessions/{id}/events`
+pub async fn session_events_handler(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Path(session_id): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    let grant = match state.authenticate(addr.ip(), provided_token(&headers, &query), true) {
+        Ok(grant) => grant,
+        Err(rejection) => return rejection.into_response(),
+    };
+
+    let Ok(permit) = state.sse_clients.clone().try_acquire_owned() else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("Too many event streams (max {MAX_SSE_CLIENTS})"),
+        )
+            .into_response();
+    };
+
+    let metadata = match load_metadata(&state.app, &session_id) {
+        Ok(Some(metadata)) => metadata,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                format!("Session not found: {session_id}"),
+            )
+                .into_response()
+        }
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    };
+
+    let target = json!({ "sessionId": session_id, "worktreeId": metadata.worktree_id });
+    if let Err(e) = grant.authorize(&state.app, "get_session", &target) {
+        return (StatusCode::FORBIDDEN, e).into_response();
+    }
+
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .or_else(|| query.get("lastEventId").map(String::as_str));
+    log::trace!("SSE client following session {session_id} (Last-Event-ID: {last_event_id:?})");
+
+    let stream = SessionStream::new(state.app.clone(), &metadata, last_event_id);
+    Sse::new(session_events(stream, permit))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_event_id() {
+        assert_eq!(parse_event_id("run-1:4.0"), Some(("run-1", Some((4, 1)))));
+        assert_eq!(parse_event_id("run-1:4.2"), Some(("run-1", Some((4, 3)))));
+        assert_eq!(parse_event_id("run-1:4"), Some(("run-1", Some((5, 0)))));
+        assert_eq!(parse_event_id("run-1:end"), Some(("run-1", None)));
+        assert_eq!(parse_event_id("run-1:x"), None);
+        assert_eq!(parse_event_id("run-1:4.x"), None);
+        assert_eq!(parse_event_id(":3"), None);
+        assert_eq!(parse_event_id("garbage"), None);
+    }
+
+    #[test]
+    fn test_line_to_events_assistant() {
+        let line = r#"{"type":"assistant","parent_tool_use_id":null,"message":{"content":[
+            {"type":"text","text":"Hello"},
+            {"type":"text","text":"(no content)"},
+            {"type":"tool_use","id":"t1","name":"Bash","input":{"command":"ls"}}
+        ]}}"#
+            .replace('\n', "");
+        let events = line_to_events("s1", "w1", &line);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].0, "chat:chunk");
+        assert_eq!(events[0].1["content"], "Hello");
+        assert_eq!(events[1].0, "chat:tool_use");
+        assert_eq!(events[1].1["name"], "Bash");
+        assert_eq!(events[1].1["input"]["command"], "ls");
+        assert_eq!(events[1].1["session_id"], "s1");
+    }
+
+    #[test]
+    fn test_line_to_events_tool_result() {
+        let line = r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":"ok"}]}}"#;
+        let events = line_to_events("s1", "w1", line);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, "chat:tool_result");
+        assert_eq!(events[0].1["tool_use_id"], "t1");
+        assert_eq!(events[0].1["output"], "ok");
+    }
+
+    #[test]
+    fn test_line_to_events_ignores_other_lines() {
+        assert!(line_to_events("s1", "w1", r#"{"_run_meta":true}"#).is_empty());
+        assert!(line_to_events("s1", "w1", r#"{"type":"result","result":"x"}"#).is_empty());
+        assert!(line_to_events("s1", "w1", "not json").is_empty());
+        assert!(is_result_line(r#"{"type":"result","result":"x"}"#));
+        assert!(!is_result_line(
+            r#"{"type":"assistant","message":{"content":[]}}"#
+        ));
+    }
+}
@@ -0,0 +1,453 @@
+//! Per-client event subscriptions for WebSocket connections
+//!
+//! A client that never subscribes receives every broadcast event. Once it has
+//! subscriptions, an event is forwarded only if it matches at least one of
+//! them. Within a filter every non-empty field must match: the event name,
+//! and the `session_id` / `worktree_id` / `project_id` / `terminal_id` found
+//! in the payload (the project is looked up from the worktree when the payload
+//! has none).
+//!
+//! Terminal output is opt-in: `terminal:output` is only forwarded through
+//! filters that name the terminal in `terminal_ids`. Such terminal filters
+//! don't restrict other events, so a client can follow a terminal without
+//! also subscribing to everything else. Output is sent as text unless a
+//! matching filter sets `binary`, in which case `data` holds the raw bytes in
+//! base64 (`encoding: "base64"`) and `offset` counts those bytes.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::Value;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use super::WsEvent;
+
+/// What a subscription lets through (empty fields match anything)
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct EventFilter {
+    /// Event names; `chat:*` matches every event starting with `chat:`
+    #[serde(default)]
+    pub events: Vec<String>,
+    #[serde(default, alias = "sessionIds")]
+    pub session_ids: Vec<String>,
+    #[serde(default, alias = "worktreeIds")]
+    pub worktree_ids: Vec<String>,
+    #[serde(default, alias = "projectIds")]
+    pub project_ids: Vec<String>,
+    #[serde(default, alias = "terminalIds")]
+    pub terminal_ids: Vec<String>,
+    /// Receive terminal output as raw bytes instead of text
+    #[serde(default)]
+    pub binary: bool,
+}
+
+/// Events only forwarded to clients subscribed to their terminal
+const TERMINAL_OUTPUT_EVENT: &str = "terminal:output";
+
+/// Ids an event refers to, read from its payload
+#[derive(Debug, Default, PartialEq)]
+pub struct EventTarget<'a> {
+    pub session_id: Option<&'a str>,
+    pub worktree_id: Option<&'a str>,
+    pub project_id: Option<&'a str>,
+    pub terminal_id: Option<&'a str>,
+}
+
+impl<'a> EventTarget<'a> {
+    pub fn of(payload: &'a Value) -> Self {
+        let id = |snake: &str, camel: &str| {
+            payload
+                .get(snake)
+                .or_else(|| payload.get(camel))
+                .and_then(Value::as_str)
+        };
+        Self {
+            session_id: id("session_id", "sessionId"),
+            worktree_id: id("worktree_id", "worktreeId"),
+            project_id: id("project_id", "projectId"),
+            terminal_id: id("terminal_id", "terminalId"),
+        }
+    }
+
+    /// Project of the event, from the payload or via its worktree
+    pub fn project(&self, project_of: &mut impl FnMut(&str) -> Option<String>) -> Option<String> {
+        match (self.project_id, self.worktree_id) {
+            (Some(project_id), _) => Some(project_id.to_string()),
+            (None, Some(worktree_id)) => project_of(worktree_id),
+            (None, None) => None,
+        }
+    }
+}
+
+fn event_name_matches(pattern: &str, event: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => event.starts_with(prefix),
+        None => pattern == event,
+    }
+}
+
+fn id_matches(allowed: &[String], id: Option<&str>) -> bool {
+    allowed.is_empty() || id.is_some_and(|id| allowed.iter().any(|a| a == id))
+}
+
+impl EventFilter {
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+            && self.session_ids.is_empty()
+            && self.worktree_ids.is_empty()
+            && self.project_ids.is_empty()
+            && self.terminal_ids.is_empty()
+    }
+
+    /// Whether this filter follows specific terminals
+    pub fn is_terminal_filter(&self) -> bool {
+        !self.terminal_ids.is_empty()
+    }
+
+    /// `project_of` maps a worktree id to its project id
+    pub fn matches(
+        &self,
+        event: &str,
+        target: &EventTarget,
+        project_of: &mut impl FnMut(&str) -> Option<String>,
+    ) -> bool {
+        if !self.events.is_empty() && !self.events.iter().any(|p| event_name_matches(p, event)) {
+            return false;
+        }
+        if !id_matches(&self.session_ids, target.session_id)
+            || !id_matches(&self.worktree_ids, target.worktree_id)
+            || !id_matches(&self.terminal_ids, target.terminal_id)
+        {
+            return false;
+        }
+        if self.project_ids.is_empty() {
+            return true;
+        }
+        id_matches(&self.project_ids, target.project(project_of).as_deref())
+    }
+}
+
+/// Subscriptions of one WebSocket client
+#[derive(Debug, Default)]
+pub struct Subscriptions {
+    filters: Vec<(String, EventFilter)>,
+}
+
+impl Subscriptions {
+    /// Add a filter, returning its subscription id
+    pub fn add(&mut self, filter: EventFilter) -> String {
+        let id = Uuid::new_v4().to_string();
+        self.filters.push((id.clone(), filter));
+        id
+    }
+
+    /// Remove one subscription; false if the id is unknown
+    pub fn remove(&mut self, id: &str) -> bool {
+        let before = self.filters.len();
+        self.filters.retain(|(sid, _)| sid != id);
+        self.filters.len() != before
+    }
+
+    pub fn clear(&mut self) {
+        self.filters.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.filters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    pub fn matches(
+        &self,
+        event: &str,
+        target: &EventTarget,
+        project_of: &mut impl FnMut(&str) -> Option<String>,
+    ) -> bool {
+        if event == TERMINAL_OUTPUT_EVENT {
+            return self
+                .filters
+                .iter()
+                .any(|(_, f)| f.is_terminal_filter() && f.matches(event, target, project_of));
+        }
+        let mut general = self
+            .filters
+            .iter()
+            .filter(|(_, f)| !f.is_terminal_filter())
+            .peekable();
+        general.peek().is_none() || general.any(|(_, f)| f.matches(event, target, project_of))
+    }
+
+    /// Whether terminal output should be sent as raw bytes
+    pub fn wants_binary(
+        &self,
+        event: &str,
+        target: &EventTarget,
+        project_of: &mut impl FnMut(&str) -> Option<String>,
+    ) -> bool {
+        event == TERMINAL_OUTPUT_EVENT
+            && self.filters.iter().any(|(_, f)| {
+                f.binary && f.is_terminal_filter() && f.matches(event, target, project_of)
+            })
+    }
+}
+
+/// Worktree → project lookups for the forwarder, cached per connection
+pub struct ProjectResolver {
+    app: AppHandle,
+    cache: HashMap<String, Option<String>>,
+}
+
+impl ProjectResolver {
+    pub fn new(app: AppHandle) -> Self {
+        Self {
+            app,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Project of a worktree; projects data is reloaded for unseen worktrees
+    pub fn project_of(&mut self, worktree_id: &str) -> Option<String> {
+        if let Some(project_id) = self.cache.get(worktree_id) {
+            return project_id.clone();
+        }
+        if let Ok(data) = crate::projects::storage::load_projects_data(&self.app) {
+            for worktree in &data.worktrees {
+                self.cache
+                    .insert(worktree.id.clone(), Some(worktree.project_id.clone()));
+            }
+        }
+        self.cache
+            .entry(worktree_id.to_string())
+            .or_insert(None)
+            .clone()
+    }
+}
+
+/// Whether a broadcast event should be forwarded to a client
+pub fn should_forward(
+    subscriptions: &Subscriptions,
+    event: &WsEvent,
+    project_of: &mut impl FnMut(&str) -> Option<String>,
+) -> bool {
+    let target = EventTarget::of(&event.payload);
+    subscriptions.matches(&event.event, &target, project_of)
+}
+
+/// Payload of a `terminal:output` event as sent to one client
+///
+/// Broadcasts carry the decoded text along with the raw bytes (`raw`, base64)
+/// and their stream offset (`raw_offset`); a client gets one or the other.
+pub fn terminal_output_payload(mut payload: Value, binary: bool) -> Value {
+    let Some(fields) = payload.as_object_mut() else {
+        return payload;
+    };
+    let raw = fields.remove("raw");
+    let raw_offset = fields.remove("raw_offset");
+    if binary {
+        if let (Some(raw), Some(raw_offset)) = (raw, raw_offset) {
+            fields.insert("data".to_string(), raw);
+            fields.insert("offset".to_string(), raw_offset);
+            fields.insert("encoding".to_string(), "base64".into());
+        }
+    }
+    payload
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn projects(worktree_id: &str) -> Option<String> {
+        match worktree_id {
+            "w1" | "w2" => Some("p1".to_string()),
+            "w3" => Some("p2".to_string()),
+            _ => None,
+        }
+    }
+
+    fn forwarded(subs: &Subscriptions, event: &str, payload: Value) -> bool {
+        let event = WsEvent {
+            seq: 1,
+            event: event.to_string(),
+            payload,
+        };
+        should_forward(subs, &event, &mut projects)
+    }
+
+    #[test]
+    fn test_no_subscriptions_forwards_everything() {
+        let subs = Subscriptions::default();
+        assert!(forwarded(
+            &subs,
+            "chat:chunk",
+            json!({ "session_id": "s1" })
+        ));
+        assert!(forwarded(&subs, "cache:invalidate", json!({})));
+    }
+
+    #[test]
+    fn test_filter_by_session_and_event() {
+        let mut subs = Subscriptions::default();
+        subs.add(EventFilter {
+            events: vec!["chat:*".to_string()],
+            session_ids: vec!["s1".to_string()],
+            ..Default::default()
+        });
+        assert!(forwarded(
+            &subs,
+            "chat:chunk",
+            json!({ "session_id": "s1" })
+        ));
+        assert!(forwarded(&subs, "chat:done", json!({ "sessionId": "s1" })));
+        assert!(!forwarded(
+            &subs,
+            "chat:chunk",
+            json!({ "session_id": "s2" })
+        ));
+        assert!(!forwarded(
+            &subs,
+            "git:status",
+            json!({ "session_id": "s1" })
+        ));
+        // Events without a session id don't match a session filter
+        assert!(!forwarded(&subs, "chat:queue_updated", json!({})));
+    }
+
+    #[test]
+    fn test_filter_by_project_resolves_worktree() {
+        let mut subs = Subscriptions::default();
+        subs.add(EventFilter {
+            project_ids: vec!["p1".to_string()],
+            ..Default::default()
+        });
+        assert!(forwarded(
+            &subs,
+            "chat:chunk",
+            json!({ "worktree_id": "w2" })
+        ));
+        assert!(!forwarded(
+            &subs,
+            "chat:chunk",
+            json!({ "worktree_id": "w3" })
+        ));
+        assert!(forwarded(&subs, "x", json!({ "project_id": "p1" })));
+        assert!(!forwarded(&subs, "x", json!({ "worktree_id": "unknown" })));
+    }
+
+    #[test]
+    fn test_subscriptions_are_ored_and_removable() {
+        let mut subs = Subscriptions::default();
+        let chat = subs.add(EventFilter {
+            events: vec!["chat:chunk".to_string()],
+            ..Default::default()
+        });
+        subs.add(EventFilter {
+            events: vec!["cache:invalidate".to_string()],
+            ..Default::default()
+        });
+        assert!(forwarded(&subs, "chat:chunk", json!({})));
+        assert!(forwarded(&subs, "cache:invalidate", json!({})));
+        assert!(!forwarded(&subs, "chat:done", json!({})));
+
+        assert!(subs.remove(&chat));
+        assert!(!subs.remove(&chat));
+        assert!(!forwarded(&subs, "chat:chunk", json!({})));
+        assert_eq!(subs.len(), 1);
+    }
+
+    #[test]
+    fn test_terminal_output_is_opt_in() {
+        let mut subs = Subscriptions::default();
+        let output = json!({ "terminal_id": "t1" });
+        assert!(!forwarded(&subs, "terminal:output", output.clone()));
+        assert!(forwarded(&subs, "terminal:started", output.clone()));
+
+        subs.add(EventFilter {
+            terminal_ids: vec!["t1".to_string()],
+            ..Default::default()
+        });
+        assert!(forwarded(&subs, "terminal:output", output));
+        assert!(!forwarded(
+            &subs,
+            "terminal:output",
+            json!({ "terminal_id": "t2" })
+        ));
+        // Following a terminal doesn't filter out other events
+        assert!(forwarded(
+            &subs,
+            "chat:chunk",
+            json!({ "session_id": "s1" })
+        ));
+
+        subs.add(EventFilter {
+            session_ids: vec!["s2".to_string()],
+            ..Default::default()
+        });
+        assert!(!forwarded(
+            &subs,
+            "chat:chunk",
+            json!({ "session_id": "s1" })
+        ));
+        assert!(forwarded(
+            &subs,
+            "terminal:output",
+            json!({ "terminal_id": "t1" })
+        ));
+    }
+
+    #[test]
+    fn test_binary_terminal_output() {
+        let mut subs = Subscriptions::default();
+        subs.add(EventFilter {
+            terminal_ids: vec!["t1".to_string()],
+            binary: true,
+            ..Default::default()
+        });
+        subs.add(EventFilter {
+            terminal_ids: vec!["t2".to_string()],
+            ..Default::default()
+        });
+        let target = |id| EventTarget {
+            terminal_id: Some(id),
+            ..Default::default()
+        };
+        assert!(subs.wants_binary("terminal:output", &target("t1"), &mut projects));
+        assert!(!subs.wants_binary("terminal:output", &target("t2"), &mut projects));
+        assert!(!subs.wants_binary("terminal:started", &target("t1"), &mut projects));
+
+        let payload = json!({
+            "terminal_id": "t1",
+            "data": "é",
+            "offset": 10,
+            "raw": "w6k=",
+            "raw_offset": 9,
+        });
+        assert_eq!(
+            terminal_output_payload(payload.clone(), true),
+            json!({
+                "terminal_id": "t1",
+                "data": "w6k=",
+                "offset": 9,
+                "encoding": "base64",
+            })
+        );
+        assert_eq!(
+            terminal_output_payload(payload, false),
+            json!({ "terminal_id": "t1", "data": "é", "offset": 10 })
+        );
+    }
+
+    #[test]
+    fn test_filter_deserializes_camel_case() {
+        let filter: EventFilter =
+            serde_json::from_value(json!({ "events": ["chat:*"], "sessionIds": ["s1"] })).unwrap();
+        assert_eq!(filter.session_ids, vec!["s1".to_string()]);
+        assert!(!filter.is_empty());
+        assert!(EventFilter::default().is_empty());
+    }
+}
@@ -0,0 +1,329 @@
+//! Named API tokens for the HTTP server
+//!
+//! Besides the shared token in preferences (full access), clients can be given
+//! named tokens with a set of scopes, an optional project restriction and an
+//! optional expiry. Tokens are stored hashed in `api_tokens.json` in the app
+//! data directory; the secret is returned only once, when the token is created.
+
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+use super::auth::{generate_token, hash_token, validate_token, Grant, TokenScope};
+
+/// Prefix of API token secrets, to tell them apart from the shared token
+const API_TOKEN_PREFIX: &str = "jean_";
+
+/// `last_used_at` is only rewritten when older than this (seconds)
+const LAST_USED_RESOLUTION_SECS: u64 = 60;
+
+/// Serializes reads and writes of the tokens file
+static TOKENS_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// A named API token as stored on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    /// SHA-256 of the secret (see `auth::hash_token`)
+    pub token_hash: String,
+    /// First characters of the secret, for recognising it in the UI
+    pub prefix: String,
+    pub scopes: Vec<TokenScope>,
+    /// Projects the token may access (empty = all)
+    #[serde(default)]
+    pub project_ids: Vec<String>,
+    pub created_at: u64,
+    /// Unix timestamp after which the token stops working
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    #[serde(default)]
+    pub last_used_at: Option<u64>,
+}
+
+/// An API token as shown to clients (without its hash)
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiTokenInfo {
+    pub id: String,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<TokenScope>,
+    pub project_ids: Vec<String>,
+    pub created_at: u64,
+    pub expires_at: Option<u64>,
+    pub last_used_at: Option<u64>,
+    pub expired: bool,
+}
+
+/// Result of `create_api_token`; `secret` is not retrievable later
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedApiToken {
+    pub token: ApiTokenInfo,
+    pub secret: String,
+}
+
+impl ApiToken {
+    fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+
+    fn grant(&self) -> Grant {
+        Grant {
+            token_id: Some(self.id.clone()),
+            token_name: Some(self.name.clone()),
+            scopes: self.scopes.clone(),
+            project_ids: self.project_ids.clone(),
+        }
+    }
+
+    fn info(&self) -> ApiTokenInfo {
+        ApiTokenInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            prefix: self.prefix.clone(),
+            scopes: self.scopes.clone(),
+            project_ids: self.project_ids.clone(),
+            created_at: self.created_at,
+            expires_at: self.expires_at,
+            last_used_at: self.last_used_at,
+            expired: self.is_expired(now()),
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+fn get_tokens_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    std::fs::create_dir_all(&app_data_dir)
+        .map_err(|e| format!("Failed to create app data directory: {e}"))?;
+    Ok(app_data_dir.join("api_tokens.json"))
+}
+
+fn load_tokens_internal(app: &AppHandle) -> Result<Vec<ApiToken>, String> {
+    let path = get_tokens_path(app)?;
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read API tokens file: {e}"))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Failed to parse API tokens file: {e}"))
+}
+
+fn save_tokens_internal(app: &AppHandle, tokens: &[ApiToken]) -> Result<(), String> {
+    let path = get_tokens_path(app)?;
+    let json = serde_json::to_string_pretty(tokens)
+        .map_err(|e| format!("Failed to serialize API tokens: {e}"))?;
+
+    // Write to a temporary file first, then rename (atomic operation)
+    let temp_path = path.with_extension("tmp");
+    std::fs::write(&temp_path, json)
+        .map_err(|e| format!("Failed to write API tokens file: {e}"))?;
+    std::fs::rename(&temp_path, &path)
+        .map_err(|e| format!("Failed to finalize API tokens file: {e}"))
+}
+
+fn load_api_tokens(app: &AppHandle) -> Result<Vec<ApiToken>, String> {
+    let _lock = TOKENS_LOCK.lock().unwrap();
+    load_tokens_internal(app)
+}
+
+fn with_api_tokens_mut<F, T>(app: &AppHandle, f: F) -> Result<T, String>
+where
+    F: FnOnce(&mut Vec<ApiToken>) -> Result<T, String>,
+{
+    let _lock = TOKENS_LOCK.lock().unwrap();
+    let mut tokens = load_tokens_internal(app)?;
+    let result = f(&mut tokens)?;
+    save_tokens_internal(app, &tokens)?;
+    Ok(result)
+}
+
+// ============================================================================
+// Authentication
+// ============================================================================
+
+/// Grant for the unexpired API token whose secret is `secret`, if any
+pub fn authenticate_api_token(app: &AppHandle, secret: &str) -> Option<Grant> {
+    if !secret.starts_with(API_TOKEN_PREFIX) {
+        return None;
+    }
+    let hash = hash_token(secret);
+    let now = now();
+    let token = load_api_tokens(app)
+        .ok()?
+        .into_iter()
+        .find(|t| validate_token(&hash, &t.token_hash))?;
+    if token.is_expired(now) {
+        log::debug!("Rejected expired API token '{}'", token.name);
+        return None;
+    }
+
+    let stale = token
+        .last_used_at
+        .is_none_or(|at| now.saturating_sub(at) >= LAST_USED_RESOLUTION_SECS);
+    if stale {
+        let result = with_api_tokens_mut(app, |tokens| {
+            if let Some(t) = tokens.iter_mut().find(|t| t.id == token.id) {
+                t.last_used_at = Some(now);
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            log::warn!("Failed to record API token use: {e}");
+        }
+    }
+
+    Some(token.grant())
+}
+
+/// Error unless the API token still exists and hasn't expired
+pub fn check_token_active(app: &AppHandle, token_id: &str) -> Result<(), String> {
+    let tokens = load_api_tokens(app)?;
+    match tokens.iter().find(|t| t.id == token_id) {
+        Some(token) if !token.is_expired(now()) => Ok(()),
+        Some(_) => Err("Forbidden: API token has expired".to_string()),
+        None => Err("Forbidden: API token has been revoked".to_string()),
+    }
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Create an API token. The returned secret is shown once and never stored.
+#[tauri::command]
+pub async fn create_api_token(
+    app: AppHandle,
+    name: String,
+    scopes: Vec<TokenScope>,
+    project_ids: Option<Vec<String>>,
+    expires_at: Option<u64>,
+) -> Result<CreatedApiToken, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Token name cannot be empty".to_string());
+    }
+    if scopes.is_empty() {
+        return Err("Token must have at least one scope".to_string());
+    }
+    if expires_at.is_some_and(|at| at <= now()) {
+        return Err("Token expiry must be in the future".to_string());
+    }
+    let project_ids = project_ids.unwrap_or_default();
+    if !project_ids.is_empty() {
+        let data = crate::projects::storage::load_projects_data(&app)?;
+        if let Some(missing) = project_ids
+            .iter()
+            .find(|id| data.find_project(id).is_none())
+        {
+            return Err(format!("Project not found: {missing}"));
+        }
+    }
+
+    log::trace!("Creating API token '{name}' with scopes {scopes:?}");
+    let secret = format!("{API_TOKEN_PREFIX}{}", generate_token());
+    let token = ApiToken {
+        id: Uuid::new_v4().to_string(),
+        name,
+        token_hash: hash_token(&secret),
+        prefix: secret.chars().take(API_TOKEN_PREFIX.len() + 4).collect(),
+        scopes,
+        project_ids,
+        created_at: now(),
+        expires_at,
+        last_used_at: None,
+    };
+
+    with_api_tokens_mut(&app, |tokens| {
+        if tokens.iter().any(|t| t.name == token.name) {
+            return Err(format!("A token named '{}' already exists", token.name));
+        }
+        tokens.push(token.clone());
+        Ok(())
+    })?;
+
+    Ok(CreatedApiToken {
+        token: token.info(),
+        secret,
+    })
+}
+
+/// List API tokens (without secrets)
+#[tauri::command]
+pub async fn list_api_tokens(app: AppHandle) -> Result<Vec<ApiTokenInfo>, String> {
+    Ok(load_api_tokens(&app)?.iter().map(ApiToken::info).collect())
+}
+
+/// Revoke an API token; connections using it lose access on their next command
+#[tauri::command]
+pub async fn revoke_api_token(app: AppHandle, token_id: String) -> Result<(), String> {
+    log::trace!("Revoking API token {token_id}");
+    with_api_tokens_mut(&app, |tokens| {
+        let before = tokens.len();
+        tokens.retain(|t| t.id != token_id);
+        if tokens.len() == before {
+            return Err(format!("API token not found: {token_id}"));
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(expires_at: Option<u64>) -> ApiToken {
+        ApiToken {
+            id: "t1".to_string(),
+            name: "CI".to_string(),
+            token_hash: hash_token("jean_abc"),
+            prefix: "jean_abc".to_string(),
+            scopes: vec![TokenScope::ReadOnly],
+            project_ids: vec![],
+            created_at: 100,
+            expires_at,
+            last_used_at: None,
+        }
+    }
+
+    #[test]
+    fn test_expiry() {
+        assert!(!token(None).is_expired(1_000));
+        assert!(!token(Some(2_000)).is_expired(1_000));
+        assert!(token(Some(1_000)).is_expired(1_000));
+    }
+
+    #[test]
+    fn test_info_omits_hash() {
+        let info = serde_json::to_value(token(None).info()).unwrap();
+        assert!(info.get("token_hash").is_none());
+        assert_eq!(info["scopes"][0], "read_only");
+        assert_eq!(info["expired"], false);
+    }
+
+    #[test]
+    fn test_stored_token_defaults() {
+        let json = r#"{"id":"t","name":"n","token_hash":"h","prefix":"jean_","scopes":["chat"],"created_at":1}"#;
+        let token: ApiToken = serde_json::from_str(json).unwrap();
+        assert!(token.project_ids.is_empty());
+        assert_eq!(token.expires_at, None);
+        assert_eq!(token.grant().scopes, vec![TokenScope::Chat]);
+    }
+}
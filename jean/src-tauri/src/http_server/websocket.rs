@@ -1,19 +1,93 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
 use axum::extract::ws::{Message, WebSocket};
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tauri::AppHandle;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc, Semaphore};
+use tokio::task::AbortHandle;
 
-use super::dispatch::dispatch_command;
+use super::audit::{self, dispatch_audited, AuditClient, AuditKind};
+use super::auth::Grant;
+use super::replay::Catchup;
+use super::subscriptions::{
+    should_forward, terminal_output_payload, EventFilter, EventTarget, ProjectResolver,
+    Subscriptions,
+};
 use super::WsEvent;
 
-#[derive(Deserialize)]
+/// Maximum number of subscriptions per client
+const MAX_SUBSCRIPTIONS: usize = 64;
+
+/// Requests of one client that may run at the same time; more wait their turn
+const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// Requests of one client that may be running or waiting before new ones are rejected
+const MAX_PENDING_REQUESTS: usize = 128;
+
+#[derive(Debug, Deserialize)]
 struct InvokeRequest {
     id: String,
     command: String,
     #[serde(default)]
     args: Value,
+    /// Fail the request if it hasn't finished after this many milliseconds
+    #[serde(default, alias = "timeoutMs")]
+    timeout_ms: Option<u64>,
+}
+
+/// A parsed message from the client
+#[derive(Debug)]
+enum ClientMessage {
+    Invoke(InvokeRequest),
+    Subscription(SubscriptionRequest),
+    /// Abort the in-flight request with this id
+    Cancel {
+        id: String,
+    },
+}
+
+impl ClientMessage {
+    /// Parse a text frame; on error returns the request id (if any) and message
+    fn parse(text: &str) -> Result<Self, (String, String)> {
+        let value: Value = serde_json::from_str(text)
+            .map_err(|e| ("unknown".to_string(), format!("Invalid request: {e}")))?;
+        let id = value
+            .get("id")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown")
+            .to_string();
+        match value.get("type").and_then(Value::as_str) {
+            Some("subscribe" | "unsubscribe") => serde_json::from_value(value)
+                .map(Self::Subscription)
+                .map_err(|e| (id, format!("Invalid subscription request: {e}"))),
+            Some("cancel") if id != "unknown" => Ok(Self::Cancel { id }),
+            Some("cancel") => Err((id, "Cancel request is missing the id".to_string())),
+            // `type: "invoke"`, or no type for older clients
+            _ => serde_json::from_value(value)
+                .map(Self::Invoke)
+                .map_err(|e| (id, format!("Invalid request: {e}"))),
+        }
+    }
+}
+
+/// Subscription management messages (`type: "subscribe" | "unsubscribe"`)
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum SubscriptionRequest {
+    Subscribe {
+        id: String,
+        filter: EventFilter,
+    },
+    /// Without `subscription_id`, removes every subscription
+    Unsubscribe {
+        id: String,
+        #[serde(default, alias = "subscriptionId")]
+        subscription_id: Option<String>,
+    },
 }
 
 #[derive(Serialize)]
@@ -27,103 +101,354 @@ struct InvokeResponse {
     error: Option<String>,
 }
 
+impl InvokeResponse {
+    fn from_result(id: String, result: Result<Value, String>) -> Self {
+        match result {
+            Ok(data) => Self {
+                msg_type: "response".to_string(),
+                id,
+                data: Some(data),
+                error: None,
+            },
+            Err(err) => Self {
+                msg_type: "error".to_string(),
+                id,
+                data: None,
+                error: Some(err),
+            },
+        }
+    }
+
+    fn to_json(&self) -> Option<String> {
+        serde_json::to_string(self).ok()
+    }
+}
+
 #[derive(Serialize)]
 struct EventMessage {
     #[serde(rename = "type")]
     msg_type: String,
+    seq: u64,
     event: String,
     payload: Value,
 }
 
-/// Handle a single WebSocket connection.
-/// Reads invoke requests, dispatches to command handlers, writes responses.
-/// Also forwards broadcast events to the client.
-pub async fn handle_ws_connection(
-    socket: WebSocket,
+/// Tells the client where its event stream stands: sent after the missed
+/// events have been replayed on connect, and again if the client fell behind.
+/// With `resync` set, events were lost and the client must reload its state.
+#[derive(Serialize)]
+struct SyncMessage {
+    #[serde(rename = "type")]
+    msg_type: String,
+    stream_id: String,
+    /// Every event up to this sequence number has been sent (or filtered out)
+    seq: u64,
+    replayed: usize,
+    resync: bool,
+}
+
+/// Add or remove a subscription, returning the response data
+fn handle_subscription(
+    subscriptions: &RwLock<Subscriptions>,
+    request: SubscriptionRequest,
+) -> (String, Result<Value, String>) {
+    let mut subscriptions = subscriptions.write().unwrap();
+    match request {
+        SubscriptionRequest::Subscribe { id, filter } => {
+            if filter.is_empty() {
+                return (id, Err("Subscription filter cannot be empty".to_string()));
+            }
+            if subscriptions.len() >= MAX_SUBSCRIPTIONS {
+                return (
+                    id,
+                    Err(format!("Too many subscriptions (max {MAX_SUBSCRIPTIONS})")),
+                );
+            }
+            let subscription_id = subscriptions.add(filter);
+            log::trace!("WS client subscribed ({subscription_id})");
+            (
+                id,
+                Ok(serde_json::json!({ "subscription_id": subscription_id })),
+            )
+        }
+        SubscriptionRequest::Unsubscribe {
+            id,
+            subscription_id: Some(subscription_id),
+        } => {
+            if subscriptions.remove(&subscription_id) {
+                (id, Ok(Value::Null))
+            } else {
+                (
+                    id,
+                    Err(format!("Subscription not found: {subscription_id}")),
+                )
+            }
+        }
+        SubscriptionRequest::Unsubscribe {
+            id,
+            subscription_id: None,
+        } => {
+            subscriptions.clear();
+            (id, Ok(Value::Null))
+        }
+    }
+}
+
+/// Requests of one connection that haven't responded yet, by request id
+#[derive(Clone, Default)]
+struct InFlight(Arc<Mutex<HashMap<String, AbortHandle>>>);
+
+impl InFlight {
+    /// Stop tracking a request; false if it was already finished or cancelled
+    fn finish(&self, id: &str) -> bool {
+        self.0.lock().unwrap().remove(id).is_some()
+    }
+
+    /// Abort a request; false if it isn't in flight
+    fn cancel(&self, id: &str) -> bool {
+        match self.0.lock().unwrap().remove(id) {
+            Some(handle) => {
+                handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn cancel_all(&self) {
+        for (_, handle) in self.0.lock().unwrap().drain() {
+            handle.abort();
+        }
+    }
+}
+
+/// Everything a request task needs from its connection
+#[derive(Clone)]
+struct RequestContext {
     app: AppHandle,
-    mut event_rx: broadcast::Receiver<WsEvent>,
-) {
-    let (mut ws_tx, mut ws_rx) = socket.split();
+    grant: Grant,
+    client: AuditClient,
+    limiter: Arc<Semaphore>,
+    in_flight: InFlight,
+    out_tx: mpsc::Sender<String>,
+}
+
+impl RequestContext {
+    /// Run an invoke as its own task so slow commands don't block the socket.
+    /// Returns an error response if the request can't be started.
+    fn spawn(&self, req: InvokeRequest) -> Option<InvokeResponse> {
+        let mut in_flight = self.in_flight.0.lock().unwrap();
+        if in_flight.contains_key(&req.id) {
+            let error = format!("Request id already in use: {}", req.id);
+            return Some(InvokeResponse::from_result(req.id, Err(error)));
+        }
+        if in_flight.len() >= MAX_PENDING_REQUESTS {
+            let error = format!("Too many pending requests (max {MAX_PENDING_REQUESTS})");
+            return Some(InvokeResponse::from_result(req.id, Err(error)));
+        }
+
+        let id = req.id.clone();
+        let ctx = self.clone();
+        // The map stays locked until the handle is stored, so the task can't
+        // finish (and try to remove itself) before it is tracked
+        let task = tokio::spawn(async move { ctx.run(req).await });
+        in_flight.insert(id, task.abort_handle());
+        None
+    }
+
+    async fn run(self, req: InvokeRequest) {
+        let Ok(_permit) = self.limiter.acquire().await else {
+            return;
+        };
+        let dispatch =
+            dispatch_audited(&self.app, &self.client, &self.grant, &req.command, req.args);
+        let result = match req.timeout_ms {
+            Some(ms) => tokio::time::timeout(Duration::from_millis(ms), dispatch)
+                .await
+                .map_err(|_| format!("Request timed out after {ms}ms"))
+                .and_then(|result| result.map_err(|e| e.to_string())),
+            None => dispatch.await.map_err(|e| e.to_string()),
+        };
+
+        // Cancelled meanwhile: the client already got its response
+        if !self.in_flight.finish(&req.id) {
+            return;
+        }
+        if let Some(json) = InvokeResponse::from_result(req.id, result).to_json() {
+            let _ = self.out_tx.send(json).await;
+        }
+    }
+
+    /// Abort an in-flight request and answer it with an error
+    fn cancel(&self, id: String) -> Option<InvokeResponse> {
+        if !self.in_flight.cancel(&id) {
+            log::trace!("WS cancel for unknown or finished request {id}");
+            return None;
+        }
+        log::trace!("WS request {id} cancelled");
+        Some(InvokeResponse::from_result(
+            id,
+            Err("Request cancelled".to_string()),
+        ))
+    }
+}
+
+/// Sends the broadcast events a client wants to see
+struct EventForwarder {
+    subscriptions: Arc<RwLock<Subscriptions>>,
+    grant: Grant,
+    resolver: ProjectResolver,
+    out_tx: mpsc::Sender<String>,
+}
+
+impl EventForwarder {
+    /// Forward an event if it passes the filters; false once the client is gone
+    async fn forward(&mut self, ws_event: WsEvent) -> bool {
+        let mut project_of = |worktree_id: &str| self.resolver.project_of(worktree_id);
+        let (wanted, binary) = {
+            let subscriptions = self.subscriptions.read().unwrap();
+            let target = EventTarget::of(&ws_event.payload);
+            (
+                should_forward(&subscriptions, &ws_event, &mut project_of),
+                subscriptions.wants_binary(&ws_event.event, &target, &mut project_of),
+            )
+        };
+        if !wanted || !self.grant.allows_event(&ws_event, &mut project_of) {
+            return true;
+        }
+
+        let payload = if ws_event.event == "terminal:output" {
+            terminal_output_payload(ws_event.payload, binary)
+        } else {
+            ws_event.payload
+        };
+        let msg = EventMessage {
+            msg_type: "event".to_string(),
+            seq: ws_event.seq,
+            event: ws_event.event,
+            payload,
+        };
+        self.send(&msg).await
+    }
+
+    async fn sync(&self, stream_id: &str, seq: u64, replayed: usize, resync: bool) -> bool {
+        let msg = SyncMessage {
+            msg_type: "sync".to_string(),
+            stream_id: stream_id.to_string(),
+            seq,
+            replayed,
+            resync,
+        };
+        self.send(&msg).await
+    }
 
-    // Spawn a task to forward broadcast events to this client
-    let (client_tx, mut client_rx) = tokio::sync::mpsc::channel::<String>(256);
+    async fn send(&self, msg: &impl Serialize) -> bool {
+        match serde_json::to_string(msg) {
+            Ok(json) => self.out_tx.send(json).await.is_ok(),
+            Err(_) => true,
+        }
+    }
+
+    /// Replay what the client missed, then forward live events until either side closes
+    async fn run(mut self, catchup: Catchup, mut event_rx: broadcast::Receiver<WsEvent>) {
+        let replayed = catchup.events.len();
+        for ws_event in catchup.events {
+            if !self.forward(ws_event).await {
+                return;
+            }
+        }
+        let mut last_seq = catchup.seq;
+        if !self
+            .sync(&catchup.stream_id, last_seq, replayed, catchup.resync)
+            .await
+        {
+            return;
+        }
 
-    let event_forwarder = tokio::spawn(async move {
         loop {
             match event_rx.recv().await {
                 Ok(ws_event) => {
-                    let msg = EventMessage {
-                        msg_type: "event".to_string(),
-                        event: ws_event.event,
-                        payload: ws_event.payload,
-                    };
-                    if let Ok(json) = serde_json::to_string(&msg) {
-                        if client_tx.send(json).await.is_err() {
-                            break; // Client disconnected
-                        }
+                    last_seq = ws_event.seq;
+                    if !self.forward(ws_event).await {
+                        break; // Client disconnected
                     }
                 }
                 Err(broadcast::error::RecvError::Lagged(n)) => {
                     log::warn!("WS client lagged, skipped {n} events");
+                    if !self.sync(&catchup.stream_id, last_seq, 0, true).await {
+                        break;
+                    }
                 }
                 Err(broadcast::error::RecvError::Closed) => break,
             }
         }
-    });
+    }
+}
+
+/// Handle a single WebSocket connection.
+/// Reads client messages and writes responses and events.
+///
+/// Each invoke runs as its own task (at most [`MAX_CONCURRENT_REQUESTS`] at a
+/// time), so a slow command doesn't hold up other requests or events; responses
+/// are correlated by request id. Clients can abort a request with
+/// `{ "type": "cancel", "id": ... }` or bound it with `timeout_ms`.
+/// Broadcast events are filtered by the client's subscriptions (see
+/// `subscriptions`). Commands are authorized against the client's `grant`.
+///
+/// Events missed before a reconnect (`catchup`) are sent first, followed by a
+/// `sync` message; see `replay`. The connection and every command are
+/// recorded in the audit log.
+pub async fn handle_ws_connection(
+    socket: WebSocket,
+    app: AppHandle,
+    grant: Grant,
+    client: AuditClient,
+    event_rx: broadcast::Receiver<WsEvent>,
+    catchup: Catchup,
+) {
+    audit::record_connection(&app, &client, &grant, AuditKind::Connect);
+    let (mut ws_tx, mut ws_rx) = socket.split();
+    let subscriptions = Arc::new(RwLock::new(Subscriptions::default()));
+
+    // Events and request responses are written to the socket by the main loop
+    let (client_tx, mut client_rx) = mpsc::channel::<String>(256);
+
+    let forwarder = EventForwarder {
+        subscriptions: subscriptions.clone(),
+        grant: grant.clone(),
+        resolver: ProjectResolver::new(app.clone()),
+        out_tx: client_tx.clone(),
+    };
+    let event_forwarder = tokio::spawn(forwarder.run(catchup, event_rx));
 
-    // Main loop: handle incoming messages and outgoing events
+    let requests = RequestContext {
+        app,
+        grant,
+        client,
+        limiter: Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS)),
+        in_flight: InFlight::default(),
+        out_tx: client_tx,
+    };
+
+    // Main loop: handle incoming messages and outgoing events/responses
     loop {
         tokio::select! {
             // Incoming message from client
             msg = ws_rx.next() => {
                 match msg {
                     Some(Ok(Message::Text(text))) => {
-                        let app_clone = app.clone();
-                        // Parse and dispatch
-                        match serde_json::from_str::<InvokeRequest>(&text) {
-                            Ok(req) => {
-                                let id = req.id.clone();
-                                match dispatch_command(&app_clone, &req.command, req.args).await {
-                                    Ok(data) => {
-                                        let resp = InvokeResponse {
-                                            msg_type: "response".to_string(),
-                                            id,
-                                            data: Some(data),
-                                            error: None,
-                                        };
-                                        if let Ok(json) = serde_json::to_string(&resp) {
-                                            if ws_tx.send(Message::Text(json.into())).await.is_err() {
-                                                break;
-                                            }
-                                        }
-                                    }
-                                    Err(err) => {
-                                        let resp = InvokeResponse {
-                                            msg_type: "error".to_string(),
-                                            id,
-                                            data: None,
-                                            error: Some(err),
-                                        };
-                                        if let Ok(json) = serde_json::to_string(&resp) {
-                                            if ws_tx.send(Message::Text(json.into())).await.is_err() {
-                                                break;
-                                            }
-                                        }
-                                    }
-                                }
+                        let resp = match ClientMessage::parse(&text) {
+                            Ok(ClientMessage::Invoke(req)) => requests.spawn(req),
+                            Ok(ClientMessage::Cancel { id }) => requests.cancel(id),
+                            Ok(ClientMessage::Subscription(request)) => {
+                                let (id, result) = handle_subscription(&subscriptions, request);
+                                Some(InvokeResponse::from_result(id, result))
                             }
-                            Err(e) => {
-                                let resp = InvokeResponse {
-                                    msg_type: "error".to_string(),
-                                    id: "unknown".to_string(),
-                                    data: None,
-                                    error: Some(format!("Invalid request: {e}")),
-                                };
-                                if let Ok(json) = serde_json::to_string(&resp) {
-                                    if ws_tx.send(Message::Text(json.into())).await.is_err() {
-                                        break;
-                                    }
-                                }
+                            Err((id, error)) => Some(InvokeResponse::from_result(id, Err(error))),
+                        };
+                        if let Some(json) = resp.and_then(|r| r.to_json()) {
+                            if ws_tx.send(Message::Text(json.into())).await.is_err() {
+                                break;
                             }
                         }
                     }
@@ -136,7 +461,7 @@ pub async fn handle_ws_connection(
                     _ => {} // Ignore binary, pong
                 }
             }
-            // Outgoing event from broadcast
+            // Outgoing event or response
             Some(json) = client_rx.recv() => {
                 if ws_tx.send(Message::Text(json.into())).await.is_err() {
                     break;
@@ -146,5 +471,61 @@ pub async fn handle_ws_connection(
     }
 
     event_forwarder.abort();
+    requests.in_flight.cancel_all();
+    audit::record_connection(
+        &requests.app,
+        &requests.client,
+        &requests.grant,
+        AuditKind::Disconnect,
+    );
     log::trace!("WebSocket client disconnected");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_invoke() {
+        let msg = ClientMessage::parse(
+            r#"{"type":"invoke","id":"1","command":"list_projects","timeoutMs":500}"#,
+        )
+        .unwrap();
+        let ClientMessage::Invoke(req) = msg else {
+            panic!("expected invoke, got {msg:?}");
+        };
+        assert_eq!(req.id, "1");
+        assert_eq!(req.command, "list_projects");
+        assert_eq!(req.timeout_ms, Some(500));
+        assert_eq!(req.args, Value::Null);
+
+        // Older clients send no type
+        let msg = ClientMessage::parse(r#"{"id":"2","command":"greet","args":{}}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::Invoke(_)));
+    }
+
+    #[test]
+    fn test_parse_cancel_and_subscriptions() {
+        let msg = ClientMessage::parse(r#"{"type":"cancel","id":"7"}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::Cancel { id } if id == "7"));
+        assert!(ClientMessage::parse(r#"{"type":"cancel"}"#).is_err());
+
+        let msg =
+            ClientMessage::parse(r#"{"type":"subscribe","id":"3","filter":{"events":["chat:*"]}}"#)
+                .unwrap();
+        assert!(matches!(
+            msg,
+            ClientMessage::Subscription(SubscriptionRequest::Subscribe { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_errors_keep_request_id() {
+        let (id, error) = ClientMessage::parse(r#"{"type":"invoke","id":"9"}"#).unwrap_err();
+        assert_eq!(id, "9");
+        assert!(error.starts_with("Invalid request"));
+
+        let (id, _) = ClientMessage::parse("not json").unwrap_err();
+        assert_eq!(id, "unknown");
+    }
+}
@@ -12,9 +12,11 @@ mod background_tasks;
 mod chat;
 mod claude_cli;
 mod gh_cli;
+mod headless;
 pub mod http_server;
 mod platform;
 mod projects;
+mod scheduler;
 mod terminal;
 
 // Validation functions
@@ -147,12 +149,24 @@ pub struct AppPreferences {
     pub http_server_localhost_only: bool, // Bind to localhost only (more secure)
     #[serde(default = "default_http_server_token_required")]
     pub http_server_token_required: bool, // Require token for web access (default true)
+    #[serde(default)]
+    pub http_server_tls_enabled: bool, // Serve HTTPS instead of HTTP
+    #[serde(default)]
+    pub http_server_tls_cert_path: Option<String>, // PEM certificate chain (None = self-signed)
+    #[serde(default)]
+    pub http_server_tls_key_path: Option<String>, // PEM private key for the certificate
+    #[serde(default)]
+    pub http_server_allowed_ips: Vec<String>, // IPs/CIDR ranges allowed to connect (empty = any)
     #[serde(default = "default_auto_archive_on_pr_merged")]
     pub auto_archive_on_pr_merged: bool, // Auto-archive worktrees when their PR is merged
     #[serde(default = "default_show_keybinding_hints")]
     pub show_keybinding_hints: bool, // Show keyboard shortcut hints at bottom of canvas views
     #[serde(default)]
     pub debug_mode_enabled: bool, // Show debug panel in chat sessions (default: false)
+    #[serde(default = "chat::usage::default_model_pricing")]
+    pub model_pricing: std::collections::HashMap<String, chat::usage::ModelPricing>, // USD per million tokens, keyed by model alias
+    #[serde(default)]
+    pub usage_budgets: Vec<chat::budget::UsageBudget>, // Per-project/worktree/day spending limits checked before each run
 }
 
 fn default_auto_branch_naming() -> bool {
@@ -574,9 +588,15 @@ impl Default for AppPreferences {
             http_server_token: None,
             http_server_localhost_only: true, // Default to localhost-only for security
             http_server_token_required: default_http_server_token_required(),
+            http_server_tls_enabled: false,
+            http_server_tls_cert_path: None,
+            http_server_tls_key_path: None,
+            http_server_allowed_ips: Vec::new(),
             auto_archive_on_pr_merged: default_auto_archive_on_pr_merged(),
             show_keybinding_hints: default_show_keybinding_hints(),
             debug_mode_enabled: false,
+            model_pricing: chat::usage::default_model_pricing(),
+            usage_budgets: Vec::new(),
         }
     }
 }
@@ -1017,6 +1037,27 @@ async fn cleanup_old_recovery_files(app: AppHandle) -> Result<u32, String> {
 // HTTP Server Tauri Commands
 // =============================================================================
 
+/// Certificate to serve when HTTPS is enabled in preferences
+fn load_http_server_tls(
+    app: &AppHandle,
+    prefs: &AppPreferences,
+) -> Result<Option<http_server::tls::TlsMaterial>, String> {
+    if !prefs.http_server_tls_enabled {
+        return Ok(None);
+    }
+    let tls = http_server::tls::load_tls_material(
+        app,
+        prefs.http_server_tls_cert_path.as_deref(),
+        prefs.http_server_tls_key_path.as_deref(),
+    )?;
+    log::info!(
+        "HTTP server TLS certificate (self-signed: {}) SHA-256 {}",
+        tls.self_signed,
+        tls.fingerprint
+    );
+    Ok(Some(tls))
+}
+
 #[tauri::command]
 async fn start_http_server(
     app: AppHandle,
@@ -1029,6 +1070,7 @@ async fn start_http_server(
     let actual_port = port.unwrap_or(prefs.http_server_port);
     let localhost_only = prefs.http_server_localhost_only;
     let token_required = prefs.http_server_token_required;
+    let tls = load_http_server_tls(&app, &prefs)?;
 
     // Generate or load token
     let token = match prefs.http_server_token {
@@ -1056,15 +1098,17 @@ async fn start_http_server(
     }
 
     // Start the server
-    let handle =
-        http_server::server::start_server(app.clone(), actual_port, token, localhost_only, token_required).await?;
-    let status = http_server::server::ServerStatus {
-        running: true,
-        url: Some(handle.url.clone()),
-        token: Some(handle.token.clone()),
-        port: Some(handle.port),
-        localhost_only: Some(handle.localhost_only),
-    };
+    let handle = http_server::server::start_server(
+        app.clone(),
+        actual_port,
+        token,
+        localhost_only,
+        token_required,
+        tls,
+        &prefs.http_server_allowed_ips,
+    )
+    .await?;
+    let status = handle.status();
 
     // Store the handle
     let handle_state = app.try_state::<Arc<Mutex<Option<http_server::server::HttpServerHandle>>>>();
@@ -1115,6 +1159,7 @@ async fn start_http_server_headless(
         prefs.http_server_localhost_only
     };
     let token_required = prefs.http_server_token_required;
+    let tls = load_http_server_tls(&app, &prefs)?;
 
     // Generate or load token
     let token = match prefs.http_server_token {
@@ -1142,15 +1187,17 @@ async fn start_http_server_headless(
     }
 
     // Start the server
-    let handle =
-        http_server::server::start_server(app.clone(), port, token, localhost_only, token_required).await?;
-    let status = http_server::server::ServerStatus {
-        running: true,
-        url: Some(handle.url.clone()),
-        token: Some(handle.token.clone()),
-        port: Some(handle.port),
-        localhost_only: Some(handle.localhost_only),
-    };
+    let handle = http_server::server::start_server(
+        app.clone(),
+        port,
+        token,
+        localhost_only,
+        token_required,
+        tls,
+        &prefs.http_server_allowed_ips,
+    )
+    .await?;
+    let status = handle.status();
 
     // Store the handle
     let handle_state = app.try_state::<Arc<Mutex<Option<http_server::server::HttpServerHandle>>>>();
@@ -1284,9 +1331,35 @@ fn fix_macos_path() {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Parse CLI arguments for headless mode
     let args: Vec<String> = std::env::args().collect();
-    let headless = args.iter().any(|a| a == "--headless");
+
+    // Jean re-executes itself to host detached terminals (see terminal::host)
+    #[cfg(unix)]
+    if let Some(dir) = terminal::host_dir_from_args(&args) {
+        if let Err(e) = terminal::run_terminal_host(&dir) {
+            eprintln!("Terminal host failed: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Parse CLI arguments for headless mode
+    let headless_options = match headless::parse_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            eprintln!("Usage: jean [--headless [--port <port>] [--bind-all]]");
+            std::process::exit(2);
+        }
+    };
+    let headless = headless_options.is_some();
+    let headless_options = headless_options.unwrap_or_default();
+    if headless {
+        if let Err(e) = headless::check_display() {
+            eprintln!("Error: {e}");
+            std::process::exit(2);
+        }
+    }
 
     // Fix PATH environment for macOS GUI applications
     // GUI apps don't inherit shell PATH - spawns login shell to get PATH from profiles
@@ -1348,6 +1421,12 @@ pub fn run() {
         tauri_plugin_log::TargetKind::LogDir { file_name: None },
     ));
 
+    // Headless mode runs without windows, so drop the ones from tauri.conf.json
+    let mut context = tauri::generate_context!();
+    if headless {
+        context.config_mut().app.windows.clear();
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
@@ -1379,12 +1458,10 @@ pub fn run() {
                 app.package_info().name
             );
 
-            // In headless mode, close the window immediately
+            // In headless mode no window is created (see the context below)
             if headless {
                 log::info!("Running in headless mode");
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.close();
-                }
+                headless::spawn_shutdown_handler(app.handle().clone());
             }
 
             // Recover any incomplete runs from previous session (crash recovery)
@@ -1407,6 +1484,15 @@ pub fn run() {
                 }
             }
 
+            // Reattach terminals whose host kept running while Jean was closed
+            #[cfg(unix)]
+            {
+                let reattached = terminal::reattach_terminals(&app_handle);
+                if reattached > 0 {
+                    log::trace!("Reattached {reattached} terminal(s) from previous session");
+                }
+            }
+
             // Skip menu creation in headless mode (no window to attach to)
             #[cfg(target_os = "macos")]
             if !headless {
@@ -1509,6 +1595,12 @@ pub fn run() {
             app.manage(task_manager);
             log::trace!("Background task manager initialized");
 
+            // Initialize scheduler for scheduled/recurring session runs
+            let scheduler = scheduler::SchedulerManager::new(app.handle().clone());
+            scheduler.start();
+            app.manage(scheduler);
+            log::trace!("Scheduler initialized");
+
             // Initialize HTTP server infrastructure
             let (broadcaster, _) = http_server::WsBroadcaster::new();
             app.manage(broadcaster);
@@ -1522,12 +1614,12 @@ pub fn run() {
             tauri::async_runtime::spawn(async move {
                 match load_preferences(app_handle_http.clone()).await {
                     Ok(prefs) if headless || prefs.http_server_auto_start => {
-                        let port = prefs.http_server_port;
+                        let port = headless_options.port.unwrap_or(prefs.http_server_port);
                         log::info!("Starting HTTP server on port {port}");
                         match start_http_server_headless(
                             app_handle_http,
                             port,
-                            headless, // In headless mode, bind to 0.0.0.0
+                            headless_options.bind_all, // --bind-all: listen on 0.0.0.0
                         )
                         .await
                         {
@@ -1536,14 +1628,20 @@ pub fn run() {
                                 let token = status.token.unwrap_or_default();
                                 log::info!("HTTP server started: {url}");
                                 if headless {
+                                    // Headless mode used to listen on all interfaces
+                                    // by default; say so when it no longer does
+                                    let reach = if status.localhost_only == Some(true) {
+                                        "localhost only (--bind-all to listen on all interfaces)"
+                                    } else {
+                                        "all interfaces"
+                                    };
                                     // Print to stdout for scripts/users to capture
                                     println!("\n╔══════════════════════════════════════════════════════════════╗");
                                     println!("║  Jean server running in headless mode                        ║");
                                     println!("╠══════════════════════════════════════════════════════════════╣");
                                     println!("║  URL: {url:<54} ║");
                                     println!("║  Token: {token:<52} ║");
-                                    println!("║                                                              ║");
-                                    println!("║  Open in browser: {url}?token={token}");
+                                    println!("║  Listening on: {reach}");
                                     println!("╚══════════════════════════════════════════════════════════════╝\n");
                                 }
                             }
@@ -1679,6 +1777,14 @@ pub fn run() {
             terminal::stop_terminal,
             terminal::get_active_terminals,
             terminal::has_active_terminal,
+            terminal::list_terminals,
+            terminal::get_terminal_scrollback,
+            terminal::start_terminal_recording,
+            terminal::stop_terminal_recording,
+            terminal::list_terminal_recordings,
+            terminal::read_terminal_recording,
+            terminal::delete_terminal_recording,
+            terminal::attach_terminal_recording,
             terminal::get_run_script,
             terminal::kill_all_terminals,
             // Chat commands - Session management
@@ -1734,6 +1840,29 @@ pub fn run() {
             chat::broadcast_session_setting,
             // Chat commands - Debug info
             chat::get_session_debug_info,
+            chat::usage::get_usage_report,
+            chat::budget::get_budget_status,
+            chat::search::search_sessions,
+            chat::export::export_session,
+            chat::import::export_session_bundle,
+            chat::import::import_session_bundle,
+            chat::import::list_claude_cli_sessions,
+            chat::import::import_claude_cli_session,
+            chat::fork::fork_session,
+            chat::queue::enqueue_message,
+            chat::queue::list_queued_messages,
+            chat::queue::update_queued_message,
+            chat::queue::reorder_queued_messages,
+            chat::queue::remove_queued_message,
+            chat::queue::clear_message_queue,
+            chat::queue::send_next_queued_message,
+            scheduler::commands::list_schedules,
+            scheduler::commands::create_schedule,
+            scheduler::commands::update_schedule,
+            scheduler::commands::set_schedule_enabled,
+            scheduler::commands::delete_schedule,
+            scheduler::commands::run_schedule_now,
+            scheduler::commands::preview_schedule,
             // Chat commands - Session resume (detached process recovery)
             chat::resume_session,
             chat::check_resumable_sessions,
@@ -1750,6 +1879,7 @@ pub fn run() {
             // Background task commands
             background_tasks::commands::set_app_focus_state,
             background_tasks::commands::set_active_worktree_for_polling,
+            background_tasks::commands::set_polled_worktrees,
             background_tasks::commands::set_git_poll_interval,
             background_tasks::commands::get_git_poll_interval,
             background_tasks::commands::trigger_immediate_git_poll,
@@ -1761,18 +1891,25 @@ pub fn run() {
             stop_http_server,
             get_http_server_status,
             regenerate_http_token,
+            http_server::tokens::create_api_token,
+            http_server::tokens::list_api_tokens,
+            http_server::tokens::revoke_api_token,
+            http_server::audit::query_audit_log,
         ])
-        .build(tauri::generate_context!())
+        .build(context)
         .expect("error building tauri application")
-        .run(move |_app_handle, event| match &event {
+        .run(move |app_handle, event| match &event {
             tauri::RunEvent::Exit => {
+                if let Some(scheduler) = app_handle.try_state::<scheduler::SchedulerManager>() {
+                    scheduler.stop();
+                }
                 eprintln!("[TERMINAL CLEANUP] RunEvent::Exit received");
                 let killed = terminal::cleanup_all_terminals();
                 eprintln!("[TERMINAL CLEANUP] Killed {killed} terminal(s)");
             }
-            tauri::RunEvent::ExitRequested { api, .. } => {
-                // In headless mode, prevent exit when window closes
-                if headless {
+            tauri::RunEvent::ExitRequested { api, code, .. } => {
+                // In headless mode, only exit when asked to (e.g. on SIGTERM)
+                if headless && code.is_none() {
                     api.prevent_exit();
                     return;
                 }
@@ -1782,7 +1919,7 @@ pub fn run() {
             }
             tauri::RunEvent::WindowEvent { label, event, .. } => {
                 if let tauri::WindowEvent::CloseRequested { .. } = event {
-                    // In headless mode, we already closed the window, don't cleanup terminals
+                    // Headless mode has no windows; terminals belong to remote clients
                     if headless {
                         return;
                     }
@@ -343,6 +343,7 @@ pub async fn remove_project(app: AppHandle, project_id: String) -> Result<(), St
 
     // Clean up sessions files for archived worktrees (in background, non-blocking)
     for worktree_id in archived_worktree_ids {
+        crate::chat::search::remove_worktree_from_index(&app, &worktree_id);
         if let Ok(sessions_file) = crate::chat::storage::get_sessions_path(&app, &worktree_id) {
             if sessions_file.exists() {
                 if let Err(e) = std::fs::remove_file(&sessions_file) {
@@ -1737,6 +1738,7 @@ pub async fn delete_worktree(app: AppHandle, worktree_id: String) -> Result<(),
     data.remove_worktree(&worktree_id);
     save_projects_data(&app, &data)?;
     log::trace!("Worktree removed from storage: {worktree_id}");
+    crate::chat::search::remove_worktree_from_index(&app, &worktree_id);
 
     // Emit deleting event immediately
     let deleting_event = WorktreeDeletingEvent {
@@ -1925,6 +1927,7 @@ async fn close_base_session_internal(
         crate::chat::preserve_base_sessions(app, worktree_id, &worktree.project_id)?;
     } else {
         // Delete the sessions file entirely for a clean close
+        crate::chat::search::remove_worktree_from_index(app, worktree_id);
         if let Ok(sessions_file) = crate::chat::storage::get_sessions_path(app, worktree_id) {
             if sessions_file.exists() {
                 if let Err(e) = std::fs::remove_file(&sessions_file) {
@@ -2222,6 +2225,7 @@ pub async fn permanently_delete_worktree(
     data.remove_worktree(&worktree_id);
     save_projects_data(&app, &data)?;
     log::trace!("Worktree removed from storage: {worktree_id}");
+    crate::chat::search::remove_worktree_from_index(&app, &worktree_id);
 
     // Clone values for background thread
     let app_clone = app.clone();
@@ -4934,6 +4938,7 @@ pub async fn cleanup_old_archives(
         }
 
         // Delete the sessions file
+        crate::chat::search::remove_worktree_from_index(&app, &worktree.id);
         if let Ok(app_data_dir) = app.path().app_data_dir() {
             let sessions_file = app_data_dir
                 .join("sessions")
@@ -4963,8 +4968,7 @@ pub async fn cleanup_old_archives(
         let worktree_id = worktree.id.clone();
         let result =
             crate::chat::with_sessions_mut(&app, &worktree_path, &worktree_id, |sessions| {
-                let original_count = sessions.sessions.len();
-                let mut removed_count = 0;
+                let mut removed = Vec::new();
 
                 // Remove sessions that are archived and older than cutoff
                 sessions.sessions.retain(|s| {
@@ -4975,22 +4979,19 @@ pub async fn cleanup_old_archives(
                                 s.name,
                                 (now() - archived_at) / 86400
                             );
-                            removed_count += 1;
+                            removed.push(s.id.clone());
                             return false; // Remove this session
                         }
                     }
                     true // Keep this session
                 });
 
-                if sessions.sessions.len() < original_count {
-                    Ok(removed_count)
-                } else {
-                    Ok(0)
-                }
+                Ok(removed)
             });
 
-        if let Ok(count) = result {
-            deleted_sessions += count;
+        if let Ok(removed) = result {
+            deleted_sessions += removed.len() as u32;
+            crate::chat::search::delete_sessions_from_index(&app, removed);
         }
     }
 
@@ -5062,6 +5063,7 @@ pub async fn delete_all_archives(app: AppHandle) -> Result<CleanupResult, String
         }
 
         // Delete the sessions file
+        crate::chat::search::remove_worktree_from_index(&app, &worktree.id);
         if let Ok(app_data_dir) = app.path().app_data_dir() {
             let sessions_file = app_data_dir
                 .join("sessions")
@@ -5090,28 +5092,24 @@ pub async fn delete_all_archives(app: AppHandle) -> Result<CleanupResult, String
         let worktree_id = worktree.id.clone();
         let result =
             crate::chat::with_sessions_mut(&app, &worktree_path, &worktree_id, |sessions| {
-                let original_count = sessions.sessions.len();
-                let mut removed_count = 0;
+                let mut removed = Vec::new();
 
                 // Remove all archived sessions
                 sessions.sessions.retain(|s| {
                     if s.archived_at.is_some() {
                         log::trace!("Deleting archived session: {}", s.name);
-                        removed_count += 1;
+                        removed.push(s.id.clone());
                         return false; // Remove this session
                     }
                     true // Keep this session
                 });
 
-                if sessions.sessions.len() < original_count {
-                    Ok(removed_count)
-                } else {
-                    Ok(0)
-                }
+                Ok(removed)
             });
 
-        if let Ok(count) = result {
-            deleted_sessions += count;
+        if let Ok(removed) = result {
+            deleted_sessions += removed.len() as u32;
+            crate::chat::search::delete_sessions_from_index(&app, removed);
         }
     }
 
@@ -1,5 +1,7 @@
 use crate::platform::silent_command;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::io::Read;
+use std::process::Stdio;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use serde::Serialize;
 
@@ -43,18 +45,52 @@ pub struct GitBranchStatus {
     pub unpushed_count: u32,
 }
 
+/// A fetch running longer than this is killed, so a hung remote can't hold a
+/// polling slot forever
+const FETCH_TIMEOUT: Duration = Duration::from_secs(60);
+
 /// Fetch the latest changes from origin for a specific branch
 fn fetch_origin_branch(repo_path: &str, branch: &str) -> Result<(), String> {
     log::trace!("Fetching origin/{branch} in {repo_path}");
 
-    let output = silent_command("git")
+    let mut child = silent_command("git")
         .args(["fetch", "origin", branch])
         .current_dir(repo_path)
-        .output()
+        // Never wait on a credential prompt nobody can answer
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
         .map_err(|e| format!("Failed to run git fetch: {e}"))?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+    // Read stderr on the side so a chatty fetch can't fill the pipe and stall
+    let mut stderr_pipe = child.stderr.take();
+    let stderr_reader = std::thread::spawn(move || {
+        let mut stderr = String::new();
+        if let Some(pipe) = stderr_pipe.as_mut() {
+            let _ = pipe.read_to_string(&mut stderr);
+        }
+        stderr
+    });
+
+    let deadline = Instant::now() + FETCH_TIMEOUT;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                log::warn!("git fetch of origin/{branch} in {repo_path} timed out");
+                return Err("git fetch timed out".to_string());
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+            Err(e) => return Err(format!("Failed to wait for git fetch: {e}")),
+        }
+    };
+    let stderr = stderr_reader.join().unwrap_or_default();
+
+    if !status.success() {
         // Don't fail if no remote - just log and continue
         if stderr.contains("does not appear to be a git repository")
             || stderr.contains("Could not read from remote")
@@ -658,12 +694,29 @@ pub fn get_git_diff(
 /// This fetches the latest from origin and compares the current HEAD
 /// to origin/{base_branch} to determine ahead/behind counts.
 pub fn get_branch_status(info: &ActiveWorktreeInfo) -> Result<GitBranchStatus, String> {
+    compute_branch_status(info, true)
+}
+
+/// Get the branch status for a worktree without fetching from origin
+///
+/// Used when a local change (file edit, commit, checkout) triggers a refresh:
+/// remote refs are compared as they are, so no network round-trip is needed.
+pub fn get_local_branch_status(info: &ActiveWorktreeInfo) -> Result<GitBranchStatus, String> {
+    compute_branch_status(info, false)
+}
+
+fn compute_branch_status(
+    info: &ActiveWorktreeInfo,
+    fetch: bool,
+) -> Result<GitBranchStatus, String> {
     let repo_path = &info.worktree_path;
     let base_branch = &info.base_branch;
 
     // Fetch latest from origin for the base branch
     // This is best-effort; if it fails, we'll compare with stale data
-    let _ = fetch_origin_branch(repo_path, base_branch);
+    if fetch {
+        let _ = fetch_origin_branch(repo_path, base_branch);
+    }
 
     // Get current branch name
     let current_branch = get_current_branch(repo_path)?;
@@ -696,7 +749,9 @@ pub fn get_branch_status(info: &ActiveWorktreeInfo) -> Result<GitBranchStatus, S
     let origin_current_ref = format!("origin/{current_branch}");
     let unpushed_count = if current_branch != *base_branch {
         // Fetch origin/{current_branch} so we have up-to-date remote info
-        let _ = fetch_origin_branch(repo_path, &current_branch);
+        if fetch {
+            let _ = fetch_origin_branch(repo_path, &current_branch);
+        }
         if ref_exists(repo_path, &origin_current_ref) {
             count_commits_between(repo_path, &origin_current_ref, "HEAD")
         } else {
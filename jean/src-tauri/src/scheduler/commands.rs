@@ -0,0 +1,201 @@
+//! Tauri commands for managing schedules
+
+use serde::Deserialize;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use super::{
+    load_schedules, next_fire_time, now, run_now, with_schedules_mut, Schedule, ScheduleTarget,
+    ScheduleTrigger,
+};
+use crate::chat::types::ThinkingLevel;
+use crate::http_server::EmitExt;
+
+/// Upper bound for `preview_schedule`
+const MAX_PREVIEW_COUNT: usize = 20;
+
+/// Editable fields of a schedule
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScheduleInput {
+    pub name: String,
+    pub prompt: String,
+    pub trigger: ScheduleTrigger,
+    pub target: ScheduleTarget,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub execution_mode: Option<String>,
+    #[serde(default)]
+    pub thinking_level: Option<ThinkingLevel>,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+impl ScheduleInput {
+    fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Schedule name cannot be empty".to_string());
+        }
+        if self.prompt.trim().is_empty() {
+            return Err("Schedule prompt cannot be empty".to_string());
+        }
+        if self.target.project_id.is_empty() {
+            return Err("Schedule target must include a project".to_string());
+        }
+        if next_fire_time(&self.trigger, now())?.is_none() {
+            return Err("Schedule would never run (the time is in the past)".to_string());
+        }
+        Ok(())
+    }
+
+    fn apply(self, schedule: &mut Schedule) -> Result<(), String> {
+        schedule.name = self.name.trim().to_string();
+        schedule.prompt = self.prompt;
+        schedule.trigger = self.trigger;
+        schedule.target = self.target;
+        schedule.model = self.model;
+        schedule.execution_mode = self.execution_mode;
+        schedule.thinking_level = self.thinking_level;
+        if let Some(enabled) = self.enabled {
+            schedule.enabled = enabled;
+        }
+        schedule.reschedule(now())
+    }
+}
+
+fn emit_schedules_changed(app: &AppHandle, schedules: &[Schedule]) {
+    if let Err(e) = app.emit_all("schedules:changed", &schedules) {
+        log::error!("Failed to emit schedules changed event: {e}");
+    }
+}
+
+/// Apply `f` to one schedule, save, and notify clients
+fn update_schedule_with<F>(app: &AppHandle, schedule_id: &str, f: F) -> Result<Schedule, String>
+where
+    F: FnOnce(&mut Schedule) -> Result<(), String>,
+{
+    let (schedule, all) = with_schedules_mut(app, |schedules| {
+        let schedule = schedules
+            .iter_mut()
+            .find(|s| s.id == schedule_id)
+            .ok_or_else(|| format!("Schedule not found: {schedule_id}"))?;
+        f(schedule)?;
+        Ok((schedule.clone(), schedules.clone()))
+    })?;
+    emit_schedules_changed(app, &all);
+    Ok(schedule)
+}
+
+/// List all schedules
+#[tauri::command]
+pub async fn list_schedules(app: AppHandle) -> Result<Vec<Schedule>, String> {
+    load_schedules(&app)
+}
+
+/// Create a schedule
+#[tauri::command]
+pub async fn create_schedule(app: AppHandle, schedule: ScheduleInput) -> Result<Schedule, String> {
+    log::trace!("Creating schedule: {}", schedule.name);
+    schedule.validate()?;
+
+    let mut created = Schedule {
+        id: Uuid::new_v4().to_string(),
+        name: String::new(),
+        prompt: String::new(),
+        trigger: schedule.trigger.clone(),
+        target: schedule.target.clone(),
+        model: None,
+        execution_mode: None,
+        thinking_level: None,
+        enabled: true,
+        created_at: now(),
+        next_run_at: None,
+        history: vec![],
+    };
+    schedule.apply(&mut created)?;
+
+    let all = with_schedules_mut(&app, |schedules| {
+        schedules.push(created.clone());
+        Ok(schedules.clone())
+    })?;
+    emit_schedules_changed(&app, &all);
+    Ok(created)
+}
+
+/// Replace a schedule's settings (its run history is kept)
+#[tauri::command]
+pub async fn update_schedule(
+    app: AppHandle,
+    schedule_id: String,
+    schedule: ScheduleInput,
+) -> Result<Schedule, String> {
+    log::trace!("Updating schedule {schedule_id}");
+    schedule.validate()?;
+    update_schedule_with(&app, &schedule_id, |existing| schedule.apply(existing))
+}
+
+/// Pause or resume a schedule
+#[tauri::command]
+pub async fn set_schedule_enabled(
+    app: AppHandle,
+    schedule_id: String,
+    enabled: bool,
+) -> Result<Schedule, String> {
+    log::trace!("Setting schedule {schedule_id} enabled: {enabled}");
+    update_schedule_with(&app, &schedule_id, |schedule| {
+        schedule.enabled = enabled;
+        schedule.reschedule(now())?;
+        if enabled && schedule.next_run_at.is_none() {
+            return Err("Schedule would never run (the time is in the past)".to_string());
+        }
+        Ok(())
+    })
+}
+
+/// Delete a schedule
+#[tauri::command]
+pub async fn delete_schedule(app: AppHandle, schedule_id: String) -> Result<(), String> {
+    log::trace!("Deleting schedule {schedule_id}");
+    let all = with_schedules_mut(&app, |schedules| {
+        let before = schedules.len();
+        schedules.retain(|s| s.id != schedule_id);
+        if schedules.len() == before {
+            return Err(format!("Schedule not found: {schedule_id}"));
+        }
+        Ok(schedules.clone())
+    })?;
+    emit_schedules_changed(&app, &all);
+    Ok(())
+}
+
+/// Fire a schedule right away without changing its next run
+#[tauri::command]
+pub async fn run_schedule_now(app: AppHandle, schedule_id: String) -> Result<(), String> {
+    let schedule = load_schedules(&app)?
+        .into_iter()
+        .find(|s| s.id == schedule_id)
+        .ok_or_else(|| format!("Schedule not found: {schedule_id}"))?;
+    run_now(&app, schedule);
+    Ok(())
+}
+
+/// Next fire times (Unix timestamps) of a trigger, for previewing cron expressions
+#[tauri::command]
+pub async fn preview_schedule(
+    trigger: ScheduleTrigger,
+    count: Option<usize>,
+) -> Result<Vec<u64>, String> {
+    let count = count.unwrap_or(5).min(MAX_PREVIEW_COUNT);
+    let mut times = Vec::with_capacity(count);
+    let mut after = now();
+    while times.len() < count {
+        match next_fire_time(&trigger, after)? {
+            Some(next) => {
+                times.push(next);
+                after = next;
+            }
+            None => break,
+        }
+    }
+    Ok(times)
+}
@@ -0,0 +1,274 @@
+//! Cron expression parsing
+//!
+//! Supports the standard five fields (`minute hour day-of-month month
+//! day-of-week`) with `*`, lists, ranges, steps and three-letter month/day
+//! names, plus the usual `@daily`-style aliases. As in Vixie cron, when
+//! neither day field starts with `*` a date matches if either of them does;
+//! otherwise it must match both (so `*/2` restricts, but is not ORed).
+
+use chrono::{DateTime, Datelike, Duration, LocalResult, NaiveDate, TimeZone, Timelike};
+
+/// How far ahead to look for a matching date (covers Feb 29 schedules)
+const MAX_SEARCH_DAYS: i64 = 366 * 8;
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const DAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// A parsed cron expression; each field is a bitset of allowed values
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    days_of_week: u8,
+    /// Day-of-month field starts with `*` (including steps like `*/2`)
+    any_day_of_month: bool,
+    /// Day-of-week field starts with `*`
+    any_day_of_week: bool,
+}
+
+impl CronSchedule {
+    /// Parse a five-field cron expression or an alias such as `@daily`
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expanded = match expression.trim().to_ascii_lowercase().as_str() {
+            "@hourly" => "0 * * * *".to_string(),
+            "@daily" | "@midnight" => "0 0 * * *".to_string(),
+            "@weekly" => "0 0 * * 0".to_string(),
+            "@monthly" => "0 0 1 * *".to_string(),
+            "@yearly" | "@annually" => "0 0 1 1 *".to_string(),
+            other => other.to_string(),
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, dom, month, dow] = fields[..] else {
+            return Err(format!(
+                "Invalid cron expression '{expression}': expected 5 fields, got {}",
+                fields.len()
+            ));
+        };
+
+        let field_err =
+            |name: &str, e: String| format!("Invalid {name} field in '{expression}': {e}");
+        let minutes = parse_field(minute, 0, 59, &[]).map_err(|e| field_err("minute", e))?;
+        let hours = parse_field(hour, 0, 23, &[]).map_err(|e| field_err("hour", e))?;
+        let days_of_month =
+            parse_field(dom, 1, 31, &[]).map_err(|e| field_err("day-of-month", e))?;
+        let months = parse_field(month, 1, 12, &MONTH_NAMES).map_err(|e| field_err("month", e))?;
+        let mut days_of_week =
+            parse_field(dow, 0, 7, &DAY_NAMES).map_err(|e| field_err("day-of-week", e))?;
+        // 7 is an alias for Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes,
+            hours: hours as u32,
+            days_of_month: days_of_month as u32,
+            months: months as u16,
+            days_of_week: days_of_week as u8,
+            any_day_of_month: dom.starts_with('*'),
+            any_day_of_week: dow.starts_with('*'),
+        })
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let dom = self.days_of_month & (1 << date.day()) != 0;
+        let dow = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        // Vixie cron: the day fields are ORed only when neither starts with `*`
+        if self.any_day_of_month || self.any_day_of_week {
+            dom && dow
+        } else {
+            dom || dow
+        }
+    }
+
+    /// First matching minute strictly after `after`, in `after`'s timezone
+    ///
+    /// Local times skipped by a DST transition never fire; times repeated by
+    /// one fire once (at the earlier instant).
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+
+        for offset in 0..MAX_SEARCH_DAYS {
+            let date = start.date() + Duration::days(offset);
+            if !self.matches_date(date) {
+                continue;
+            }
+            for hour in 0..24u32 {
+                if self.hours & (1 << hour) == 0 || (offset == 0 && hour < start.hour()) {
+                    continue;
+                }
+                for minute in 0..60u32 {
+                    if self.minutes & (1 << minute) == 0
+                        || (offset == 0 && hour == start.hour() && minute < start.minute())
+                    {
+                        continue;
+                    }
+                    let naive = date.and_hms_opt(hour, minute, 0)?;
+                    match tz.from_local_datetime(&naive) {
+                        LocalResult::Single(t) => return Some(t),
+                        LocalResult::Ambiguous(earliest, _) => return Some(earliest),
+                        LocalResult::None => continue,
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Parse one cron field into a bitset of the values it allows
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let value = |s: &str| -> Result<u32, String> {
+        if let Some(i) = names.iter().position(|n| *n == s) {
+            return Ok(min + i as u32);
+        }
+        let v: u32 = s.parse().map_err(|_| format!("'{s}' is not a number"))?;
+        if v < min || v > max {
+            return Err(format!("{v} is outside {min}-{max}"));
+        }
+        Ok(v)
+    };
+
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("'{step}' is not a valid step"))?;
+                if step == 0 {
+                    return Err("step cannot be 0".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (value(a)?, value(b)?)
+        } else {
+            let start = value(range)?;
+            // `5/15` means "from 5 to the end, every 15"
+            (start, if part.contains('/') { max } else { start })
+        };
+        if start > end {
+            return Err(format!("range {start}-{end} is reversed"));
+        }
+
+        for v in (start..=end).step_by(step as usize) {
+            bits |= 1 << v;
+        }
+    }
+    Ok(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn next(expr: &str, after: &str) -> String {
+        CronSchedule::parse(expr)
+            .unwrap()
+            .next_after(&at(after))
+            .unwrap()
+            .to_rfc3339()
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid() {
+        assert!(CronSchedule::parse("* * * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("* * 0 * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("5-1 * * * *").is_err());
+        assert!(CronSchedule::parse("@sometimes").is_err());
+    }
+
+    #[test]
+    fn test_weekdays_at_nine() {
+        // 2026-10-16 is a Friday
+        let expr = "0 9 * * 1-5";
+        assert_eq!(
+            next(expr, "2026-10-16T08:30:00Z"),
+            "2026-10-16T09:00:00+00:00"
+        );
+        assert_eq!(
+            next(expr, "2026-10-16T09:00:00Z"),
+            "2026-10-19T09:00:00+00:00"
+        );
+        assert_eq!(
+            next("0 9 * * mon-fri", "2026-10-17T12:00:00Z"),
+            "2026-10-19T09:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_steps_lists_and_aliases() {
+        assert_eq!(
+            next("*/15 * * * *", "2026-10-16T10:07:42Z"),
+            "2026-10-16T10:15:00+00:00"
+        );
+        assert_eq!(
+            next("0 2,14 * * *", "2026-10-16T03:00:00Z"),
+            "2026-10-16T14:00:00+00:00"
+        );
+        assert_eq!(
+            next("@monthly", "2026-10-16T03:00:00Z"),
+            "2026-11-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            next("0 0 * * 7", "2026-10-16T03:00:00Z"),
+            "2026-10-18T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_day_fields_are_ored_when_both_restricted() {
+        // The 1st of the month or any Monday, whichever comes first
+        assert_eq!(
+            next("0 0 1 * mon", "2026-10-16T00:00:00Z"),
+            "2026-10-19T00:00:00+00:00"
+        );
+        assert_eq!(
+            next("0 0 1 * mon", "2026-10-27T00:00:00Z"),
+            "2026-11-01T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_stepped_star_day_field_is_not_ored() {
+        // Odd days of the month that are Mondays: `*/2` is ANDed, not ORed
+        assert_eq!(
+            next("0 0 */2 * mon", "2026-10-16T00:00:00Z"),
+            "2026-10-19T00:00:00+00:00"
+        );
+        assert_eq!(
+            next("0 0 */2 * mon", "2026-10-20T00:00:00Z"),
+            "2026-11-09T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_leap_day() {
+        assert_eq!(
+            next("0 0 29 2 *", "2026-10-16T00:00:00Z"),
+            "2028-02-29T00:00:00+00:00"
+        );
+    }
+}
@@ -0,0 +1,796 @@
+//! Scheduled and recurring session runs
+//!
+//! Schedules (persisted in `schedules.json` in the app data directory) pair a
+//! prompt and send options with a trigger: a cron expression evaluated in the
+//! local timezone (see [`cron`]) or a single point in time. The scheduler
+//! thread checks for due schedules every few seconds and fires them through
+//! `send_chat_message`, so a scheduled run behaves exactly like one started
+//! from the chat input. If the target session is busy the prompt is queued
+//! behind the current run instead (see `chat::queue`), and the run is finished
+//! when the queued message's own run ends.
+//!
+//! Runs that were due while the app was not running are recorded as skipped
+//! unless they are less than [`MISSED_RUN_GRACE_SECS`] late.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{Local, TimeZone};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+use crate::chat::types::{QueuedMessage, ThinkingLevel};
+use crate::http_server::EmitExt;
+use crate::projects::storage::load_projects_data;
+use crate::projects::types::SessionType;
+
+pub mod commands;
+pub mod cron;
+
+use cron::CronSchedule;
+
+/// How often the scheduler thread checks for due schedules (seconds)
+const SCHEDULER_TICK_SECS: u64 = 5;
+
+/// A run this late (e.g. the app was closed at the time) is skipped, not fired
+pub const MISSED_RUN_GRACE_SECS: u64 = 15 * 60;
+
+/// Number of past runs kept per schedule
+const MAX_SCHEDULE_HISTORY: usize = 20;
+
+/// Serializes reads and writes of the schedules file
+static SCHEDULES_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// When a schedule fires
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduleTrigger {
+    /// Recurring, five-field cron expression in local time (e.g. `0 9 * * 1-5`)
+    Cron { expression: String },
+    /// Once, at a Unix timestamp
+    Once { at: u64 },
+}
+
+/// Where a scheduled prompt is sent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleTarget {
+    pub project_id: String,
+    /// Worktree to run in; `None` means the project's base session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree_id: Option<String>,
+    /// Session to send to; `None` creates a new session for every run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+}
+
+/// How a scheduled run ended
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleOutcome {
+    /// Sent and still running
+    Running,
+    /// Claude responded
+    Completed,
+    /// The run was cancelled by the user
+    Cancelled,
+    /// The target session was busy; the prompt is waiting in its queue
+    Queued,
+    /// Not sent (missed while the app was closed)
+    Skipped,
+    /// Sending failed (target missing, budget exceeded, CLI error, ...)
+    Failed,
+}
+
+/// One firing of a schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleRun {
+    /// Unique identifier (UUID v4); empty for runs recorded before runs had ids
+    #[serde(default)]
+    pub id: String,
+    /// Unix timestamp the run was due
+    pub scheduled_for: u64,
+    /// Unix timestamp the run was started (or skipped)
+    pub fired_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+    pub outcome: ScheduleOutcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Assistant message produced by the run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+    /// Queued message carrying the prompt, when the target session was busy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queued_message_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ScheduleRun {
+    fn new(scheduled_for: u64, fired_at: u64, outcome: ScheduleOutcome) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            scheduled_for,
+            fired_at,
+            finished_at: None,
+            outcome,
+            worktree_id: None,
+            session_id: None,
+            message_id: None,
+            queued_message_id: None,
+            error: None,
+        }
+    }
+
+    /// Record how the run ended (or that its prompt was queued)
+    fn finish(&mut self, result: Result<(ScheduleOutcome, Option<String>), String>) {
+        match result {
+            Ok((outcome, message_id)) => {
+                self.outcome = outcome;
+                self.message_id = message_id;
+            }
+            Err(e) => {
+                self.outcome = ScheduleOutcome::Failed;
+                self.error = Some(e);
+            }
+        }
+        if self.outcome != ScheduleOutcome::Queued {
+            self.finished_at = Some(now());
+        }
+    }
+}
+
+/// A persisted schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schedule {
+    /// Unique identifier (UUID v4)
+    pub id: String,
+    pub name: String,
+    /// Prompt sent on every run
+    pub prompt: String,
+    pub trigger: ScheduleTrigger,
+    pub target: ScheduleTarget,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking_level: Option<ThinkingLevel>,
+    pub enabled: bool,
+    /// Unix timestamp when the schedule was created
+    pub created_at: u64,
+    /// Unix timestamp of the next run (`None` when disabled or finished)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_run_at: Option<u64>,
+    /// Past runs, most recent last
+    #[serde(default)]
+    pub history: Vec<ScheduleRun>,
+}
+
+impl Schedule {
+    /// Recompute `next_run_at` for runs strictly after `after`
+    pub fn reschedule(&mut self, after: u64) -> Result<(), String> {
+        self.next_run_at = if self.enabled {
+            next_fire_time(&self.trigger, after)?
+        } else {
+            None
+        };
+        Ok(())
+    }
+
+    fn record_run(&mut self, run: ScheduleRun) {
+        self.history.push(run);
+        if self.history.len() > MAX_SCHEDULE_HISTORY {
+            let excess = self.history.len() - MAX_SCHEDULE_HISTORY;
+            self.history.drain(..excess);
+        }
+    }
+}
+
+/// Next time `trigger` fires strictly after `after`, evaluated in local time
+pub fn next_fire_time(trigger: &ScheduleTrigger, after: u64) -> Result<Option<u64>, String> {
+    match trigger {
+        ScheduleTrigger::Cron { expression } => {
+            let cron = CronSchedule::parse(expression)?;
+            let after = Local
+                .timestamp_opt(after as i64, 0)
+                .single()
+                .ok_or_else(|| format!("Invalid timestamp: {after}"))?;
+            Ok(cron.next_after(&after).map(|t| t.timestamp() as u64))
+        }
+        ScheduleTrigger::Once { at } => Ok((*at > after).then_some(*at)),
+    }
+}
+
+/// Payload for `schedule:fired` and `schedule:finished`
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleRunEvent {
+    pub schedule_id: String,
+    pub schedule_name: String,
+    pub run: ScheduleRun,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+fn get_schedules_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    std::fs::create_dir_all(&app_data_dir)
+        .map_err(|e| format!("Failed to create app data directory: {e}"))?;
+    Ok(app_data_dir.join("schedules.json"))
+}
+
+fn load_schedules_internal(app: &AppHandle) -> Result<Vec<Schedule>, String> {
+    let path = get_schedules_path(app)?;
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read schedules file: {e}"))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Failed to parse schedules file: {e}"))
+}
+
+fn save_schedules_internal(app: &AppHandle, schedules: &[Schedule]) -> Result<(), String> {
+    let path = get_schedules_path(app)?;
+    let json = serde_json::to_string_pretty(schedules)
+        .map_err(|e| format!("Failed to serialize schedules: {e}"))?;
+
+    // Write to a temporary file first, then rename (atomic operation)
+    let temp_path = path.with_extension("tmp");
+    std::fs::write(&temp_path, json).map_err(|e| format!("Failed to write schedules file: {e}"))?;
+    std::fs::rename(&temp_path, &path)
+        .map_err(|e| format!("Failed to finalize schedules file: {e}"))
+}
+
+/// Load all schedules
+pub fn load_schedules(app: &AppHandle) -> Result<Vec<Schedule>, String> {
+    let _lock = SCHEDULES_LOCK.lock().unwrap();
+    load_schedules_internal(app)
+}
+
+/// Load, modify and save the schedules under the lock
+pub fn with_schedules_mut<F, T>(app: &AppHandle, f: F) -> Result<T, String>
+where
+    F: FnOnce(&mut Vec<Schedule>) -> Result<T, String>,
+{
+    let _lock = SCHEDULES_LOCK.lock().unwrap();
+    let mut schedules = load_schedules_internal(app)?;
+    let result = f(&mut schedules)?;
+    save_schedules_internal(app, &schedules)?;
+    Ok(result)
+}
+
+/// Update a run of a schedule by id
+fn update_run<F>(app: &AppHandle, schedule_id: &str, run_id: &str, f: F) -> Option<ScheduleRun>
+where
+    F: FnOnce(&mut ScheduleRun),
+{
+    let result = with_schedules_mut(app, |schedules| {
+        Ok(schedules
+            .iter_mut()
+            .find(|s| s.id == schedule_id)
+            .and_then(|s| s.history.iter_mut().find(|r| r.id == run_id))
+            .map(|run| {
+                f(run);
+                run.clone()
+            }))
+    });
+    match result {
+        Ok(run) => run,
+        Err(e) => {
+            log::error!("Failed to record run of schedule {schedule_id}: {e}");
+            None
+        }
+    }
+}
+
+// ============================================================================
+// Scheduler
+// ============================================================================
+
+/// Runs the scheduler loop on a background thread
+pub struct SchedulerManager {
+    app: AppHandle,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl SchedulerManager {
+    /// Create a new scheduler
+    pub fn new(app: AppHandle) -> Self {
+        Self {
+            app,
+            shutdown: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Start the scheduler loop
+    pub fn start(&self) {
+        log::trace!("Starting scheduler");
+
+        let app = self.app.clone();
+        let shutdown = Arc::clone(&self.shutdown);
+
+        thread::spawn(move || {
+            log::trace!("Scheduler loop started");
+            recover_interrupted_runs(&app);
+
+            loop {
+                if shutdown.load(Ordering::Relaxed) {
+                    log::trace!("Scheduler shutting down");
+                    break;
+                }
+
+                for due in collect_due_runs(&app, now()) {
+                    fire(&app, due);
+                }
+
+                thread::sleep(Duration::from_secs(SCHEDULER_TICK_SECS));
+            }
+        });
+    }
+
+    /// Signal the scheduler to stop
+    pub fn stop(&self) {
+        log::trace!("Signaling scheduler to stop");
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Whether a queued run's message is still waiting in its session's queue
+fn is_still_queued(app: &AppHandle, run: &ScheduleRun) -> bool {
+    let (Some(session_id), Some(item_id)) = (&run.session_id, &run.queued_message_id) else {
+        return false;
+    };
+    crate::chat::storage::load_metadata(app, session_id)
+        .ok()
+        .flatten()
+        .is_some_and(|m| m.message_queue.iter().any(|item| &item.id == item_id))
+}
+
+/// Mark runs left `Running` by a previous app instance as failed
+///
+/// Queued runs survive a restart as long as their message is still queued;
+/// one that was already being sent was interrupted like any other run.
+fn recover_interrupted_runs(app: &AppHandle) {
+    let result = with_schedules_mut(app, |schedules| {
+        let finished_at = now();
+        for run in schedules.iter_mut().flat_map(|s| s.history.iter_mut()) {
+            let interrupted = match run.outcome {
+                ScheduleOutcome::Running => true,
+                ScheduleOutcome::Queued => !is_still_queued(app, run),
+                _ => false,
+            };
+            if interrupted {
+                run.outcome = ScheduleOutcome::Failed;
+                run.finished_at = Some(finished_at);
+                run.error = Some("Interrupted by app shutdown".to_string());
+            }
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        log::error!("Failed to recover interrupted scheduled runs: {e}");
+    }
+}
+
+/// A schedule that is due, with the time it was due at
+struct DueRun {
+    schedule: Schedule,
+    scheduled_for: u64,
+}
+
+/// Find due schedules, advance their `next_run_at` and record missed runs
+///
+/// Advancing under the lock guarantees each due time fires at most once.
+fn collect_due_runs(app: &AppHandle, now: u64) -> Vec<DueRun> {
+    // Cheap read first so idle ticks don't rewrite the file
+    match load_schedules(app) {
+        Ok(schedules)
+            if schedules
+                .iter()
+                .any(|s| s.next_run_at.is_some_and(|t| t <= now)) => {}
+        Ok(_) => return vec![],
+        Err(e) => {
+            log::error!("Failed to load schedules: {e}");
+            return vec![];
+        }
+    }
+
+    let result = with_schedules_mut(app, |schedules| {
+        let mut due = Vec::new();
+        let mut skipped = Vec::new();
+        for schedule in schedules.iter_mut() {
+            let Some(scheduled_for) = schedule.next_run_at.filter(|t| *t <= now) else {
+                continue;
+            };
+            if schedule.enabled {
+                if now - scheduled_for > MISSED_RUN_GRACE_SECS {
+                    let run = ScheduleRun {
+                        finished_at: Some(now),
+                        error: Some("Missed while the app was not running".to_string()),
+                        ..ScheduleRun::new(scheduled_for, now, ScheduleOutcome::Skipped)
+                    };
+                    schedule.record_run(run.clone());
+                    skipped.push((schedule.clone(), run));
+                } else {
+                    due.push(DueRun {
+                        schedule: schedule.clone(),
+                        scheduled_for,
+                    });
+                }
+            }
+
+            if matches!(schedule.trigger, ScheduleTrigger::Once { .. }) {
+                schedule.enabled = false;
+            }
+            if let Err(e) = schedule.reschedule(now) {
+                log::warn!(
+                    "Disabling schedule {} ({}): {e}",
+                    schedule.id,
+                    schedule.name
+                );
+                schedule.enabled = false;
+                schedule.next_run_at = None;
+            }
+        }
+        Ok((due, skipped))
+    });
+
+    match result {
+        Ok((due, skipped)) => {
+            for (schedule, run) in skipped {
+                log::trace!("Skipped missed run of schedule {}", schedule.id);
+                emit_run_event(app, "schedule:finished", &schedule, run);
+            }
+            due
+        }
+        Err(e) => {
+            log::error!("Failed to check schedules: {e}");
+            vec![]
+        }
+    }
+}
+
+fn emit_run_event(app: &AppHandle, event: &str, schedule: &Schedule, run: ScheduleRun) {
+    let payload = ScheduleRunEvent {
+        schedule_id: schedule.id.clone(),
+        schedule_name: schedule.name.clone(),
+        run,
+    };
+    if let Err(e) = app.emit_all(event, &payload) {
+        log::error!("Failed to emit {event} event: {e}");
+    }
+}
+
+/// Resolve a target to (worktree_id, worktree_path)
+fn resolve_worktree(app: &AppHandle, target: &ScheduleTarget) -> Result<(String, String), String> {
+    let data = load_projects_data(app)?;
+    let project = data
+        .find_project(&target.project_id)
+        .ok_or_else(|| format!("Project not found: {}", target.project_id))?;
+
+    let worktree = match &target.worktree_id {
+        Some(worktree_id) => data
+            .find_worktree(worktree_id)
+            .filter(|w| w.project_id == project.id)
+            .ok_or_else(|| format!("Worktree not found: {worktree_id}"))?,
+        None => data
+            .worktrees_for_project(&project.id)
+            .into_iter()
+            .find(|w| w.session_type == SessionType::Base)
+            .ok_or_else(|| format!("Project {} has no open base session", project.name))?,
+    };
+    Ok((worktree.id.clone(), worktree.path.clone()))
+}
+
+/// Fire a schedule now, recording the run in its history
+fn fire(app: &AppHandle, due: DueRun) {
+    let DueRun {
+        schedule,
+        scheduled_for,
+    } = due;
+    log::trace!("Firing schedule {} ({})", schedule.id, schedule.name);
+
+    let run = ScheduleRun::new(scheduled_for, now(), ScheduleOutcome::Running);
+    let run_id = run.id.clone();
+    let recorded = with_schedules_mut(app, |schedules| {
+        if let Some(s) = schedules.iter_mut().find(|s| s.id == schedule.id) {
+            s.record_run(run.clone());
+        }
+        Ok(())
+    });
+    if let Err(e) = recorded {
+        log::error!("Failed to record run of schedule {}: {e}", schedule.id);
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = send_scheduled_prompt(&app, &schedule, &run_id).await;
+        if let Err(e) = &result {
+            log::warn!("Scheduled run of {} failed: {e}", schedule.id);
+        }
+        // A queued prompt may already have been sent and finished
+        let mut updated = false;
+        let run = update_run(&app, &schedule.id, &run_id, |run| {
+            if run.finished_at.is_none() {
+                run.finish(result);
+                updated = true;
+            }
+        });
+        if let Some(run) = run.filter(|_| updated) {
+            emit_finished_or_queued(&app, &schedule, run);
+        }
+    });
+}
+
+fn emit_finished_or_queued(app: &AppHandle, schedule: &Schedule, run: ScheduleRun) {
+    let event = if run.outcome == ScheduleOutcome::Queued {
+        "schedule:queued"
+    } else {
+        "schedule:finished"
+    };
+    emit_run_event(app, event, schedule, run);
+}
+
+/// Finish the run whose prompt was queued as `queued_message_id`, if any
+///
+/// Called by `chat::queue` when a queued message's run ends or the message is
+/// removed from the queue.
+pub fn finish_queued_run(
+    app: &AppHandle,
+    queued_message_id: &str,
+    result: Result<(ScheduleOutcome, Option<String>), String>,
+) {
+    let is_match = |run: &ScheduleRun| {
+        run.outcome == ScheduleOutcome::Queued
+            && run.queued_message_id.as_deref() == Some(queued_message_id)
+    };
+
+    // Cheap read first: most queued messages weren't queued by a schedule
+    let found = match load_schedules(app) {
+        Ok(schedules) => schedules.into_iter().find_map(|s| {
+            let run_id = s.history.iter().find(|r| is_match(r))?.id.clone();
+            Some((s, run_id))
+        }),
+        Err(e) => {
+            log::error!("Failed to load schedules: {e}");
+            return;
+        }
+    };
+    let Some((schedule, run_id)) = found else {
+        return;
+    };
+
+    let finished = update_run(app, &schedule.id, &run_id, |run| {
+        if is_match(run) {
+            run.finish(result);
+        }
+    });
+    if let Some(run) = finished.filter(|run| run.outcome != ScheduleOutcome::Queued) {
+        emit_run_event(app, "schedule:finished", &schedule, run);
+    }
+}
+
+/// Send a schedule's prompt to its target, returning the outcome
+async fn send_scheduled_prompt(
+    app: &AppHandle,
+    schedule: &Schedule,
+    run_id: &str,
+) -> Result<(ScheduleOutcome, Option<String>), String> {
+    let (worktree_id, worktree_path) = resolve_worktree(app, &schedule.target)?;
+
+    let session_id = match &schedule.target.session_id {
+        Some(session_id) => {
+            crate::chat::storage::load_index(app, &worktree_id)?
+                .find_session(session_id)
+                .ok_or_else(|| format!("Session not found: {session_id}"))?;
+            session_id.clone()
+        }
+        None => {
+            crate::chat::create_session(
+                app.clone(),
+                worktree_id.clone(),
+                worktree_path.clone(),
+                Some(schedule.name.clone()),
+            )
+            .await?
+            .id
+        }
+    };
+
+    if let Some(run) = update_run(app, &schedule.id, run_id, |run| {
+        run.worktree_id = Some(worktree_id.clone());
+        run.session_id = Some(session_id.clone());
+    }) {
+        emit_run_event(app, "schedule:fired", schedule, run);
+    }
+
+    let metadata = crate::chat::storage::load_metadata(app, &session_id)?;
+    if crate::chat::queue::is_session_busy(&session_id, metadata.as_ref()) {
+        let item = QueuedMessage {
+            id: Uuid::new_v4().to_string(),
+            message: schedule.prompt.clone(),
+            model: schedule.model.clone(),
+            execution_mode: schedule.execution_mode.clone(),
+            thinking_level: schedule.thinking_level.clone(),
+            disable_thinking_for_mode: None,
+            parallel_execution_prompt_enabled: None,
+            ai_language: None,
+            allowed_tools: None,
+            queued_at: now(),
+        };
+        // Link the run before queueing so `finish_queued_run` can always find it
+        update_run(app, &schedule.id, run_id, |run| {
+            run.outcome = ScheduleOutcome::Queued;
+            run.queued_message_id = Some(item.id.clone());
+        });
+        crate::chat::queue::enqueue(app, &session_id, &worktree_id, &worktree_path, item)?;
+        return Ok((ScheduleOutcome::Queued, None));
+    }
+
+    let message = crate::chat::send_chat_message(
+        app.clone(),
+        session_id,
+        worktree_id,
+        worktree_path,
+        schedule.prompt.clone(),
+        schedule.model.clone(),
+        schedule.execution_mode.clone(),
+        schedule.thinking_level.clone(),
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await?;
+
+    let outcome = if message.cancelled {
+        ScheduleOutcome::Cancelled
+    } else {
+        ScheduleOutcome::Completed
+    };
+    Ok((outcome, Some(message.id)))
+}
+
+/// Fire a schedule immediately, independent of its trigger
+pub fn run_now(app: &AppHandle, schedule: Schedule) {
+    fire(
+        app,
+        DueRun {
+            schedule,
+            scheduled_for: now(),
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(trigger: ScheduleTrigger) -> Schedule {
+        Schedule {
+            id: "s1".to_string(),
+            name: "Audit".to_string(),
+            prompt: "Run the dependency audit".to_string(),
+            trigger,
+            target: ScheduleTarget {
+                project_id: "p1".to_string(),
+                worktree_id: None,
+                session_id: None,
+            },
+            model: None,
+            execution_mode: None,
+            thinking_level: None,
+            enabled: true,
+            created_at: 0,
+            next_run_at: None,
+            history: vec![],
+        }
+    }
+
+    #[test]
+    fn test_once_trigger_fires_only_in_future() {
+        let trigger = ScheduleTrigger::Once { at: 1_000 };
+        assert_eq!(next_fire_time(&trigger, 999), Ok(Some(1_000)));
+        assert_eq!(next_fire_time(&trigger, 1_000), Ok(None));
+    }
+
+    #[test]
+    fn test_cron_trigger_next_fire_time() {
+        let trigger = ScheduleTrigger::Cron {
+            expression: "*/5 * * * *".to_string(),
+        };
+        let after = 1_790_000_000; // on a whole minute
+        let next = next_fire_time(&trigger, after).unwrap().unwrap();
+        assert!(next > after && next <= after + 5 * 60);
+        assert_eq!(next % 60, 0);
+
+        let invalid = ScheduleTrigger::Cron {
+            expression: "every day".to_string(),
+        };
+        assert!(next_fire_time(&invalid, after).is_err());
+    }
+
+    #[test]
+    fn test_reschedule_respects_enabled() {
+        let mut s = schedule(ScheduleTrigger::Once { at: 1_000 });
+        s.reschedule(500).unwrap();
+        assert_eq!(s.next_run_at, Some(1_000));
+        s.enabled = false;
+        s.reschedule(500).unwrap();
+        assert_eq!(s.next_run_at, None);
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let mut s = schedule(ScheduleTrigger::Once { at: 1_000 });
+        for i in 0..(MAX_SCHEDULE_HISTORY as u64 + 5) {
+            s.record_run(ScheduleRun::new(i, i, ScheduleOutcome::Completed));
+        }
+        assert_eq!(s.history.len(), MAX_SCHEDULE_HISTORY);
+        assert_eq!(s.history[0].scheduled_for, 5);
+    }
+
+    #[test]
+    fn test_runs_have_unique_ids() {
+        // Two runs due at the same second (e.g. "Run now" twice) stay distinct
+        let a = ScheduleRun::new(1_000, 1_000, ScheduleOutcome::Running);
+        let b = ScheduleRun::new(1_000, 1_000, ScheduleOutcome::Running);
+        assert_ne!(a.id, b.id);
+
+        // Runs recorded before runs had ids still load
+        let json = r#"{"scheduled_for":1,"fired_at":1,"outcome":"completed"}"#;
+        let legacy: ScheduleRun = serde_json::from_str(json).unwrap();
+        assert!(legacy.id.is_empty());
+    }
+
+    #[test]
+    fn test_queued_run_finishes_later() {
+        let mut run = ScheduleRun::new(1_000, 1_000, ScheduleOutcome::Running);
+        run.finish(Ok((ScheduleOutcome::Queued, None)));
+        assert_eq!(run.outcome, ScheduleOutcome::Queued);
+        assert_eq!(run.finished_at, None);
+
+        run.finish(Ok((ScheduleOutcome::Completed, Some("m1".to_string()))));
+        assert_eq!(run.outcome, ScheduleOutcome::Completed);
+        assert_eq!(run.message_id.as_deref(), Some("m1"));
+        assert!(run.finished_at.is_some());
+
+        let mut failed = ScheduleRun::new(1_000, 1_000, ScheduleOutcome::Running);
+        failed.finish(Err("Budget exceeded".to_string()));
+        assert_eq!(failed.outcome, ScheduleOutcome::Failed);
+        assert_eq!(failed.error.as_deref(), Some("Budget exceeded"));
+    }
+
+    #[test]
+    fn test_trigger_serialization() {
+        let json = serde_json::to_value(ScheduleTrigger::Cron {
+            expression: "0 9 * * 1-5".to_string(),
+        })
+        .unwrap();
+        assert_eq!(json["type"], "cron");
+        assert_eq!(json["expression"], "0 9 * * 1-5");
+    }
+}
@@ -1,10 +1,14 @@
 use tauri::AppHandle;
 
 use super::pty::{
-    kill_all_terminals as pty_kill_all_terminals, kill_terminal, resize_terminal, spawn_terminal,
-    write_to_terminal,
+    get_scrollback, kill_all_terminals as pty_kill_all_terminals, kill_terminal,
+    list_terminals as pty_list_terminals, resize_terminal, spawn_terminal, write_to_terminal,
 };
-use super::registry::{get_all_terminal_ids, has_terminal};
+use super::recording;
+use super::registry::{get_all_terminal_ids, has_terminal, with_terminal};
+use super::types::{TerminalInfo, TerminalRecording, TerminalRecordingData, TerminalScrollback};
+use crate::chat::storage::get_pastes_dir;
+use crate::chat::types::SaveTextResponse;
 use crate::projects::git::read_jean_config;
 
 /// Start a terminal
@@ -65,9 +69,119 @@ pub async fn has_active_terminal(terminal_id: String) -> bool {
     has_terminal(&terminal_id)
 }
 
+/// List running terminals, including ones reattached after a restart
+#[tauri::command]
+pub async fn list_terminals() -> Vec<TerminalInfo> {
+    pty_list_terminals()
+}
+
+/// Get recent output of a running terminal, for replay before live output
+#[tauri::command]
+pub async fn get_terminal_scrollback(terminal_id: String) -> Result<TerminalScrollback, String> {
+    get_scrollback(&terminal_id)
+}
+
 /// Kill all active terminals (used during app shutdown/refresh)
 #[tauri::command]
 pub fn kill_all_terminals() -> usize {
     log::trace!("kill_all_terminals command invoked");
     pty_kill_all_terminals()
 }
+
+// ============================================================================
+// Recordings
+// ============================================================================
+
+/// Largest transcript attached to a chat; longer ones keep their end
+const MAX_TRANSCRIPT_SIZE: usize = 1024 * 1024;
+
+/// Start recording a terminal to an asciicast file
+#[tauri::command]
+pub async fn start_terminal_recording(
+    app: AppHandle,
+    terminal_id: String,
+    include_input: Option<bool>,
+) -> Result<TerminalRecording, String> {
+    let (worktree_path, command, cols, rows) = with_terminal(&terminal_id, |session| {
+        (
+            session.worktree_path.clone(),
+            session.command.clone(),
+            session.cols,
+            session.rows,
+        )
+    })
+    .ok_or_else(|| "Terminal not found".to_string())?;
+
+    recording::start_recording(
+        &app,
+        &terminal_id,
+        &worktree_path,
+        command,
+        cols,
+        rows,
+        include_input.unwrap_or(false),
+    )
+}
+
+/// Stop recording a terminal, returning the recording id
+#[tauri::command]
+pub async fn stop_terminal_recording(terminal_id: String) -> Option<String> {
+    recording::stop_recording(&terminal_id)
+}
+
+/// List terminal recordings, newest first
+#[tauri::command]
+pub async fn list_terminal_recordings(app: AppHandle) -> Result<Vec<TerminalRecording>, String> {
+    recording::list_recordings(&app)
+}
+
+/// Read a recording's events, for replaying it
+#[tauri::command]
+pub async fn read_terminal_recording(
+    app: AppHandle,
+    recording_id: String,
+) -> Result<TerminalRecordingData, String> {
+    recording::read_recording(&app, &recording_id)
+}
+
+/// Delete a terminal recording
+#[tauri::command]
+pub async fn delete_terminal_recording(app: AppHandle, recording_id: String) -> Result<(), String> {
+    recording::delete_recording(&app, &recording_id)
+}
+
+/// Save a recording's output as a text file to attach to a chat session
+///
+/// The file is stored with pasted texts, so the frontend attaches it like a
+/// large paste.
+#[tauri::command]
+pub async fn attach_terminal_recording(
+    app: AppHandle,
+    recording_id: String,
+) -> Result<SaveTextResponse, String> {
+    let data = recording::read_recording(&app, &recording_id)?;
+    let mut transcript = recording::transcript(&data.events);
+    if transcript.len() > MAX_TRANSCRIPT_SIZE {
+        let mut cut = transcript.len() - MAX_TRANSCRIPT_SIZE;
+        while !transcript.is_char_boundary(cut) {
+            cut += 1;
+        }
+        transcript.drain(..cut);
+    }
+    let content = format!(
+        "Terminal recording: {}\n\n{}\n",
+        data.recording.title,
+        transcript.trim_end()
+    );
+
+    let filename = format!("terminal-{}.txt", data.recording.id);
+    let file_path = get_pastes_dir(&app)?.join(&filename);
+    std::fs::write(&file_path, &content).map_err(|e| format!("Failed to write transcript: {e}"))?;
+
+    Ok(SaveTextResponse {
+        id: uuid::Uuid::new_v4().to_string(),
+        filename,
+        path: file_path.to_string_lossy().into_owned(),
+        size: content.len(),
+    })
+}
@@ -0,0 +1,209 @@
+//! Framing of raw PTY output
+//!
+//! PTY reads end at arbitrary byte boundaries, so a multibyte UTF-8
+//! character or an escape sequence can be split between two reads. Reads are
+//! coalesced into frames bounded by time and size, which also keeps chatty
+//! programs from flooding clients with tiny events, and `OutputDecoder` holds
+//! back an incomplete character or escape sequence at the end of a frame until
+//! the next one completes it.
+
+use std::io::Read;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Longest time output is held back to coalesce it with following output
+pub const FRAME_INTERVAL: Duration = Duration::from_millis(8);
+
+/// Frames are flushed early once they reach this size
+pub const MAX_FRAME_SIZE: usize = 64 * 1024;
+
+/// Longest unterminated escape sequence held back; longer ones are passed on
+const MAX_ESCAPE_CARRY: usize = 256;
+
+/// Read a PTY on a background thread, sending chunks until EOF or an error
+pub fn read_chunks(mut reader: Box<dyn Read + Send>) -> Receiver<Vec<u8>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buf = [0u8; 4096];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    if tx.send(buf[..n].to_vec()).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    log::trace!("Error reading from terminal: {e}");
+                    break;
+                }
+            }
+        }
+    });
+    rx
+}
+
+/// Coalesce chunks into frames, calling `on_frame` for each until the sender
+/// is dropped
+///
+/// A frame is flushed `FRAME_INTERVAL` after its first chunk arrived, or as
+/// soon as it reaches `MAX_FRAME_SIZE`.
+pub fn coalesce(chunks: Receiver<Vec<u8>>, mut on_frame: impl FnMut(&[u8])) {
+    while let Ok(mut frame) = chunks.recv() {
+        let deadline = Instant::now() + FRAME_INTERVAL;
+        while frame.len() < MAX_FRAME_SIZE {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            match chunks.recv_timeout(timeout) {
+                Ok(chunk) => frame.extend_from_slice(&chunk),
+                Err(_) => break,
+            }
+        }
+        on_frame(&frame);
+    }
+}
+
+/// Turns frames of a terminal's output into text
+#[derive(Default)]
+pub struct OutputDecoder {
+    /// Incomplete character or escape sequence from the previous frame
+    pending: Vec<u8>,
+}
+
+impl OutputDecoder {
+    /// Bytes held back from previous frames
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Decode a frame, holding back an incomplete trailing character or
+    /// escape sequence; invalid UTF-8 becomes replacement characters
+    pub fn decode(&mut self, bytes: &[u8]) -> String {
+        self.pending.extend_from_slice(bytes);
+        let complete = complete_len(&self.pending);
+        let text = String::from_utf8_lossy(&self.pending[..complete]).into_owned();
+        self.pending.drain(..complete);
+        text
+    }
+}
+
+/// Length of `bytes` without an incomplete character or escape sequence at
+/// the end, i.e. what `OutputDecoder` would pass on
+pub fn complete_len(bytes: &[u8]) -> usize {
+    let tail = incomplete_escape_len(bytes).max(incomplete_utf8_len(bytes));
+    bytes.len() - tail
+}
+
+/// Bytes of a UTF-8 character cut off at the end of `bytes`
+fn incomplete_utf8_len(bytes: &[u8]) -> usize {
+    for i in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - i];
+        if byte & 0xc0 == 0x80 {
+            // Continuation byte; look further back for the lead byte
+            continue;
+        }
+        let needed = match byte {
+            0xf0.. => 4,
+            0xe0.. => 3,
+            0xc0.. => 2,
+            _ => 1,
+        };
+        return if needed > i { i } else { 0 };
+    }
+    0
+}
+
+/// Bytes of an escape sequence cut off at the end of `bytes`
+fn incomplete_escape_len(bytes: &[u8]) -> usize {
+    let window = &bytes[bytes.len().saturating_sub(MAX_ESCAPE_CARRY)..];
+    let Some(esc) = window.iter().rposition(|&b| b == 0x1b) else {
+        return 0;
+    };
+    let sequence = &window[esc..];
+    let complete = match sequence.get(1) {
+        None => false,
+        // CSI ends with a final byte in 0x40..=0x7e
+        Some(b'[') => sequence[2..].iter().any(|b| (0x40..=0x7e).contains(b)),
+        // OSC, DCS, APC and PM strings end with BEL or ST (ESC \); an ST
+        // would itself be the last escape, so only BEL can follow here
+        Some(b']' | b'P' | b'_' | b'^') => sequence.contains(&0x07),
+        // Character set selection takes one more byte
+        Some(b'(' | b')' | b'*' | b'+') => sequence.len() > 2,
+        Some(_) => true,
+    };
+    if complete {
+        0
+    } else {
+        sequence.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multibyte_characters_split_across_frames() {
+        let text = "日本語 🎉";
+        let bytes = text.as_bytes();
+        let mut decoder = OutputDecoder::default();
+
+        // Feed one byte at a time: every character must come out whole
+        let decoded: String = bytes.chunks(1).map(|b| decoder.decode(b)).collect();
+        assert_eq!(decoded, text);
+        assert_eq!(decoder.pending_len(), 0);
+
+        let mut decoder = OutputDecoder::default();
+        assert_eq!(decoder.decode(&bytes[..4]), "日");
+        assert_eq!(decoder.pending_len(), 1);
+        assert_eq!(decoder.decode(&bytes[4..]), "本語 🎉");
+    }
+
+    #[test]
+    fn test_invalid_bytes_are_replaced() {
+        let mut decoder = OutputDecoder::default();
+        assert_eq!(decoder.decode(b"a\xffb"), "a\u{fffd}b");
+        // A stray continuation byte isn't held back
+        assert_eq!(decoder.decode(b"\x80"), "\u{fffd}");
+    }
+
+    #[test]
+    fn test_escape_sequences_split_across_frames() {
+        let mut decoder = OutputDecoder::default();
+        assert_eq!(decoder.decode(b"ok \x1b[1;3"), "ok ");
+        assert_eq!(decoder.decode(b"2mgreen\x1b"), "\x1b[1;32mgreen");
+        assert_eq!(decoder.decode(b"[0m"), "\x1b[0m");
+
+        assert_eq!(decoder.decode(b"\x1b]0;tit"), "");
+        assert_eq!(decoder.decode(b"le\x07$ "), "\x1b]0;title\x07$ ");
+        // The string terminator completes, not the string itself
+        assert_eq!(decoder.decode(b"\x1b]8;;url\x1b"), "\x1b]8;;url");
+        assert_eq!(decoder.decode(b"\\x"), "\x1b\\x");
+    }
+
+    #[test]
+    fn test_long_unterminated_escape_is_passed_on() {
+        let mut decoder = OutputDecoder::default();
+        let mut bytes = b"\x1b]0;".to_vec();
+        bytes.resize(bytes.len() + MAX_ESCAPE_CARRY, b'a');
+        assert_eq!(decoder.decode(&bytes).len(), bytes.len());
+        assert_eq!(decoder.pending_len(), 0);
+    }
+
+    #[test]
+    fn test_coalesces_bursts_into_frames() {
+        let (tx, rx) = mpsc::channel();
+        for chunk in [b"ab".to_vec(), b"cd".to_vec()] {
+            tx.send(chunk).unwrap();
+        }
+        let late = thread::spawn(move || {
+            thread::sleep(FRAME_INTERVAL * 10);
+            tx.send(b"ef".to_vec()).unwrap();
+        });
+
+        let mut frames = Vec::new();
+        coalesce(rx, |frame| frames.push(frame.to_vec()));
+        late.join().unwrap();
+        assert_eq!(frames, vec![b"abcd".to_vec(), b"ef".to_vec()]);
+    }
+}
@@ -0,0 +1,777 @@
+//! Detached terminal hosts (Unix)
+//!
+//! A terminal's PTY is owned by a small detached process instead of Jean:
+//! Jean re-executes itself as `jean --terminal-host <dir>`, similar to how
+//! `chat::detached` keeps the Claude CLI running. The host survives Jean
+//! quitting, so a dev server started from the `jean.json` run script keeps
+//! running, and Jean reattaches to it with its scrollback on the next start.
+//!
+//! `<dir>` is `<app data>/terminals/<terminal id>/` and holds the host's
+//! `terminal.json`. The host listens on a Unix socket in a directory only the
+//! current user can access (see [`socket_dir`]) and serves one client
+//! at a time with JSON lines. On connect it sends a `hello` frame with its
+//! terminal id, which Jean checks before attaching, then its scrollback as a
+//! single `output` frame, live output, and finally `exit` when the shell exits.
+//! Output frames carry the stream offset of their first byte, so Jean's
+//! scrollback and `terminal:output` offsets continue the host's stream. The
+//! host removes its directory and socket when the shell exits.
+
+use std::fs;
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::Shutdown;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use portable_pty::{ChildKiller, MasterPty, PtySize};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+
+use super::framing::{coalesce, read_chunks, OutputDecoder};
+use super::pty::{build_command, emit_output, emit_stopped, open_pty, register_and_announce};
+use super::registry::{has_terminal, unregister_terminal};
+use super::scrollback::Scrollback;
+use super::types::{TerminalBackend, TerminalSession};
+
+/// Argument that makes the Jean binary run as a terminal host
+pub const HOST_ARG: &str = "--terminal-host";
+
+const SPEC_FILE: &str = "terminal.json";
+
+/// How long to wait for a new host to start listening
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A client that stops reading for this long is dropped by the host
+const CLIENT_WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What a host runs, written to its directory by Jean
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HostSpec {
+    pub terminal_id: String,
+    pub worktree_id: Option<String>,
+    pub worktree_path: String,
+    pub command: Option<String>,
+    pub cols: u16,
+    pub rows: u16,
+    pub socket_path: PathBuf,
+}
+
+impl HostSpec {
+    pub fn new(
+        terminal_id: &str,
+        worktree_id: Option<String>,
+        worktree_path: &str,
+        command: Option<String>,
+        cols: u16,
+        rows: u16,
+    ) -> Self {
+        Self {
+            terminal_id: terminal_id.to_string(),
+            worktree_id,
+            worktree_path: worktree_path.to_string(),
+            command,
+            cols,
+            rows,
+            socket_path: socket_path(terminal_id),
+        }
+    }
+}
+
+/// Longest `hello` frame accepted from a host
+const MAX_HELLO_BYTES: usize = 1024;
+
+/// Frames sent by a host to Jean
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HostEvent {
+    /// First frame on every connection, naming the terminal the host runs
+    Hello {
+        terminal_id: String,
+    },
+    /// Raw output (base64) starting at `offset` in the terminal's stream
+    Output {
+        data: String,
+        offset: u64,
+    },
+    Exit {
+        exit_code: Option<i32>,
+    },
+}
+
+impl HostEvent {
+    fn output(bytes: &[u8], offset: u64) -> Self {
+        Self::Output {
+            data: STANDARD.encode(bytes),
+            offset,
+        }
+    }
+}
+
+/// Frames sent by Jean to a host
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HostCommand {
+    Input { data: String },
+    Resize { cols: u16, rows: u16 },
+    Kill,
+}
+
+fn send_frame<T: Serialize>(mut stream: &UnixStream, frame: &T) -> Result<(), String> {
+    let mut line =
+        serde_json::to_string(frame).map_err(|e| format!("Failed to serialize frame: {e}"))?;
+    line.push('\n');
+    stream
+        .write_all(line.as_bytes())
+        .map_err(|e| format!("Failed to write to terminal host socket: {e}"))
+}
+
+/// Ids become directory and socket names, so only allow UUID-like ids
+fn is_valid_terminal_id(terminal_id: &str) -> bool {
+    !terminal_id.is_empty()
+        && terminal_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Directory holding the hosts' sockets
+///
+/// Socket paths are limited to about 100 bytes and the app data dir on macOS
+/// uses up most of that, so this is `$XDG_RUNTIME_DIR/jean` or a per-user
+/// directory in the temp dir.
+fn socket_dir() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty()) {
+        Some(runtime_dir) => PathBuf::from(runtime_dir).join("jean"),
+        None => std::env::temp_dir().join(format!("jean-{}", unsafe { libc::getuid() })),
+    }
+}
+
+/// Socket of a terminal's host, named after a hash of the terminal id so the
+/// name stays short whatever the id
+fn socket_path(terminal_id: &str) -> PathBuf {
+    let digest = Sha256::digest(terminal_id.as_bytes());
+    let short: String = digest[..12].iter().map(|b| format!("{b:02x}")).collect();
+    socket_dir().join(format!("term-{short}.sock"))
+}
+
+/// Create the socket directory with mode 0700, or check that an existing one
+/// belongs to the current user and is closed to everyone else
+///
+/// The temp dir is shared, so another user could otherwise create the
+/// directory first and swap a host's socket for their own.
+fn ensure_socket_dir(dir: &Path) -> Result<(), String> {
+    match fs::DirBuilder::new().mode(0o700).create(dir) {
+        Ok(()) => return Ok(()),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+        Err(e) => return Err(format!("Failed to create terminal socket directory: {e}")),
+    }
+    let metadata = fs::symlink_metadata(dir)
+        .map_err(|e| format!("Failed to inspect terminal socket directory: {e}"))?;
+    let uid = unsafe { libc::getuid() };
+    if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+        return Err(format!(
+            "Terminal socket directory {} must be a directory only the current user can access",
+            dir.display()
+        ));
+    }
+    Ok(())
+}
+
+fn ensure_socket_parent(socket_path: &Path) -> Result<(), String> {
+    let dir = socket_path
+        .parent()
+        .ok_or_else(|| format!("Invalid terminal socket path: {}", socket_path.display()))?;
+    ensure_socket_dir(dir)
+}
+
+fn terminals_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    Ok(app_data_dir.join("terminals"))
+}
+
+fn read_spec(dir: &Path) -> Result<HostSpec, String> {
+    let content = fs::read_to_string(dir.join(SPEC_FILE))
+        .map_err(|e| format!("Failed to read terminal spec: {e}"))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse terminal spec: {e}"))
+}
+
+fn write_spec(dir: &Path, spec: &HostSpec) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(spec)
+        .map_err(|e| format!("Failed to serialize terminal spec: {e}"))?;
+    fs::write(dir.join(SPEC_FILE), content)
+        .map_err(|e| format!("Failed to write terminal spec: {e}"))
+}
+
+// ============================================================================
+// Host process
+// ============================================================================
+
+/// Directory passed to `--terminal-host`, if this process is a terminal host
+pub fn host_dir_from_args(args: &[String]) -> Option<PathBuf> {
+    let position = args.iter().position(|arg| arg == HOST_ARG)?;
+    args.get(position + 1).map(PathBuf::from)
+}
+
+/// The host's PTY, shared with the thread serving the client
+struct HostPty {
+    master: Mutex<Box<dyn MasterPty + Send>>,
+    writer: Mutex<Box<dyn Write + Send>>,
+    killer: Mutex<Box<dyn ChildKiller + Send + Sync>>,
+    pid: Option<u32>,
+}
+
+impl HostPty {
+    fn apply(&self, command: HostCommand) {
+        match command {
+            HostCommand::Input { data } => {
+                let mut writer = self.writer.lock().unwrap();
+                let _ = writer.write_all(data.as_bytes());
+                let _ = writer.flush();
+            }
+            HostCommand::Resize { cols, rows } => {
+                let _ = self.master.lock().unwrap().resize(PtySize {
+                    rows,
+                    cols,
+                    pixel_width: 0,
+                    pixel_height: 0,
+                });
+            }
+            HostCommand::Kill => {
+                // Try graceful termination first, like in-process terminals
+                if let Some(pid) = self.pid {
+                    let _ = crate::platform::terminate_process(pid);
+                }
+                let _ = self.killer.lock().unwrap().kill();
+            }
+        }
+    }
+}
+
+/// Output and the connected client, if any
+struct HostState {
+    scrollback: Scrollback,
+    client: Option<UnixStream>,
+}
+
+/// Run as a terminal host until the shell exits
+pub fn run_host(dir: &Path) -> Result<(), String> {
+    let spec = read_spec(dir)?;
+
+    ensure_socket_parent(&spec.socket_path)?;
+    // Binding fails if the socket exists; it may belong to a live host, so it
+    // is never removed here
+    let listener = UnixListener::bind(&spec.socket_path)
+        .map_err(|e| format!("Failed to listen on terminal socket: {e}"))?;
+    // Only the current user may drive the shell
+    fs::set_permissions(&spec.socket_path, fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("Failed to restrict terminal socket: {e}"))?;
+
+    let pair = open_pty(spec.cols, spec.rows)?;
+    let mut child = pair
+        .slave
+        .spawn_command(build_command(&spec.worktree_path, spec.command.as_deref()))
+        .map_err(|e| format!("Failed to spawn shell: {e}"))?;
+    // Only the shell keeps the slave open, so reading ends when it exits
+    drop(pair.slave);
+
+    let reader = pair
+        .master
+        .try_clone_reader()
+        .map_err(|e| format!("Failed to clone reader: {e}"))?;
+    let writer = pair
+        .master
+        .take_writer()
+        .map_err(|e| format!("Failed to take writer: {e}"))?;
+    let pty = Arc::new(HostPty {
+        master: Mutex::new(pair.master),
+        writer: Mutex::new(writer),
+        killer: Mutex::new(child.clone_killer()),
+        pid: child.process_id(),
+    });
+
+    let state = Arc::new(Mutex::new(HostState {
+        scrollback: Scrollback::default(),
+        client: None,
+    }));
+
+    let accept_state = state.clone();
+    let terminal_id = spec.terminal_id.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            attach_client(&accept_state, &pty, &terminal_id, stream);
+        }
+    });
+
+    // Frames are coalesced here too, so the socket isn't flooded either
+    coalesce(read_chunks(reader), |frame| {
+        let mut state = state.lock().unwrap();
+        let offset = state.scrollback.push(frame);
+        let event = HostEvent::output(frame, offset);
+        let failed = state
+            .client
+            .as_ref()
+            .is_some_and(|client| send_frame(client, &event).is_err());
+        if failed {
+            state.client = None;
+        }
+    });
+
+    let exit_code = child.wait().ok().and_then(|s| {
+        if s.success() {
+            Some(0)
+        } else {
+            // portable-pty ExitStatus doesn't expose code directly
+            None
+        }
+    });
+
+    let state = state.lock().unwrap();
+    if let Some(client) = &state.client {
+        let _ = send_frame(client, &HostEvent::Exit { exit_code });
+    }
+    let _ = fs::remove_file(&spec.socket_path);
+    let _ = fs::remove_dir_all(dir);
+    Ok(())
+}
+
+/// Greet a new client and send it the scrollback; it takes over from the
+/// previous one
+fn attach_client(
+    state: &Mutex<HostState>,
+    pty: &Arc<HostPty>,
+    terminal_id: &str,
+    stream: UnixStream,
+) {
+    let _ = stream.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT));
+    let Ok(commands) = stream.try_clone() else {
+        return;
+    };
+    let hello = HostEvent::Hello {
+        terminal_id: terminal_id.to_string(),
+    };
+    if send_frame(&stream, &hello).is_err() {
+        return;
+    }
+
+    let mut state = state.lock().unwrap();
+    let (start, bytes) = state.scrollback.contents();
+    if send_frame(&stream, &HostEvent::output(&bytes, start)).is_err() {
+        return;
+    }
+    if let Some(previous) = state.client.replace(stream) {
+        let _ = previous.shutdown(Shutdown::Both);
+    }
+    drop(state);
+
+    let pty = pty.clone();
+    thread::spawn(move || {
+        for line in BufReader::new(commands).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if let Ok(command) = serde_json::from_str::<HostCommand>(&line) {
+                pty.apply(command);
+            }
+        }
+    });
+}
+
+// ============================================================================
+// Jean side
+// ============================================================================
+
+/// Jean's connection to a terminal host
+pub struct HostClient {
+    stream: UnixStream,
+}
+
+impl HostClient {
+    fn send(&self, command: &HostCommand) -> Result<(), String> {
+        send_frame(&self.stream, command)
+    }
+
+    pub fn write(&self, data: &str) -> Result<(), String> {
+        self.send(&HostCommand::Input {
+            data: data.to_string(),
+        })
+    }
+
+    pub fn resize(&self, cols: u16, rows: u16) -> Result<(), String> {
+        self.send(&HostCommand::Resize { cols, rows })
+    }
+
+    pub fn kill(&self) -> Result<(), String> {
+        self.send(&HostCommand::Kill)
+    }
+}
+
+/// Command that starts the Jean binary as a terminal host
+fn host_command(dir: &Path) -> Result<Command, String> {
+    // Inside an AppImage the executable lives in a mount that goes away when
+    // Jean quits, so start the AppImage itself
+    let exe = match std::env::var_os("APPIMAGE") {
+        Some(appimage) => PathBuf::from(appimage),
+        None => {
+            std::env::current_exe().map_err(|e| format!("Failed to get current executable: {e}"))?
+        }
+    };
+
+    let mut command = Command::new(exe);
+    command
+        .arg(HOST_ARG)
+        .arg(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // A new session keeps the host alive when Jean's process group or
+    // controlling terminal goes away
+    unsafe {
+        command.pre_exec(|| {
+            libc::setsid();
+            Ok(())
+        });
+    }
+    Ok(command)
+}
+
+/// Connect to a new host's socket once it listens, failing early if the host
+/// exits first (e.g. because it couldn't bind the socket)
+fn connect_new_host(
+    socket_path: &Path,
+    host: &mut Child,
+    timeout: Duration,
+) -> Result<UnixStream, String> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match UnixStream::connect(socket_path) {
+            Ok(stream) => return Ok(stream),
+            Err(e) if Instant::now() >= deadline => {
+                return Err(format!("Failed to connect to terminal host: {e}"));
+            }
+            Err(_) => {
+                if let Ok(Some(status)) = host.try_wait() {
+                    return Err(format!("Terminal host exited before listening ({status})"));
+                }
+                thread::sleep(Duration::from_millis(20));
+            }
+        }
+    }
+}
+
+/// Read the host's `hello` frame and check that it runs `terminal_id`
+///
+/// Bytes are read one at a time so nothing after the frame is consumed.
+fn handshake(stream: &UnixStream, terminal_id: &str) -> Result<(), String> {
+    stream
+        .set_read_timeout(Some(CONNECT_TIMEOUT))
+        .map_err(|e| format!("Failed to configure terminal host socket: {e}"))?;
+    let mut line = Vec::new();
+    let mut byte = [0u8; 1];
+    while line.len() < MAX_HELLO_BYTES {
+        match (&*stream).read(&mut byte) {
+            Ok(0) => break,
+            Ok(_) if byte[0] == b'\n' => break,
+            Ok(_) => line.push(byte[0]),
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(format!("Failed to read from terminal host: {e}")),
+        }
+    }
+    stream
+        .set_read_timeout(None)
+        .map_err(|e| format!("Failed to configure terminal host socket: {e}"))?;
+
+    match serde_json::from_slice::<HostEvent>(&line) {
+        Ok(HostEvent::Hello { terminal_id: id }) if id == terminal_id => Ok(()),
+        Ok(HostEvent::Hello { terminal_id: id }) => Err(format!(
+            "Terminal host socket belongs to {id}, not {terminal_id}"
+        )),
+        _ => Err("Terminal host did not identify itself".to_string()),
+    }
+}
+
+/// Start a terminal in a new host and attach to it
+pub fn spawn_host(app: &AppHandle, spec: HostSpec) -> Result<(), String> {
+    if !is_valid_terminal_id(&spec.terminal_id) {
+        return Err(format!("Invalid terminal id: {}", spec.terminal_id));
+    }
+    ensure_socket_parent(&spec.socket_path)?;
+
+    let dir = terminals_dir(app)?.join(&spec.terminal_id);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create terminal directory: {e}"))?;
+    write_spec(&dir, &spec)?;
+
+    let mut host = match host_command(&dir)?.spawn() {
+        Ok(host) => host,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&dir);
+            return Err(format!("Failed to start terminal host: {e}"));
+        }
+    };
+    let connected = connect_new_host(&spec.socket_path, &mut host, CONNECT_TIMEOUT)
+        .and_then(|stream| handshake(&stream, &spec.terminal_id).map(|()| stream));
+    let stream = match connected {
+        Ok(stream) => stream,
+        Err(e) => {
+            let _ = host.kill();
+            let _ = host.wait();
+            let _ = fs::remove_dir_all(&dir);
+            return Err(e);
+        }
+    };
+    log::trace!("Terminal host started for {}", spec.terminal_id);
+
+    // Reap the host if its shell exits while Jean is running
+    thread::spawn(move || {
+        let _ = host.wait();
+    });
+
+    attach(app, spec, stream)
+}
+
+/// Reattach to hosts left running by a previous Jean process
+///
+/// Directories whose host is gone are removed. Returns the number of
+/// terminals reattached.
+pub fn reattach_terminals(app: &AppHandle) -> usize {
+    let Ok(entries) = terminals_dir(app).and_then(|dir| {
+        fs::read_dir(dir).map_err(|e| format!("Failed to read terminals directory: {e}"))
+    }) else {
+        return 0;
+    };
+
+    let mut count = 0;
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        let attached = read_spec(&dir).and_then(|spec| {
+            if has_terminal(&spec.terminal_id) {
+                return Ok(());
+            }
+            let stream = UnixStream::connect(&spec.socket_path).map_err(|e| {
+                let _ = fs::remove_file(&spec.socket_path);
+                format!("Terminal host is gone: {e}")
+            })?;
+            // The socket may now belong to another terminal's host; leave it
+            handshake(&stream, &spec.terminal_id)?;
+            attach(app, spec, stream)
+        });
+        match attached {
+            Ok(()) => count += 1,
+            Err(e) => {
+                log::debug!("Removing stale terminal {}: {e}", dir.display());
+                let _ = fs::remove_dir_all(&dir);
+            }
+        }
+    }
+
+    log::trace!("Reattached {count} terminal(s)");
+    count
+}
+
+/// Register a hosted terminal and forward its output
+fn attach(app: &AppHandle, spec: HostSpec, stream: UnixStream) -> Result<(), String> {
+    let reader = stream
+        .try_clone()
+        .map_err(|e| format!("Failed to clone terminal host socket: {e}"))?;
+    let scrollback = Arc::new(Mutex::new(Scrollback::default()));
+    let terminal_id = spec.terminal_id.clone();
+    let worktree_id = spec.worktree_id.clone();
+
+    register_and_announce(
+        app,
+        TerminalSession {
+            terminal_id: spec.terminal_id,
+            worktree_id: spec.worktree_id,
+            worktree_path: spec.worktree_path,
+            command: spec.command,
+            backend: TerminalBackend::Hosted(HostClient { stream }),
+            cols: spec.cols,
+            rows: spec.rows,
+            scrollback: scrollback.clone(),
+        },
+    );
+
+    let app = app.clone();
+    thread::spawn(move || {
+        let mut exit_code = None;
+        let mut decoder = OutputDecoder::default();
+        for line in BufReader::new(reader).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            match serde_json::from_str::<HostEvent>(&line) {
+                Ok(HostEvent::Output { data, offset }) => {
+                    let Ok(bytes) = STANDARD.decode(data) else {
+                        continue;
+                    };
+                    let mut scrollback = scrollback.lock().unwrap();
+                    // The first frame is the host's scrollback, which
+                    // usually starts mid-stream
+                    if scrollback.end() != offset {
+                        *scrollback = Scrollback::resume_at(offset);
+                        decoder = OutputDecoder::default();
+                    }
+                    emit_output(
+                        &app,
+                        &terminal_id,
+                        &worktree_id,
+                        &mut scrollback,
+                        &mut decoder,
+                        &bytes,
+                    );
+                }
+                Ok(HostEvent::Exit { exit_code: code }) => {
+                    exit_code = code;
+                    break;
+                }
+                Ok(HostEvent::Hello { .. }) => {}
+                Err(e) => log::warn!("Invalid frame from terminal host {terminal_id}: {e}"),
+            }
+        }
+
+        log::trace!("Terminal host closed for: {terminal_id}");
+        if let Some(session) = unregister_terminal(&terminal_id) {
+            emit_stopped(&app, &session, exit_code);
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_frames_are_tagged_json() {
+        let event = HostEvent::output(b"hi\n", 42);
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({ "type": "output", "data": "aGkK", "offset": 42 })
+        );
+        assert_eq!(
+            serde_json::from_value::<HostEvent>(json!({ "type": "exit", "exit_code": null }))
+                .unwrap(),
+            HostEvent::Exit { exit_code: None }
+        );
+        assert_eq!(
+            serde_json::from_value::<HostCommand>(
+                json!({ "type": "resize", "cols": 80, "rows": 24 })
+            )
+            .unwrap(),
+            HostCommand::Resize { cols: 80, rows: 24 }
+        );
+        assert_eq!(
+            serde_json::to_value(HostCommand::Kill).unwrap(),
+            json!({ "type": "kill" })
+        );
+    }
+
+    #[test]
+    fn test_host_dir_from_args() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            host_dir_from_args(&args(&["jean", HOST_ARG, "/tmp/t1"])),
+            Some(PathBuf::from("/tmp/t1"))
+        );
+        assert_eq!(host_dir_from_args(&args(&["jean", HOST_ARG])), None);
+        assert_eq!(host_dir_from_args(&args(&["jean", "--headless"])), None);
+    }
+
+    #[test]
+    fn test_terminal_ids_are_safe_paths() {
+        assert!(is_valid_terminal_id("6f1c2a9e-8b7d-4c3e-9a10-2b3c4d5e6f70"));
+        assert!(!is_valid_terminal_id(""));
+        assert!(!is_valid_terminal_id("../../etc"));
+        assert!(!is_valid_terminal_id("a/b"));
+
+        let socket = socket_path("6f1c2a9e-8b7d-4c3e-9a10-2b3c4d5e6f70");
+        let name = socket.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("term-") && name.ends_with(".sock"));
+        assert_eq!(name.len(), "term-.sock".len() + 24);
+        assert_eq!(socket.parent(), Some(socket_dir().as_path()));
+    }
+
+    #[test]
+    fn test_socket_names_use_the_whole_id() {
+        // Ids sharing their first 16 alphanumerics used to share a socket
+        let a = socket_path("6f1c2a9e-8b7d-4c3e-9a10-2b3c4d5e6f70");
+        let b = socket_path("6f1c2a9e-8b7d-4c3e-0000-000000000000");
+        assert_ne!(a, b);
+        assert_eq!(a, socket_path("6f1c2a9e-8b7d-4c3e-9a10-2b3c4d5e6f70"));
+    }
+
+    #[test]
+    fn test_handshake_checks_terminal_id() {
+        let hello = |id: &str| {
+            let (host, jean) = UnixStream::pair().unwrap();
+            send_frame(
+                &host,
+                &HostEvent::Hello {
+                    terminal_id: id.to_string(),
+                },
+            )
+            .unwrap();
+            send_frame(&host, &HostEvent::output(b"hi", 0)).unwrap();
+            (host, jean)
+        };
+
+        let (_host, jean) = hello("t1");
+        handshake(&jean, "t1").unwrap();
+        // The frame after the handshake is left for the reader
+        let mut next = String::new();
+        BufReader::new(&jean).read_line(&mut next).unwrap();
+        assert!(next.contains(r#""type":"output""#));
+
+        let (_host, jean) = hello("t2");
+        assert!(handshake(&jean, "t1").is_err());
+
+        let (host, jean) = UnixStream::pair().unwrap();
+        drop(host);
+        assert!(handshake(&jean, "t1").is_err());
+    }
+
+    #[test]
+    fn test_connect_fails_when_host_exits() {
+        let temp = tempfile::tempdir().unwrap();
+        let mut host = Command::new("true").spawn().unwrap();
+        let started = Instant::now();
+        let result = connect_new_host(
+            &temp.path().join("missing.sock"),
+            &mut host,
+            Duration::from_secs(5),
+        );
+        assert!(result.unwrap_err().contains("exited"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_socket_dir_must_be_private() {
+        let temp = tempfile::tempdir().unwrap();
+
+        let fresh = temp.path().join("fresh");
+        ensure_socket_dir(&fresh).unwrap();
+        let mode = fs::metadata(&fresh).unwrap().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        ensure_socket_dir(&fresh).unwrap();
+
+        let shared = temp.path().join("shared");
+        fs::create_dir(&shared).unwrap();
+        fs::set_permissions(&shared, fs::Permissions::from_mode(0o777)).unwrap();
+        assert!(ensure_socket_dir(&shared).is_err());
+
+        let link = temp.path().join("link");
+        std::os::unix::fs::symlink(&fresh, &link).unwrap();
+        assert!(ensure_socket_dir(&link).is_err());
+    }
+}
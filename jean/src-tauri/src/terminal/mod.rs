@@ -1,10 +1,22 @@
 mod commands;
+mod framing;
+#[cfg(unix)]
+mod host;
 mod pty;
+mod recording;
 mod registry;
+mod scrollback;
 mod types;
 
 // Re-export commands for registration in lib.rs
 pub use commands::*;
 
 // Re-export internal functions for app lifecycle cleanup
-pub use pty::kill_all_terminals as cleanup_all_terminals;
+pub use pty::shutdown_terminals as cleanup_all_terminals;
+
+// Detached terminal hosts: the host entry point and reattaching on startup
+#[cfg(unix)]
+pub use host::{host_dir_from_args, reattach_terminals, run_host as run_terminal_host};
+
+// Used to authorize remote terminal commands by project
+pub use registry::terminal_worktree_id;
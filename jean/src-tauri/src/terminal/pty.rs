@@ -1,12 +1,18 @@
-use portable_pty::{native_pty_system, CommandBuilder, PtySize};
-use std::io::Read;
-use std::sync::Mutex;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use portable_pty::{native_pty_system, CommandBuilder, PtyPair, PtySize};
+use std::sync::{Arc, Mutex};
 use std::thread;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::framing::{coalesce, complete_len, read_chunks, OutputDecoder};
+use super::registry::{register_terminal, unregister_terminal, with_terminal};
+use super::scrollback::Scrollback;
+use crate::http_server::{EmitExt, WsBroadcaster};
 
-use super::registry::{register_terminal, unregister_terminal};
 use super::types::{
-    TerminalOutputEvent, TerminalSession, TerminalStartedEvent, TerminalStoppedEvent,
+    TerminalBackend, TerminalInfo, TerminalOutputEvent, TerminalScrollback, TerminalSession,
+    TerminalStartedEvent, TerminalStoppedEvent,
 };
 
 /// Detect user's default shell (cross-platform)
@@ -14,38 +20,23 @@ fn get_user_shell() -> String {
     crate::platform::get_default_shell()
 }
 
-/// Spawn a terminal, optionally running a command
-pub fn spawn_terminal(
-    app: &AppHandle,
-    terminal_id: String,
-    worktree_path: String,
-    cols: u16,
-    rows: u16,
-    command: Option<String>,
-) -> Result<(), String> {
-    log::trace!("Spawning terminal {terminal_id} at {worktree_path}");
-    if let Some(ref cmd) = command {
-        log::trace!("Running command: {cmd}");
-    }
-
-    let pty_system = native_pty_system();
-
-    // Create PTY pair
-    let pair = pty_system
-        .openpty(PtySize {
-            rows,
-            cols,
-            pixel_width: 0,
-            pixel_height: 0,
-        })
-        .map_err(|e| format!("Failed to open PTY: {e}"))?;
+/// Id of the worktree at `worktree_path`, so events can be routed by project
+fn find_worktree_id(app: &AppHandle, worktree_path: &str) -> Option<String> {
+    let data = crate::projects::storage::load_projects_data(app).ok()?;
+    data.worktrees
+        .iter()
+        .find(|w| w.path == worktree_path)
+        .map(|w| w.id.clone())
+}
 
+/// Build the shell for a terminal, optionally running `command` in it
+pub(super) fn build_command(worktree_path: &str, command: Option<&str>) -> CommandBuilder {
     // Get user's shell
     let shell = get_user_shell();
     log::trace!("Using shell: {shell}");
 
     // Build command - either run a specific command or start interactive shell
-    let mut cmd = if let Some(ref run_command) = command {
+    let mut cmd = if let Some(run_command) = command {
         // Run the command in shell, then keep shell open for inspection
         let mut c = CommandBuilder::new(&shell);
         #[cfg(windows)]
@@ -68,21 +59,166 @@ pub fn spawn_terminal(
     } else {
         CommandBuilder::new(&shell)
     };
-    cmd.cwd(&worktree_path);
+    cmd.cwd(worktree_path);
     cmd.env("TERM", "xterm-256color");
     cmd.env("COLORTERM", "truecolor");
-    cmd.env("JEAN_WORKTREE_PATH", &worktree_path);
+    cmd.env("JEAN_WORKTREE_PATH", worktree_path);
+    cmd
+}
+
+/// Open a PTY pair of the given size
+pub(super) fn open_pty(cols: u16, rows: u16) -> Result<PtyPair, String> {
+    native_pty_system()
+        .openpty(PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+        .map_err(|e| format!("Failed to open PTY: {e}"))
+}
+
+/// Register a terminal and announce it to clients
+pub(super) fn register_and_announce(app: &AppHandle, session: TerminalSession) {
+    let started_event = TerminalStartedEvent {
+        terminal_id: session.terminal_id.clone(),
+        worktree_id: session.worktree_id.clone(),
+        cols: session.cols,
+        rows: session.rows,
+    };
+    register_terminal(session);
+
+    if let Err(e) = app.emit_all("terminal:started", &started_event) {
+        log::error!("Failed to emit terminal:started event: {e}");
+    }
+}
+
+/// Buffer a frame of output and emit it
+///
+/// Buffering and emitting happen under the same lock, so a scrollback
+/// snapshot either contains a frame or precedes its event. The native window
+/// gets the decoded text; WebSocket clients may ask for the raw bytes instead
+/// (see `http_server::subscriptions`), so the broadcast carries both.
+pub(super) fn emit_output(
+    app: &AppHandle,
+    terminal_id: &str,
+    worktree_id: &Option<String>,
+    scrollback: &mut Scrollback,
+    decoder: &mut OutputDecoder,
+    bytes: &[u8],
+) {
+    let raw_offset = scrollback.push(bytes);
+    // The text starts with bytes held back from the previous frame
+    let offset = raw_offset - decoder.pending_len() as u64;
+    let data = decoder.decode(bytes);
+    super::recording::record_output(terminal_id, &data);
+
+    let event = TerminalOutputEvent {
+        terminal_id: terminal_id.to_string(),
+        worktree_id: worktree_id.clone(),
+        data,
+        offset,
+    };
+    if !event.data.is_empty() {
+        if let Err(e) = app.emit("terminal:output", &event) {
+            log::error!("Failed to emit terminal:output event: {e}");
+        }
+    }
+
+    if let Some(ws) = app.try_state::<WsBroadcaster>() {
+        match serde_json::to_value(&event) {
+            Ok(mut payload) => {
+                payload["raw"] = STANDARD.encode(bytes).into();
+                payload["raw_offset"] = raw_offset.into();
+                ws.broadcast("terminal:output", &payload);
+            }
+            Err(e) => log::error!("Failed to serialize terminal:output event: {e}"),
+        }
+    }
+}
+
+/// Emit the stopped event of a terminal that was unregistered
+pub(super) fn emit_stopped(app: &AppHandle, session: &TerminalSession, exit_code: Option<i32>) {
+    let stopped_event = TerminalStoppedEvent {
+        terminal_id: session.terminal_id.clone(),
+        worktree_id: session.worktree_id.clone(),
+        exit_code,
+    };
+    if let Err(e) = app.emit_all("terminal:stopped", &stopped_event) {
+        log::error!("Failed to emit terminal:stopped event: {e}");
+    }
+}
+
+/// Spawn a terminal, optionally running a command
+///
+/// On Unix the PTY is owned by a detached terminal host, so the terminal
+/// survives Jean restarting. If the host can't be started, or on Windows,
+/// the PTY is owned by this process instead.
+pub fn spawn_terminal(
+    app: &AppHandle,
+    terminal_id: String,
+    worktree_path: String,
+    cols: u16,
+    rows: u16,
+    command: Option<String>,
+) -> Result<(), String> {
+    log::trace!("Spawning terminal {terminal_id} at {worktree_path}");
+    if let Some(ref cmd) = command {
+        log::trace!("Running command: {cmd}");
+    }
+
+    let worktree_id = find_worktree_id(app, &worktree_path);
+
+    #[cfg(unix)]
+    {
+        let spec = super::host::HostSpec::new(
+            &terminal_id,
+            worktree_id.clone(),
+            &worktree_path,
+            command.clone(),
+            cols,
+            rows,
+        );
+        match super::host::spawn_host(app, spec) {
+            Ok(()) => return Ok(()),
+            Err(e) => log::warn!("Failed to start terminal host, using an in-process PTY: {e}"),
+        }
+    }
+
+    spawn_local_terminal(
+        app,
+        terminal_id,
+        worktree_id,
+        worktree_path,
+        cols,
+        rows,
+        command,
+    )
+}
+
+/// Spawn a terminal whose PTY is owned by this process
+fn spawn_local_terminal(
+    app: &AppHandle,
+    terminal_id: String,
+    worktree_id: Option<String>,
+    worktree_path: String,
+    cols: u16,
+    rows: u16,
+    command: Option<String>,
+) -> Result<(), String> {
+    // Create PTY pair
+    let pair = open_pty(cols, rows)?;
 
     // Spawn the shell
     let child = pair
         .slave
-        .spawn_command(cmd)
+        .spawn_command(build_command(&worktree_path, command.as_deref()))
         .map_err(|e| format!("Failed to spawn shell: {e}"))?;
 
     log::trace!("Spawned terminal process");
 
     // Get reader from master
-    let mut reader = pair
+    let reader = pair
         .master
         .try_clone_reader()
         .map_err(|e| format!("Failed to clone reader: {e}"))?;
@@ -93,75 +229,59 @@ pub fn spawn_terminal(
         .take_writer()
         .map_err(|e| format!("Failed to take writer: {e}"))?;
 
-    // Register the session
-    let session = TerminalSession {
-        terminal_id: terminal_id.clone(),
-        master: pair.master,
-        writer: Mutex::new(writer),
-        child,
-        cols,
-        rows,
-    };
-    register_terminal(session);
-
-    // Emit started event
-    let started_event = TerminalStartedEvent {
-        terminal_id: terminal_id.clone(),
-        cols,
-        rows,
-    };
-    if let Err(e) = app.emit("terminal:started", &started_event) {
-        log::error!("Failed to emit terminal:started event: {e}");
-    }
+    let scrollback = Arc::new(Mutex::new(Scrollback::default()));
+
+    register_and_announce(
+        app,
+        TerminalSession {
+            terminal_id: terminal_id.clone(),
+            worktree_id: worktree_id.clone(),
+            worktree_path,
+            command,
+            backend: TerminalBackend::Local {
+                master: pair.master,
+                writer: Mutex::new(writer),
+                child,
+            },
+            cols,
+            rows,
+            scrollback: scrollback.clone(),
+        },
+    );
 
-    // Spawn reader thread
+    // Read on one thread and emit coalesced frames on another
+    let chunks = read_chunks(reader);
     let app_clone = app.clone();
-    let terminal_id_clone = terminal_id.clone();
     thread::spawn(move || {
-        let mut buf = [0u8; 4096];
-        loop {
-            match reader.read(&mut buf) {
-                Ok(0) => {
-                    // EOF - terminal closed
-                    log::trace!("Terminal EOF for: {terminal_id_clone}");
-                    break;
-                }
-                Ok(n) => {
-                    // Convert bytes to string (lossy conversion for non-UTF8)
-                    let data = String::from_utf8_lossy(&buf[..n]).to_string();
-                    let event = TerminalOutputEvent {
-                        terminal_id: terminal_id_clone.clone(),
-                        data,
-                    };
-                    if let Err(e) = app_clone.emit("terminal:output", &event) {
-                        log::error!("Failed to emit terminal:output event: {e}");
-                    }
-                }
-                Err(e) => {
-                    log::error!("Error reading from terminal: {e}");
-                    break;
-                }
-            }
-        }
+        let mut decoder = OutputDecoder::default();
+        coalesce(chunks, |frame| {
+            let mut scrollback = scrollback.lock().unwrap();
+            emit_output(
+                &app_clone,
+                &terminal_id,
+                &worktree_id,
+                &mut scrollback,
+                &mut decoder,
+                frame,
+            );
+        });
+        log::trace!("Terminal EOF for: {terminal_id}");
 
         // Terminal has exited, get exit code and cleanup
-        if let Some(mut session) = unregister_terminal(&terminal_id_clone) {
-            let exit_code = session.child.wait().ok().and_then(|s| {
-                if s.success() {
-                    Some(0)
-                } else {
-                    // portable-pty ExitStatus doesn't expose code directly
-                    None
-                }
-            });
-
-            let stopped_event = TerminalStoppedEvent {
-                terminal_id: terminal_id_clone,
-                exit_code,
+        if let Some(mut session) = unregister_terminal(&terminal_id) {
+            let exit_code = match &mut session.backend {
+                TerminalBackend::Local { child, .. } => child.wait().ok().and_then(|s| {
+                    if s.success() {
+                        Some(0)
+                    } else {
+                        // portable-pty ExitStatus doesn't expose code directly
+                        None
+                    }
+                }),
+                #[cfg(unix)]
+                TerminalBackend::Hosted(_) => None,
             };
-            if let Err(e) = app_clone.emit("terminal:stopped", &stopped_event) {
-                log::error!("Failed to emit terminal:stopped event: {e}");
-            }
+            emit_stopped(&app_clone, &session, exit_code);
         }
     });
 
@@ -172,92 +292,148 @@ pub fn spawn_terminal(
 pub fn write_to_terminal(terminal_id: &str, data: &str) -> Result<(), String> {
     use std::io::Write;
 
-    super::registry::with_terminal(terminal_id, |session| {
-        let mut writer = session
-            .writer
-            .lock()
-            .map_err(|e| format!("Failed to lock writer: {e}"))?;
-        writer
-            .write_all(data.as_bytes())
-            .map_err(|e| format!("Failed to write: {e}"))?;
-        writer.flush().map_err(|e| format!("Failed to flush: {e}"))
+    super::recording::record_input(terminal_id, data);
+    super::registry::with_terminal(terminal_id, |session| match &session.backend {
+        TerminalBackend::Local { writer, .. } => {
+            let mut writer = writer
+                .lock()
+                .map_err(|e| format!("Failed to lock writer: {e}"))?;
+            writer
+                .write_all(data.as_bytes())
+                .map_err(|e| format!("Failed to write: {e}"))?;
+            writer.flush().map_err(|e| format!("Failed to flush: {e}"))
+        }
+        #[cfg(unix)]
+        TerminalBackend::Hosted(client) => client.write(data),
     })
     .ok_or_else(|| "Terminal not found".to_string())?
 }
 
+/// Get the buffered output of a running terminal
+pub fn get_scrollback(terminal_id: &str) -> Result<TerminalScrollback, String> {
+    // Clone the handle so the registry isn't locked while copying the buffer
+    let (scrollback, cols, rows) = with_terminal(terminal_id, |session| {
+        (session.scrollback.clone(), session.cols, session.rows)
+    })
+    .ok_or_else(|| "Terminal not found".to_string())?;
+
+    let (start, mut bytes) = scrollback.lock().unwrap().contents();
+    // Leave out an incomplete tail; live output delivers it once completed
+    bytes.truncate(complete_len(&bytes));
+    Ok(TerminalScrollback {
+        terminal_id: terminal_id.to_string(),
+        data: String::from_utf8_lossy(&bytes).into_owned(),
+        start,
+        end: start + bytes.len() as u64,
+        cols,
+        rows,
+    })
+}
+
 /// Resize a terminal
 pub fn resize_terminal(terminal_id: &str, cols: u16, rows: u16) -> Result<(), String> {
     super::registry::with_terminal(terminal_id, |session| {
-        session
-            .master
-            .resize(PtySize {
-                rows,
-                cols,
-                pixel_width: 0,
-                pixel_height: 0,
-            })
-            .map_err(|e| format!("Failed to resize: {e}"))?;
+        match &session.backend {
+            TerminalBackend::Local { master, .. } => master
+                .resize(PtySize {
+                    rows,
+                    cols,
+                    pixel_width: 0,
+                    pixel_height: 0,
+                })
+                .map_err(|e| format!("Failed to resize: {e}"))?,
+            #[cfg(unix)]
+            TerminalBackend::Hosted(client) => client.resize(cols, rows)?,
+        }
         session.cols = cols;
         session.rows = rows;
+        super::recording::record_resize(terminal_id, cols, rows);
         Ok(())
     })
     .ok_or_else(|| "Terminal not found".to_string())?
 }
 
-/// Kill a terminal
-pub fn kill_terminal(app: &AppHandle, terminal_id: &str) -> Result<bool, String> {
-    if let Some(mut session) = unregister_terminal(terminal_id) {
-        // Kill the child process - try graceful termination first
-        if let Some(pid) = session.child.process_id() {
-            if let Err(e) = crate::platform::terminate_process(pid) {
-                log::trace!("Graceful termination of pid={pid} failed: {e}");
+/// List running terminals
+pub fn list_terminals() -> Vec<TerminalInfo> {
+    let sessions = super::registry::TERMINAL_SESSIONS.lock().unwrap();
+    sessions.values().map(TerminalSession::info).collect()
+}
+
+/// Kill the process behind a terminal that was unregistered
+fn kill_session(session: &mut TerminalSession) {
+    match &mut session.backend {
+        TerminalBackend::Local { child, .. } => {
+            // Kill the child process - try graceful termination first
+            if let Some(pid) = child.process_id() {
+                if let Err(e) = crate::platform::terminate_process(pid) {
+                    log::trace!("Graceful termination of pid={pid} failed: {e}");
+                }
             }
+            let _ = child.kill();
         }
-
-        // Wait for the process to exit
-        let _ = session.child.kill();
-
-        // Emit stopped event
-        let stopped_event = TerminalStoppedEvent {
-            terminal_id: terminal_id.to_string(),
-            exit_code: None,
-        };
-        if let Err(e) = app.emit("terminal:stopped", &stopped_event) {
-            log::error!("Failed to emit terminal:stopped event: {e}");
+        #[cfg(unix)]
+        TerminalBackend::Hosted(client) => {
+            if let Err(e) = client.kill() {
+                log::warn!("Failed to stop terminal {}: {e}", session.terminal_id);
+            }
         }
+    }
+}
 
+/// Kill a terminal
+pub fn kill_terminal(app: &AppHandle, terminal_id: &str) -> Result<bool, String> {
+    if let Some(mut session) = unregister_terminal(terminal_id) {
+        kill_session(&mut session);
+        emit_stopped(app, &session, None);
         Ok(true)
     } else {
         Ok(false)
     }
 }
 
-/// Kill all active terminals (used during app shutdown)
+/// Kill all active terminals, including those owned by terminal hosts
 pub fn kill_all_terminals() -> usize {
     use super::registry::TERMINAL_SESSIONS;
 
-    eprintln!("[TERMINAL CLEANUP] kill_all_terminals called");
-
     let mut sessions = TERMINAL_SESSIONS.lock().unwrap();
     let count = sessions.len();
+    for (terminal_id, mut session) in sessions.drain() {
+        super::recording::stop_recording(&terminal_id);
+        kill_session(&mut session);
+    }
+    log::trace!("Killed {count} terminal(s)");
+    count
+}
+
+/// Clean up terminals when Jean quits
+///
+/// Terminals owned by this process are killed. Terminals owned by a terminal
+/// host are only detached from: they keep running and are reattached on the
+/// next start. Returns the number of terminals killed.
+pub fn shutdown_terminals() -> usize {
+    use super::registry::TERMINAL_SESSIONS;
 
-    eprintln!("[TERMINAL CLEANUP] Found {count} active terminal(s)");
+    eprintln!("[TERMINAL CLEANUP] shutdown_terminals called");
 
-    for (terminal_id, mut session) in sessions.drain() {
-        eprintln!("[TERMINAL CLEANUP] Killing terminal: {terminal_id}");
+    let mut sessions = TERMINAL_SESSIONS.lock().unwrap();
+    eprintln!(
+        "[TERMINAL CLEANUP] Found {} active terminal(s)",
+        sessions.len()
+    );
 
-        if let Some(pid) = session.child.process_id() {
-            eprintln!("[TERMINAL CLEANUP] Sending terminate signal to PID {pid}");
-            if let Err(e) = crate::platform::terminate_process(pid) {
-                eprintln!("[TERMINAL CLEANUP] Graceful termination failed: {e}");
-            }
+    let mut killed = 0;
+    for (terminal_id, mut session) in sessions.drain() {
+        super::recording::stop_recording(&terminal_id);
+        if !matches!(session.backend, TerminalBackend::Local { .. }) {
+            eprintln!("[TERMINAL CLEANUP] Detaching from terminal: {terminal_id}");
+            continue;
         }
-
-        let _ = session.child.kill();
-        eprintln!("[TERMINAL CLEANUP] Killed terminal: {terminal_id}");
+        eprintln!("[TERMINAL CLEANUP] Killing terminal: {terminal_id}");
+        kill_session(&mut session);
+        killed += 1;
     }
 
-    eprintln!("[TERMINAL CLEANUP] Cleanup complete, killed {count} terminal(s)");
+    eprintln!("[TERMINAL CLEANUP] Cleanup complete, killed {killed} terminal(s)");
 
-    count
+    killed
 }
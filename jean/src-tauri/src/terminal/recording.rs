@@ -0,0 +1,413 @@
+//! Terminal recordings in asciicast v2 format
+//!
+//! A recording is `<app data>/terminal-recordings/<id>.cast`: a JSON header
+//! line followed by one `[time, code, data]` line per event, where `time` is
+//! seconds since the recording started and `code` is `o` for output, `i` for
+//! input (only when requested) or `r` for a resize (`"COLSxROWS"`). Files play
+//! in asciinema, can be replayed by clients, and can be turned into a plain
+//! text transcript to attach to a chat session.
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Write};
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
+use std::str::Chars;
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+use super::types::{RecordingEvent, TerminalRecording, TerminalRecordingData};
+
+/// Active recordings (terminal_id -> recorder)
+static RECORDERS: Lazy<Mutex<HashMap<String, Recorder>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// asciicast v2 header line
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct CastHeader {
+    version: u8,
+    width: u16,
+    height: u16,
+    #[serde(default)]
+    timestamp: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    env: HashMap<String, String>,
+}
+
+struct Recorder {
+    id: String,
+    file: File,
+    started: Instant,
+    include_input: bool,
+}
+
+impl Recorder {
+    fn write_event(&mut self, code: &str, data: &str) {
+        let line = event_line(self.started.elapsed().as_secs_f64(), code, data);
+        if let Err(e) = self.file.write_all(line.as_bytes()) {
+            log::warn!("Failed to write terminal recording {}: {e}", self.id);
+        }
+    }
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn event_line(time: f64, code: &str, data: &str) -> String {
+    // Microsecond precision, as written by asciinema
+    let time = (time * 1_000_000.0).round() / 1_000_000.0;
+    let mut line = serde_json::json!([time, code, data]).to_string();
+    line.push('\n');
+    line
+}
+
+fn get_recordings_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {e}"))?;
+    let path = app_data_dir.join("terminal-recordings");
+    fs::create_dir_all(&path).map_err(|e| format!("Failed to create recordings directory: {e}"))?;
+    Ok(path)
+}
+
+/// Path of a recording, rejecting ids that could escape the recordings dir
+fn recording_path(app: &AppHandle, recording_id: &str) -> Result<PathBuf, String> {
+    let valid = !recording_id.is_empty()
+        && recording_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if !valid {
+        return Err(format!("Invalid recording id: {recording_id}"));
+    }
+    Ok(get_recordings_dir(app)?.join(format!("{recording_id}.cast")))
+}
+
+/// Start recording a terminal
+pub fn start_recording(
+    app: &AppHandle,
+    terminal_id: &str,
+    worktree_path: &str,
+    command: Option<String>,
+    cols: u16,
+    rows: u16,
+    include_input: bool,
+) -> Result<TerminalRecording, String> {
+    let mut recorders = RECORDERS.lock().unwrap();
+    if recorders.contains_key(terminal_id) {
+        return Err("Terminal is already being recorded".to_string());
+    }
+
+    let started_at = now();
+    let id = format!("{started_at}-{}", &Uuid::new_v4().to_string()[..8]);
+    let path = recording_path(app, &id)?;
+
+    let title = command.clone().unwrap_or_else(|| {
+        Path::new(worktree_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "Terminal".to_string())
+    });
+    let header = CastHeader {
+        version: 2,
+        width: cols,
+        height: rows,
+        timestamp: started_at,
+        command: command.clone(),
+        title: Some(title.clone()),
+        env: HashMap::from([
+            ("TERM".to_string(), "xterm-256color".to_string()),
+            ("SHELL".to_string(), crate::platform::get_default_shell()),
+        ]),
+    };
+    let mut line = serde_json::to_string(&header)
+        .map_err(|e| format!("Failed to serialize recording header: {e}"))?;
+    line.push('\n');
+
+    let mut file =
+        File::create(&path).map_err(|e| format!("Failed to create recording file: {e}"))?;
+    file.write_all(line.as_bytes())
+        .map_err(|e| format!("Failed to write recording header: {e}"))?;
+
+    log::trace!("Recording terminal {terminal_id} to {}", path.display());
+    recorders.insert(
+        terminal_id.to_string(),
+        Recorder {
+            id: id.clone(),
+            file,
+            started: Instant::now(),
+            include_input,
+        },
+    );
+
+    Ok(TerminalRecording {
+        id,
+        title,
+        command,
+        cols,
+        rows,
+        started_at,
+        size: line.len() as u64,
+        path: path.to_string_lossy().into_owned(),
+        terminal_id: Some(terminal_id.to_string()),
+    })
+}
+
+/// Stop recording a terminal, returning the recording id
+pub fn stop_recording(terminal_id: &str) -> Option<String> {
+    let recorder = RECORDERS.lock().unwrap().remove(terminal_id)?;
+    log::trace!("Stopped recording terminal {terminal_id}");
+    Some(recorder.id)
+}
+
+fn record(terminal_id: &str, code: &str, data: &str) {
+    let mut recorders = RECORDERS.lock().unwrap();
+    if let Some(recorder) = recorders.get_mut(terminal_id) {
+        if code != "i" || recorder.include_input {
+            recorder.write_event(code, data);
+        }
+    }
+}
+
+pub fn record_output(terminal_id: &str, data: &str) {
+    record(terminal_id, "o", data);
+}
+
+pub fn record_input(terminal_id: &str, data: &str) {
+    record(terminal_id, "i", data);
+}
+
+pub fn record_resize(terminal_id: &str, cols: u16, rows: u16) {
+    record(terminal_id, "r", &format!("{cols}x{rows}"));
+}
+
+/// Terminal currently recording to `recording_id`, if any
+fn recording_terminal(recording_id: &str) -> Option<String> {
+    let recorders = RECORDERS.lock().unwrap();
+    recorders
+        .iter()
+        .find(|(_, recorder)| recorder.id == recording_id)
+        .map(|(terminal_id, _)| terminal_id.clone())
+}
+
+fn recording_info(path: &Path, header: CastHeader) -> Option<TerminalRecording> {
+    let id = path.file_stem()?.to_str()?.to_string();
+    let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    Some(TerminalRecording {
+        terminal_id: recording_terminal(&id),
+        title: header
+            .title
+            .or_else(|| header.command.clone())
+            .unwrap_or_else(|| id.clone()),
+        id,
+        command: header.command,
+        cols: header.width,
+        rows: header.height,
+        started_at: header.timestamp,
+        size,
+        path: path.to_string_lossy().into_owned(),
+    })
+}
+
+fn read_header(reader: &mut impl BufRead) -> Result<CastHeader, String> {
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .map_err(|e| format!("Failed to read recording: {e}"))?;
+    let header: CastHeader =
+        serde_json::from_str(&line).map_err(|e| format!("Invalid recording header: {e}"))?;
+    if header.version != 2 {
+        return Err(format!("Unsupported asciicast version: {}", header.version));
+    }
+    Ok(header)
+}
+
+/// Events of a recording; a truncated last line (e.g. after a crash) is skipped
+fn read_events(reader: impl BufRead) -> Vec<RecordingEvent> {
+    reader
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<(f64, String, String)>(&line).ok())
+        .map(|(time, code, data)| RecordingEvent { time, code, data })
+        .collect()
+}
+
+/// List recordings, newest first
+pub fn list_recordings(app: &AppHandle) -> Result<Vec<TerminalRecording>, String> {
+    let dir = get_recordings_dir(app)?;
+    let entries =
+        fs::read_dir(&dir).map_err(|e| format!("Failed to read recordings directory: {e}"))?;
+
+    let mut recordings: Vec<TerminalRecording> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "cast"))
+        .filter_map(|path| {
+            let file = File::open(&path).ok()?;
+            let header = read_header(&mut BufReader::new(file)).ok()?;
+            recording_info(&path, header)
+        })
+        .collect();
+
+    recordings.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    Ok(recordings)
+}
+
+/// Read a recording with its events
+pub fn read_recording(
+    app: &AppHandle,
+    recording_id: &str,
+) -> Result<TerminalRecordingData, String> {
+    let path = recording_path(app, recording_id)?;
+    let file = File::open(&path).map_err(|e| format!("Failed to open recording: {e}"))?;
+    let mut reader = BufReader::new(file);
+    let header = read_header(&mut reader)?;
+    let recording = recording_info(&path, header).ok_or("Invalid recording path")?;
+    Ok(TerminalRecordingData {
+        recording,
+        events: read_events(reader),
+    })
+}
+
+/// Delete a recording, stopping it first if it is in progress
+pub fn delete_recording(app: &AppHandle, recording_id: &str) -> Result<(), String> {
+    let path = recording_path(app, recording_id)?;
+    if let Some(terminal_id) = recording_terminal(recording_id) {
+        stop_recording(&terminal_id);
+    }
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to delete recording: {e}")),
+    }
+}
+
+/// Plain text of a recording's output, for use as chat context
+pub fn transcript(events: &[RecordingEvent]) -> String {
+    let output: String = events
+        .iter()
+        .filter(|event| event.code == "o")
+        .map(|event| event.data.as_str())
+        .collect();
+    plain_text(&output)
+}
+
+/// Strip escape sequences and apply carriage returns and backspaces, so
+/// progress bars and prompts end up as the text that was left on screen
+fn plain_text(output: &str) -> String {
+    let mut text = String::new();
+    let mut line = String::new();
+    let mut chars = output.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => skip_escape(&mut chars),
+            '\n' => {
+                text.push_str(line.trim_end());
+                text.push('\n');
+                line.clear();
+            }
+            // A lone carriage return redraws the line
+            '\r' if chars.peek() != Some(&'\n') => line.clear(),
+            '\x08' => {
+                line.pop();
+            }
+            '\t' => line.push('\t'),
+            c if c.is_control() => {}
+            c => line.push(c),
+        }
+    }
+
+    text.push_str(line.trim_end());
+    text
+}
+
+fn skip_escape(chars: &mut Peekable<Chars>) {
+    match chars.next() {
+        // CSI: parameters and intermediates up to a final byte
+        Some('[') => {
+            for c in chars.by_ref() {
+                if ('\x40'..='\x7e').contains(&c) {
+                    break;
+                }
+            }
+        }
+        // OSC, DCS, APC, PM: a string ended by BEL or ESC \
+        Some(']' | 'P' | '_' | '^') => {
+            while let Some(c) = chars.next() {
+                if c == '\x07' {
+                    break;
+                }
+                if c == '\x1b' && chars.peek() == Some(&'\\') {
+                    chars.next();
+                    break;
+                }
+            }
+        }
+        // Character set selection takes one more character
+        Some('(' | ')' | '*' | '+') => {
+            chars.next();
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_lines_are_asciicast() {
+        assert_eq!(
+            event_line(1.2345678, "o", "hi\r\n"),
+            "[1.234568,\"o\",\"hi\\r\\n\"]\n"
+        );
+        let events = read_events(BufReader::new(
+            "[0.5,\"o\",\"a\"]\n[1.0,\"r\",\"80x24\"]\n[1.5,\"o\"".as_bytes(),
+        ));
+        assert_eq!(
+            events,
+            vec![
+                RecordingEvent {
+                    time: 0.5,
+                    code: "o".to_string(),
+                    data: "a".to_string(),
+                },
+                RecordingEvent {
+                    time: 1.0,
+                    code: "r".to_string(),
+                    data: "80x24".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_header_requires_version_2() {
+        let header = "{\"version\":2,\"width\":80,\"height\":24,\"timestamp\":1}\n";
+        let parsed = read_header(&mut BufReader::new(header.as_bytes())).unwrap();
+        assert_eq!((parsed.width, parsed.height), (80, 24));
+
+        let v1 = "{\"version\":1,\"width\":80,\"height\":24}\n";
+        assert!(read_header(&mut BufReader::new(v1.as_bytes())).is_err());
+    }
+
+    #[test]
+    fn test_plain_text_strips_escapes() {
+        assert_eq!(plain_text("\x1b[1;32mok\x1b[0m done\r\n"), "ok done\n");
+        assert_eq!(plain_text("\x1b]0;title\x07$ ls\r\nsrc\r\n"), "$ ls\nsrc\n");
+        assert_eq!(plain_text("10%\r50%\r100%\r\n"), "100%\n");
+        assert_eq!(plain_text("lsx\x08 \x08\x08s"), "ls");
+    }
+}
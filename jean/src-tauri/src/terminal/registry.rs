@@ -14,8 +14,9 @@ pub fn register_terminal(session: TerminalSession) {
     sessions.insert(session.terminal_id.clone(), session);
 }
 
-/// Unregister a terminal session
+/// Unregister a terminal session, ending any recording of it
 pub fn unregister_terminal(terminal_id: &str) -> Option<TerminalSession> {
+    super::recording::stop_recording(terminal_id);
     let mut sessions = TERMINAL_SESSIONS.lock().unwrap();
     sessions.remove(terminal_id)
 }
@@ -32,6 +33,14 @@ pub fn get_all_terminal_ids() -> Vec<String> {
     sessions.keys().cloned().collect()
 }
 
+/// Get the worktree a terminal runs in
+pub fn terminal_worktree_id(terminal_id: &str) -> Option<String> {
+    let sessions = TERMINAL_SESSIONS.lock().unwrap();
+    sessions
+        .get(terminal_id)
+        .and_then(|session| session.worktree_id.clone())
+}
+
 /// Execute a function with mutable access to a terminal session
 pub fn with_terminal<F, R>(terminal_id: &str, f: F) -> Option<R>
 where
@@ -0,0 +1,125 @@
+//! Bounded buffer of raw terminal output
+//!
+//! Keeps the most recent output of each terminal so a client that attaches
+//! late (a reopened drawer after a reload, or the web UI) can redraw the
+//! screen before live output continues. Output is addressed by byte offset in
+//! the terminal's stream: a `terminal:output` event carries the offset of its
+//! first byte, and a snapshot reports where it ends, so clients can drop
+//! events the snapshot already contains.
+
+use std::collections::VecDeque;
+
+/// Output kept per terminal
+pub const SCROLLBACK_CAPACITY: usize = 1024 * 1024;
+
+pub struct Scrollback {
+    buf: VecDeque<u8>,
+    capacity: usize,
+    /// Bytes written over the terminal's lifetime
+    total: u64,
+    /// Older output was dropped, so the buffer may start mid-line
+    trimmed: bool,
+}
+
+impl Default for Scrollback {
+    fn default() -> Self {
+        Self::with_capacity(SCROLLBACK_CAPACITY)
+    }
+}
+
+impl Scrollback {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buf: VecDeque::new(),
+            capacity,
+            total: 0,
+            trimmed: false,
+        }
+    }
+
+    /// Empty buffer continuing a stream at `offset`, e.g. a terminal host's
+    /// output after reattaching
+    pub fn resume_at(offset: u64) -> Self {
+        Self {
+            total: offset,
+            ..Self::default()
+        }
+    }
+
+    /// Append output, returning the stream offset of its first byte
+    pub fn push(&mut self, data: &[u8]) -> u64 {
+        let offset = self.total;
+        self.total += data.len() as u64;
+
+        let kept = &data[data.len().saturating_sub(self.capacity)..];
+        let overflow = (self.buf.len() + kept.len()).saturating_sub(self.capacity);
+        self.trimmed |= overflow > 0 || kept.len() < data.len();
+        self.buf.drain(..overflow);
+        self.buf.extend(kept);
+        offset
+    }
+
+    /// Stream offset just past the last byte written
+    pub fn end(&self) -> u64 {
+        self.total
+    }
+
+    /// Buffered output, starting at a line boundary if older output was dropped
+    ///
+    /// Returns the stream offset of the first returned byte with the bytes.
+    pub fn contents(&self) -> (u64, Vec<u8>) {
+        let mut start = self.total - self.buf.len() as u64;
+        let mut bytes: Vec<u8> = self.buf.iter().copied().collect();
+        if self.trimmed {
+            // Dropping mid-line can split a UTF-8 character or an escape
+            // sequence; begin at the next full line instead
+            if let Some(newline) = bytes.iter().position(|&b| b == b'\n') {
+                bytes.drain(..=newline);
+                start += newline as u64 + 1;
+            }
+        }
+        (start, bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offsets_track_total_output() {
+        let mut scrollback = Scrollback::with_capacity(16);
+        assert_eq!(scrollback.push(b"hello "), 0);
+        assert_eq!(scrollback.push(b"world"), 6);
+        assert_eq!(scrollback.end(), 11);
+        assert_eq!(scrollback.contents(), (0, b"hello world".to_vec()));
+    }
+
+    #[test]
+    fn test_drops_oldest_output_at_line_boundary() {
+        let mut scrollback = Scrollback::with_capacity(12);
+        scrollback.push(b"first line\n");
+        scrollback.push(b"second\nthird");
+        // Only "second\nthird" fits; its first line may be partial, so skip it
+        let (start, bytes) = scrollback.contents();
+        assert_eq!(bytes, b"third");
+        assert_eq!(start, scrollback.end() - 5);
+    }
+
+    #[test]
+    fn test_resumed_stream_keeps_offsets() {
+        let mut scrollback = Scrollback::resume_at(100);
+        assert_eq!(scrollback.push(b"partial\nline"), 100);
+        assert_eq!(scrollback.end(), 112);
+        // Nothing was dropped here, so the first line is kept
+        assert_eq!(scrollback.contents(), (100, b"partial\nline".to_vec()));
+    }
+
+    #[test]
+    fn test_chunk_larger_than_capacity() {
+        let mut scrollback = Scrollback::with_capacity(4);
+        assert_eq!(scrollback.push(b"abcdefgh"), 0);
+        assert_eq!(scrollback.end(), 8);
+        assert_eq!(scrollback.contents(), (4, b"efgh".to_vec()));
+    }
+}
@@ -1,19 +1,27 @@
 use portable_pty::{Child, MasterPty};
 use serde::{Deserialize, Serialize};
 use std::io::Write;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+
+use super::scrollback::Scrollback;
 
 /// Event payload for terminal output
 #[derive(Clone, Serialize, Deserialize)]
 pub struct TerminalOutputEvent {
     pub terminal_id: String,
+    pub worktree_id: Option<String>,
+    /// Output decoded as text; an incomplete character or escape sequence at
+    /// the end is held back until the next event
     pub data: String,
+    /// Stream offset of the first byte of `data`
+    pub offset: u64,
 }
 
 /// Event payload for terminal started
 #[derive(Clone, Serialize, Deserialize)]
 pub struct TerminalStartedEvent {
     pub terminal_id: String,
+    pub worktree_id: Option<String>,
     pub cols: u16,
     pub rows: u16,
 }
@@ -22,15 +30,103 @@ pub struct TerminalStartedEvent {
 #[derive(Clone, Serialize, Deserialize)]
 pub struct TerminalStoppedEvent {
     pub terminal_id: String,
+    pub worktree_id: Option<String>,
     pub exit_code: Option<i32>,
 }
 
+/// Buffered output for a client attaching to a running terminal
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TerminalScrollback {
+    pub terminal_id: String,
+    pub data: String,
+    /// Stream offset of the first byte of `data`
+    pub start: u64,
+    /// Stream offset after `data`; output events before this are included
+    pub end: u64,
+    pub cols: u16,
+    pub rows: u16,
+}
+
+/// A terminal as listed for clients restoring their terminal tabs
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TerminalInfo {
+    pub terminal_id: String,
+    pub worktree_id: Option<String>,
+    pub worktree_path: String,
+    pub command: Option<String>,
+    /// Runs in a detached host and survives Jean restarting
+    pub persistent: bool,
+}
+
+/// A terminal recording (asciicast v2 file under the app data dir)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TerminalRecording {
+    pub id: String,
+    pub title: String,
+    pub command: Option<String>,
+    /// Terminal size when the recording started
+    pub cols: u16,
+    pub rows: u16,
+    /// Unix timestamp (seconds) when the recording started
+    pub started_at: u64,
+    /// File size in bytes
+    pub size: u64,
+    pub path: String,
+    /// Terminal being recorded, while the recording is in progress
+    pub terminal_id: Option<String>,
+}
+
+/// One recorded event: `code` is `o` (output), `i` (input) or `r` (resize,
+/// with `data` as `COLSxROWS`); `time` is seconds since the recording started
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordingEvent {
+    pub time: f64,
+    pub code: String,
+    pub data: String,
+}
+
+/// A recording with its events, for replaying it in a client
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TerminalRecordingData {
+    pub recording: TerminalRecording,
+    pub events: Vec<RecordingEvent>,
+}
+
+/// Where a terminal's PTY lives
+pub enum TerminalBackend {
+    /// Owned by this process; killed when Jean quits
+    Local {
+        master: Box<dyn MasterPty + Send>,
+        writer: Mutex<Box<dyn Write + Send>>,
+        child: Box<dyn Child + Send + Sync>,
+    },
+    /// Owned by a detached terminal host process (see `host`)
+    #[cfg(unix)]
+    Hosted(super::host::HostClient),
+}
+
 /// Active terminal session state
 pub struct TerminalSession {
     pub terminal_id: String,
-    pub master: Box<dyn MasterPty + Send>,
-    pub writer: Mutex<Box<dyn Write + Send>>,
-    pub child: Box<dyn Child + Send + Sync>,
+    /// Worktree the terminal was started in, if it is a known worktree
+    pub worktree_id: Option<String>,
+    pub worktree_path: String,
+    pub command: Option<String>,
+    pub backend: TerminalBackend,
     pub cols: u16,
     pub rows: u16,
+    /// Recent output, shared with the reader thread
+    pub scrollback: Arc<Mutex<Scrollback>>,
+}
+
+impl TerminalSession {
+    pub fn info(&self) -> TerminalInfo {
+        TerminalInfo {
+            terminal_id: self.terminal_id.clone(),
+            worktree_id: self.worktree_id.clone(),
+            worktree_path: self.worktree_path.clone(),
+            command: self.command.clone(),
+            persistent: !matches!(self.backend, TerminalBackend::Local { .. }),
+        }
+    }
 }
@@ -2,8 +2,11 @@ import { useEffect, useState } from 'react'
 import { useQueryClient } from '@tanstack/react-query'
 import {
   invoke,
+  listen,
+  WS_RESYNC_EVENT,
   useWsConnectionStatus,
   useWsAuthError,
+  useCanUseTerminals,
   preloadInitialData,
   type InitialData,
 } from '@/lib/transport'
@@ -11,6 +14,7 @@ import { isNativeApp } from '@/lib/environment'
 import { projectsQueryKeys } from '@/services/projects'
 import { chatQueryKeys } from '@/services/chat'
 import type { WorktreeSessions } from '@/types/chat'
+import type { TerminalInfo } from '@/types/terminal'
 import { initializeCommandSystem } from './lib/commands'
 import { logger } from './lib/logger'
 import { cleanupOldFiles } from './lib/recovery'
@@ -22,10 +26,10 @@ import { useClaudeCliStatus, useClaudeCliAuth } from './services/claude-cli'
 import { useGhCliStatus, useGhCliAuth } from './services/gh-cli'
 import { useUIStore } from './store/ui-store'
 import { useChatStore } from './store/chat-store'
+import { useTerminalStore } from './store/terminal-store'
 import { useFontSettings } from './hooks/use-font-settings'
 import { useImmediateSessionStateSave } from './hooks/useImmediateSessionStateSave'
 import { useCliVersionCheck } from './hooks/useCliVersionCheck'
-import { useQueueProcessor } from './hooks/useQueueProcessor'
 import { useAutoArchiveOnMerge } from './hooks/useAutoArchiveOnMerge'
 import useStreamingEvents from './components/chat/hooks/useStreamingEvents'
 import { preloadAllSounds } from './lib/sounds'
@@ -230,10 +234,6 @@ function App() {
   // even when ChatWindow is unmounted (e.g., when viewing session board)
   useStreamingEvents({ queryClient })
 
-  // Global queue processor - must be at App level so queued messages execute
-  // even when the worktree is not focused (ChatWindow unmounted)
-  useQueueProcessor()
-
   // Auto-archive worktrees when their PR is merged (if enabled in preferences)
   useAutoArchiveOnMerge()
 
@@ -259,6 +259,18 @@ function App() {
     }
   }, [wsConnected, queryClient])
 
+  // Events were lost while disconnected and couldn't be replayed: refetch everything
+  useEffect(() => {
+    if (isNativeApp()) return
+    const unlisten = listen(WS_RESYNC_EVENT, () => {
+      logger.info('WebSocket events missed, refetching all queries')
+      queryClient.invalidateQueries()
+    })
+    return () => {
+      unlisten.then(f => f())
+    }
+  }, [queryClient])
+
   // Add native-app class to body for desktop-only CSS (cursor, user-select, etc.)
   useEffect(() => {
     if (isNativeApp()) {
@@ -316,17 +328,24 @@ function App() {
     isGhAuthLoading,
   ])
 
-  // Kill all terminals on page refresh/close (backup for Rust-side cleanup)
+  // Restore tabs for terminals that are still running, e.g. after a reload
+  // or when a terminal host outlived the previous app session
+  const canUseTerminals = useCanUseTerminals()
   useEffect(() => {
-    const handleBeforeUnload = () => {
-      // Best-effort sync cleanup for refresh scenarios
-      // Note: async operations may not complete, but Rust-side RunEvent::Exit
-      // will handle proper cleanup on app quit
-      invoke('kill_all_terminals').catch(() => {})
-    }
-    window.addEventListener('beforeunload', handleBeforeUnload)
-    return () => window.removeEventListener('beforeunload', handleBeforeUnload)
-  }, [])
+    if (!canUseTerminals) return
+    invoke<TerminalInfo[]>('list_terminals')
+      .then(terminals => {
+        const restored = useTerminalStore
+          .getState()
+          .restoreTerminals(terminals)
+        if (restored > 0) {
+          logger.info(`Restored ${restored} running terminal(s)`)
+        }
+      })
+      .catch(error => {
+        logger.warn('Failed to restore terminals', { error })
+      })
+  }, [canUseTerminals])
 
   // Initialize command system and cleanup on app startup
   useEffect(() => {
@@ -337,20 +356,6 @@ function App() {
     // Preload notification sounds for instant playback
     preloadAllSounds()
 
-    // Kill any orphaned terminals from previous session/reload
-    // This ensures cleanup even if beforeunload didn't complete
-    invoke<number>('kill_all_terminals')
-      .then(killed => {
-        if (killed > 0) {
-          logger.info(
-            `Cleaned up ${killed} orphaned terminal(s) from previous session`
-          )
-        }
-      })
-      .catch(error => {
-        logger.warn('Failed to cleanup orphaned terminals', { error })
-      })
-
     // Clean up old recovery files on startup
     cleanupOldFiles().catch(error => {
       logger.warn('Failed to cleanup old recovery files', { error })
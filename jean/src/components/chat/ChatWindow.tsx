@@ -7,7 +7,7 @@ import {
   useState,
 } from 'react'
 import { useQueryClient } from '@tanstack/react-query'
-import { invoke } from '@/lib/transport'
+import { canUseTerminals, invoke, useCanUseTerminals } from '@/lib/transport'
 import { toast } from 'sonner'
 import { formatShortcutDisplay, DEFAULT_KEYBINDINGS } from '@/types/keybindings'
 import { ScrollArea } from '@/components/ui/scroll-area'
@@ -30,6 +30,10 @@ import {
   useSetSessionModel,
   useSetSessionThinkingLevel,
   useCreateSession,
+  useForkSession,
+  useMessageQueue,
+  useEnqueueMessage,
+  useRemoveQueuedMessage,
   cancelChatMessage,
   chatQueryKeys,
   markPlanApproved as markPlanApprovedService,
@@ -96,10 +100,14 @@ import {
 } from './VirtualizedMessageList'
 import { useUIStore } from '@/store/ui-store'
 import { useGitStatus } from '@/services/git-status'
-import { isNativeApp } from '@/lib/environment'
 import { usePrStatus, usePrStatusEvents } from '@/services/pr-status'
 import type { PrDisplayStatus, CheckStatus } from '@/types/pr-status'
-import type { QueuedMessage, ExecutionMode, Session } from '@/types/chat'
+import type {
+  PendingMessage,
+  QueuedMessage,
+  ExecutionMode,
+  Session,
+} from '@/types/chat'
 import type { DiffRequest } from '@/types/git-diff'
 import { GitDiffModal } from './GitDiffModal'
 import { FileDiffModal } from './FileDiffModal'
@@ -220,6 +228,7 @@ export function ChatWindow({
       : false
   )
   const { setTerminalVisible } = useTerminalStore.getState()
+  const terminalsAvailable = useCanUseTerminals()
 
   // Sync terminal panel with terminalVisible state
   useEffect(() => {
@@ -327,6 +336,9 @@ export function ChatWindow({
   )
   const sendMessage = useSendMessage()
   const createSession = useCreateSession()
+  const { mutate: forkSession } = useForkSession()
+  const { mutate: enqueueMessage } = useEnqueueMessage()
+  const { mutate: removeQueuedMessage } = useRemoveQueuedMessage()
   const setSessionModel = useSetSessionModel()
   const setSessionThinkingLevel = useSetSessionThinkingLevel()
 
@@ -525,12 +537,9 @@ export function ChatWindow({
       (files?.length ?? 0) > 0
     )
   })
-  // Per-session message queue (uses deferredSessionId for content consistency)
-  const currentQueuedMessages = useChatStore(state =>
-    deferredSessionId
-      ? (state.messageQueues[deferredSessionId] ?? EMPTY_QUEUED_MESSAGES)
-      : EMPTY_QUEUED_MESSAGES
-  )
+  // Per-session backend message queue (uses deferredSessionId for content consistency)
+  const { data: currentQueuedMessages = EMPTY_QUEUED_MESSAGES } =
+    useMessageQueue(deferredSessionId ?? null)
   // Per-session pending permission denials (uses deferredSessionId for content consistency)
   const pendingDenials = useChatStore(state =>
     deferredSessionId
@@ -912,7 +921,7 @@ export function ChatWindow({
 
   // Helper to build full message with attachment references for backend
   const buildMessageWithRefs = useCallback(
-    (queuedMsg: QueuedMessage): string => {
+    (queuedMsg: PendingMessage): string => {
       let message = queuedMsg.message
 
       // Add file references (from @ mentions)
@@ -964,9 +973,32 @@ export function ChatWindow({
     []
   )
 
-  // Helper to send a queued message immediately
+  // Helper to build the allowed tools for a message (git, web, session-approved)
+  const getAllowedTools = useCallback(
+    (sessionId: string): string[] | undefined => {
+      // Get session-approved tools to include
+      const sessionApprovedTools = useChatStore
+        .getState()
+        .getApprovedTools(sessionId)
+
+      // Build base allowed tools (git always, web tools if enabled)
+      const webTools = preferences?.allow_web_tools_in_plan_mode
+        ? ['WebFetch', 'WebSearch']
+        : []
+      const baseAllowedTools = [...GIT_ALLOWED_TOOLS, ...webTools]
+
+      return sessionApprovedTools.length > 0
+        ? [...baseAllowedTools, ...sessionApprovedTools]
+        : baseAllowedTools.length > GIT_ALLOWED_TOOLS.length
+          ? baseAllowedTools
+          : undefined
+    },
+    [preferences?.allow_web_tools_in_plan_mode]
+  )
+
+  // Helper to send a message immediately
   const sendMessageNow = useCallback(
-    (queuedMsg: QueuedMessage) => {
+    (queuedMsg: PendingMessage) => {
       if (!activeSessionId || !activeWorktreeId || !activeWorktreePath) return
 
       const {
@@ -975,7 +1007,6 @@ export function ChatWindow({
         setError,
         setExecutingMode,
         setSelectedModel,
-        getApprovedTools,
         clearStreamingContent,
         clearToolCalls,
         clearStreamingContentBlocks,
@@ -998,21 +1029,7 @@ export function ChatWindow({
       // Track the model being used for this session (needed for permission approval flow)
       setSelectedModel(activeSessionId, queuedMsg.model)
 
-      // Get session-approved tools to include
-      const sessionApprovedTools = getApprovedTools(activeSessionId)
-
-      // Build base allowed tools (git always, web tools if enabled)
-      const webTools = preferences?.allow_web_tools_in_plan_mode
-        ? ['WebFetch', 'WebSearch']
-        : []
-      const baseAllowedTools = [...GIT_ALLOWED_TOOLS, ...webTools]
-
-      const allowedTools =
-        sessionApprovedTools.length > 0
-          ? [...baseAllowedTools, ...sessionApprovedTools]
-          : baseAllowedTools.length > GIT_ALLOWED_TOOLS.length
-            ? baseAllowedTools
-            : undefined
+      const allowedTools = getAllowedTools(activeSessionId)
 
       // Build full message with attachment refs for backend
       const fullMessage = buildMessageWithRefs(queuedMsg)
@@ -1044,10 +1061,43 @@ export function ChatWindow({
       activeWorktreeId,
       activeWorktreePath,
       buildMessageWithRefs,
+      getAllowedTools,
       sendMessage,
       preferences?.parallel_execution_prompt_enabled,
       preferences?.ai_language,
-      preferences?.allow_web_tools_in_plan_mode,
+    ]
+  )
+
+  // Helper to queue a message on the backend; it is sent as soon as the
+  // session's current run completes (or right away if the session is idle)
+  const queueMessage = useCallback(
+    (queuedMsg: PendingMessage) => {
+      if (!activeSessionId || !activeWorktreeId || !activeWorktreePath) return
+
+      enqueueMessage({
+        sessionId: activeSessionId,
+        worktreeId: activeWorktreeId,
+        worktreePath: activeWorktreePath,
+        message: buildMessageWithRefs(queuedMsg),
+        model: queuedMsg.model,
+        executionMode: queuedMsg.executionMode,
+        thinkingLevel: queuedMsg.thinkingLevel,
+        disableThinkingForMode: queuedMsg.disableThinkingForMode,
+        parallelExecutionPromptEnabled:
+          preferences?.parallel_execution_prompt_enabled ?? false,
+        aiLanguage: preferences?.ai_language,
+        allowedTools: getAllowedTools(activeSessionId),
+      })
+    },
+    [
+      activeSessionId,
+      activeWorktreeId,
+      activeWorktreePath,
+      buildMessageWithRefs,
+      getAllowedTools,
+      enqueueMessage,
+      preferences?.parallel_execution_prompt_enabled,
+      preferences?.ai_language,
     ]
   )
 
@@ -1140,7 +1190,6 @@ export function ChatWindow({
         clearPendingTextFiles,
         getPendingSkills,
         clearPendingSkills,
-        enqueueMessage,
         isSending: checkIsSendingNow,
         setSessionReviewing,
       } = useChatStore.getState()
@@ -1198,8 +1247,7 @@ export function ChatWindow({
       const hasManualOverride = useChatStore
         .getState()
         .hasManualThinkingOverride(activeSessionId)
-      const queuedMessage: QueuedMessage = {
-        id: crypto.randomUUID(),
+      const queuedMessage: PendingMessage = {
         message,
         pendingImages: images,
         pendingFiles: files,
@@ -1210,12 +1258,11 @@ export function ChatWindow({
         thinkingLevel: thinkingLvl,
         disableThinkingForMode:
           mode !== 'plan' && thinkingLvl !== 'off' && !hasManualOverride,
-        queuedAt: Date.now(),
       }
 
       // If currently sending, add to queue instead
       if (checkIsSendingNow(activeSessionId)) {
-        enqueueMessage(activeSessionId, queuedMessage)
+        queueMessage(queuedMessage)
         return
       }
 
@@ -1227,13 +1274,14 @@ export function ChatWindow({
       activeWorktreeId,
       activeWorktreePath,
       clearInputDraft,
+      queueMessage,
       sendMessageNow,
       sessionsData,
     ]
   )
 
-  // Note: Queue processing moved to useQueueProcessor hook in App.tsx
-  // This ensures queued messages execute even when the worktree is unfocused
+  // Note: Queued messages are sent by the backend when the current run
+  // completes, so they execute even when the worktree is unfocused
 
   // Git operations hook - handles commit, PR, review, merge operations
   const {
@@ -1637,7 +1685,7 @@ Begin your investigation now.`
     const handleSaveContextEvent = () => handleSaveContext()
     const handleLoadContextEvent = () => handleLoadContext()
     const handleRunScriptEvent = () => {
-      if (!isNativeApp() || !activeWorktreeId || !runScript) return
+      if (!canUseTerminals() || !activeWorktreeId || !runScript) return
       useTerminalStore.getState().startRun(activeWorktreeId, runScript)
     }
 
@@ -1722,6 +1770,27 @@ Begin your investigation now.`
     pendingPlanMessage,
   })
 
+  // Fork the active session at a message into a new session next to it
+  const handleForkFromMessage = useCallback(
+    (messageId: string) => {
+      const sessionId = activeSessionIdRef.current
+      const worktreeId = activeWorktreeIdRef.current
+      const worktreePath = activeWorktreePathRef.current
+      if (!sessionId || !worktreeId || !worktreePath) return
+
+      forkSession(
+        { worktreeId, worktreePath, sessionId, messageId },
+        {
+          onSuccess: session => {
+            useChatStore.getState().setActiveSession(worktreeId, session.id)
+            toast.success(`Forked into "${session.name}"`)
+          },
+        }
+      )
+    },
+    [forkSession]
+  )
+
   // Listen for approve-plan keyboard shortcut event
   // Skip when on canvas view (non-modal) - CanvasGrid handles it there
   useEffect(() => {
@@ -1867,9 +1936,9 @@ Begin your investigation now.`
   // Handle removing a queued message
   const handleRemoveQueuedMessage = useCallback(
     (sessionId: string, messageId: string) => {
-      useChatStore.getState().removeQueuedMessage(sessionId, messageId)
+      removeQueuedMessage({ sessionId, itemId: messageId })
     },
-    []
+    [removeQueuedMessage]
   )
 
   // Handle cancellation of running Claude process (triggered by Cmd+Option+Backspace / Ctrl+Alt+Backspace)
@@ -1921,8 +1990,7 @@ Begin your investigation now.`
 
       // Commands are executed immediately by sending as the message
       // The command name (e.g., "/commit") is sent directly, Claude CLI interprets it
-      const queuedMessage: QueuedMessage = {
-        id: crypto.randomUUID(),
+      const queuedMessage: PendingMessage = {
         message: commandName,
         pendingImages: [],
         pendingFiles: [],
@@ -1932,19 +2000,23 @@ Begin your investigation now.`
         executionMode: executionModeRef.current,
         thinkingLevel: selectedThinkingLevelRef.current,
         disableThinkingForMode: false,
-        queuedAt: Date.now(),
       }
 
       // Check if currently sending - queue if so, otherwise send immediately
-      const { isSending: checkIsSendingNow, enqueueMessage } =
-        useChatStore.getState()
+      const { isSending: checkIsSendingNow } = useChatStore.getState()
       if (checkIsSendingNow(activeSessionId)) {
-        enqueueMessage(activeSessionId, queuedMessage)
+        queueMessage(queuedMessage)
       } else {
         sendMessageNow(queuedMessage)
       }
     },
-    [activeSessionId, activeWorktreeId, activeWorktreePath, sendMessageNow]
+    [
+      activeSessionId,
+      activeWorktreeId,
+      activeWorktreePath,
+      queueMessage,
+      sendMessageNow,
+    ]
   )
 
   // Handle removing a pending file (@ mention)
@@ -2134,6 +2206,7 @@ Begin your investigation now.`
                             onEditedFileClick={setViewingFilePath}
                             onFixFinding={handleFixFinding}
                             onFixAllFindings={handleFixAllFindings}
+                            onForkFromMessage={handleForkFromMessage}
                             isQuestionAnswered={isQuestionAnswered}
                             getSubmittedAnswers={getSubmittedAnswers}
                             areQuestionsSkipped={areQuestionsSkipped}
@@ -2373,9 +2446,9 @@ Begin your investigation now.`
               </div>
             </ResizablePanel>
 
-            {/* Terminal panel - only render when panel is open (native app only, not in modal) */}
+            {/* Terminal panel - only render when panel is open and terminals are available (not in modal) */}
             {!isModal &&
-              isNativeApp() &&
+              terminalsAvailable &&
               activeWorktreePath &&
               terminalPanelOpen && (
                 <>
@@ -2482,11 +2555,10 @@ Begin your investigation now.`
                   : 'Approved'
 
                 // Queue instead of immediate execution
-                const { enqueueMessage, setExecutionMode } = useChatStore.getState()
+                const { setExecutionMode } = useChatStore.getState()
                 setExecutionMode(activeSessionId, 'build')
 
-                const queuedMessage: QueuedMessage = {
-                  id: crypto.randomUUID(),
+                const queuedMessage: PendingMessage = {
                   message,
                   pendingImages: [],
                   pendingFiles: [],
@@ -2496,10 +2568,9 @@ Begin your investigation now.`
                   executionMode: 'build',
                   thinkingLevel: selectedThinkingLevelRef.current,
                   disableThinkingForMode: true,
-                  queuedAt: Date.now(),
                 }
 
-                enqueueMessage(activeSessionId, queuedMessage)
+                queueMessage(queuedMessage)
               }}
               onApproveYolo={updatedPlan => {
                 if (!activeSessionId || !activeWorktreeId || !activeWorktreePath) return
@@ -2532,11 +2603,10 @@ Begin your investigation now.`
                   : 'Approved - yolo'
 
                 // Queue instead of immediate execution
-                const { enqueueMessage, setExecutionMode } = useChatStore.getState()
+                const { setExecutionMode } = useChatStore.getState()
                 setExecutionMode(activeSessionId, 'yolo')
 
-                const queuedMessage: QueuedMessage = {
-                  id: crypto.randomUUID(),
+                const queuedMessage: PendingMessage = {
                   message,
                   pendingImages: [],
                   pendingFiles: [],
@@ -2546,10 +2616,9 @@ Begin your investigation now.`
                   executionMode: 'yolo',
                   thinkingLevel: selectedThinkingLevelRef.current,
                   disableThinkingForMode: true,
-                  queuedAt: Date.now(),
                 }
 
-                enqueueMessage(activeSessionId, queuedMessage)
+                queueMessage(queuedMessage)
               }}
             />
           ) : latestPlanFilePath ? (
@@ -2599,11 +2668,10 @@ Begin your investigation now.`
                   : 'Approved'
 
                 // Queue instead of immediate execution
-                const { enqueueMessage, setExecutionMode } = useChatStore.getState()
+                const { setExecutionMode } = useChatStore.getState()
                 setExecutionMode(activeSessionId, 'build')
 
-                const queuedMessage: QueuedMessage = {
-                  id: crypto.randomUUID(),
+                const queuedMessage: PendingMessage = {
                   message,
                   pendingImages: [],
                   pendingFiles: [],
@@ -2613,10 +2681,9 @@ Begin your investigation now.`
                   executionMode: 'build',
                   thinkingLevel: selectedThinkingLevelRef.current,
                   disableThinkingForMode: true,
-                  queuedAt: Date.now(),
                 }
 
-                enqueueMessage(activeSessionId, queuedMessage)
+                queueMessage(queuedMessage)
               }}
               onApproveYolo={updatedPlan => {
                 if (!activeSessionId || !activeWorktreeId || !activeWorktreePath) return
@@ -2649,11 +2716,10 @@ Begin your investigation now.`
                   : 'Approved - yolo'
 
                 // Queue instead of immediate execution
-                const { enqueueMessage, setExecutionMode } = useChatStore.getState()
+                const { setExecutionMode } = useChatStore.getState()
                 setExecutionMode(activeSessionId, 'yolo')
 
-                const queuedMessage: QueuedMessage = {
-                  id: crypto.randomUUID(),
+                const queuedMessage: PendingMessage = {
                   message,
                   pendingImages: [],
                   pendingFiles: [],
@@ -2663,10 +2729,9 @@ Begin your investigation now.`
                   executionMode: 'yolo',
                   thinkingLevel: selectedThinkingLevelRef.current,
                   disableThinkingForMode: true,
-                  queuedAt: Date.now(),
                 }
 
-                enqueueMessage(activeSessionId, queuedMessage)
+                queueMessage(queuedMessage)
               }}
             />
           ) : null)}
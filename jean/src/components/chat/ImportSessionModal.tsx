@@ -0,0 +1,150 @@
+import { useCallback } from 'react'
+import { FileArchive, Loader2, MessageSquare } from 'lucide-react'
+import {
+  Dialog,
+  DialogContent,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog'
+import { ScrollArea } from '@/components/ui/scroll-area'
+import { Button } from '@/components/ui/button'
+import { useUIStore } from '@/store/ui-store'
+import { useProjectsStore } from '@/store/projects-store'
+import { useChatStore } from '@/store/chat-store'
+import { useClaudeCliSessions, useImportSession } from '@/services/chat'
+import { isNativeApp } from '@/lib/environment'
+import { notify } from '@/lib/notifications'
+import type { ClaudeCliSessionInfo } from '@/types/chat'
+
+/** Format a Unix timestamp to a human-readable date */
+function formatDate(timestamp: number): string {
+  return new Date(timestamp * 1000).toLocaleString(undefined, {
+    month: 'short',
+    day: 'numeric',
+    hour: '2-digit',
+    minute: '2-digit',
+  })
+}
+
+/**
+ * Imports a Claude CLI conversation recorded for the selected worktree, or a
+ * session bundle exported from another machine, as a new session.
+ */
+export function ImportSessionModal() {
+  const open = useUIStore(state => state.importSessionModalOpen)
+  const setOpen = useUIStore(state => state.setImportSessionModalOpen)
+  const worktreeId = useProjectsStore(state => state.selectedWorktreeId)
+  const worktreePath = useChatStore(state =>
+    worktreeId ? (state.worktreePaths[worktreeId] ?? null) : null
+  )
+
+  const { data: cliSessions = [], isLoading } = useClaudeCliSessions(
+    open ? worktreeId : null,
+    worktreePath
+  )
+  const importSession = useImportSession()
+
+  const runImport = useCallback(
+    async (source: { bundlePath: string } | { jsonlPath: string }) => {
+      if (!worktreeId || !worktreePath) return
+      const session = await importSession.mutateAsync({
+        worktreeId,
+        worktreePath,
+        source,
+      })
+      useChatStore.getState().setActiveSession(worktreeId, session.id)
+      notify('Session imported', session.name, { type: 'success' })
+      setOpen(false)
+    },
+    [worktreeId, worktreePath, importSession, setOpen]
+  )
+
+  const handleImportBundle = useCallback(async () => {
+    const { open: openDialog } = await import('@tauri-apps/plugin-dialog')
+    const selected = await openDialog({
+      multiple: false,
+      title: 'Select a session bundle',
+      filters: [{ name: 'Session bundle', extensions: ['zip'] }],
+    })
+    if (selected && typeof selected === 'string') {
+      await runImport({ bundlePath: selected }).catch(() => undefined)
+    }
+  }, [runImport])
+
+  const handleImportCli = useCallback(
+    (info: ClaudeCliSessionInfo) =>
+      runImport({ jsonlPath: info.path }).catch(() => undefined),
+    [runImport]
+  )
+
+  return (
+    <Dialog open={open} onOpenChange={setOpen}>
+      <DialogContent className="sm:max-w-[560px]">
+        <DialogHeader>
+          <DialogTitle>Import Session</DialogTitle>
+        </DialogHeader>
+
+        <p className="text-sm text-muted-foreground">
+          Claude CLI conversations recorded in this worktree:
+        </p>
+
+        <ScrollArea className="max-h-[360px]">
+          {isLoading ? (
+            <div className="flex items-center justify-center py-8">
+              <Loader2 className="h-4 w-4 animate-spin text-muted-foreground" />
+            </div>
+          ) : cliSessions.length === 0 ? (
+            <p className="py-8 text-center text-sm text-muted-foreground">
+              No Claude CLI conversations found.
+            </p>
+          ) : (
+            <div className="space-y-1">
+              {cliSessions.map(info => (
+                <div
+                  key={info.claude_session_id}
+                  className="flex items-center gap-3 rounded-md px-2 py-1.5 hover:bg-accent"
+                >
+                  <MessageSquare className="h-4 w-4 shrink-0 text-muted-foreground" />
+                  <div className="min-w-0 flex-1">
+                    <div className="truncate text-sm">
+                      {info.summary ??
+                        info.first_message ??
+                        info.claude_session_id}
+                    </div>
+                    <div className="text-xs text-muted-foreground">
+                      {formatDate(info.modified_at)}
+                    </div>
+                  </div>
+                  <Button
+                    variant="outline"
+                    size="sm"
+                    disabled={info.imported || importSession.isPending}
+                    onClick={() => handleImportCli(info)}
+                  >
+                    {info.imported ? 'Imported' : 'Import'}
+                  </Button>
+                </div>
+              ))}
+            </div>
+          )}
+        </ScrollArea>
+
+        {isNativeApp() && (
+          <div className="flex justify-end border-t pt-3">
+            <Button
+              variant="outline"
+              size="sm"
+              disabled={importSession.isPending}
+              onClick={handleImportBundle}
+            >
+              <FileArchive className="h-3.5 w-3.5" />
+              Import bundle...
+            </Button>
+          </div>
+        )}
+      </DialogContent>
+    </Dialog>
+  )
+}
+
+export default ImportSessionModal
@@ -1,4 +1,5 @@
 import { memo, useCallback } from 'react'
+import { GitFork } from 'lucide-react'
 import { cn } from '@/lib/utils'
 import { normalizePath } from '@/lib/path-utils'
 import { Markdown } from '@/components/ui/markdown'
@@ -174,6 +175,8 @@ interface MessageItemProps {
   onFixAllFindings: (
     findings: { finding: ReviewFinding; suggestion?: string }[]
   ) => Promise<void>
+  /** Callback when user forks the session at a message */
+  onForkFromMessage?: (messageId: string) => void
   /** Check if a question has been answered */
   isQuestionAnswered: (sessionId: string, toolCallId: string) => boolean
   /** Get submitted answers for a question */
@@ -211,6 +214,7 @@ export const MessageItem = memo(function MessageItem({
   onEditedFileClick,
   onFixFinding,
   onFixAllFindings,
+  onForkFromMessage,
   isQuestionAnswered,
   getSubmittedAnswers,
   areQuestionsSkipped,
@@ -256,6 +260,11 @@ export const MessageItem = memo(function MessageItem({
     onPlanApprovalYolo?.(message.id)
   }, [onPlanApprovalYolo, message.id])
 
+  // Stable callback for forking at this message
+  const handleFork = useCallback(() => {
+    onForkFromMessage?.(message.id)
+  }, [onForkFromMessage, message.id])
+
   // Stable callback for checking if finding is fixed
   const handleIsFindingFixed = useCallback(
     (findingKey: string) => isFindingFixed(sessionId, findingKey),
@@ -603,11 +612,21 @@ export const MessageItem = memo(function MessageItem({
       ) : (
         <div
           className={cn(
-            'text-muted-foreground w-full min-w-0 break-words',
+            'group/message text-muted-foreground w-full min-w-0 break-words',
             message.cancelled && 'opacity-60'
           )}
         >
           {messageBoxContent}
+          {onForkFromMessage && !skipToolCalls && (
+            <button
+              type="button"
+              onClick={handleFork}
+              className="mt-1 flex items-center gap-1 text-xs text-muted-foreground/60 opacity-0 transition-opacity hover:text-foreground focus-visible:opacity-100 group-hover/message:opacity-100"
+            >
+              <GitFork className="h-3 w-3" />
+              Fork from here
+            </button>
+          )}
         </div>
       )}
     </div>
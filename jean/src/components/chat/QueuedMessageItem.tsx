@@ -11,6 +11,41 @@ import {
 import { cn } from '@/lib/utils'
 import type { QueuedMessage } from '@/types/chat'
 
+/** Attachment references appended to queued message text, by display label */
+const ATTACHMENT_MARKERS: { label: string; regex: RegExp }[] = [
+  {
+    label: 'image(s)',
+    regex: /\[Image attached: .+? - Use the Read tool to view this image\]/g,
+  },
+  {
+    label: 'file(s)',
+    regex: /\[File: .+? - Use the Read tool to view this file\]/g,
+  },
+  {
+    label: 'skill(s)',
+    regex: /\[Skill: .+? - Read and use this skill to guide your response\]/g,
+  },
+  {
+    label: 'text file(s)',
+    regex: /\[Text file attached: .+? - Use the Read tool to view this file\]/g,
+  },
+]
+
+/** Split a queued message into its display text and attachment counts */
+function parseQueuedText(message: string): {
+  text: string
+  attachments: { label: string; count: number }[]
+} {
+  let text = message
+  const attachments = []
+  for (const { label, regex } of ATTACHMENT_MARKERS) {
+    const count = message.match(regex)?.length ?? 0
+    if (count > 0) attachments.push({ label, count })
+    text = text.replace(regex, '')
+  }
+  return { text: text.trim(), attachments }
+}
+
 interface QueuedMessageItemProps {
   message: QueuedMessage
   index: number
@@ -32,6 +67,10 @@ export const QueuedMessageItem = memo(function QueuedMessageItem({
     onRemove(sessionId, message.id)
   }, [onRemove, sessionId, message.id])
 
+  const { text, attachments } = parseQueuedText(message.message)
+  const executionMode = message.execution_mode
+  const thinkingLevel = message.thinking_level
+
   return (
     <div className="w-full flex justify-end overflow-visible">
       <div className="relative group text-foreground border border-dashed border-muted-foreground/40 rounded-lg px-3 py-2 max-w-[70%] bg-muted/10 min-w-0 break-words opacity-60 overflow-visible mr-1 mt-2">
@@ -51,65 +90,55 @@ export const QueuedMessageItem = memo(function QueuedMessageItem({
         </button>
         {/* Message content */}
         <div className="text-sm">
-          {message.message.length > 200
-            ? `${message.message.slice(0, 200)}...`
-            : message.message}
+          {text.length > 200 ? `${text.slice(0, 200)}...` : text}
         </div>
         {/* Attachment indicators */}
-        {(message.pendingImages.length > 0 ||
-          message.pendingFiles.length > 0 ||
-          message.pendingSkills.length > 0 ||
-          message.pendingTextFiles.length > 0) && (
+        {attachments.length > 0 && (
           <div className="mt-1.5 flex items-center gap-2 text-xs text-muted-foreground">
-            {message.pendingImages.length > 0 && (
-              <span>{message.pendingImages.length} image(s)</span>
-            )}
-            {message.pendingFiles.length > 0 && (
-              <span>{message.pendingFiles.length} file(s)</span>
-            )}
-            {message.pendingSkills.length > 0 && (
-              <span>{message.pendingSkills.length} skill(s)</span>
-            )}
-            {message.pendingTextFiles.length > 0 && (
-              <span>{message.pendingTextFiles.length} text file(s)</span>
-            )}
+            {attachments.map(({ label, count }) => (
+              <span key={label}>
+                {count} {label}
+              </span>
+            ))}
           </div>
         )}
         {/* Captured settings */}
         <div className="mt-1.5 flex items-center gap-1.5 flex-wrap">
           {/* Model badge */}
-          <span className="inline-flex items-center gap-1 rounded bg-muted/80 px-1.5 py-0.5 text-[10px] text-muted-foreground">
-            <Sparkles className="h-2.5 w-2.5" />
-            {message.model}
-          </span>
+          {message.model && (
+            <span className="inline-flex items-center gap-1 rounded bg-muted/80 px-1.5 py-0.5 text-[10px] text-muted-foreground">
+              <Sparkles className="h-2.5 w-2.5" />
+              {message.model}
+            </span>
+          )}
           {/* Mode badge */}
-          <span
-            className={cn(
-              'inline-flex items-center gap-1 rounded px-1.5 py-0.5 text-[10px]',
-              message.executionMode === 'plan' &&
-                'bg-yellow-500/20 text-yellow-600 dark:text-yellow-400',
-              message.executionMode === 'build' &&
-                'bg-muted/80 text-muted-foreground',
-              message.executionMode === 'yolo' &&
-                'bg-red-500/20 text-red-600 dark:text-red-400'
-            )}
-          >
-            {message.executionMode === 'plan' && (
-              <ClipboardList className="h-2.5 w-2.5" />
-            )}
-            {message.executionMode === 'build' && (
-              <Hammer className="h-2.5 w-2.5" />
-            )}
-            {message.executionMode === 'yolo' && (
-              <Zap className="h-2.5 w-2.5" />
-            )}
-            <span className="capitalize">{message.executionMode}</span>
-          </span>
+          {executionMode && (
+            <span
+              className={cn(
+                'inline-flex items-center gap-1 rounded px-1.5 py-0.5 text-[10px]',
+                executionMode === 'plan' &&
+                  'bg-yellow-500/20 text-yellow-600 dark:text-yellow-400',
+                executionMode === 'build' &&
+                  'bg-muted/80 text-muted-foreground',
+                executionMode === 'yolo' &&
+                  'bg-red-500/20 text-red-600 dark:text-red-400'
+              )}
+            >
+              {executionMode === 'plan' && (
+                <ClipboardList className="h-2.5 w-2.5" />
+              )}
+              {executionMode === 'build' && (
+                <Hammer className="h-2.5 w-2.5" />
+              )}
+              {executionMode === 'yolo' && <Zap className="h-2.5 w-2.5" />}
+              <span className="capitalize">{executionMode}</span>
+            </span>
+          )}
           {/* Thinking level badge - only show if not 'off' */}
-          {message.thinkingLevel !== 'off' && (
+          {thinkingLevel && thinkingLevel !== 'off' && (
             <span className="inline-flex items-center gap-1 rounded bg-muted/80 px-1.5 py-0.5 text-[10px] text-muted-foreground">
               <Brain className="h-2.5 w-2.5" />
-              {message.thinkingLevel}
+              {thinkingLevel}
             </span>
           )}
         </div>
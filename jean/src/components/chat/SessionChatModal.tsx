@@ -12,7 +12,7 @@ import { useTerminalStore } from '@/store/terminal-store'
 import { useSession } from '@/services/chat'
 import { usePreferences } from '@/services/preferences'
 import { useRunScript } from '@/services/projects'
-import { isNativeApp } from '@/lib/environment'
+import { useCanUseTerminals } from '@/lib/transport'
 import { notify } from '@/lib/notifications'
 import { ChatWindow } from './ChatWindow'
 import { ModalTerminalDrawer } from './ModalTerminalDrawer'
@@ -80,6 +80,8 @@ export function SessionChatModal({
     onOpenFullView()
   }, [onOpenFullView])
 
+  const canUseTerminals = useCanUseTerminals()
+
   const handleRun = useCallback(() => {
     if (!runScript) {
       notify('No run script configured in jean.json', undefined, {
@@ -105,7 +107,7 @@ export function SessionChatModal({
               {session?.name ?? 'Session'}
             </DialogTitle>
             <div className="flex items-center gap-1">
-              {isNativeApp() && (
+              {canUseTerminals && (
                 <>
                   <Button
                     variant="ghost"
@@ -158,7 +160,7 @@ export function SessionChatModal({
         </div>
 
         {/* Terminal side drawer */}
-        {isNativeApp() && (
+        {canUseTerminals && (
           <ModalTerminalDrawer
             worktreeId={worktreeId}
             worktreePath={worktreePath}
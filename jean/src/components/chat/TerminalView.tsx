@@ -1,8 +1,16 @@
 import { useEffect, useRef, useCallback, memo } from 'react'
-import { Plus, X, Minus, Terminal, ChevronUp } from 'lucide-react'
+import { Plus, X, Minus, Terminal, ChevronUp, Circle } from 'lucide-react'
+import { toast } from 'sonner'
 import { invoke } from '@/lib/transport'
 import { useTerminal } from '@/hooks/useTerminal'
 import { useTerminalStore, type TerminalInstance } from '@/store/terminal-store'
+import { useChatStore } from '@/store/chat-store'
+import {
+  attachRecordingToSession,
+  useStartTerminalRecording,
+  useStopTerminalRecording,
+  useTerminalRecordings,
+} from '@/services/terminal-recordings'
 import {
   disposeTerminal,
   disposeAllWorktreeTerminals,
@@ -159,6 +167,45 @@ export function TerminalView({
     [worktreeId, setActiveTerminal]
   )
 
+  const { data: recordings } = useTerminalRecordings()
+  const startRecording = useStartTerminalRecording()
+  const stopRecording = useStopTerminalRecording()
+  const isRecording =
+    !!activeTerminalId &&
+    (recordings ?? []).some(r => r.terminal_id === activeTerminalId)
+
+  const handleToggleRecording = useCallback(async () => {
+    if (!activeTerminalId) return
+    if (!isRecording) {
+      startRecording.mutate({ terminalId: activeTerminalId })
+      return
+    }
+
+    const recordingId = await stopRecording.mutateAsync(activeTerminalId)
+    if (!recordingId) return
+    const sessionId = useChatStore.getState().activeSessionIds[worktreeId]
+    toast.success('Recording saved', {
+      action: sessionId
+        ? {
+            label: 'Attach to chat',
+            onClick: () => {
+              attachRecordingToSession(sessionId, recordingId).catch(error =>
+                toast.error('Failed to attach recording', {
+                  description: String(error),
+                })
+              )
+            },
+          }
+        : undefined,
+    })
+  }, [
+    activeTerminalId,
+    isRecording,
+    worktreeId,
+    startRecording,
+    stopRecording,
+  ])
+
   const handleMinimize = useCallback(() => {
     setTerminalVisible(false)
   }, [setTerminalVisible])
@@ -266,6 +313,24 @@ export function TerminalView({
           <Plus className="h-3.5 w-3.5" />
         </button>
 
+        {/* Record active terminal (asciicast) */}
+        {activeTerminalId && runningTerminals.has(activeTerminalId) && (
+          <button
+            type="button"
+            onClick={handleToggleRecording}
+            className={cn(
+              'flex shrink-0 items-center px-2 transition-colors hover:bg-neutral-800/50',
+              isRecording
+                ? 'text-red-500 hover:text-red-400'
+                : 'text-neutral-400 hover:text-neutral-300'
+            )}
+            aria-label={isRecording ? 'Stop recording' : 'Record terminal'}
+            title={isRecording ? 'Stop recording' : 'Record terminal'}
+          >
+            <Circle className={cn('h-3 w-3', isRecording && 'fill-current')} />
+          </button>
+        )}
+
         {/* Spacer */}
         <div className="flex-1" />
 
@@ -79,6 +79,8 @@ interface VirtualizedMessageListProps {
   onFixAllFindings: (
     findings: { finding: ReviewFinding; suggestion?: string }[]
   ) => Promise<void>
+  /** Callback when user forks the session at a message */
+  onForkFromMessage?: (messageId: string) => void
   /** Check if a question has been answered */
   isQuestionAnswered: (sessionId: string, toolCallId: string) => boolean
   /** Get submitted answers for a question */
@@ -124,6 +126,7 @@ export const VirtualizedMessageList = memo(
         onEditedFileClick,
         onFixFinding,
         onFixAllFindings,
+        onForkFromMessage,
         isQuestionAnswered,
         getSubmittedAnswers,
         areQuestionsSkipped,
@@ -299,6 +302,7 @@ export const VirtualizedMessageList = memo(
                   onEditedFileClick={onEditedFileClick}
                   onFixFinding={onFixFinding}
                   onFixAllFindings={onFixAllFindings}
+                  onForkFromMessage={onForkFromMessage}
                   isQuestionAnswered={isQuestionAnswered}
                   getSubmittedAnswers={getSubmittedAnswers}
                   areQuestionsSkipped={areQuestionsSkipped}
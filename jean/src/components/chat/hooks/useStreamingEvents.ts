@@ -25,6 +25,9 @@ import type {
   PermissionDeniedEvent,
   CompactingEvent,
   CompactedEvent,
+  QueuedMessage,
+  QueueDispatchedEvent,
+  QueueUpdatedEvent,
   Session,
   SessionDigest,
 } from '@/types/chat'
@@ -39,7 +42,7 @@ interface UseStreamingEventsParams {
  *
  * Handles: chat:chunk, chat:tool_use, chat:tool_block, chat:thinking,
  * chat:tool_result, chat:permission_denied, chat:done, chat:error,
- * chat:cancelled, chat:compacted
+ * chat:cancelled, chat:compacted, chat:queue_updated, chat:queue_dispatched
  */
 export default function useStreamingEvents({
   queryClient,
@@ -307,28 +310,22 @@ export default function useStreamingEvents({
       clearLastSentMessage(sessionId)
 
       if (hasUnansweredBlockingTool) {
-        // Check if there are queued messages AND only ExitPlanMode is blocking (not AskUserQuestion)
-        const { messageQueues } = useChatStore.getState()
-        const hasQueuedMessages = (messageQueues[sessionId]?.length ?? 0) > 0
-        const isOnlyExitPlanMode =
-          toolCalls?.every(
-            tc => !isAskUserQuestion(tc) || isQuestionAnswered(sessionId, tc.id)
-          ) &&
-          toolCalls?.some(
-            tc => isExitPlanMode(tc) && !isQuestionAnswered(sessionId, tc.id)
-          )
-
-        if (hasQueuedMessages && isOnlyExitPlanMode) {
-          // Queued message takes priority over plan approval
-          // Clear tool calls so approval UI doesn't show, let queue processor handle the queued message
-          // Don't set waitingForInput(true) - this allows queue processor to send the queued message
+        // The backend sends the next queued message once the run completes
+        const hasQueuedMessages =
+          (queryClient.getQueryData<QueuedMessage[]>(
+            chatQueryKeys.queue(sessionId)
+          )?.length ?? 0) > 0
+
+        if (hasQueuedMessages) {
+          // Queued message takes priority over the question or plan approval
+          // Clear tool calls so the blocking UI doesn't show while it runs
           clearStreamingContent(sessionId)
           clearStreamingContentBlocks(sessionId)
           clearToolCalls(sessionId)
           clearExecutingMode(sessionId)
           removeSendingSession(sessionId)
           console.log(
-            '[useStreamingEvents] ExitPlanMode with queued messages - skipping wait state, queue will process'
+            '[useStreamingEvents] Blocking tool with queued messages - skipping wait state, queue will process'
           )
         } else {
           // Original behavior: show blocking tool UI and wait for user input
@@ -849,6 +846,38 @@ export default function useStreamingEvents({
       }
     )
 
+    // Keep the per-session queue cache in sync with the backend queue
+    const unlistenQueueUpdated = listen<QueueUpdatedEvent>(
+      'chat:queue_updated',
+      event => {
+        const { session_id, queue } = event.payload
+        queryClient.setQueryData(chatQueryKeys.queue(session_id), queue)
+      }
+    )
+
+    // A queued message is being sent: set up sending state as for a direct send
+    const unlistenQueueDispatched = listen<QueueDispatchedEvent>(
+      'chat:queue_dispatched',
+      event => {
+        const { session_id, item } = event.payload
+        const store = useChatStore.getState()
+        store.clearStreamingContent(session_id)
+        store.clearToolCalls(session_id)
+        store.clearStreamingContentBlocks(session_id)
+        store.setLastSentMessage(session_id, item.message)
+        store.setError(session_id, null)
+        store.setWaitingForInput(session_id, false)
+        store.setSessionReviewing(session_id, false)
+        store.addSendingSession(session_id)
+        if (item.execution_mode) {
+          store.setExecutingMode(session_id, item.execution_mode)
+        }
+        if (item.model) {
+          store.setSelectedModel(session_id, item.model)
+        }
+      }
+    )
+
     // Handle session setting changes (model, thinking level, execution mode)
     // Broadcast by other clients via broadcast_session_setting command
     const unlistenSettingChanged = listen<{
@@ -888,6 +917,8 @@ export default function useStreamingEvents({
       unlistenCompacting.then(f => f())
       unlistenCompacted.then(f => f())
       unlistenSettingChanged.then(f => f())
+      unlistenQueueUpdated.then(f => f())
+      unlistenQueueDispatched.then(f => f())
     }
   }, [queryClient, wsConnected])
 }
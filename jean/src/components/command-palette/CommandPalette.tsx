@@ -3,11 +3,14 @@ import { useUIStore } from '@/store/ui-store'
 import { useCommandContext } from '@/hooks/use-command-context'
 import { usePreferences } from '@/services/preferences'
 import { useProjects, useAppDataDir } from '@/services/projects'
+import { useSearchSessions } from '@/services/chat'
+import { useDebouncedValue } from '@/hooks/useDebouncedValue'
 import { useChatStore } from '@/store/chat-store'
 import { useProjectsStore } from '@/store/projects-store'
 import { convertFileSrc } from '@tauri-apps/api/core'
 import { getAllCommands, executeCommand } from '@/lib/commands'
 import { formatShortcutDisplay } from '@/types/keybindings'
+import type { SearchHit } from '@/types/chat'
 import {
   CommandDialog,
   CommandInput,
@@ -29,6 +32,20 @@ interface ProjectCommand {
   execute: () => void
 }
 
+// Session history search kicks in from this many characters
+const MIN_SESSION_SEARCH_CHARS = 3
+
+function openSearchHit(hit: SearchHit) {
+  if (!hit.worktree_path) return
+  if (hit.project_id) {
+    useProjectsStore.getState().selectProject(hit.project_id)
+  }
+  useProjectsStore.getState().selectWorktree(hit.worktree_id)
+  const { setActiveWorktree, setActiveSession } = useChatStore.getState()
+  setActiveWorktree(hit.worktree_id, hit.worktree_path)
+  setActiveSession(hit.worktree_id, hit.session_id)
+}
+
 export function CommandPalette() {
   const { commandPaletteOpen, setCommandPaletteOpen } = useUIStore()
   const { data: preferences } = usePreferences()
@@ -39,6 +56,20 @@ export function CommandPalette() {
   const { data: projects = [] } = useProjects()
   const { data: appDataDir } = useAppDataDir()
 
+  // Full-text search over session histories (archived sessions can't be opened)
+  const debouncedSearch = useDebouncedValue(search.trim(), 250)
+  const { data: sessionHits = [] } = useSearchSessions(
+    commandPaletteOpen && debouncedSearch.length >= MIN_SESSION_SEARCH_CHARS
+      ? debouncedSearch
+      : '',
+    { include_archived: false },
+    8
+  )
+  const openableHits = useMemo(
+    () => sessionHits.filter(hit => hit.worktree_path),
+    [sessionHits]
+  )
+
   // Create dynamic project commands
   const projectCommands = useMemo((): ProjectCommand[] => {
     return projects
@@ -89,6 +120,15 @@ export function CommandPalette() {
     return { staticGroups, projectCommands: filteredProjectCommands }
   }, [commandContext, search, projectCommands])
 
+  const handleSessionSelect = useCallback(
+    (hit: SearchHit) => {
+      setCommandPaletteOpen(false)
+      setSearch('')
+      openSearchHit(hit)
+    },
+    [setCommandPaletteOpen]
+  )
+
   // Handle command execution
   const handleCommandSelect = useCallback(
     async (commandId: string) => {
@@ -184,6 +224,34 @@ export function CommandPalette() {
           </CommandGroup>
         )}
 
+        {/* Matches in session histories */}
+        {openableHits.length > 0 && (
+          <CommandGroup heading="Session History">
+            {openableHits.map(hit => (
+              <CommandItem
+                key={`${hit.session_id}-${hit.message_id}`}
+                value={`session-${hit.session_id}-${hit.message_id}`}
+                keywords={[search]}
+                onSelect={() => handleSessionSelect(hit)}
+              >
+                <div className="flex min-w-0 flex-col">
+                  <span className="truncate">
+                    {hit.session_name}
+                    <span className="ml-2 text-xs text-muted-foreground">
+                      {[hit.project_name, hit.worktree_name]
+                        .filter(Boolean)
+                        .join(' / ')}
+                    </span>
+                  </span>
+                  <span className="truncate text-xs text-muted-foreground">
+                    {hit.snippet}
+                  </span>
+                </div>
+              </CommandItem>
+            ))}
+          </CommandGroup>
+        )}
+
         {/* Static command groups */}
         {Object.entries(commandGroups.staticGroups).map(
           ([groupName, groupCommands]) => (
@@ -11,6 +11,7 @@ import { OnboardingDialog } from '@/components/onboarding/OnboardingDialog'
 import { CliUpdateModal } from '@/components/layout/CliUpdateModal'
 import { CliLoginModal } from '@/components/preferences/CliLoginModal'
 import { OpenInModal } from '@/components/open-in/OpenInModal'
+import { ImportSessionModal } from '@/components/chat/ImportSessionModal'
 import { MagicModal } from '@/components/magic/MagicModal'
 import { CheckoutPRModal } from '@/components/magic/CheckoutPRModal'
 import { NewWorktreeModal } from '@/components/worktree/NewWorktreeModal'
@@ -41,6 +42,7 @@ import {
   useCreateWorktreeKeybinding,
   useWorktreeEvents,
 } from '@/services/projects'
+import { useScheduleEvents } from '@/services/scheduler'
 import { usePreferences } from '@/services/preferences'
 import { useSessions } from '@/services/chat'
 import { useChatStore } from '@/store/chat-store'
@@ -166,6 +168,7 @@ export function MainWindow() {
   // Listen for background worktree events (creation/deletion) - must be here
   // (not in sidebar) so events are received even when sidebar is closed
   useWorktreeEvents()
+  useScheduleEvents()
 
   // Handle CMD+N keybinding to create new worktree
   useCreateWorktreeKeybinding()
@@ -263,6 +266,7 @@ export function MainWindow() {
       <CliUpdateModal />
       <CliLoginModal />
       <OpenInModal />
+      <ImportSessionModal />
       <MagicModal />
       <CheckoutPRModal />
       <NewWorktreeModal />
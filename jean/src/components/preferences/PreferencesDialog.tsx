@@ -6,6 +6,8 @@ import {
   Wand2,
   FlaskConical,
   Globe,
+  Coins,
+  CalendarClock,
 } from 'lucide-react'
 import {
   Breadcrumb,
@@ -38,6 +40,8 @@ import { KeybindingsPane } from './panes/KeybindingsPane'
 import { MagicPromptsPane } from './panes/MagicPromptsPane'
 import { ExperimentalPane } from './panes/ExperimentalPane'
 import { WebAccessPane } from './panes/WebAccessPane'
+import { UsagePane } from './panes/UsagePane'
+import { SchedulesPane } from './panes/SchedulesPane'
 
 const navigationItems = [
   {
@@ -60,6 +64,16 @@ const navigationItems = [
     name: 'Magic Prompts',
     icon: Wand2,
   },
+  {
+    id: 'usage' as const,
+    name: 'Usage',
+    icon: Coins,
+  },
+  {
+    id: 'schedules' as const,
+    name: 'Schedules',
+    icon: CalendarClock,
+  },
   {
     id: 'experimental' as const,
     name: 'Experimental',
@@ -82,6 +96,10 @@ const getPaneTitle = (pane: PreferencePane): string => {
       return 'Keybindings'
     case 'magic-prompts':
       return 'Magic Prompts'
+    case 'usage':
+      return 'Usage'
+    case 'schedules':
+      return 'Schedules'
     case 'experimental':
       return 'Experimental'
     case 'web-access':
@@ -173,6 +191,8 @@ export function PreferencesDialog() {
               {activePane === 'appearance' && <AppearancePane />}
               {activePane === 'keybindings' && <KeybindingsPane />}
               {activePane === 'magic-prompts' && <MagicPromptsPane />}
+              {activePane === 'usage' && <UsagePane />}
+              {activePane === 'schedules' && <SchedulesPane />}
               {activePane === 'experimental' && <ExperimentalPane />}
               {activePane === 'web-access' && <WebAccessPane />}
             </div>
@@ -0,0 +1,411 @@
+import React, { useDeferredValue, useState } from 'react'
+import { Pencil, Play, Plus, Trash2 } from 'lucide-react'
+import { Button } from '@/components/ui/button'
+import { Input } from '@/components/ui/input'
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select'
+import { Separator } from '@/components/ui/separator'
+import { Switch } from '@/components/ui/switch'
+import { Textarea } from '@/components/ui/textarea'
+import {
+  useSaveSchedule,
+  useScheduleAction,
+  useSchedulePreview,
+  useSchedules,
+} from '@/services/scheduler'
+import { useProjects, useWorktrees } from '@/services/projects'
+import { isFolder } from '@/types/projects'
+import type {
+  Schedule,
+  ScheduleOutcome,
+  ScheduleRun,
+  ScheduleTrigger,
+} from '@/types/scheduler'
+
+const BASE_SESSION = 'base'
+
+const outcomeLabels: Record<ScheduleOutcome, string> = {
+  running: 'Running',
+  completed: 'Completed',
+  cancelled: 'Cancelled',
+  queued: 'Queued',
+  skipped: 'Skipped',
+  failed: 'Failed',
+}
+
+function formatTime(timestamp: number): string {
+  return new Date(timestamp * 1000).toLocaleString(undefined, {
+    month: 'short',
+    day: 'numeric',
+    hour: '2-digit',
+    minute: '2-digit',
+  })
+}
+
+function describeTrigger(trigger: ScheduleTrigger): string {
+  return trigger.type === 'cron'
+    ? `Cron: ${trigger.expression}`
+    : `Once at ${formatTime(trigger.at)}`
+}
+
+// Value for a datetime-local input, in local time
+function toLocalInput(timestamp: number): string {
+  const date = new Date(timestamp * 1000)
+  date.setMinutes(date.getMinutes() - date.getTimezoneOffset())
+  return date.toISOString().slice(0, 16)
+}
+
+const SettingsSection: React.FC<{
+  title: string
+  actions?: React.ReactNode
+  children: React.ReactNode
+}> = ({ title, actions, children }) => (
+  <div className="space-y-4">
+    <div>
+      <div className="flex items-center justify-between gap-4">
+        <h3 className="text-lg font-medium text-foreground">{title}</h3>
+        {actions}
+      </div>
+      <Separator className="mt-2" />
+    </div>
+    {children}
+  </div>
+)
+
+const ScheduleEditor: React.FC<{
+  /** Omit to create a new schedule */
+  schedule?: Schedule
+  onDone: () => void
+}> = ({ schedule, onDone }) => {
+  const { data: projects = [] } = useProjects()
+  const saveSchedule = useSaveSchedule()
+
+  const [name, setName] = useState(schedule?.name ?? '')
+  const [prompt, setPrompt] = useState(schedule?.prompt ?? '')
+  const [projectId, setProjectId] = useState(schedule?.target.project_id ?? '')
+  const [worktreeId, setWorktreeId] = useState(
+    schedule?.target.worktree_id ?? BASE_SESSION
+  )
+  const [triggerType, setTriggerType] = useState<ScheduleTrigger['type']>(
+    schedule?.trigger.type ?? 'cron'
+  )
+  const [expression, setExpression] = useState(
+    schedule?.trigger.type === 'cron'
+      ? schedule.trigger.expression
+      : '0 9 * * 1-5'
+  )
+  const [onceAt, setOnceAt] = useState(
+    toLocalInput(
+      schedule?.trigger.type === 'once'
+        ? schedule.trigger.at
+        : Math.floor(Date.now() / 1000) + 60 * 60
+    )
+  )
+
+  const { data: worktrees = [] } = useWorktrees(projectId || null)
+
+  const trigger: ScheduleTrigger =
+    triggerType === 'cron'
+      ? { type: 'cron', expression: expression.trim() }
+      : { type: 'once', at: Math.floor(new Date(onceAt).getTime() / 1000) }
+  const deferredTrigger = useDeferredValue(trigger)
+  const { data: preview = [], error: previewError } = useSchedulePreview(
+    triggerType === 'cron' && !expression.trim() ? null : deferredTrigger,
+    3
+  )
+
+  const target = {
+    project_id: projectId,
+    worktree_id: worktreeId === BASE_SESSION ? undefined : worktreeId,
+  }
+  // Keep sending to the same session while the target is unchanged
+  const sameTarget =
+    schedule?.target.project_id === target.project_id &&
+    schedule?.target.worktree_id === target.worktree_id
+
+  const handleSave = () =>
+    saveSchedule.mutate(
+      {
+        scheduleId: schedule?.id,
+        schedule: {
+          name,
+          prompt,
+          trigger,
+          target: {
+            ...target,
+            session_id: sameTarget ? schedule?.target.session_id : undefined,
+          },
+          model: schedule?.model,
+          execution_mode: schedule?.execution_mode,
+          thinking_level: schedule?.thinking_level,
+          enabled: schedule?.enabled ?? true,
+        },
+      },
+      { onSuccess: onDone }
+    )
+
+  return (
+    <div className="space-y-3 rounded-md border border-border p-3">
+      <Input
+        className="h-8"
+        placeholder="Schedule name"
+        value={name}
+        onChange={e => setName(e.target.value)}
+      />
+      <Textarea
+        placeholder="Prompt sent on every run"
+        value={prompt}
+        onChange={e => setPrompt(e.target.value)}
+      />
+      <div className="flex items-center gap-2">
+        <Select
+          value={projectId}
+          onValueChange={value => {
+            setProjectId(value)
+            setWorktreeId(BASE_SESSION)
+          }}
+        >
+          <SelectTrigger className="h-8 flex-1">
+            <SelectValue placeholder="Project" />
+          </SelectTrigger>
+          <SelectContent>
+            {projects
+              .filter(project => !isFolder(project))
+              .map(project => (
+                <SelectItem key={project.id} value={project.id}>
+                  {project.name}
+                </SelectItem>
+              ))}
+          </SelectContent>
+        </Select>
+        <Select
+          value={worktreeId}
+          onValueChange={setWorktreeId}
+          disabled={!projectId}
+        >
+          <SelectTrigger className="h-8 flex-1">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            <SelectItem value={BASE_SESSION}>Base session</SelectItem>
+            {worktrees
+              .filter(worktree => worktree.session_type !== 'base')
+              .map(worktree => (
+                <SelectItem key={worktree.id} value={worktree.id}>
+                  {worktree.name}
+                </SelectItem>
+              ))}
+          </SelectContent>
+        </Select>
+      </div>
+      <div className="flex items-center gap-2">
+        <Select
+          value={triggerType}
+          onValueChange={value =>
+            setTriggerType(value as ScheduleTrigger['type'])
+          }
+        >
+          <SelectTrigger className="h-8 w-32">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            <SelectItem value="cron">Repeat</SelectItem>
+            <SelectItem value="once">Once</SelectItem>
+          </SelectContent>
+        </Select>
+        {triggerType === 'cron' ? (
+          <Input
+            className="h-8 flex-1 font-mono"
+            placeholder="Cron expression, e.g. 0 9 * * 1-5"
+            value={expression}
+            onChange={e => setExpression(e.target.value)}
+          />
+        ) : (
+          <Input
+            className="h-8 flex-1"
+            type="datetime-local"
+            value={onceAt}
+            onChange={e => setOnceAt(e.target.value)}
+          />
+        )}
+      </div>
+      <p className="text-xs text-muted-foreground">
+        {previewError
+          ? String(previewError)
+          : preview.length > 0
+            ? `Next: ${preview.map(formatTime).join(', ')}`
+            : 'Never runs'}
+      </p>
+      <div className="flex justify-end gap-2">
+        <Button variant="ghost" size="sm" onClick={onDone}>
+          Cancel
+        </Button>
+        <Button
+          size="sm"
+          disabled={
+            !name.trim() ||
+            !prompt.trim() ||
+            !projectId ||
+            saveSchedule.isPending
+          }
+          onClick={handleSave}
+        >
+          {schedule ? 'Save' : 'Create'}
+        </Button>
+      </div>
+    </div>
+  )
+}
+
+const RunHistory: React.FC<{ runs: ScheduleRun[] }> = ({ runs }) => {
+  if (runs.length === 0) return null
+
+  return (
+    <div className="space-y-0.5 text-xs text-muted-foreground">
+      {runs
+        .slice(-3)
+        .reverse()
+        .map(run => (
+          <div key={run.id || run.fired_at} className="flex gap-2">
+            <span className="w-28 shrink-0">{formatTime(run.fired_at)}</span>
+            <span
+              className={run.outcome === 'failed' ? 'text-destructive' : ''}
+            >
+              {outcomeLabels[run.outcome]}
+            </span>
+            {run.error && <span className="truncate">{run.error}</span>}
+          </div>
+        ))}
+    </div>
+  )
+}
+
+export const SchedulesPane: React.FC = () => {
+  const { data: schedules = [], isLoading } = useSchedules()
+  const { data: projects = [] } = useProjects()
+  const scheduleAction = useScheduleAction()
+  // Schedule being edited: an id, 'new', or null
+  const [editing, setEditing] = useState<string | null>(null)
+
+  const projectName = (projectId: string) =>
+    projects.find(project => project.id === projectId)?.name ?? 'Unknown'
+
+  return (
+    <div className="space-y-6">
+      <SettingsSection
+        title="Schedules"
+        actions={
+          <Button
+            variant="outline"
+            size="sm"
+            disabled={editing !== null}
+            onClick={() => setEditing('new')}
+          >
+            <Plus className="h-3 w-3" />
+            Add schedule
+          </Button>
+        }
+      >
+        <p className="text-sm text-muted-foreground">
+          Send a prompt on a cron schedule (local time) or once at a set time.
+          Each run starts a new session unless the schedule targets one; if the
+          session is busy the prompt is queued behind the current run.
+        </p>
+        {editing === 'new' && (
+          <ScheduleEditor onDone={() => setEditing(null)} />
+        )}
+        {isLoading ? (
+          <p className="text-sm text-muted-foreground">Loading schedules...</p>
+        ) : schedules.length === 0 && editing !== 'new' ? (
+          <p className="text-sm text-muted-foreground">
+            No schedules configured.
+          </p>
+        ) : (
+          <div className="space-y-3">
+            {schedules.map(schedule =>
+              editing === schedule.id ? (
+                <ScheduleEditor
+                  key={schedule.id}
+                  schedule={schedule}
+                  onDone={() => setEditing(null)}
+                />
+              ) : (
+                <div
+                  key={schedule.id}
+                  className="space-y-2 rounded-md border border-border p-3"
+                >
+                  <div className="flex items-center gap-2">
+                    <div className="min-w-0 flex-1">
+                      <div className="truncate text-sm font-medium">
+                        {schedule.name}
+                      </div>
+                      <div className="truncate text-xs text-muted-foreground">
+                        {projectName(schedule.target.project_id)} ·{' '}
+                        {describeTrigger(schedule.trigger)}
+                        {schedule.next_run_at &&
+                          ` · next ${formatTime(schedule.next_run_at)}`}
+                      </div>
+                    </div>
+                    <Switch
+                      checked={schedule.enabled}
+                      onCheckedChange={enabled =>
+                        scheduleAction.mutate({
+                          scheduleId: schedule.id,
+                          action: enabled ? 'enable' : 'disable',
+                        })
+                      }
+                    />
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      className="h-8 w-8 p-0"
+                      title="Run now"
+                      onClick={() =>
+                        scheduleAction.mutate({
+                          scheduleId: schedule.id,
+                          action: 'run',
+                        })
+                      }
+                    >
+                      <Play className="h-3.5 w-3.5" />
+                    </Button>
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      className="h-8 w-8 p-0"
+                      title="Edit"
+                      disabled={editing !== null}
+                      onClick={() => setEditing(schedule.id)}
+                    >
+                      <Pencil className="h-3.5 w-3.5" />
+                    </Button>
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      className="h-8 w-8 p-0"
+                      title="Delete"
+                      onClick={() =>
+                        scheduleAction.mutate({
+                          scheduleId: schedule.id,
+                          action: 'delete',
+                        })
+                      }
+                    >
+                      <Trash2 className="h-3.5 w-3.5" />
+                    </Button>
+                  </div>
+                  <RunHistory runs={schedule.history} />
+                </div>
+              )
+            )}
+          </div>
+        )}
+      </SettingsSection>
+    </div>
+  )
+}
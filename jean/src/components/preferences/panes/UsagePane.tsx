@@ -0,0 +1,402 @@
+import React, { useMemo, useState } from 'react'
+import { Plus, Trash2 } from 'lucide-react'
+import { Button } from '@/components/ui/button'
+import { Input } from '@/components/ui/input'
+import { Label } from '@/components/ui/label'
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select'
+import { Separator } from '@/components/ui/separator'
+import { Switch } from '@/components/ui/switch'
+import { useUsageReport } from '@/services/chat'
+import { usePreferences, useSavePreferences } from '@/services/preferences'
+import { useProjects } from '@/services/projects'
+import type {
+  UsageBucket,
+  UsageBudget,
+  UsageFilter,
+  UsageTotals,
+} from '@/types/chat'
+
+type UsageRange = 'all' | '30d' | '7d' | 'today'
+
+const rangeOptions: { value: UsageRange; label: string }[] = [
+  { value: 'today', label: 'Today' },
+  { value: '7d', label: 'Last 7 days' },
+  { value: '30d', label: 'Last 30 days' },
+  { value: 'all', label: 'All time' },
+]
+
+const DAY_SECS = 24 * 60 * 60
+
+function rangeFilter(range: UsageRange): UsageFilter {
+  const now = Math.floor(Date.now() / 1000)
+  switch (range) {
+    case 'today': {
+      const midnight = new Date()
+      midnight.setHours(0, 0, 0, 0)
+      return { since: Math.floor(midnight.getTime() / 1000) }
+    }
+    case '7d':
+      return { since: now - 7 * DAY_SECS }
+    case '30d':
+      return { since: now - 30 * DAY_SECS }
+    default:
+      return {}
+  }
+}
+
+function formatCost(usd: number): string {
+  return `$${usd.toFixed(usd >= 100 ? 0 : 2)}`
+}
+
+function formatTokens(count: number): string {
+  if (count >= 1_000_000) return `${(count / 1_000_000).toFixed(1)}M`
+  if (count >= 1_000) return `${(count / 1_000).toFixed(1)}k`
+  return String(count)
+}
+
+function totalTokens(totals: UsageTotals): number {
+  return (
+    totals.input_tokens +
+    totals.output_tokens +
+    totals.cache_read_input_tokens +
+    totals.cache_creation_input_tokens
+  )
+}
+
+const SettingsSection: React.FC<{
+  title: string
+  actions?: React.ReactNode
+  children: React.ReactNode
+}> = ({ title, actions, children }) => (
+  <div className="space-y-4">
+    <div>
+      <div className="flex items-center justify-between gap-4">
+        <h3 className="text-lg font-medium text-foreground">{title}</h3>
+        {actions}
+      </div>
+      <Separator className="mt-2" />
+    </div>
+    {children}
+  </div>
+)
+
+const UsageTable: React.FC<{
+  label: string
+  buckets: UsageBucket[]
+  limit?: number
+  /** Newest day first instead of most expensive first */
+  byKey?: boolean
+}> = ({ label, buckets, limit = 10, byKey = false }) => {
+  if (buckets.length === 0) return null
+
+  const rows = [...buckets]
+    .sort((a, b) =>
+      byKey
+        ? b.key.localeCompare(a.key)
+        : b.totals.cost_usd - a.totals.cost_usd
+    )
+    .slice(0, limit)
+
+  return (
+    <div className="space-y-2">
+      <Label className="text-sm text-foreground">{label}</Label>
+      <div className="rounded-md border border-border text-sm">
+        {rows.map(bucket => (
+          <div
+            key={bucket.key}
+            className="flex items-center gap-4 border-b border-border px-3 py-1.5 last:border-b-0"
+          >
+            <span className="min-w-0 flex-1 truncate">
+              {bucket.name ?? bucket.key}
+            </span>
+            <span className="w-20 text-right text-muted-foreground">
+              {formatTokens(totalTokens(bucket.totals))}
+            </span>
+            <span className="w-20 text-right font-medium">
+              {formatCost(bucket.totals.cost_usd)}
+            </span>
+          </div>
+        ))}
+      </div>
+    </div>
+  )
+}
+
+type LimitField =
+  | 'soft_limit_usd'
+  | 'hard_limit_usd'
+  | 'soft_limit_tokens'
+  | 'hard_limit_tokens'
+
+const limitFields: { field: LimitField; label: string }[] = [
+  { field: 'soft_limit_usd', label: 'Warn at $' },
+  { field: 'hard_limit_usd', label: 'Stop at $' },
+  { field: 'soft_limit_tokens', label: 'Warn at tokens' },
+  { field: 'hard_limit_tokens', label: 'Stop at tokens' },
+]
+
+// Select value for a budget scope ("global", "project:<id>", "worktree:<id>")
+function scopeValue(scope: UsageBudget['scope']): string {
+  switch (scope.type) {
+    case 'project':
+      return `project:${scope.project_id}`
+    case 'worktree':
+      return `worktree:${scope.worktree_id}`
+    default:
+      return 'global'
+  }
+}
+
+function parseScope(value: string): UsageBudget['scope'] {
+  if (value.startsWith('project:')) {
+    return { type: 'project', project_id: value.slice('project:'.length) }
+  }
+  if (value.startsWith('worktree:')) {
+    return { type: 'worktree', worktree_id: value.slice('worktree:'.length) }
+  }
+  return { type: 'global' }
+}
+
+const BudgetsSection: React.FC = () => {
+  const { data: preferences } = usePreferences()
+  const savePreferences = useSavePreferences()
+  const { data: projects = [] } = useProjects()
+
+  const budgets = preferences?.usage_budgets ?? []
+
+  const saveBudgets = (next: UsageBudget[]) => {
+    if (preferences) {
+      savePreferences.mutate({ ...preferences, usage_budgets: next })
+    }
+  }
+
+  const updateBudget = (id: string, changes: Partial<UsageBudget>) =>
+    saveBudgets(budgets.map(b => (b.id === id ? { ...b, ...changes } : b)))
+
+  const addBudget = () =>
+    saveBudgets([
+      ...budgets,
+      {
+        id: crypto.randomUUID(),
+        scope: { type: 'global' },
+        period: 'daily',
+        soft_limit_usd: 20,
+        hard_limit_usd: 50,
+        enabled: true,
+      },
+    ])
+
+  return (
+    <SettingsSection
+      title="Budgets"
+      actions={
+        <Button variant="outline" size="sm" onClick={addBudget}>
+          <Plus className="h-3 w-3" />
+          Add budget
+        </Button>
+      }
+    >
+      <p className="text-sm text-muted-foreground">
+        Checked before every message. Reaching a warning limit shows a
+        notification; reaching a stop limit refuses the message.
+      </p>
+      {budgets.length === 0 ? (
+        <p className="text-sm text-muted-foreground">No budgets configured.</p>
+      ) : (
+        <div className="space-y-3">
+          {budgets.map(budget => (
+            <div
+              key={budget.id}
+              className="space-y-3 rounded-md border border-border p-3"
+            >
+              <div className="flex items-center gap-2">
+                <Input
+                  className="h-8 flex-1"
+                  placeholder="Budget name (optional)"
+                  value={budget.name ?? ''}
+                  onChange={e =>
+                    updateBudget(budget.id, {
+                      name: e.target.value || undefined,
+                    })
+                  }
+                />
+                <Select
+                  value={scopeValue(budget.scope)}
+                  onValueChange={value =>
+                    updateBudget(budget.id, { scope: parseScope(value) })
+                  }
+                >
+                  <SelectTrigger className="h-8 w-48">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectItem value="global">All projects</SelectItem>
+                    {budget.scope.type === 'worktree' && (
+                      <SelectItem value={scopeValue(budget.scope)}>
+                        Single worktree
+                      </SelectItem>
+                    )}
+                    {projects.map(project => (
+                      <SelectItem
+                        key={project.id}
+                        value={`project:${project.id}`}
+                      >
+                        {project.name}
+                      </SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
+                <Select
+                  value={budget.period}
+                  onValueChange={value =>
+                    updateBudget(budget.id, {
+                      period: value as UsageBudget['period'],
+                    })
+                  }
+                >
+                  <SelectTrigger className="h-8 w-28">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectItem value="daily">Per day</SelectItem>
+                    <SelectItem value="total">All time</SelectItem>
+                  </SelectContent>
+                </Select>
+                <Switch
+                  checked={budget.enabled}
+                  onCheckedChange={enabled =>
+                    updateBudget(budget.id, { enabled })
+                  }
+                />
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  className="h-8 w-8 p-0"
+                  onClick={() =>
+                    saveBudgets(budgets.filter(b => b.id !== budget.id))
+                  }
+                >
+                  <Trash2 className="h-3.5 w-3.5" />
+                </Button>
+              </div>
+              <div className="grid grid-cols-4 gap-2">
+                {limitFields.map(({ field, label }) => (
+                  <div key={field} className="space-y-1">
+                    <Label className="text-xs text-muted-foreground">
+                      {label}
+                    </Label>
+                    <Input
+                      className="h-8"
+                      type="number"
+                      min={0}
+                      placeholder="None"
+                      value={budget[field] ?? ''}
+                      onChange={e => {
+                        const parsed = parseFloat(e.target.value)
+                        // Token limits are whole numbers on the backend
+                        const value = field.endsWith('_tokens')
+                          ? Math.floor(parsed)
+                          : parsed
+                        updateBudget(budget.id, {
+                          [field]:
+                            Number.isFinite(value) && value >= 0
+                              ? value
+                              : undefined,
+                        })
+                      }}
+                    />
+                  </div>
+                ))}
+              </div>
+            </div>
+          ))}
+        </div>
+      )}
+    </SettingsSection>
+  )
+}
+
+export const UsagePane: React.FC = () => {
+  const [range, setRange] = useState<UsageRange>('30d')
+  const filter = useMemo(() => rangeFilter(range), [range])
+  const { data: report, isLoading } = useUsageReport(filter)
+
+  return (
+    <div className="space-y-6">
+      <SettingsSection
+        title="Token Usage"
+        actions={
+          <Select
+            value={range}
+            onValueChange={value => setRange(value as UsageRange)}
+          >
+            <SelectTrigger className="w-40">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {rangeOptions.map(option => (
+                <SelectItem key={option.value} value={option.value}>
+                  {option.label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        }
+      >
+        {isLoading ? (
+          <p className="text-sm text-muted-foreground">Loading usage...</p>
+        ) : !report || report.total.run_count === 0 ? (
+          <p className="text-sm text-muted-foreground">
+            No runs with usage data in this period.
+          </p>
+        ) : (
+          <div className="space-y-4">
+            <div className="flex gap-8 text-sm">
+              <div>
+                <div className="text-muted-foreground">Estimated cost</div>
+                <div className="text-2xl font-semibold">
+                  {formatCost(report.total.cost_usd)}
+                </div>
+              </div>
+              <div>
+                <div className="text-muted-foreground">Tokens</div>
+                <div className="text-2xl font-semibold">
+                  {formatTokens(totalTokens(report.total))}
+                </div>
+              </div>
+              <div>
+                <div className="text-muted-foreground">Runs</div>
+                <div className="text-2xl font-semibold">
+                  {report.total.run_count}
+                </div>
+              </div>
+            </div>
+            {report.total.unpriced_run_count > 0 && (
+              <p className="text-xs text-muted-foreground">
+                {report.total.unpriced_run_count} run(s) used a model with no
+                configured price and are not included in the cost.
+              </p>
+            )}
+            <UsageTable label="By project" buckets={report.by_project} />
+            <UsageTable label="By worktree" buckets={report.by_worktree} />
+            <UsageTable label="By model" buckets={report.by_model} />
+            <UsageTable
+              label="By day"
+              buckets={report.by_day}
+              limit={31}
+              byKey
+            />
+          </div>
+        )}
+      </SettingsSection>
+
+      <BudgetsSection />
+    </div>
+  )
+}
@@ -1,11 +1,32 @@
 import React, { useCallback, useEffect, useState } from 'react'
-import { Copy, Eye, EyeOff, ExternalLink, RefreshCw, ShieldAlert } from 'lucide-react'
+import {
+  Copy,
+  Eye,
+  EyeOff,
+  ExternalLink,
+  RefreshCw,
+  ShieldAlert,
+  Trash2,
+} from 'lucide-react'
 import { Label } from '@/components/ui/label'
+import { Checkbox } from '@/components/ui/checkbox'
+import {
+  NativeSelect,
+  NativeSelectOption,
+} from '@/components/ui/native-select'
 import { Separator } from '@/components/ui/separator'
 import { Switch } from '@/components/ui/switch'
 import { Input } from '@/components/ui/input'
 import { Button } from '@/components/ui/button'
 import { usePreferences, useSavePreferences } from '@/services/preferences'
+import { useProjects } from '@/services/projects'
+import {
+  useApiTokens,
+  useCreateApiToken,
+  useRevokeApiToken,
+} from '@/services/api-tokens'
+import type { TokenScope } from '@/types/api-tokens'
+import { isFolder } from '@/types/projects'
 import { invoke } from '@/lib/transport'
 import { toast } from 'sonner'
 import { isNativeApp } from '@/lib/environment'
@@ -40,12 +61,215 @@ const InlineField: React.FC<{
   </div>
 )
 
+const SCOPE_OPTIONS: { value: TokenScope; label: string }[] = [
+  { value: 'read_only', label: 'Read-only' },
+  { value: 'chat', label: 'Chat' },
+  { value: 'git_write', label: 'Git write' },
+  { value: 'terminal', label: 'Terminal' },
+  { value: 'admin', label: 'Admin' },
+]
+
+const EXPIRY_OPTIONS = [
+  { days: 0, label: 'Never expires' },
+  { days: 7, label: '7 days' },
+  { days: 30, label: '30 days' },
+  { days: 90, label: '90 days' },
+]
+
+const formatDate = (timestamp: number) =>
+  new Date(timestamp * 1000).toLocaleDateString()
+
+const ApiTokensSection: React.FC = () => {
+  const { data: tokens = [] } = useApiTokens()
+  const { data: projects = [] } = useProjects()
+  const createToken = useCreateApiToken()
+  const revokeToken = useRevokeApiToken()
+  const [name, setName] = useState('')
+  const [scopes, setScopes] = useState<TokenScope[]>(['read_only'])
+  const [projectId, setProjectId] = useState('')
+  const [expiryDays, setExpiryDays] = useState(0)
+  const [secret, setSecret] = useState<string | null>(null)
+
+  const projectName = useCallback(
+    (id: string) => projects.find(p => p.id === id)?.name ?? id,
+    [projects]
+  )
+
+  const toggleScope = useCallback((scope: TokenScope, checked: boolean) => {
+    setScopes(current =>
+      checked ? [...current, scope] : current.filter(s => s !== scope)
+    )
+  }, [])
+
+  const handleCreate = useCallback(() => {
+    createToken.mutate(
+      {
+        name,
+        scopes,
+        projectIds: projectId ? [projectId] : undefined,
+        expiresAt: expiryDays
+          ? Math.floor(Date.now() / 1000) + expiryDays * 24 * 60 * 60
+          : undefined,
+      },
+      {
+        onSuccess: created => {
+          setSecret(created.secret)
+          setName('')
+        },
+      }
+    )
+  }, [createToken, name, scopes, projectId, expiryDays])
+
+  const handleCopySecret = useCallback(() => {
+    if (!secret) return
+    navigator.clipboard.writeText(secret)
+    toast.success('Token copied to clipboard')
+  }, [secret])
+
+  return (
+    <SettingsSection title="API Tokens">
+      <div className="space-y-4">
+        <p className="text-xs text-muted-foreground">
+          Named tokens with limited permissions for scripts and other devices.
+          Every scope includes read access; yolo mode requires admin.
+        </p>
+
+        <div className="space-y-3 rounded-md border border-muted p-3">
+          <div className="flex items-center gap-2">
+            <Input
+              placeholder="Token name"
+              className="w-48"
+              value={name}
+              onChange={e => setName(e.target.value)}
+            />
+            <NativeSelect
+              value={projectId}
+              onChange={e => setProjectId(e.target.value)}
+            >
+              <NativeSelectOption value="">All projects</NativeSelectOption>
+              {projects
+                .filter(p => !isFolder(p))
+                .map(p => (
+                  <NativeSelectOption key={p.id} value={p.id}>
+                    {p.name}
+                  </NativeSelectOption>
+                ))}
+            </NativeSelect>
+            <NativeSelect
+              value={expiryDays}
+              onChange={e => setExpiryDays(Number(e.target.value))}
+            >
+              {EXPIRY_OPTIONS.map(option => (
+                <NativeSelectOption key={option.days} value={option.days}>
+                  {option.label}
+                </NativeSelectOption>
+              ))}
+            </NativeSelect>
+          </div>
+          <div className="flex items-center gap-4">
+            {SCOPE_OPTIONS.map(option => (
+              <label
+                key={option.value}
+                className="flex items-center gap-1.5 text-sm"
+              >
+                <Checkbox
+                  checked={scopes.includes(option.value)}
+                  onCheckedChange={checked =>
+                    toggleScope(option.value, checked === true)
+                  }
+                />
+                {option.label}
+              </label>
+            ))}
+            <Button
+              size="sm"
+              className="ml-auto"
+              onClick={handleCreate}
+              disabled={
+                !name.trim() || scopes.length === 0 || createToken.isPending
+              }
+            >
+              Create token
+            </Button>
+          </div>
+          {secret && (
+            <div className="flex items-center gap-2">
+              <Input
+                className="w-96 font-mono text-xs"
+                value={secret}
+                readOnly
+              />
+              <Button variant="ghost" size="icon" onClick={handleCopySecret}>
+                <Copy className="h-4 w-4" />
+              </Button>
+              <span className="text-xs text-muted-foreground">
+                Copy it now, it won&apos;t be shown again
+              </span>
+            </div>
+          )}
+        </div>
+
+        {tokens.length > 0 && (
+          <div className="divide-y divide-muted rounded-md border border-muted">
+            {tokens.map(token => (
+              <div key={token.id} className="flex items-center gap-3 p-3">
+                <div className="min-w-0 flex-1 space-y-0.5">
+                  <div className="flex items-center gap-2 text-sm">
+                    <span className="font-medium">{token.name}</span>
+                    <span className="font-mono text-xs text-muted-foreground">
+                      {token.prefix}…
+                    </span>
+                    {token.expired && (
+                      <span className="text-xs text-destructive">Expired</span>
+                    )}
+                  </div>
+                  <div className="text-xs text-muted-foreground">
+                    {token.scopes
+                      .map(
+                        scope =>
+                          SCOPE_OPTIONS.find(o => o.value === scope)?.label ??
+                          scope
+                      )
+                      .join(', ')}
+                    {' · '}
+                    {token.project_ids.length > 0
+                      ? token.project_ids.map(projectName).join(', ')
+                      : 'All projects'}
+                    {token.expires_at &&
+                      ` · Expires ${formatDate(token.expires_at)}`}
+                    {' · '}
+                    {token.last_used_at
+                      ? `Last used ${formatDate(token.last_used_at)}`
+                      : 'Never used'}
+                  </div>
+                </div>
+                <Button
+                  variant="ghost"
+                  size="icon"
+                  onClick={() => revokeToken.mutate(token.id)}
+                  disabled={revokeToken.isPending}
+                  title="Revoke token"
+                >
+                  <Trash2 className="h-4 w-4" />
+                </Button>
+              </div>
+            ))}
+          </div>
+        )}
+      </div>
+    </SettingsSection>
+  )
+}
+
 interface ServerStatus {
   running: boolean
   port: number | null
   url: string | null
   token: string | null
   localhost_only: boolean | null
+  tls: boolean | null
+  /** SHA-256 of the served certificate (HTTPS only) */
+  tls_fingerprint: string | null
 }
 
 export const WebAccessPane: React.FC = () => {
@@ -152,6 +376,83 @@ export const WebAccessPane: React.FC = () => {
     [preferences, savePreferences, serverStatus?.running, refreshStatus]
   )
 
+  const restartServer = useCallback(
+    async (message: string) => {
+      setIsToggling(true)
+      try {
+        await invoke('stop_http_server')
+        await new Promise(resolve => setTimeout(resolve, 100))
+        await invoke('start_http_server')
+        await refreshStatus()
+        toast.success(message)
+      } catch (error) {
+        toast.error(`Failed to restart server: ${error}`)
+      } finally {
+        setIsToggling(false)
+      }
+    },
+    [refreshStatus]
+  )
+
+  const handleTlsEnabledChange = useCallback(
+    async (checked: boolean) => {
+      if (!preferences) return
+      await savePreferences.mutateAsync({
+        ...preferences,
+        http_server_tls_enabled: checked,
+      })
+      if (serverStatus?.running) {
+        await restartServer(
+          checked ? 'Server restarted with HTTPS' : 'Server restarted with HTTP'
+        )
+      }
+    },
+    [preferences, savePreferences, serverStatus?.running, restartServer]
+  )
+
+  const handleTlsPathChange = useCallback(
+    (
+      key: 'http_server_tls_cert_path' | 'http_server_tls_key_path',
+      value: string
+    ) => {
+      if (!preferences) return
+      const path = value.trim() || null
+      if ((preferences[key] ?? null) === path) return
+      savePreferences.mutate({ ...preferences, [key]: path })
+    },
+    [preferences, savePreferences]
+  )
+
+  const handleAllowedIpsChange = useCallback(
+    async (value: string) => {
+      if (!preferences) return
+      const allowedIps = value
+        .split(/[\s,]+/)
+        .map(entry => entry.trim())
+        .filter(Boolean)
+      if (
+        allowedIps.join(',') ===
+        (preferences.http_server_allowed_ips ?? []).join(',')
+      ) {
+        return
+      }
+      await savePreferences.mutateAsync({
+        ...preferences,
+        http_server_allowed_ips: allowedIps,
+      })
+      if (serverStatus?.running) {
+        await restartServer('Server restarted with new allowed addresses')
+      }
+    },
+    [preferences, savePreferences, serverStatus?.running, restartServer]
+  )
+
+  const handleCopyFingerprint = useCallback(() => {
+    if (!serverStatus?.tls_fingerprint) return
+    navigator.clipboard.writeText(serverStatus.tls_fingerprint)
+    toast.success('Fingerprint copied to clipboard')
+  }, [serverStatus])
+
   const handleCopyToken = useCallback(() => {
     if (!serverStatus?.token) return
     navigator.clipboard.writeText(serverStatus.token)
@@ -197,6 +498,9 @@ export const WebAccessPane: React.FC = () => {
     )
   }
 
+  const scheme = serverStatus?.tls ? 'https' : 'http'
+  const localUrl = `${scheme}://localhost:${serverStatus?.port}`
+
   return (
     <div className="space-y-6">
       <div className="rounded-lg border border-muted p-4">
@@ -276,6 +580,24 @@ export const WebAccessPane: React.FC = () => {
               disabled={isToggling}
             />
           </InlineField>
+
+          {!(preferences?.http_server_localhost_only ?? true) && (
+            <InlineField
+              label="Allowed addresses"
+              description="IPs or CIDR ranges, comma-separated (empty = any). This device is always allowed."
+            >
+              <Input
+                key={(preferences?.http_server_allowed_ips ?? []).join(',')}
+                className="w-64 font-mono text-xs"
+                placeholder="192.168.1.0/24"
+                defaultValue={(preferences?.http_server_allowed_ips ?? []).join(
+                  ', '
+                )}
+                onBlur={e => handleAllowedIpsChange(e.target.value)}
+                disabled={isToggling}
+              />
+            </InlineField>
+          )}
         </div>
       </SettingsSection>
 
@@ -351,16 +673,14 @@ export const WebAccessPane: React.FC = () => {
                   <Input
                     type="text"
                     className="w-64 font-mono text-xs"
-                    value={`http://localhost:${serverStatus.port}`}
+                    value={localUrl}
                     readOnly
                   />
                   <Button
                     variant="ghost"
                     size="icon"
                     onClick={() =>
-                      openUrl(
-                        `http://localhost:${serverStatus.port}?token=${serverStatus.token}`
-                      )
+                      openUrl(`${localUrl}?token=${serverStatus.token}`)
                     }
                     title="Open in browser"
                   >
@@ -369,9 +689,7 @@ export const WebAccessPane: React.FC = () => {
                   <Button
                     variant="ghost"
                     size="icon"
-                    onClick={() =>
-                      handleCopyUrl(`http://localhost:${serverStatus.port}`)
-                    }
+                    onClick={() => handleCopyUrl(localUrl)}
                     title="Copy URL with token"
                   >
                     <Copy className="h-4 w-4" />
@@ -414,6 +732,89 @@ export const WebAccessPane: React.FC = () => {
           )}
         </div>
       </SettingsSection>
+
+      <SettingsSection title="HTTPS">
+        <div className="space-y-4">
+          <InlineField
+            label="Use HTTPS"
+            description="Encrypt traffic, including the access token, with TLS"
+          >
+            <Switch
+              checked={preferences?.http_server_tls_enabled ?? false}
+              onCheckedChange={handleTlsEnabledChange}
+              disabled={isToggling}
+            />
+          </InlineField>
+
+          {preferences?.http_server_tls_enabled && (
+            <>
+              <InlineField
+                label="Certificate file"
+                description="PEM certificate chain; leave empty for a self-signed certificate"
+              >
+                <Input
+                  key={preferences.http_server_tls_cert_path ?? ''}
+                  className="w-64 font-mono text-xs"
+                  placeholder="Self-signed"
+                  defaultValue={preferences.http_server_tls_cert_path ?? ''}
+                  onBlur={e =>
+                    handleTlsPathChange(
+                      'http_server_tls_cert_path',
+                      e.target.value
+                    )
+                  }
+                  disabled={serverStatus?.running}
+                />
+              </InlineField>
+
+              <InlineField
+                label="Key file"
+                description="PEM private key for the certificate"
+              >
+                <Input
+                  key={preferences.http_server_tls_key_path ?? ''}
+                  className="w-64 font-mono text-xs"
+                  placeholder="Self-signed"
+                  defaultValue={preferences.http_server_tls_key_path ?? ''}
+                  onBlur={e =>
+                    handleTlsPathChange(
+                      'http_server_tls_key_path',
+                      e.target.value
+                    )
+                  }
+                  disabled={serverStatus?.running}
+                />
+              </InlineField>
+            </>
+          )}
+
+          {serverStatus?.running && serverStatus.tls_fingerprint && (
+            <InlineField
+              label="Certificate fingerprint"
+              description="SHA-256; compare with the certificate your browser shows"
+            >
+              <div className="flex items-center gap-2">
+                <Input
+                  className="w-64 font-mono text-xs"
+                  value={serverStatus.tls_fingerprint}
+                  readOnly
+                />
+                <Button
+                  variant="ghost"
+                  size="icon"
+                  onClick={handleCopyFingerprint}
+                >
+                  <Copy className="h-4 w-4" />
+                </Button>
+              </div>
+            </InlineField>
+          )}
+        </div>
+      </SettingsSection>
+
+      {(preferences?.http_server_token_required ?? true) && (
+        <ApiTokensSection />
+      )}
     </div>
   )
 }
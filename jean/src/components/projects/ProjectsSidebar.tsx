@@ -8,7 +8,10 @@ import {
   DropdownMenuTrigger,
 } from '@/components/ui/dropdown-menu'
 import { useProjects, useCreateFolder } from '@/services/projects'
-import { fetchWorktreesStatus } from '@/services/git-status'
+import {
+  fetchWorktreesStatus,
+  usePolledWorktrees,
+} from '@/services/git-status'
 import { useProjectsStore } from '@/store/projects-store'
 import { ProjectTree } from './ProjectTree'
 import { AddProjectDialog } from './AddProjectDialog'
@@ -18,6 +21,7 @@ import { ArchivedModal } from '@/components/archive/ArchivedModal'
 export function ProjectsSidebar() {
   const { data: projects = [], isLoading } = useProjects()
   const { setAddProjectDialogOpen } = useProjectsStore()
+  usePolledWorktrees()
   const [archivedModalOpen, setArchivedModalOpen] = useState(false)
   const createFolder = useCreateFolder()
   const sidebarWidth = useSidebarWidth()
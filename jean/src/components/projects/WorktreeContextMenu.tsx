@@ -29,6 +29,7 @@ import {
 import type { Worktree } from '@/types/projects'
 import { getEditorLabel, getTerminalLabel } from '@/types/preferences'
 import { isNativeApp } from '@/lib/environment'
+import { useCanUseTerminals } from '@/lib/transport'
 import { useWorktreeMenuActions } from './useWorktreeMenuActions'
 
 interface WorktreeContextMenuProps {
@@ -60,6 +61,7 @@ export function WorktreeContextMenu({
     handleOpenJeanConfig,
     handleGenerateRecap,
   } = useWorktreeMenuActions({ worktree, projectId })
+  const canUseTerminals = useCanUseTerminals()
 
   // Suppress unused variable warning
   void projectPath
@@ -68,7 +70,7 @@ export function WorktreeContextMenu({
     <ContextMenu>
       <ContextMenuTrigger asChild>{children}</ContextMenuTrigger>
       <ContextMenuContent className="w-48">
-        {isNativeApp() && runScript && (
+        {canUseTerminals && runScript && (
           <ContextMenuItem onClick={handleRun}>
             <Play className="mr-2 h-4 w-4" />
             Run
@@ -31,6 +31,7 @@ import { Button } from '@/components/ui/button'
 import type { Worktree } from '@/types/projects'
 import { getEditorLabel, getTerminalLabel } from '@/types/preferences'
 import { isNativeApp } from '@/lib/environment'
+import { useCanUseTerminals } from '@/lib/transport'
 import { useWorktreeMenuActions } from './useWorktreeMenuActions'
 
 interface WorktreeDropdownMenuProps {
@@ -58,6 +59,7 @@ export function WorktreeDropdownMenu({
     handleOpenJeanConfig,
     handleGenerateRecap,
   } = useWorktreeMenuActions({ worktree, projectId })
+  const canUseTerminals = useCanUseTerminals()
 
   return (
     <>
@@ -73,7 +75,7 @@ export function WorktreeDropdownMenu({
           </Button>
         </DropdownMenuTrigger>
         <DropdownMenuContent align="start" className="w-48">
-          {isNativeApp() && runScript && (
+          {canUseTerminals && runScript && (
             <DropdownMenuItem onClick={handleRun}>
               <Play className="mr-2 h-4 w-4" />
               Run
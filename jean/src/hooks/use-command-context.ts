@@ -1,5 +1,6 @@
 import { useCallback, useContext, useMemo } from 'react'
 import { invoke } from '@/lib/transport'
+import { isNativeApp } from '@/lib/environment'
 import { toast } from 'sonner'
 import { useUIStore } from '@/store/ui-store'
 import { useProjectsStore } from '@/store/projects-store'
@@ -8,12 +9,19 @@ import { useTerminalStore } from '@/store/terminal-store'
 import { ThemeProviderContext, type Theme } from '@/lib/theme-context'
 import { notify } from '@/lib/notifications'
 import { logger } from '@/lib/logger'
-import type { CommandContext } from '@/lib/commands/types'
+import type {
+  CommandContext,
+  SessionExportTarget,
+} from '@/lib/commands/types'
 import type { AppPreferences, ClaudeModel } from '@/types/preferences'
-import type { ThinkingLevel, ExecutionMode } from '@/types/chat'
+import type { ThinkingLevel, ExecutionMode, Session } from '@/types/chat'
 import type { Project, ReviewResponse } from '@/types/projects'
 import { useQueryClient } from '@tanstack/react-query'
-import { chatQueryKeys } from '@/services/chat'
+import {
+  chatQueryKeys,
+  exportSession as renderSessionExport,
+  exportSessionBundle,
+} from '@/services/chat'
 import { projectsQueryKeys } from '@/services/projects'
 import { gitPull, triggerImmediateGitPoll } from '@/services/git-status'
 
@@ -655,6 +663,80 @@ export function useCommandContext(
     }
   }, [])
 
+  // Session - Export active session to a file
+  const exportSession = useCallback(
+    async (target: SessionExportTarget) => {
+      const { activeWorktreeId, getActiveSession } = useChatStore.getState()
+      const sessionId = activeWorktreeId
+        ? getActiveSession(activeWorktreeId)
+        : undefined
+      if (!sessionId) {
+        notify('No session selected', undefined, { type: 'error' })
+        return
+      }
+
+      const extension =
+        target === 'markdown' ? 'md' : target === 'bundle' ? 'zip' : target
+      const name =
+        queryClient.getQueryData<Session>(chatQueryKeys.session(sessionId))
+          ?.name ?? 'session'
+      const fileName = `${name.replace(/[^\w.-]+/g, '-')}.${extension}`
+
+      try {
+        // Web clients can't pick a path on the server, so download instead
+        if (!isNativeApp()) {
+          if (target === 'bundle') {
+            notify(
+              'Session bundles can only be exported from the desktop app',
+              undefined,
+              { type: 'error' }
+            )
+            return
+          }
+          const content = await renderSessionExport(sessionId, target)
+          const url = URL.createObjectURL(new Blob([content]))
+          const link = document.createElement('a')
+          link.href = url
+          link.download = fileName
+          link.click()
+          URL.revokeObjectURL(url)
+          return
+        }
+
+        const { save } = await import('@tauri-apps/plugin-dialog')
+        const destPath = await save({
+          title: 'Export session',
+          defaultPath: fileName,
+          filters: [
+            { name: extension.toUpperCase(), extensions: [extension] },
+          ],
+        })
+        if (!destPath) return
+
+        if (target === 'bundle') {
+          await exportSessionBundle(sessionId, destPath)
+        } else {
+          const content = await renderSessionExport(sessionId, target)
+          await invoke('write_file_content', { path: destPath, content })
+        }
+        notify('Session exported', destPath, { type: 'success' })
+      } catch (error) {
+        const message = error instanceof Error ? error.message : String(error)
+        notify('Failed to export session', message, { type: 'error' })
+      }
+    },
+    [queryClient]
+  )
+
+  // Session - Import a Claude CLI conversation or session bundle
+  const openImportSessionModal = useCallback(() => {
+    if (!useProjectsStore.getState().selectedWorktreeId) {
+      notify('No worktree selected', undefined, { type: 'error' })
+      return
+    }
+    useUIStore.getState().setImportSessionModalOpen(true)
+  }, [])
+
   // State getter - Check if run script is available
   const hasRunScript = useCallback(() => {
     // This needs to check if jean.json has a run script
@@ -691,6 +773,8 @@ export function useCommandContext(
       clearSessionHistory,
       renameSession,
       resumeSession,
+      exportSession,
+      openImportSessionModal,
 
       // Worktrees
       createWorktree,
@@ -772,6 +856,8 @@ export function useCommandContext(
       clearSessionHistory,
       renameSession,
       resumeSession,
+      exportSession,
+      openImportSessionModal,
       createWorktree,
       nextWorktree,
       previousWorktree,
@@ -3,6 +3,7 @@ import { listen, invoke } from '@/lib/transport'
 import { isNativeApp } from '@/lib/environment'
 import { notify } from '@/lib/notifications'
 import { useQueryClient, type QueryClient } from '@tanstack/react-query'
+import { toast } from 'sonner'
 import { useUIStore } from '@/store/ui-store'
 import { useProjectsStore } from '@/store/projects-store'
 import { useChatStore } from '@/store/chat-store'
@@ -20,6 +21,29 @@ import {
   type KeybindingsMap,
 } from '@/types/keybindings'
 import { isBaseSession, type Project, type Worktree } from '@/types/projects'
+import type { BudgetEvent, BudgetStatus } from '@/types/chat'
+
+// "Daily budget: $4.20 of $5.00" for budget event toasts
+function describeBudget(status: BudgetStatus): string {
+  const { budget, spent_usd, spent_tokens, level } = status
+  const name =
+    budget.name ??
+    `${budget.period === 'daily' ? 'Daily' : 'Total'} ${budget.scope.type} budget`
+  const usdLimit =
+    level === 'exceeded' ? budget.hard_limit_usd : budget.soft_limit_usd
+  const tokenLimit =
+    level === 'exceeded' ? budget.hard_limit_tokens : budget.soft_limit_tokens
+  const parts: string[] = []
+  if (usdLimit != null) {
+    parts.push(`$${spent_usd.toFixed(2)} of $${usdLimit.toFixed(2)}`)
+  }
+  if (tokenLimit != null) {
+    parts.push(
+      `${spent_tokens.toLocaleString()} of ${tokenLimit.toLocaleString()} tokens`
+    )
+  }
+  return `${name}: ${parts.join(', ')}`
+}
 
 // Throttle tracking for worktree switching
 let lastWorktreeSwitchTime = 0
@@ -568,6 +592,61 @@ export function useMainWindowEventListeners() {
           // Silent failure - don't show toast to avoid interrupting workflow
         }),
 
+        // Rejected remote access attempts (emitted to the desktop app only)
+        listen<{
+          ip: string
+          reason: 'invalid_token' | 'rate_limited' | 'locked_out' | 'not_allowed'
+          failures: number
+          retry_after_secs: number | null
+        }>('http-server:auth-rejected', event => {
+          const { ip, reason, failures, retry_after_secs } = event.payload
+          logger.warn('Web access attempt rejected', { ip, reason, failures })
+          const description =
+            reason === 'locked_out'
+              ? `${failures} failed attempts, blocked for ${retry_after_secs}s`
+              : reason === 'rate_limited'
+                ? 'Too many connection attempts'
+                : reason === 'not_allowed'
+                  ? 'Address is not in the allowlist'
+                  : 'Invalid access token'
+          notify(`Web access from ${ip} rejected`, description, {
+            type: 'warning',
+          })
+        }),
+
+        // Usage budgets (checked before every run)
+        listen<BudgetEvent>('budget:warning', event => {
+          logger.warn('Usage budget warning', { ...event.payload })
+          toast.warning('Approaching usage budget', {
+            id: `budget-warning-${event.payload.worktree_id}`,
+            description: event.payload.statuses.map(describeBudget).join('\n'),
+            action: {
+              label: 'Budgets',
+              onClick: () =>
+                useUIStore.getState().openPreferencesPane('usage'),
+            },
+          })
+        }),
+
+        listen<BudgetEvent>('budget:exceeded', event => {
+          const { statuses, overridden } = event.payload
+          logger.warn('Usage budget exceeded', { ...event.payload })
+          toast.error(
+            overridden
+              ? 'Usage budget exceeded (overridden)'
+              : 'Usage budget exceeded, message not sent',
+            {
+              id: `budget-exceeded-${event.payload.worktree_id}`,
+              description: statuses.map(describeBudget).join('\n'),
+              action: {
+                label: 'Budgets',
+                onClick: () =>
+                  useUIStore.getState().openPreferencesPane('usage'),
+              },
+            }
+          )
+        }),
+
         // Real-time cache sync between native + web clients
         listen<{ keys: string[] }>('cache:invalidate', event => {
           const { keys } = event.payload
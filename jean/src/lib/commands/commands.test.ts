@@ -9,6 +9,7 @@ const {
 } = await import('./registry')
 const { notificationCommands } = await import('./notification-commands')
 const { projectCommands } = await import('./project-commands')
+const { sessionCommands } = await import('./session-commands')
 
 const createMockContext = (): CommandContext => ({
   // Query client
@@ -40,6 +41,8 @@ const createMockContext = (): CommandContext => ({
   clearSessionHistory: vi.fn().mockResolvedValue(undefined),
   renameSession: vi.fn(),
   resumeSession: vi.fn().mockResolvedValue(undefined),
+  exportSession: vi.fn().mockResolvedValue(undefined),
+  openImportSessionModal: vi.fn(),
 
   // Worktrees
   createWorktree: vi.fn(),
@@ -207,6 +210,42 @@ describe('Project Commands', () => {
   })
 })
 
+describe('Session Commands', () => {
+  let mockContext: CommandContext
+
+  beforeEach(() => {
+    clearRegistry()
+    mockContext = createMockContext()
+    registerCommands(sessionCommands)
+  })
+
+  it('hides export commands without an active session', () => {
+    mockContext.hasActiveSession = vi.fn().mockReturnValue(false)
+    expect(getAllCommands(mockContext).map(c => c.id)).toEqual([
+      'import-session',
+    ])
+  })
+
+  it('exports the active session in the chosen format', async () => {
+    const result = await executeCommand('export-session-html', mockContext)
+    expect(result.success).toBe(true)
+    expect(mockContext.exportSession).toHaveBeenCalledWith('html')
+
+    await executeCommand('export-session-bundle', mockContext)
+    expect(mockContext.exportSession).toHaveBeenCalledWith('bundle')
+  })
+
+  it('opens the import dialog for the active worktree', async () => {
+    const result = await executeCommand('import-session', mockContext)
+    expect(result.success).toBe(true)
+    expect(mockContext.openImportSessionModal).toHaveBeenCalled()
+
+    mockContext.hasActiveSession = vi.fn().mockReturnValue(false)
+    mockContext.hasActiveWorktree = vi.fn().mockReturnValue(false)
+    expect(getAllCommands(mockContext)).toHaveLength(0)
+  })
+})
+
 describe('Notification Commands', () => {
   let mockContext: CommandContext
 
@@ -3,6 +3,7 @@ export * from './registry'
 export * from '../../hooks/use-command-context'
 import { notificationCommands } from './notification-commands'
 import { projectCommands } from './project-commands'
+import { sessionCommands } from './session-commands'
 import { registerCommands } from './registry'
 
 /**
@@ -12,10 +13,11 @@ import { registerCommands } from './registry'
 export function initializeCommandSystem(): void {
   registerCommands(notificationCommands)
   registerCommands(projectCommands)
+  registerCommands(sessionCommands)
 
   if (import.meta.env.DEV) {
     console.log('Command system initialized')
   }
 }
 
-export { notificationCommands, projectCommands }
+export { notificationCommands, projectCommands, sessionCommands }
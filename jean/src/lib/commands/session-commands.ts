@@ -0,0 +1,70 @@
+import {
+  FileArchive,
+  FileCode,
+  FileDown,
+  FileJson,
+  FileText,
+} from 'lucide-react'
+import type { AppCommand } from './types'
+
+export const sessionCommands: AppCommand[] = [
+  {
+    id: 'export-session-markdown',
+    label: 'Export Session as Markdown',
+    description: 'Save the current session history as a Markdown file',
+    icon: FileText,
+    group: 'sessions',
+    keywords: ['session', 'export', 'markdown', 'md', 'save', 'share'],
+
+    execute: context => context.exportSession('markdown'),
+    isAvailable: context => context.hasActiveSession(),
+  },
+
+  {
+    id: 'export-session-html',
+    label: 'Export Session as HTML',
+    description: 'Save the current session history as a standalone web page',
+    icon: FileCode,
+    group: 'sessions',
+    keywords: ['session', 'export', 'html', 'save', 'share'],
+
+    execute: context => context.exportSession('html'),
+    isAvailable: context => context.hasActiveSession(),
+  },
+
+  {
+    id: 'export-session-json',
+    label: 'Export Session as JSON',
+    description: 'Save the current session history as JSON',
+    icon: FileJson,
+    group: 'sessions',
+    keywords: ['session', 'export', 'json', 'save'],
+
+    execute: context => context.exportSession('json'),
+    isAvailable: context => context.hasActiveSession(),
+  },
+
+  {
+    id: 'export-session-bundle',
+    label: 'Export Session Bundle',
+    description: 'Save the session with its run logs so it can be imported',
+    icon: FileArchive,
+    group: 'sessions',
+    keywords: ['session', 'export', 'bundle', 'zip', 'backup', 'move'],
+
+    execute: context => context.exportSession('bundle'),
+    isAvailable: context => context.hasActiveSession(),
+  },
+
+  {
+    id: 'import-session',
+    label: 'Import Session...',
+    description: 'Import a Claude CLI conversation or a session bundle',
+    icon: FileDown,
+    group: 'sessions',
+    keywords: ['session', 'import', 'claude', 'cli', 'bundle', 'restore'],
+
+    execute: context => context.openImportSessionModal(),
+    isAvailable: context => context.hasActiveWorktree(),
+  },
+]
@@ -2,7 +2,10 @@ import type { LucideIcon } from 'lucide-react'
 import type { QueryClient } from '@tanstack/react-query'
 import type { Theme } from '@/lib/theme-context'
 import type { ClaudeModel } from '@/types/preferences'
-import type { ThinkingLevel, ExecutionMode } from '@/types/chat'
+import type { ThinkingLevel, ExecutionMode, ExportFormat } from '@/types/chat'
+
+/** Rendered document formats plus the re-importable zip bundle */
+export type SessionExportTarget = ExportFormat | 'bundle'
 
 export interface AppCommand {
   id: string
@@ -50,6 +53,8 @@ export interface CommandContext {
   clearSessionHistory: () => Promise<void>
   renameSession: () => void
   resumeSession: () => Promise<void>
+  exportSession: (target: SessionExportTarget) => Promise<void>
+  openImportSessionModal: () => void
 
   // Worktrees
   createWorktree: () => void
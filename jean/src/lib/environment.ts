@@ -5,7 +5,8 @@
  * - hasBackend(): true when a backend is available (Tauri IPC or HTTP/WS)
  *
  * Services should guard with hasBackend(), not isTauri().
- * UI should use isNativeApp() to hide terminal, Finder, etc.
+ * UI should use isNativeApp() to hide Finder, native editors, etc., and
+ * canUseTerminals() from the transport for the terminal panel.
  */
 
 /** Running inside the native Tauri desktop app. */
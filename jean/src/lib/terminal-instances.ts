@@ -10,11 +10,13 @@
 
 import { Terminal } from '@xterm/xterm'
 import { FitAddon } from '@xterm/addon-fit'
-import { invoke } from '@/lib/transport'
+import { invoke, subscribeEvents, WS_RECONNECTED_EVENT } from '@/lib/transport'
 import { listen, type UnlistenFn } from '@/lib/transport'
+import { isNativeApp } from '@/lib/environment'
 import { useTerminalStore } from '@/store/terminal-store'
 import type {
   TerminalOutputEvent,
+  TerminalScrollback,
   TerminalStartedEvent,
   TerminalStoppedEvent,
 } from '@/types/terminal'
@@ -27,6 +29,11 @@ interface PersistentTerminal {
   worktreePath: string
   command: string | null
   initialized: boolean // PTY has been started
+  // Resolves once event listeners (and, in the browser, the server-side
+  // subscription to this terminal's output) are in place
+  ready: Promise<unknown>
+  // Live output received while the scrollback is being fetched
+  pendingOutput: TerminalOutputEvent[] | null
 }
 
 // Module-level Map - persists across React mount/unmount cycles
@@ -80,14 +87,41 @@ export function getOrCreateTerminal(
   })
 
   const listeners: UnlistenFn[] = []
+  const track = (unlisten: Promise<UnlistenFn>) =>
+    unlisten.then(fn => listeners.push(fn))
 
   // Setup event listeners ONCE when terminal is created
   // These persist for the lifetime of the terminal instance
-  listen<TerminalOutputEvent>('terminal:output', event => {
-    if (event.payload.terminal_id === terminalId) {
-      terminal.write(event.payload.data)
+  const outputListener = listen<TerminalOutputEvent>(
+    'terminal:output',
+    event => {
+      if (event.payload.terminal_id !== terminalId) return
+      const pending = instances.get(terminalId)?.pendingOutput
+      if (pending) {
+        pending.push(event.payload)
+      } else {
+        terminal.write(event.payload.data)
+      }
     }
-  }).then(unlisten => listeners.push(unlisten))
+  )
+
+  // The server only sends terminal output to clients that ask for it
+  const outputSubscription = subscribeEvents({
+    events: ['terminal:output'],
+    terminal_ids: [terminalId],
+  })
+
+  if (!isNativeApp()) {
+    // Output sent while disconnected is lost; redraw from the scrollback
+    track(
+      listen(WS_RECONNECTED_EVENT, () => {
+        const instance = instances.get(terminalId)
+        if (!instance?.initialized) return
+        instance.terminal.reset()
+        replayScrollback(instance, terminalId)
+      })
+    )
+  }
 
   listen<TerminalStartedEvent>('terminal:started', event => {
     if (event.payload.terminal_id === terminalId) {
@@ -113,6 +147,13 @@ export function getOrCreateTerminal(
     worktreePath,
     command,
     initialized: false,
+    pendingOutput: null,
+    ready: Promise.all([
+      track(outputListener),
+      track(outputSubscription),
+    ]).catch(error => {
+      console.error('[terminal-instances] event setup failed:', error)
+    }),
   }
 
   instances.set(terminalId, instance)
@@ -161,6 +202,7 @@ export async function attachToContainer(
   requestAnimationFrame(async () => {
     fitAddon.fit()
     const { cols, rows } = terminal
+    await instance.ready
 
     if (!initialized) {
       // First time - check if PTY already exists (reconnecting after app restart)
@@ -169,8 +211,9 @@ export async function attachToContainer(
       })
 
       if (ptyExists) {
-        // PTY exists - just resize and mark as running
+        // PTY exists - replay its recent output, resize and mark as running
         useTerminalStore.getState().setTerminalRunning(terminalId, true)
+        await replayScrollback(instance, terminalId)
         await invoke('terminal_resize', { terminalId, cols, rows }).catch(
           console.error
         )
@@ -200,6 +243,40 @@ export async function attachToContainer(
   })
 }
 
+/**
+ * Write the PTY's buffered output, then resume live output.
+ * Live chunks that arrive meanwhile are held back and only written if the
+ * scrollback doesn't already contain them.
+ */
+async function replayScrollback(
+  instance: PersistentTerminal,
+  terminalId: string
+): Promise<void> {
+  instance.pendingOutput = []
+  let end = 0
+  try {
+    const scrollback = await invoke<TerminalScrollback>(
+      'get_terminal_scrollback',
+      { terminalId }
+    )
+    instance.terminal.write(scrollback.data)
+    end = scrollback.end
+  } catch (error) {
+    console.error(
+      '[terminal-instances] get_terminal_scrollback failed:',
+      error
+    )
+  }
+
+  const pending = instance.pendingOutput
+  instance.pendingOutput = null
+  for (const output of pending) {
+    if (output.offset >= end) {
+      instance.terminal.write(output.data)
+    }
+  }
+}
+
 /**
  * Detach terminal from DOM container.
  * Terminal stays in memory with preserved buffer.
@@ -66,6 +66,35 @@ export async function listen<T>(
   return wsTransport.listen<T>(event, handler)
 }
 
+/**
+ * Filter for the events the server forwards to this client.
+ * Empty fields match anything; `chat:*` matches every `chat:` event.
+ * `terminal:output` is only sent through filters naming the terminal in
+ * `terminal_ids`, and such filters don't restrict other events.
+ */
+export interface EventSubscriptionFilter {
+  events?: string[]
+  session_ids?: string[]
+  worktree_ids?: string[]
+  project_ids?: string[]
+  terminal_ids?: string[]
+  /** Receive terminal output as base64-encoded raw bytes instead of text */
+  binary?: boolean
+}
+
+/**
+ * Restrict the events the server sends to this client.
+ * Once any subscription exists, only events matching at least one of them
+ * are delivered. No-op in the native app, where events aren't forwarded.
+ * Returns a function that removes the subscription.
+ */
+export async function subscribeEvents(
+  filter: EventSubscriptionFilter
+): Promise<UnlistenFn> {
+  if (isNativeApp()) return () => {}
+  return wsTransport.subscribeEvents(filter)
+}
+
 // ---------------------------------------------------------------------------
 // Initial data preloading (used in browser mode)
 // ---------------------------------------------------------------------------
@@ -149,15 +178,38 @@ interface PendingRequest {
   timeout: ReturnType<typeof setTimeout>
 }
 
+interface EventSubscription {
+  filter: EventSubscriptionFilter
+  /** Id assigned by the server for the current connection */
+  serverId: string | null
+}
+
 interface WsMessage {
-  type: 'response' | 'error' | 'event'
+  type: 'response' | 'error' | 'event' | 'sync'
   id?: string
   data?: unknown
   error?: string
   event?: string
   payload?: unknown
+  /** Event sequence number (events and sync messages) */
+  seq?: number
+  stream_id?: string
+  /** Events were missed; local state must be reloaded */
+  resync?: boolean
 }
 
+/**
+ * Local event emitted when the server could not replay missed events after
+ * a reconnect. Listeners should refetch everything (a full resync).
+ */
+export const WS_RESYNC_EVENT = 'ws:resync'
+
+/**
+ * Local event emitted when the connection is re-established. Terminal output
+ * is not replayed by the server, so open terminals reload their scrollback.
+ */
+export const WS_RECONNECTED_EVENT = 'ws:reconnected'
+
 class WsTransport {
   private ws: WebSocket | null = null
   private pending = new Map<string, PendingRequest>()
@@ -168,9 +220,15 @@ class WsTransport {
   private reconnectAttempt = 0
   private reconnectTimer: ReturnType<typeof setTimeout> | null = null
   private queue: Array<{ data: string; resolve: () => void }> = []
+  private subscriptions = new Map<string, EventSubscription>()
+  /** Position in the server's event stream, to resume from after reconnects */
+  private streamId: string | null = null
+  private lastSeq: number | null = null
   private _connected = false
   private _connecting = false
   private _authError: string | null = null
+  /** Scopes granted to the token, from `/api/auth` */
+  private _scopes: string[] = []
   private _subscribers = new Set<() => void>()
 
   get connected(): boolean {
@@ -192,6 +250,11 @@ class WsTransport {
     this.notifySubscribers()
   }
 
+  private setScopes(scopes: string[]): void {
+    this._scopes = scopes
+    this.notifySubscribers()
+  }
+
   private notifySubscribers(): void {
     for (const cb of this._subscribers) cb()
   }
@@ -212,6 +275,11 @@ class WsTransport {
     return this._authError
   }
 
+  /** Get granted scopes snapshot for useSyncExternalStore. */
+  getScopesSnapshot(): string[] {
+    return this._scopes
+  }
+
   /** Connect to the WebSocket server (validates token first). */
   connect(): void {
     if (
@@ -255,6 +323,19 @@ class WsTransport {
 
     try {
       const res = await fetch(authUrl)
+      if (res.status === 429) {
+        // Too many attempts — keep the token and retry after the lockout
+        const retryAfter = Number(res.headers.get('Retry-After')) || 30
+        this.setAuthError(null)
+        this.scheduleReconnect(retryAfter * 1000)
+        return
+      }
+      if (res.status === 403) {
+        this.setAuthError(
+          "This device's address is not allowed. Check the allowed addresses in Jean's Web Access settings."
+        )
+        return
+      }
       if (!res.ok) {
         // Invalid token — clear it, set error, don't reconnect
         localStorage.removeItem('jean-http-token')
@@ -263,6 +344,8 @@ class WsTransport {
         )
         return
       }
+      const body: { scopes?: string[] } = await res.json()
+      this.setScopes(body.scopes ?? [])
     } catch {
       // Server unreachable — schedule reconnect (not an auth error)
       this.setAuthError(null)
@@ -279,7 +362,11 @@ class WsTransport {
     // Derive WS URL from current page location
     const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:'
     const host = window.location.host
-    const url = `${protocol}//${host}/ws?token=${encodeURIComponent(token)}`
+    let url = `${protocol}//${host}/ws?token=${encodeURIComponent(token)}`
+    if (this.streamId !== null && this.lastSeq !== null) {
+      const streamId = encodeURIComponent(this.streamId)
+      url += `&resume_from=${this.lastSeq}&stream_id=${streamId}`
+    }
 
     this.ws = new WebSocket(url)
 
@@ -293,6 +380,16 @@ class WsTransport {
         item.resolve()
       }
       this.queue = []
+
+      // Subscriptions are per connection; restore those from before a reconnect
+      for (const [key, subscription] of this.subscriptions) {
+        if (subscription.serverId !== null) {
+          subscription.serverId = null
+          this.sendSubscription(key, subscription).catch(error =>
+            console.error('[WsTransport] Failed to restore subscription:', error)
+          )
+        }
+      }
     }
 
     this.ws.onmessage = event => {
@@ -316,18 +413,66 @@ class WsTransport {
 
   /** Call a backend command over WebSocket. */
   async invoke<T>(command: string, args?: Record<string, unknown>): Promise<T> {
-    const id = crypto.randomUUID()
-    const data = JSON.stringify({
-      type: 'invoke',
-      id,
-      command,
-      args: args || {},
+    return this.request<T>(
+      { type: 'invoke', command, args: args || {} },
+      `Command '${command}'`
+    )
+  }
+
+  /** Limit forwarded events to those matching `filter` (kept across reconnects). */
+  async subscribeEvents(filter: EventSubscriptionFilter): Promise<UnlistenFn> {
+    const key = crypto.randomUUID()
+    const subscription: EventSubscription = { filter, serverId: null }
+    this.subscriptions.set(key, subscription)
+    await this.sendSubscription(key, subscription)
+
+    return () => {
+      this.subscriptions.delete(key)
+      this.sendUnsubscribe(subscription)
+    }
+  }
+
+  private async sendSubscription(
+    key: string,
+    subscription: EventSubscription
+  ): Promise<void> {
+    const data = await this.request<{ subscription_id: string }>(
+      { type: 'subscribe', filter: subscription.filter },
+      'Subscribe'
+    )
+    subscription.serverId = data.subscription_id
+    // Removed while the request was in flight
+    if (!this.subscriptions.has(key)) {
+      this.sendUnsubscribe(subscription)
+    }
+  }
+
+  private sendUnsubscribe(subscription: EventSubscription): void {
+    if (!subscription.serverId || this.ws?.readyState !== WebSocket.OPEN) return
+    this.request(
+      { type: 'unsubscribe', subscription_id: subscription.serverId },
+      'Unsubscribe'
+    ).catch(() => {
+      // Subscription is gone with the connection anyway
     })
+  }
+
+  /** Send a message with a fresh id and wait for its response. */
+  private async request<T>(
+    message: Record<string, unknown>,
+    label: string
+  ): Promise<T> {
+    const id = crypto.randomUUID()
+    const data = JSON.stringify({ ...message, id })
 
     return new Promise<T>((resolve, reject) => {
       const timeout = setTimeout(() => {
         this.pending.delete(id)
-        reject(new Error(`Command '${command}' timed out after 60s`))
+        // Let the server stop work nobody is waiting for
+        if (this.ws?.readyState === WebSocket.OPEN) {
+          this.ws.send(JSON.stringify({ type: 'cancel', id }))
+        }
+        reject(new Error(`${label} timed out after 60s`))
       }, 60_000)
 
       this.pending.set(id, {
@@ -383,27 +528,57 @@ class WsTransport {
         this.pending.delete(msg.id)
         pending.reject(new Error(msg.error || 'Unknown error'))
       }
+    } else if (msg.type === 'sync') {
+      this.handleSync(msg)
     } else if (msg.type === 'event' && msg.event) {
-      const handlers = this.listeners.get(msg.event)
-      if (handlers) {
-        for (const handler of handlers) {
-          try {
-            handler({ payload: msg.payload })
-          } catch (e) {
-            console.error(`[WsTransport] Error in '${msg.event}' handler:`, e)
-          }
-        }
+      if (msg.seq !== undefined) {
+        this.lastSeq = Math.max(this.lastSeq ?? 0, msg.seq)
       }
+      this.dispatchEvent(msg.event, msg.payload)
     }
   }
 
-  private scheduleReconnect(): void {
+  /** Sent after missed events were replayed, or when events were lost. */
+  private handleSync(msg: WsMessage): void {
+    const reconnected = this.streamId !== null
+    const sameStream = msg.stream_id === this.streamId
+    this.streamId = msg.stream_id ?? null
+    this.lastSeq =
+      sameStream && this.lastSeq !== null
+        ? Math.max(this.lastSeq, msg.seq ?? 0)
+        : (msg.seq ?? null)
+
+    if (msg.resync) {
+      console.warn(
+        '[WsTransport] Missed events could not be replayed, resyncing'
+      )
+      this.dispatchEvent(WS_RESYNC_EVENT, null)
+    }
+    if (reconnected) {
+      this.dispatchEvent(WS_RECONNECTED_EVENT, null)
+    }
+  }
+
+  private dispatchEvent(event: string, payload: unknown): void {
+    const handlers = this.listeners.get(event)
+    if (!handlers) return
+    for (const handler of handlers) {
+      try {
+        handler({ payload })
+      } catch (e) {
+        console.error(`[WsTransport] Error in '${event}' handler:`, e)
+      }
+    }
+  }
+
+  private scheduleReconnect(minDelay = 0): void {
     if (this.reconnectTimer) return
     // Don't reconnect if there's an auth error — user needs to fix the token
     if (this._authError) return
 
     // Exponential backoff: 1s, 2s, 4s, 8s, ... max 30s
-    const delay = Math.min(1000 * 2 ** this.reconnectAttempt, 30_000)
+    const backoff = Math.min(1000 * 2 ** this.reconnectAttempt, 30_000)
+    const delay = Math.max(backoff, minDelay)
     this.reconnectAttempt++
 
     this.reconnectTimer = setTimeout(() => {
@@ -428,6 +603,18 @@ if (!isNativeApp() && typeof window !== 'undefined') {
 const subscribe = (cb: () => void) => wsTransport.subscribe(cb)
 const getSnapshot = () => wsTransport.getSnapshot()
 const getAuthErrorSnapshot = () => wsTransport.getAuthErrorSnapshot()
+const getScopesSnapshot = () => wsTransport.getScopesSnapshot()
+
+const allowsTerminals = (scopes: string[]) =>
+  isNativeApp() || scopes.includes('terminal') || scopes.includes('admin')
+
+/**
+ * Whether this client may run worktree terminals: always in the native app,
+ * and in the browser when the token has the terminal or admin scope.
+ */
+export function canUseTerminals(): boolean {
+  return allowsTerminals(getScopesSnapshot())
+}
 
 /**
  * React hook that returns the current WebSocket connection status.
@@ -444,3 +631,8 @@ export function useWsConnectionStatus(): boolean {
 export function useWsAuthError(): string | null {
   return useSyncExternalStore(subscribe, getAuthErrorSnapshot)
 }
+
+/** React hook version of canUseTerminals(). */
+export function useCanUseTerminals(): boolean {
+  return allowsTerminals(useSyncExternalStore(subscribe, getScopesSnapshot))
+}
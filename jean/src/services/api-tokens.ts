@@ -0,0 +1,104 @@
+import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
+import { invoke } from '@/lib/transport'
+import { toast } from 'sonner'
+import { logger } from '@/lib/logger'
+import type {
+  ApiToken,
+  CreateApiTokenInput,
+  CreatedApiToken,
+} from '@/types/api-tokens'
+import { isTauri } from '@/services/projects'
+
+// Query keys for API tokens
+export const apiTokenQueryKeys = {
+  all: ['api-tokens'] as const,
+  list: () => [...apiTokenQueryKeys.all, 'list'] as const,
+}
+
+function errorMessage(error: unknown): string {
+  return error instanceof Error
+    ? error.message
+    : typeof error === 'string'
+      ? error
+      : 'Unknown error occurred'
+}
+
+/**
+ * Hook to list API tokens
+ */
+export function useApiTokens() {
+  return useQuery({
+    queryKey: apiTokenQueryKeys.list(),
+    queryFn: async (): Promise<ApiToken[]> => {
+      if (!isTauri()) return []
+
+      try {
+        return await invoke<ApiToken[]>('list_api_tokens')
+      } catch (error) {
+        logger.error('Failed to load API tokens', { error })
+        return []
+      }
+    },
+  })
+}
+
+/**
+ * Hook to create an API token
+ * The secret is only available from the mutation result
+ */
+export function useCreateApiToken() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async (
+      input: CreateApiTokenInput
+    ): Promise<CreatedApiToken> => {
+      if (!isTauri()) {
+        throw new Error('Not in Tauri context')
+      }
+
+      logger.debug('Creating API token', {
+        name: input.name,
+        scopes: input.scopes,
+      })
+      return invoke<CreatedApiToken>('create_api_token', { ...input })
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: apiTokenQueryKeys.list() })
+    },
+    onError: error => {
+      logger.error('Failed to create API token', { error })
+      toast.error('Failed to create token', {
+        description: errorMessage(error),
+      })
+    },
+  })
+}
+
+/**
+ * Hook to revoke an API token
+ */
+export function useRevokeApiToken() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async (tokenId: string): Promise<void> => {
+      if (!isTauri()) {
+        throw new Error('Not in Tauri context')
+      }
+
+      logger.debug('Revoking API token', { tokenId })
+      await invoke('revoke_api_token', { tokenId })
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: apiTokenQueryKeys.list() })
+      toast.success('Token revoked')
+    },
+    onError: error => {
+      logger.error('Failed to revoke API token', { error })
+      toast.error('Failed to revoke token', {
+        description: errorMessage(error),
+      })
+    },
+  })
+}
@@ -8,12 +8,20 @@ import type {
   ArchivedSessionEntry,
   ChatMessage,
   ChatHistory,
+  ClaudeCliSessionInfo,
   Session,
   WorktreeSessions,
   Question,
   QuestionAnswer,
   ThinkingLevel,
   ExecutionMode,
+  ExportFormat,
+  ExportOptions,
+  QueuedMessage,
+  SearchFilter,
+  SearchHit,
+  UsageFilter,
+  UsageReport,
 } from '@/types/chat'
 import {
   isTauri,
@@ -37,6 +45,8 @@ export const chatQueryKeys = {
     [...chatQueryKeys.all, 'sessions', worktreeId] as const,
   session: (sessionId: string) =>
     [...chatQueryKeys.all, 'session', sessionId] as const,
+  queue: (sessionId: string) =>
+    [...chatQueryKeys.all, 'queue', sessionId] as const,
 }
 
 // ============================================================================
@@ -237,6 +247,114 @@ export function useAllSessions(enabled = true) {
   })
 }
 
+/**
+ * Hook to get token usage and cost, rolled up per project/worktree/session/day
+ */
+export function useUsageReport(filter: UsageFilter = {}, enabled = true) {
+  return useQuery({
+    queryKey: [...chatQueryKeys.all, 'usage', filter],
+    queryFn: async (): Promise<UsageReport | null> => {
+      if (!isTauri()) return null
+
+      try {
+        return await invoke<UsageReport>('get_usage_report', { filter })
+      } catch (error) {
+        logger.error('Failed to load usage report', { error, filter })
+        return null
+      }
+    },
+    enabled,
+    staleTime: 1000 * 60, // 1 minute
+  })
+}
+
+/**
+ * Hook to search all session histories (archived sessions included)
+ */
+export function useSearchSessions(
+  query: string,
+  filter: SearchFilter = {},
+  limit?: number
+) {
+  const trimmed = query.trim()
+  return useQuery({
+    queryKey: [...chatQueryKeys.all, 'search', trimmed, filter, limit],
+    queryFn: async (): Promise<SearchHit[]> => {
+      if (!isTauri()) return []
+
+      try {
+        return await invoke<SearchHit[]>('search_sessions', {
+          query: trimmed,
+          filter,
+          limit,
+        })
+      } catch (error) {
+        logger.error('Failed to search sessions', { error, query: trimmed })
+        return []
+      }
+    },
+    enabled: trimmed.length > 0,
+    staleTime: 1000 * 30,
+  })
+}
+
+/**
+ * Hook to fork a session from a message into a new session
+ * (in the same worktree, or in targetWorktreeId if given)
+ */
+export function useForkSession() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async ({
+      worktreeId,
+      worktreePath,
+      sessionId,
+      messageId,
+      targetWorktreeId,
+      name,
+    }: {
+      worktreeId: string
+      worktreePath: string
+      sessionId: string
+      messageId: string
+      targetWorktreeId?: string
+      name?: string
+    }): Promise<Session> => {
+      if (!isTauri()) {
+        throw new Error('Not in Tauri context')
+      }
+
+      logger.debug('Forking session', { sessionId, messageId })
+      const session = await invoke<Session>('fork_session', {
+        worktreeId,
+        worktreePath,
+        sessionId,
+        messageId,
+        targetWorktreeId,
+        name,
+      })
+      logger.info('Session forked', { sessionId: session.id })
+      return session
+    },
+    onSuccess: (_, { worktreeId, targetWorktreeId }) => {
+      queryClient.invalidateQueries({
+        queryKey: chatQueryKeys.sessions(targetWorktreeId ?? worktreeId),
+      })
+    },
+    onError: error => {
+      const message =
+        error instanceof Error
+          ? error.message
+          : typeof error === 'string'
+            ? error
+            : 'Unknown error occurred'
+      logger.error('Failed to fork session', { error })
+      toast.error('Failed to fork session', { description: message })
+    },
+  })
+}
+
 /**
  * Hook to get a single session with full message history
  */
@@ -1110,6 +1228,7 @@ export function useSendMessage() {
       parallelExecutionPromptEnabled,
       aiLanguage,
       allowedTools,
+      overrideBudget,
     }: {
       sessionId: string
       worktreeId: string
@@ -1122,6 +1241,8 @@ export function useSendMessage() {
       parallelExecutionPromptEnabled?: boolean
       aiLanguage?: string
       allowedTools?: string[]
+      /** Run even if a hard usage budget is exceeded */
+      overrideBudget?: boolean
     }): Promise<ChatMessage> => {
       if (!isTauri()) {
         throw new Error('Not in Tauri context')
@@ -1150,6 +1271,7 @@ export function useSendMessage() {
         parallelExecutionPromptEnabled,
         aiLanguage,
         allowedTools,
+        overrideBudget,
       })
       logger.info('Chat message sent', { responseId: response.id })
       return response
@@ -1741,6 +1863,245 @@ export async function readPlanFile(path: string): Promise<string> {
   return invoke<string>('read_plan_file', { path })
 }
 
+// ============================================================================
+// Export
+// ============================================================================
+
+/**
+ * Render a session's history as Markdown, standalone HTML or JSON
+ */
+export async function exportSession(
+  sessionId: string,
+  format: ExportFormat,
+  options?: ExportOptions
+): Promise<string> {
+  if (!isTauri()) {
+    throw new Error('Not in Tauri context')
+  }
+
+  return invoke<string>('export_session', { sessionId, format, options })
+}
+
+/**
+ * Write a session as a zip bundle (metadata.json + run logs)
+ */
+export async function exportSessionBundle(
+  sessionId: string,
+  destPath: string
+): Promise<void> {
+  if (!isTauri()) {
+    throw new Error('Not in Tauri context')
+  }
+
+  await invoke('export_session_bundle', { sessionId, destPath })
+}
+
+// ============================================================================
+// Message Queue
+// ============================================================================
+
+/**
+ * Hook to get the queued follow-up messages for a session
+ * Kept live by the global chat:queue_updated listener (useStreamingEvents)
+ */
+export function useMessageQueue(sessionId: string | null) {
+  return useQuery({
+    queryKey: chatQueryKeys.queue(sessionId ?? ''),
+    queryFn: async (): Promise<QueuedMessage[]> => {
+      if (!isTauri() || !sessionId) return []
+
+      try {
+        return await invoke<QueuedMessage[]>('list_queued_messages', {
+          sessionId,
+        })
+      } catch (error) {
+        logger.error('Failed to load message queue', { error, sessionId })
+        return []
+      }
+    },
+    enabled: !!sessionId,
+  })
+}
+
+/**
+ * Hook to queue a follow-up message on the backend
+ * Sent right away if the session is idle, otherwise when its run completes
+ */
+export function useEnqueueMessage() {
+  return useMutation({
+    mutationFn: async ({
+      sessionId,
+      worktreeId,
+      worktreePath,
+      message,
+      model,
+      executionMode,
+      thinkingLevel,
+      disableThinkingForMode,
+      parallelExecutionPromptEnabled,
+      aiLanguage,
+      allowedTools,
+    }: {
+      sessionId: string
+      worktreeId: string
+      worktreePath: string
+      message: string
+      model?: string
+      executionMode?: ExecutionMode
+      thinkingLevel?: ThinkingLevel
+      disableThinkingForMode?: boolean
+      parallelExecutionPromptEnabled?: boolean
+      aiLanguage?: string
+      allowedTools?: string[]
+    }): Promise<QueuedMessage> => {
+      if (!isTauri()) {
+        throw new Error('Not in Tauri context')
+      }
+
+      logger.debug('Queueing message', { sessionId })
+      return invoke<QueuedMessage>('enqueue_message', {
+        sessionId,
+        worktreeId,
+        worktreePath,
+        message,
+        model,
+        executionMode,
+        thinkingLevel,
+        disableThinkingForMode,
+        parallelExecutionPromptEnabled,
+        aiLanguage,
+        allowedTools,
+      })
+    },
+    onError: error => {
+      const message =
+        error instanceof Error
+          ? error.message
+          : typeof error === 'string'
+            ? error
+            : 'Unknown error occurred'
+      logger.error('Failed to queue message', { error })
+      toast.error('Failed to queue message', { description: message })
+    },
+  })
+}
+
+/**
+ * Hook to drop a queued message
+ * The cache is updated by the resulting chat:queue_updated event
+ */
+export function useRemoveQueuedMessage() {
+  return useMutation({
+    mutationFn: async ({
+      sessionId,
+      itemId,
+    }: {
+      sessionId: string
+      itemId: string
+    }): Promise<void> => {
+      if (!isTauri()) {
+        throw new Error('Not in Tauri context')
+      }
+
+      await invoke('remove_queued_message', { sessionId, itemId })
+    },
+    onError: error => {
+      const message =
+        error instanceof Error
+          ? error.message
+          : typeof error === 'string'
+            ? error
+            : 'Unknown error occurred'
+      logger.error('Failed to remove queued message', { error })
+      toast.error('Failed to remove queued message', { description: message })
+    },
+  })
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+/**
+ * Hook to list Claude CLI conversations that can be imported into a worktree
+ */
+export function useClaudeCliSessions(
+  worktreeId: string | null,
+  worktreePath: string | null
+) {
+  return useQuery({
+    queryKey: [...chatQueryKeys.all, 'cli-sessions', worktreeId],
+    queryFn: async (): Promise<ClaudeCliSessionInfo[]> => {
+      if (!isTauri() || !worktreeId || !worktreePath) return []
+
+      try {
+        return await invoke<ClaudeCliSessionInfo[]>(
+          'list_claude_cli_sessions',
+          { worktreeId, worktreePath }
+        )
+      } catch (error) {
+        logger.error('Failed to list Claude CLI sessions', { error })
+        return []
+      }
+    },
+    enabled: !!worktreeId && !!worktreePath,
+  })
+}
+
+/**
+ * Hook to import a session bundle or a Claude CLI conversation into a worktree
+ */
+export function useImportSession() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async ({
+      worktreeId,
+      worktreePath,
+      source,
+    }: {
+      worktreeId: string
+      worktreePath: string
+      source: { bundlePath: string } | { jsonlPath: string }
+    }): Promise<Session> => {
+      if (!isTauri()) {
+        throw new Error('Not in Tauri context')
+      }
+
+      if ('bundlePath' in source) {
+        return invoke<Session>('import_session_bundle', {
+          worktreeId,
+          worktreePath,
+          bundlePath: source.bundlePath,
+        })
+      }
+      return invoke<Session>('import_claude_cli_session', {
+        worktreeId,
+        worktreePath,
+        jsonlPath: source.jsonlPath,
+      })
+    },
+    onSuccess: (_, { worktreeId }) => {
+      queryClient.invalidateQueries({
+        queryKey: chatQueryKeys.sessions(worktreeId),
+      })
+      queryClient.invalidateQueries({
+        queryKey: [...chatQueryKeys.all, 'cli-sessions', worktreeId],
+      })
+    },
+    onError: error => {
+      const message =
+        error instanceof Error
+          ? error.message
+          : typeof error === 'string'
+            ? error
+            : 'Unknown error occurred'
+      logger.error('Failed to import session', { error })
+      toast.error('Failed to import session', { description: message })
+    },
+  })
+}
+
 // ============================================================================
 // Plan Approval
 // ============================================================================
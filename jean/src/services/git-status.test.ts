@@ -15,8 +15,10 @@ import {
   triggerImmediateRemotePoll,
   getGitDiff,
   useGitStatus,
+  usePolledWorktrees,
   type WorktreePollingInfo,
 } from './git-status'
+import { useProjectsStore } from '@/store/projects-store'
 
 const mockInvoke = vi.fn()
 const mockListen = vi.fn()
@@ -29,6 +31,23 @@ vi.mock('@/lib/transport', () => ({
 vi.mock('@/services/projects', () => ({
   isTauri: vi.fn(() => true),
   updateWorktreeCachedStatus: vi.fn(),
+  useProjects: () => ({
+    data: [
+      { id: 'p1', default_branch: 'main' },
+      { id: 'p2', default_branch: 'develop' },
+      { id: 'f1', is_folder: true },
+    ],
+  }),
+  worktreesQueryOptions: (projectId: string) => ({
+    queryKey: ['projects', 'worktrees', projectId],
+    queryFn: async () =>
+      projectId === 'p1'
+        ? [
+            { id: 'wt-1', path: '/p1/wt-1', pr_number: 7 },
+            { id: 'wt-2', path: '/p1/wt-2', status: 'pending' },
+          ]
+        : [{ id: 'wt-3', path: '/p2/wt-3' }],
+  }),
 }))
 
 const createTestQueryClient = () =>
@@ -340,4 +359,38 @@ describe('git-status service', () => {
       expect(result.current.data?.ahead_count).toBe(2)
     })
   })
+
+  describe('usePolledWorktrees', () => {
+    it('tracks worktrees with priority by project expansion', async () => {
+      useProjectsStore.setState({ expandedProjectIds: new Set(['p1']) })
+      mockInvoke.mockResolvedValue(undefined)
+
+      renderHook(() => usePolledWorktrees(), {
+        wrapper: createWrapper(queryClient),
+      })
+
+      await waitFor(() =>
+        expect(mockInvoke).toHaveBeenCalledWith('set_polled_worktrees', {
+          worktrees: [
+            {
+              worktreeId: 'wt-1',
+              worktreePath: '/p1/wt-1',
+              baseBranch: 'main',
+              prNumber: 7,
+              prUrl: null,
+              priority: 'visible',
+            },
+            {
+              worktreeId: 'wt-3',
+              worktreePath: '/p2/wt-3',
+              baseBranch: 'develop',
+              prNumber: null,
+              prUrl: null,
+              priority: 'idle',
+            },
+          ],
+        })
+      )
+    })
+  })
 })
@@ -8,10 +8,18 @@
 import { invoke, useWsConnectionStatus } from '@/lib/transport'
 import { listen, type UnlistenFn } from '@/lib/transport'
 import { useEffect, useRef } from 'react'
-import { useQuery, useQueryClient } from '@tanstack/react-query'
-
-import { isTauri, updateWorktreeCachedStatus } from '@/services/projects'
+import { useQueries, useQuery, useQueryClient } from '@tanstack/react-query'
+
+import { logger } from '@/lib/logger'
+import {
+  isTauri,
+  updateWorktreeCachedStatus,
+  useProjects,
+  worktreesQueryOptions,
+} from '@/services/projects'
+import { useProjectsStore } from '@/store/projects-store'
 import type { GitDiff } from '@/types/git-diff'
+import { isFolder } from '@/types/projects'
 
 // ============================================================================
 // Types
@@ -100,6 +108,69 @@ export async function setActiveWorktreeForPolling(
   }
 }
 
+/** Background polling priority for a non-active worktree */
+export type PollPriority = 'visible' | 'idle'
+
+/**
+ * Set the worktrees polled for git status in the background.
+ * Visible worktrees poll at 3x the interval, idle ones at 10x (max 30 minutes).
+ */
+export async function setPolledWorktrees(
+  worktrees: (WorktreePollingInfo & { priority: PollPriority })[]
+): Promise<void> {
+  if (!isTauri()) return
+  await invoke('set_polled_worktrees', {
+    worktrees: worktrees.map(w => ({
+      worktreeId: w.worktreeId,
+      worktreePath: w.worktreePath,
+      baseBranch: w.baseBranch,
+      prNumber: w.prNumber ?? null,
+      prUrl: w.prUrl ?? null,
+      priority: w.priority,
+    })),
+  })
+}
+
+/**
+ * Keep the background poller tracking every worktree in the sidebar.
+ * Worktrees of expanded projects poll as visible, the rest as idle; the
+ * active worktree is polled separately at the normal interval.
+ */
+export function usePolledWorktrees() {
+  const { data: projects = [] } = useProjects()
+  const expandedProjectIds = useProjectsStore(
+    state => state.expandedProjectIds
+  )
+  const repoProjects = projects.filter(p => !isFolder(p))
+  const worktreeLists = useQueries({
+    queries: repoProjects.map(p => worktreesQueryOptions(p.id)),
+  })
+
+  const polled = repoProjects.flatMap((project, i) => {
+    const priority: PollPriority = expandedProjectIds.has(project.id)
+      ? 'visible'
+      : 'idle'
+    return (worktreeLists[i]?.data ?? [])
+      .filter(w => w.status !== 'pending' && w.status !== 'deleting')
+      .map(w => ({
+        worktreeId: w.id,
+        worktreePath: w.path,
+        baseBranch: project.default_branch,
+        prNumber: w.pr_number,
+        prUrl: w.pr_url,
+        priority,
+      }))
+  })
+  // Only update the poller when the set of worktrees actually changed
+  const polledKey = JSON.stringify(polled)
+
+  useEffect(() => {
+    setPolledWorktrees(JSON.parse(polledKey)).catch(error =>
+      logger.error('Failed to set polled worktrees', { error })
+    )
+  }, [polledKey])
+}
+
 /**
  * Set the git polling interval in seconds.
  * Valid range: 10-600 seconds (10 seconds to 10 minutes).
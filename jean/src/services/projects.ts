@@ -71,11 +71,11 @@ export function useProjects() {
 }
 
 /**
- * Hook to list worktrees for a specific project
+ * Query options for a project's worktrees, shared by every query of the list
  */
-export function useWorktrees(projectId: string | null) {
-  return useQuery({
-    queryKey: projectsQueryKeys.worktrees(projectId ?? ''),
+export function worktreesQueryOptions(projectId: string) {
+  return {
+    queryKey: projectsQueryKeys.worktrees(projectId),
     queryFn: async (): Promise<Worktree[]> => {
       if (!isTauri() || !projectId) {
         return []
@@ -98,7 +98,14 @@ export function useWorktrees(projectId: string | null) {
     enabled: !!projectId,
     staleTime: 1000 * 60 * 5,
     gcTime: 1000 * 60 * 10,
-  })
+  }
+}
+
+/**
+ * Hook to list worktrees for a specific project
+ */
+export function useWorktrees(projectId: string | null) {
+  return useQuery(worktreesQueryOptions(projectId ?? ''))
 }
 
 /**
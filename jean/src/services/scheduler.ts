@@ -0,0 +1,198 @@
+import { useEffect } from 'react'
+import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
+import { invoke, listen, useWsConnectionStatus } from '@/lib/transport'
+import type { UnlistenFn } from '@/lib/transport'
+import { toast } from 'sonner'
+import { logger } from '@/lib/logger'
+import type {
+  Schedule,
+  ScheduleInput,
+  ScheduleRunEvent,
+  ScheduleTrigger,
+} from '@/types/scheduler'
+import { isTauri } from '@/services/projects'
+import { chatQueryKeys } from '@/services/chat'
+
+// Query keys for schedules
+export const schedulerQueryKeys = {
+  all: ['schedules'] as const,
+  list: () => [...schedulerQueryKeys.all, 'list'] as const,
+  preview: (trigger: ScheduleTrigger) =>
+    [...schedulerQueryKeys.all, 'preview', trigger] as const,
+}
+
+function errorMessage(error: unknown): string {
+  return error instanceof Error
+    ? error.message
+    : typeof error === 'string'
+      ? error
+      : 'Unknown error occurred'
+}
+
+/**
+ * Hook to list all schedules
+ */
+export function useSchedules() {
+  return useQuery({
+    queryKey: schedulerQueryKeys.list(),
+    queryFn: async (): Promise<Schedule[]> => {
+      if (!isTauri()) return []
+
+      try {
+        return await invoke<Schedule[]>('list_schedules')
+      } catch (error) {
+        logger.error('Failed to load schedules', { error })
+        return []
+      }
+    },
+  })
+}
+
+/**
+ * Hook to preview the next fire times (Unix timestamps) of a trigger
+ */
+export function useSchedulePreview(trigger: ScheduleTrigger | null, count = 5) {
+  return useQuery({
+    queryKey: schedulerQueryKeys.preview(
+      trigger ?? { type: 'cron', expression: '' }
+    ),
+    queryFn: async (): Promise<number[]> => {
+      if (!isTauri() || !trigger) return []
+      return invoke<number[]>('preview_schedule', { trigger, count })
+    },
+    enabled: !!trigger,
+    retry: false,
+  })
+}
+
+/**
+ * Hook to keep schedules in sync with the scheduler
+ * Mount once; updates the cache on schedule changes and run events
+ */
+export function useScheduleEvents() {
+  const queryClient = useQueryClient()
+  const wsConnected = useWsConnectionStatus()
+
+  useEffect(() => {
+    if (!isTauri()) return
+
+    const unlistenPromises: Promise<UnlistenFn>[] = [
+      listen<Schedule[]>('schedules:changed', event => {
+        queryClient.setQueryData(schedulerQueryKeys.list(), event.payload)
+      }),
+      listen<ScheduleRunEvent>('schedule:fired', event => {
+        logger.info('Schedule fired', {
+          scheduleId: event.payload.schedule_id,
+        })
+        queryClient.invalidateQueries({ queryKey: schedulerQueryKeys.all })
+        const { worktree_id } = event.payload.run
+        if (worktree_id) {
+          queryClient.invalidateQueries({
+            queryKey: chatQueryKeys.sessions(worktree_id),
+          })
+        }
+      }),
+      listen<ScheduleRunEvent>('schedule:queued', () => {
+        queryClient.invalidateQueries({ queryKey: schedulerQueryKeys.all })
+      }),
+      listen<ScheduleRunEvent>('schedule:finished', event => {
+        const { schedule_name, run } = event.payload
+        queryClient.invalidateQueries({ queryKey: schedulerQueryKeys.all })
+        if (run.outcome === 'failed') {
+          toast.error(`Scheduled run "${schedule_name}" failed`, {
+            description: run.error,
+          })
+        }
+      }),
+    ]
+
+    return () => {
+      Promise.all(unlistenPromises).then(unlistens => {
+        unlistens.forEach(unlisten => unlisten())
+      })
+    }
+  }, [queryClient, wsConnected])
+}
+
+/**
+ * Hook to create or update a schedule
+ */
+export function useSaveSchedule() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async ({
+      scheduleId,
+      schedule,
+    }: {
+      /** Omit to create a new schedule */
+      scheduleId?: string
+      schedule: ScheduleInput
+    }): Promise<Schedule> => {
+      if (!isTauri()) {
+        throw new Error('Not in Tauri context')
+      }
+
+      logger.debug('Saving schedule', { scheduleId, name: schedule.name })
+      return scheduleId
+        ? invoke<Schedule>('update_schedule', { scheduleId, schedule })
+        : invoke<Schedule>('create_schedule', { schedule })
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: schedulerQueryKeys.list() })
+    },
+    onError: error => {
+      logger.error('Failed to save schedule', { error })
+      toast.error('Failed to save schedule', {
+        description: errorMessage(error),
+      })
+    },
+  })
+}
+
+/**
+ * Hook to pause/resume, delete or immediately run a schedule
+ */
+export function useScheduleAction() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async ({
+      scheduleId,
+      action,
+    }: {
+      scheduleId: string
+      action: 'enable' | 'disable' | 'delete' | 'run'
+    }): Promise<void> => {
+      if (!isTauri()) {
+        throw new Error('Not in Tauri context')
+      }
+
+      logger.debug('Schedule action', { scheduleId, action })
+      switch (action) {
+        case 'enable':
+        case 'disable':
+          await invoke('set_schedule_enabled', {
+            scheduleId,
+            enabled: action === 'enable',
+          })
+          break
+        case 'delete':
+          await invoke('delete_schedule', { scheduleId })
+          break
+        case 'run':
+          await invoke('run_schedule_now', { scheduleId })
+          break
+      }
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: schedulerQueryKeys.list() })
+    },
+    onError: error => {
+      logger.error('Schedule action failed', { error })
+      toast.error('Schedule action failed', {
+        description: errorMessage(error),
+      })
+    },
+  })
+}
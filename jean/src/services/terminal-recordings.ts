@@ -0,0 +1,165 @@
+import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query'
+import { invoke } from '@/lib/transport'
+import { toast } from 'sonner'
+import { logger } from '@/lib/logger'
+import { useChatStore } from '@/store/chat-store'
+import type { ReadTextResponse, SaveTextResponse } from '@/types/chat'
+import type {
+  TerminalRecording,
+  TerminalRecordingData,
+} from '@/types/terminal'
+import { isTauri } from '@/services/projects'
+
+// Query keys for terminal recordings
+export const terminalRecordingQueryKeys = {
+  all: ['terminal-recordings'] as const,
+  list: () => [...terminalRecordingQueryKeys.all, 'list'] as const,
+}
+
+function errorMessage(error: unknown): string {
+  return error instanceof Error
+    ? error.message
+    : typeof error === 'string'
+      ? error
+      : 'Unknown error occurred'
+}
+
+/**
+ * Hook to list terminal recordings, newest first
+ */
+export function useTerminalRecordings() {
+  return useQuery({
+    queryKey: terminalRecordingQueryKeys.list(),
+    queryFn: async (): Promise<TerminalRecording[]> => {
+      if (!isTauri()) return []
+
+      try {
+        return await invoke<TerminalRecording[]>('list_terminal_recordings')
+      } catch (error) {
+        logger.error('Failed to load terminal recordings', { error })
+        return []
+      }
+    },
+  })
+}
+
+/**
+ * Hook to start recording a terminal
+ * Input is only recorded when asked for, since it may contain secrets
+ */
+export function useStartTerminalRecording() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async ({
+      terminalId,
+      includeInput = false,
+    }: {
+      terminalId: string
+      includeInput?: boolean
+    }): Promise<TerminalRecording> => {
+      logger.debug('Starting terminal recording', { terminalId })
+      return invoke<TerminalRecording>('start_terminal_recording', {
+        terminalId,
+        includeInput,
+      })
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({
+        queryKey: terminalRecordingQueryKeys.list(),
+      })
+    },
+    onError: error => {
+      logger.error('Failed to start terminal recording', { error })
+      toast.error('Failed to start recording', {
+        description: errorMessage(error),
+      })
+    },
+  })
+}
+
+/**
+ * Hook to stop recording a terminal
+ * Resolves to the recording id, or null if the terminal wasn't recording
+ */
+export function useStopTerminalRecording() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async (terminalId: string): Promise<string | null> => {
+      logger.debug('Stopping terminal recording', { terminalId })
+      return invoke<string | null>('stop_terminal_recording', { terminalId })
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({
+        queryKey: terminalRecordingQueryKeys.list(),
+      })
+    },
+    onError: error => {
+      logger.error('Failed to stop terminal recording', { error })
+      toast.error('Failed to stop recording', {
+        description: errorMessage(error),
+      })
+    },
+  })
+}
+
+/**
+ * Hook to delete a terminal recording
+ */
+export function useDeleteTerminalRecording() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async (recordingId: string): Promise<void> => {
+      logger.debug('Deleting terminal recording', { recordingId })
+      await invoke('delete_terminal_recording', { recordingId })
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({
+        queryKey: terminalRecordingQueryKeys.list(),
+      })
+      toast.success('Recording deleted')
+    },
+    onError: error => {
+      logger.error('Failed to delete terminal recording', { error })
+      toast.error('Failed to delete recording', {
+        description: errorMessage(error),
+      })
+    },
+  })
+}
+
+/**
+ * Load a recording's events for replaying it in an xterm instance
+ */
+export function readTerminalRecording(
+  recordingId: string
+): Promise<TerminalRecordingData> {
+  return invoke<TerminalRecordingData>('read_terminal_recording', {
+    recordingId,
+  })
+}
+
+/**
+ * Attach a recording's output to a chat session as a text file
+ */
+export async function attachRecordingToSession(
+  sessionId: string,
+  recordingId: string
+): Promise<void> {
+  const saved = await invoke<SaveTextResponse>('attach_terminal_recording', {
+    recordingId,
+  })
+  const { content } = await invoke<ReadTextResponse>('read_pasted_text', {
+    path: saved.path,
+  })
+
+  useChatStore.getState().addPendingTextFile(sessionId, {
+    id: saved.id,
+    path: saved.path,
+    filename: saved.filename,
+    size: saved.size,
+    content,
+  })
+}
@@ -2,7 +2,6 @@ import { describe, it, expect, beforeEach } from 'vitest'
 import { useChatStore } from './chat-store'
 import type {
   ToolCall,
-  QueuedMessage,
   PermissionDenial,
   PendingImage,
   QuestionAnswer,
@@ -42,7 +41,6 @@ describe('ChatStore', () => {
       activeTodos: {},
       fixedFindings: {},
       streamingPlanApprovals: {},
-      messageQueues: {},
       executingModes: {},
       approvedTools: {},
       pendingPermissionDenials: {},
@@ -381,81 +379,6 @@ describe('ChatStore', () => {
     })
   })
 
-  describe('message queue', () => {
-    const createMockMessage = (id: string, message: string): QueuedMessage => ({
-      id,
-      message,
-      pendingImages: [],
-      pendingFiles: [],
-      pendingSkills: [],
-      pendingTextFiles: [],
-      model: 'sonnet',
-      executionMode: 'plan',
-      thinkingLevel: 'off',
-      disableThinkingForMode: false,
-      queuedAt: Date.now(),
-    })
-
-    const mockMessage = createMockMessage('msg-1', 'Hello')
-
-    it('enqueues message', () => {
-      const { enqueueMessage, getQueueLength, getQueuedMessages } =
-        useChatStore.getState()
-
-      enqueueMessage('session-1', mockMessage)
-
-      expect(getQueueLength('session-1')).toBe(1)
-      expect(getQueuedMessages('session-1')[0]?.id).toBe(mockMessage.id)
-    })
-
-    it('dequeues message (FIFO)', () => {
-      const { enqueueMessage, dequeueMessage } = useChatStore.getState()
-
-      const msg1 = createMockMessage('msg-1', 'First')
-      const msg2 = createMockMessage('msg-2', 'Second')
-
-      enqueueMessage('session-1', msg1)
-      enqueueMessage('session-1', msg2)
-
-      const dequeued = dequeueMessage('session-1')
-      expect(dequeued?.id).toBe(msg1.id)
-      expect(useChatStore.getState().getQueueLength('session-1')).toBe(1)
-    })
-
-    it('returns undefined when dequeuing empty queue', () => {
-      const { dequeueMessage } = useChatStore.getState()
-
-      expect(dequeueMessage('session-1')).toBeUndefined()
-    })
-
-    it('removes specific queued message', () => {
-      const { enqueueMessage, removeQueuedMessage, getQueuedMessages } =
-        useChatStore.getState()
-
-      const msg1 = createMockMessage('msg-1', 'First')
-      const msg2 = createMockMessage('msg-2', 'Second')
-
-      enqueueMessage('session-1', msg1)
-      enqueueMessage('session-1', msg2)
-
-      removeQueuedMessage('session-1', 'msg-1')
-
-      const messages = getQueuedMessages('session-1')
-      expect(messages).toHaveLength(1)
-      expect(messages[0]?.id).toBe('msg-2')
-    })
-
-    it('clears queue', () => {
-      const { enqueueMessage, clearQueue, getQueueLength } =
-        useChatStore.getState()
-
-      enqueueMessage('session-1', mockMessage)
-      clearQueue('session-1')
-
-      expect(getQueueLength('session-1')).toBe(0)
-    })
-  })
-
   describe('permission approvals', () => {
     it('adds approved tool', () => {
       const { addApprovedTool, getApprovedTools } = useChatStore.getState()
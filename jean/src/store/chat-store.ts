@@ -12,7 +12,6 @@ import {
   type PendingTextFile,
   type ContentBlock,
   type Todo,
-  type QueuedMessage,
   type PermissionDenial,
   type ExecutionMode,
   type SessionDigest,
@@ -123,9 +122,6 @@ interface ChatUIState {
   // Streaming plan approvals per session (tracks approvals given during streaming)
   streamingPlanApprovals: Record<string, boolean>
 
-  // Message queues per session (FIFO - messages waiting to be sent)
-  messageQueues: Record<string, QueuedMessage[]>
-
   // Execution mode the currently-executing prompt was sent with (per session)
   executingModes: Record<string, ExecutionMode>
 
@@ -341,14 +337,6 @@ interface ChatUIState {
   isStreamingPlanApproved: (sessionId: string) => boolean
   clearStreamingPlanApproval: (sessionId: string) => void
 
-  // Actions - Message queue (session-based)
-  enqueueMessage: (sessionId: string, message: QueuedMessage) => void
-  dequeueMessage: (sessionId: string) => QueuedMessage | undefined
-  removeQueuedMessage: (sessionId: string, messageId: string) => void
-  clearQueue: (sessionId: string) => void
-  getQueueLength: (sessionId: string) => number
-  getQueuedMessages: (sessionId: string) => QueuedMessage[]
-
   // Actions - Executing mode (tracks mode prompt was sent with)
   setExecutingMode: (sessionId: string, mode: ExecutionMode) => void
   clearExecutingMode: (sessionId: string) => void
@@ -461,7 +449,6 @@ export const useChatStore = create<ChatUIState>()(
       activeTodos: {},
       fixedFindings: {},
       streamingPlanApprovals: {},
-      messageQueues: {},
       executingModes: {},
       approvedTools: {},
       pendingPermissionDenials: {},
@@ -1430,66 +1417,6 @@ export const useChatStore = create<ChatUIState>()(
           'clearStreamingPlanApproval'
         ),
 
-      // Message queue (session-based)
-      enqueueMessage: (sessionId, message) =>
-        set(
-          state => ({
-            messageQueues: {
-              ...state.messageQueues,
-              [sessionId]: [...(state.messageQueues[sessionId] ?? []), message],
-            },
-          }),
-          undefined,
-          'enqueueMessage'
-        ),
-
-      dequeueMessage: sessionId => {
-        const queue = get().messageQueues[sessionId] ?? []
-        if (queue.length === 0) return undefined
-
-        const [first, ...rest] = queue
-        set(
-          state => ({
-            messageQueues: {
-              ...state.messageQueues,
-              [sessionId]: rest,
-            },
-          }),
-          undefined,
-          'dequeueMessage'
-        )
-        return first
-      },
-
-      removeQueuedMessage: (sessionId, messageId) =>
-        set(
-          state => ({
-            messageQueues: {
-              ...state.messageQueues,
-              [sessionId]: (state.messageQueues[sessionId] ?? []).filter(
-                m => m.id !== messageId
-              ),
-            },
-          }),
-          undefined,
-          'removeQueuedMessage'
-        ),
-
-      clearQueue: sessionId =>
-        set(
-          state => {
-            const { [sessionId]: _, ...rest } = state.messageQueues
-            return { messageQueues: rest }
-          },
-          undefined,
-          'clearQueue'
-        ),
-
-      getQueueLength: sessionId =>
-        (get().messageQueues[sessionId] ?? []).length,
-
-      getQueuedMessages: sessionId => get().messageQueues[sessionId] ?? [],
-
       // Executing mode actions (tracks mode prompt was sent with)
       setExecutingMode: (sessionId, mode) =>
         set(
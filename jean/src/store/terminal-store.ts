@@ -1,5 +1,6 @@
 import { create } from 'zustand'
 import { getFilename } from '@/lib/path-utils'
+import type { TerminalInfo } from '@/types/terminal'
 
 /** A single terminal instance */
 export interface TerminalInstance {
@@ -57,6 +58,9 @@ interface TerminalState {
 
   // Close all terminals for a worktree (returns terminal IDs that need to be stopped)
   closeAllTerminals: (worktreeId: string) => string[]
+
+  // Add tabs for running backend terminals not in the store (returns count added)
+  restoreTerminals: (terminals: TerminalInfo[]) => number
 }
 
 function generateTerminalId(): string {
@@ -269,4 +273,40 @@ export const useTerminalStore = create<TerminalState>((set, get) => ({
 
     return terminalIds
   },
+
+  restoreTerminals: infos => {
+    const state = get()
+    const known = new Set(
+      Object.values(state.terminals).flatMap(list => list.map(t => t.id))
+    )
+    const terminals = { ...state.terminals }
+    const activeTerminalIds = { ...state.activeTerminalIds }
+    const runningTerminals = new Set(state.runningTerminals)
+    let restored = 0
+
+    for (const info of infos) {
+      // Terminals outside a known worktree have nowhere to be shown
+      if (!info.worktree_id || known.has(info.terminal_id)) continue
+      const worktreeId = info.worktree_id
+      terminals[worktreeId] = [
+        ...(terminals[worktreeId] ?? []),
+        {
+          id: info.terminal_id,
+          worktreeId,
+          command: info.command,
+          label: getDefaultLabel(info.command),
+        },
+      ]
+      if (!activeTerminalIds[worktreeId]) {
+        activeTerminalIds[worktreeId] = info.terminal_id
+      }
+      runningTerminals.add(info.terminal_id)
+      restored++
+    }
+
+    if (restored > 0) {
+      set({ terminals, activeTerminalIds, runningTerminals })
+    }
+    return restored
+  },
 }))
@@ -6,6 +6,8 @@ export type PreferencePane =
   | 'appearance'
   | 'keybindings'
   | 'magic-prompts'
+  | 'usage'
+  | 'schedules'
   | 'experimental'
   | 'web-access'
 
@@ -86,6 +88,7 @@ interface UIState {
   onboardingOpen: boolean
   onboardingStartStep: OnboardingStartStep
   openInModalOpen: boolean
+  importSessionModalOpen: boolean
   magicModalOpen: boolean
   newWorktreeModalOpen: boolean
   checkoutPRModalOpen: boolean
@@ -127,6 +130,7 @@ interface UIState {
   setOnboardingOpen: (open: boolean) => void
   setOnboardingStartStep: (step: OnboardingStartStep) => void
   setOpenInModalOpen: (open: boolean) => void
+  setImportSessionModalOpen: (open: boolean) => void
   setMagicModalOpen: (open: boolean) => void
   setNewWorktreeModalOpen: (open: boolean) => void
   setCheckoutPRModalOpen: (open: boolean) => void
@@ -163,6 +167,7 @@ export const useUIStore = create<UIState>()(
       onboardingOpen: false,
       onboardingStartStep: null,
       openInModalOpen: false,
+      importSessionModalOpen: false,
       magicModalOpen: false,
       newWorktreeModalOpen: false,
       checkoutPRModalOpen: false,
@@ -255,6 +260,13 @@ export const useUIStore = create<UIState>()(
       setOpenInModalOpen: open =>
         set({ openInModalOpen: open }, undefined, 'setOpenInModalOpen'),
 
+      setImportSessionModalOpen: open =>
+        set(
+          { importSessionModalOpen: open },
+          undefined,
+          'setImportSessionModalOpen'
+        ),
+
       setMagicModalOpen: open =>
         set({ magicModalOpen: open }, undefined, 'setMagicModalOpen'),
 
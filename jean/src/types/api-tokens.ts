@@ -0,0 +1,43 @@
+/**
+ * Permission carried by an API token
+ * Every scope implies read_only; admin implies everything
+ */
+export type TokenScope =
+  | 'read_only'
+  | 'chat'
+  | 'git_write'
+  | 'terminal'
+  | 'admin'
+
+/**
+ * Named token for HTTP/WebSocket access (the secret itself is never listed)
+ */
+export interface ApiToken {
+  id: string
+  name: string
+  /** First characters of the secret, to recognise it */
+  prefix: string
+  scopes: TokenScope[]
+  /** Projects the token may access (empty = all) */
+  project_ids: string[]
+  created_at: number
+  expires_at: number | null
+  last_used_at: number | null
+  expired: boolean
+}
+
+export interface CreateApiTokenInput {
+  name: string
+  scopes: TokenScope[]
+  projectIds?: string[]
+  /** Unix timestamp */
+  expiresAt?: number
+}
+
+/**
+ * Returned once on creation; `secret` cannot be retrieved again
+ */
+export interface CreatedApiToken {
+  token: ApiToken
+  secret: string
+}
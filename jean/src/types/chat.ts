@@ -151,6 +151,8 @@ export interface Session {
   pending_plan_message_id?: string
   /** Persisted session digest (recap summary) */
   digest?: SessionDigest
+  /** Source of this session if it was forked from another one */
+  forked_from?: ForkOrigin
   /** Status of the last run (for immediate status on app restart) */
   last_run_status?: RunStatus
   /** Execution mode of the last run (plan/build/yolo) */
@@ -220,6 +222,222 @@ export interface UsageData {
   cache_creation_input_tokens?: number
 }
 
+/**
+ * Aggregated token usage and estimated cost (USD)
+ */
+export interface UsageTotals {
+  input_tokens: number
+  output_tokens: number
+  cache_read_input_tokens: number
+  cache_creation_input_tokens: number
+  cost_usd: number
+  /** Number of runs with usage data */
+  run_count: number
+  /** Runs whose model had no price configured */
+  unpriced_run_count: number
+}
+
+/**
+ * Usage for one session, worktree, project, model or day
+ */
+export interface UsageBucket {
+  /** Session/worktree/project ID, model name, or YYYY-MM-DD for days */
+  key: string
+  name?: string
+  worktree_id?: string
+  project_id?: string
+  totals: UsageTotals
+}
+
+/**
+ * Usage roll-up returned by get_usage_report
+ */
+export interface UsageReport {
+  total: UsageTotals
+  by_project: UsageBucket[]
+  by_worktree: UsageBucket[]
+  by_session: UsageBucket[]
+  by_model: UsageBucket[]
+  by_day: UsageBucket[]
+}
+
+/**
+ * Restricts which runs are counted in a usage report
+ */
+export interface UsageFilter {
+  project_id?: string
+  worktree_id?: string
+  session_id?: string
+  /** Unix timestamp (inclusive) */
+  since?: number
+  /** Unix timestamp (exclusive) */
+  until?: number
+}
+
+/**
+ * Spending/token budget (stored in preferences.usage_budgets)
+ */
+export interface UsageBudget {
+  id: string
+  name?: string
+  scope:
+    | { type: 'global' }
+    | { type: 'project'; project_id: string }
+    | { type: 'worktree'; worktree_id: string }
+  period: 'daily' | 'total'
+  soft_limit_usd?: number
+  hard_limit_usd?: number
+  soft_limit_tokens?: number
+  hard_limit_tokens?: number
+  enabled: boolean
+}
+
+/**
+ * Current spend against a budget
+ */
+export interface BudgetStatus {
+  budget: UsageBudget
+  spent_usd: number
+  spent_tokens: number
+  level: 'ok' | 'warning' | 'exceeded'
+}
+
+/**
+ * Payload of budget:warning and budget:exceeded events
+ */
+export interface BudgetEvent {
+  session_id: string
+  worktree_id: string
+  statuses: BudgetStatus[]
+  /** Whether the run went ahead anyway (hard limit overridden) */
+  overridden: boolean
+}
+
+/**
+ * Payload of budget:warning and budget:exceeded events
+ */
+export interface BudgetEvent {
+  session_id: string
+  worktree_id: string
+  statuses: BudgetStatus[]
+  /** Whether the run went ahead anyway (hard limit overridden) */
+  overridden: boolean
+}
+
+// ============================================================================
+// Search Types
+// ============================================================================
+
+/** Part of a run a search hit matched in */
+export type SearchField =
+  | 'user_message'
+  | 'assistant_text'
+  | 'tool_name'
+  | 'tool_input'
+
+/**
+ * Optional restrictions for search_sessions
+ */
+export interface SearchFilter {
+  project_id?: string
+  worktree_id?: string
+  /** Include archived sessions (default: true) */
+  include_archived?: boolean
+}
+
+/**
+ * A ranked full-text search result (one per matching message)
+ */
+export interface SearchHit {
+  session_id: string
+  session_name: string
+  worktree_id: string
+  worktree_name: string | null
+  /** Null once the worktree is deleted */
+  worktree_path: string | null
+  project_id: string | null
+  project_name: string | null
+  run_id: string
+  message_id: string
+  field: SearchField
+  snippet: string
+  score: number
+  timestamp: number
+  archived: boolean
+}
+
+// ============================================================================
+// Export Types
+// ============================================================================
+
+export type ExportFormat = 'markdown' | 'html' | 'json'
+
+/**
+ * What to include in a session export
+ */
+export interface ExportOptions {
+  /** Default: false */
+  include_thinking?: boolean
+  /** Default: true */
+  include_tool_outputs?: boolean
+  /** Default: true */
+  include_usage?: boolean
+}
+
+export type ExportedBlock =
+  | { type: 'text'; text: string }
+  | { type: 'thinking'; thinking: string }
+  | {
+      type: 'tool_use'
+      id: string
+      name: string
+      input: unknown
+      output?: string
+      parent_tool_use_id?: string
+    }
+
+export interface ExportedMessage {
+  id: string
+  role: 'user' | 'assistant'
+  timestamp: number
+  model?: string
+  execution_mode?: string
+  thinking_level?: string
+  cancelled: boolean
+  blocks: ExportedBlock[]
+  usage?: UsageData
+}
+
+/**
+ * Stable JSON export schema (format: 'json')
+ */
+export interface SessionExport {
+  schema_version: number
+  exported_at: number
+  session: {
+    id: string
+    name: string
+    worktree_id: string
+    created_at: number
+    claude_session_id?: string
+  }
+  messages: ExportedMessage[]
+  usage?: UsageData
+}
+
+/**
+ * A Claude CLI conversation found in ~/.claude/projects for a worktree
+ */
+export interface ClaudeCliSessionInfo {
+  claude_session_id: string
+  path: string
+  modified_at: number
+  summary: string | null
+  first_message: string | null
+  /** A session with this claude_session_id already exists in the worktree */
+  imported: boolean
+}
+
 // ============================================================================
 // Compaction Types
 // ============================================================================
@@ -664,13 +882,11 @@ export interface ReviewFinding {
 // ============================================================================
 
 /**
- * A message waiting in the queue to be sent
- * Captures all settings at the time of queueing so they're preserved
+ * A message composed in the chat input, before it is sent or queued
+ * Captures all settings at the time of submitting so they're preserved
  */
-export interface QueuedMessage {
-  /** Unique ID for this queued message (for reordering/removal) */
-  id: string
-  /** The message text (already formatted with file/image references) */
+export interface PendingMessage {
+  /** The user's text, without attachment references */
   message: string
   /** Snapshot of pending images at time of queue */
   pendingImages: PendingImage[]
@@ -688,8 +904,43 @@ export interface QueuedMessage {
   thinkingLevel: ThinkingLevel
   /** Whether thinking should be disabled for this mode (snapshot at queue time) */
   disableThinkingForMode: boolean
-  /** Timestamp when queued (for display ordering) */
-  queuedAt: number
+}
+
+/**
+ * A follow-up message waiting in a session's backend queue
+ * Sent automatically (with its own options) when the current run completes
+ */
+export interface QueuedMessage {
+  id: string
+  /** Message text, including attachment references */
+  message: string
+  model?: string
+  execution_mode?: ExecutionMode
+  thinking_level?: ThinkingLevel
+  disable_thinking_for_mode?: boolean
+  parallel_execution_prompt_enabled?: boolean
+  ai_language?: string
+  allowed_tools?: string[]
+  /** Unix timestamp when the message was queued */
+  queued_at: number
+}
+
+/**
+ * Event payload for queue changes from Rust (enqueue, edit, reorder, send)
+ */
+export interface QueueUpdatedEvent {
+  session_id: string
+  worktree_id: string
+  queue: QueuedMessage[]
+}
+
+/**
+ * Event payload when a queued message is sent
+ */
+export interface QueueDispatchedEvent {
+  session_id: string
+  worktree_id: string
+  item: QueuedMessage
 }
 
 // ============================================================================
@@ -820,6 +1071,16 @@ export interface SessionDebugInfo {
 // Session Digest Types (for context recall after switching)
 // ============================================================================
 
+/**
+ * Where a forked session was branched off
+ */
+export interface ForkOrigin {
+  session_id: string
+  worktree_id: string
+  message_id: string
+  forked_at: number
+}
+
 /**
  * A brief digest of a session for context recall
  * Generated when user opens a session that had activity while out of focus
@@ -1,4 +1,4 @@
-import type { ThinkingLevel } from './chat'
+import type { ThinkingLevel, UsageBudget } from './chat'
 import { DEFAULT_KEYBINDINGS, type KeybindingsMap } from './keybindings'
 
 // =============================================================================
@@ -274,9 +274,44 @@ export interface AppPreferences {
   http_server_auto_start: boolean // Auto-start HTTP server on launch
   http_server_localhost_only: boolean // Bind to localhost only (more secure)
   http_server_token_required: boolean // Require token for web access (default true)
+  http_server_tls_enabled: boolean // Serve HTTPS instead of HTTP
+  http_server_tls_cert_path: string | null // PEM certificate chain (null = self-signed)
+  http_server_tls_key_path: string | null // PEM private key for the certificate
+  http_server_allowed_ips: string[] // IPs/CIDR ranges allowed to connect (empty = any)
   auto_archive_on_pr_merged: boolean // Auto-archive worktrees when their PR is merged
   show_keybinding_hints: boolean // Show keyboard shortcut hints at bottom of canvas views
   debug_mode_enabled: boolean // Show debug panel in chat sessions
+  model_pricing: Record<string, ModelPricing> // USD per million tokens, keyed by model alias
+  usage_budgets: UsageBudget[] // Per-project/worktree/day spending limits checked before each run
+}
+
+/** Model price in USD per million tokens (used for usage cost estimates) */
+export interface ModelPricing {
+  input_per_mtok: number
+  output_per_mtok: number
+  cache_read_per_mtok: number
+  cache_write_per_mtok: number
+}
+
+export const DEFAULT_MODEL_PRICING: Record<string, ModelPricing> = {
+  opus: {
+    input_per_mtok: 5,
+    output_per_mtok: 25,
+    cache_read_per_mtok: 0.5,
+    cache_write_per_mtok: 6.25,
+  },
+  sonnet: {
+    input_per_mtok: 3,
+    output_per_mtok: 15,
+    cache_read_per_mtok: 0.3,
+    cache_write_per_mtok: 3.75,
+  },
+  haiku: {
+    input_per_mtok: 1,
+    output_per_mtok: 5,
+    cache_read_per_mtok: 0.1,
+    cache_write_per_mtok: 1.25,
+  },
 }
 
 export type FileEditMode = 'inline' | 'external'
@@ -493,7 +528,13 @@ export const defaultPreferences: AppPreferences = {
   http_server_auto_start: false,
   http_server_localhost_only: true, // Default to localhost-only for security
   http_server_token_required: true, // Default: require token for security
+  http_server_tls_enabled: false,
+  http_server_tls_cert_path: null,
+  http_server_tls_key_path: null,
+  http_server_allowed_ips: [],
   auto_archive_on_pr_merged: true, // Default: enabled
   show_keybinding_hints: true, // Default: enabled
   debug_mode_enabled: false, // Default: disabled
+  model_pricing: DEFAULT_MODEL_PRICING,
+  usage_budgets: [],
 }
@@ -0,0 +1,91 @@
+import type { ExecutionMode, ThinkingLevel } from '@/types/chat'
+
+/**
+ * When a schedule fires
+ * Cron expressions have five fields and are evaluated in local time
+ */
+export type ScheduleTrigger =
+  | { type: 'cron'; expression: string }
+  | { type: 'once'; at: number }
+
+/**
+ * Where a scheduled prompt is sent
+ * Without worktree_id the project's base session is used; without
+ * session_id a new session is created for every run
+ */
+export interface ScheduleTarget {
+  project_id: string
+  worktree_id?: string
+  session_id?: string
+}
+
+export type ScheduleOutcome =
+  | 'running'
+  | 'completed'
+  | 'cancelled'
+  | 'queued'
+  | 'skipped'
+  | 'failed'
+
+/**
+ * One firing of a schedule
+ */
+export interface ScheduleRun {
+  /** Unique identifier (empty for runs recorded before runs had ids) */
+  id: string
+  /** Unix timestamp the run was due */
+  scheduled_for: number
+  fired_at: number
+  finished_at?: number
+  outcome: ScheduleOutcome
+  worktree_id?: string
+  session_id?: string
+  /** Assistant message produced by the run */
+  message_id?: string
+  /** Queued message carrying the prompt, when the target session was busy */
+  queued_message_id?: string
+  error?: string
+}
+
+/**
+ * A persisted schedule
+ */
+export interface Schedule {
+  id: string
+  name: string
+  prompt: string
+  trigger: ScheduleTrigger
+  target: ScheduleTarget
+  model?: string
+  execution_mode?: ExecutionMode
+  thinking_level?: ThinkingLevel
+  enabled: boolean
+  created_at: number
+  /** Unix timestamp of the next run (absent when disabled or finished) */
+  next_run_at?: number
+  /** Past runs, most recent last */
+  history: ScheduleRun[]
+}
+
+/**
+ * Editable fields of a schedule (for create/update)
+ */
+export interface ScheduleInput {
+  name: string
+  prompt: string
+  trigger: ScheduleTrigger
+  target: ScheduleTarget
+  model?: string
+  execution_mode?: ExecutionMode
+  thinking_level?: ThinkingLevel
+  enabled?: boolean
+}
+
+/**
+ * Event payload for schedule:fired, schedule:queued and schedule:finished
+ */
+export interface ScheduleRunEvent {
+  schedule_id: string
+  schedule_name: string
+  run: ScheduleRun
+}
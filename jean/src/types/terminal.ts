@@ -1,15 +1,72 @@
 export interface TerminalOutputEvent {
   terminal_id: string
+  worktree_id: string | null
+  /** Decoded text, or raw bytes in base64 when `encoding` is set */
   data: string
+  /** Stream offset of the first byte of `data` */
+  offset: number
+  /** Set for WebSocket subscriptions that asked for binary output */
+  encoding?: 'base64'
+}
+
+/** Recent output of a running terminal, replayed when attaching */
+export interface TerminalScrollback {
+  terminal_id: string
+  data: string
+  start: number
+  /** Output events with a lower offset are already included in `data` */
+  end: number
+  cols: number
+  rows: number
 }
 
 export interface TerminalStartedEvent {
   terminal_id: string
+  worktree_id: string | null
   cols: number
   rows: number
 }
 
 export interface TerminalStoppedEvent {
   terminal_id: string
+  worktree_id: string | null
   exit_code: number | null
 }
+
+/** A running terminal, listed to restore tabs after a reload or restart */
+export interface TerminalInfo {
+  terminal_id: string
+  worktree_id: string | null
+  worktree_path: string
+  command: string | null
+  /** Runs in a detached host and survives the app restarting */
+  persistent: boolean
+}
+
+/** A terminal recording (asciicast v2 file) */
+export interface TerminalRecording {
+  id: string
+  title: string
+  command: string | null
+  cols: number
+  rows: number
+  /** Unix timestamp (seconds) when the recording started */
+  started_at: number
+  size: number
+  path: string
+  /** Terminal being recorded, while the recording is in progress */
+  terminal_id: string | null
+}
+
+/** A recorded event: `o` output, `i` input, `r` resize (`COLSxROWS`) */
+export interface RecordingEvent {
+  /** Seconds since the recording started */
+  time: number
+  code: 'o' | 'i' | 'r'
+  data: string
+}
+
+export interface TerminalRecordingData {
+  recording: TerminalRecording
+  events: RecordingEvent[]
+}